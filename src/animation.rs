@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Easing and interpolation, so that progress bars and gauges glide to new values over a few
+//! frames rather than jumping.
+//!
+//! A [`Tween`] is retargeted whenever the underlying value changes, and sampled with the time of
+//! each frame while drawing.
+
+use std::time::Duration;
+use std::time::Instant;
+
+/// Maps the linear progress of an animation, from 0 to 1, to the progress of the animated value.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    EaseInQuad,
+    EaseOutQuad,
+    EaseInOutQuad,
+    /// Starts fast and settles gently, which suits values that are updated often.
+    #[default]
+    EaseOutCubic,
+    EaseInOutCubic,
+}
+
+impl Easing {
+    /// The eased progress at `t`, which is clamped to `[0, 1]`.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInQuad => t * t,
+            Easing::EaseOutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOutQuad if t < 0.5 => 2.0 * t * t,
+            Easing::EaseInOutQuad => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOutCubic if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOutCubic => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// Linear interpolation from `from` at 0 to `to` at 1.
+pub fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+/// Animates a value towards a target over a fixed duration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tween {
+    from: f64,
+    to: f64,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Tween {
+    /// A tween resting at `value`.
+    pub fn new(value: f64, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start: Instant::now(),
+            duration,
+            easing,
+        }
+    }
+
+    pub fn target(&self) -> f64 {
+        self.to
+    }
+
+    /// Starts animating from the value at `now` towards `target`. Does nothing if `target` is
+    /// already the target, so this can be called every frame.
+    pub fn set_target_at(&mut self, target: f64, now: Instant) {
+        if target == self.to {
+            return;
+        }
+        self.from = self.value_at(now);
+        self.to = target;
+        self.start = now;
+    }
+
+    /// [`set_target_at`](Self::set_target_at) the current time.
+    pub fn set_target(&mut self, target: f64) {
+        self.set_target_at(target, Instant::now())
+    }
+
+    /// Jumps to `value` without animating.
+    pub fn set(&mut self, value: f64) {
+        self.from = value;
+        self.to = value;
+    }
+
+    /// The animated value at `now`, e.g. the time of the frame being drawn.
+    pub fn value_at(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.start);
+        if self.duration.is_zero() || elapsed >= self.duration {
+            return self.to;
+        }
+        let t = elapsed.as_secs_f64() / self.duration.as_secs_f64();
+        lerp(self.from, self.to, self.easing.apply(t))
+    }
+
+    /// The animated value at the current time.
+    pub fn value(&self) -> f64 {
+        self.value_at(Instant::now())
+    }
+
+    /// Whether the target was reached at `now`.
+    pub fn is_finished_at(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easing() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseInQuad,
+            Easing::EaseOutQuad,
+            Easing::EaseInOutQuad,
+            Easing::EaseOutCubic,
+            Easing::EaseInOutCubic,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert_eq!(Easing::EaseInOutQuad.apply(0.5), 0.5);
+        assert_eq!(Easing::EaseOutCubic.apply(0.5), 0.875);
+    }
+
+    #[test]
+    fn test_tween() {
+        let start = Instant::now();
+        let mut tween = Tween::new(0.0, Duration::from_millis(100), Easing::Linear);
+        tween.set_target_at(10.0, start);
+
+        assert_eq!(tween.value_at(start), 0.0);
+        assert_eq!(tween.value_at(start + Duration::from_millis(50)), 5.0);
+        assert!(!tween.is_finished_at(start + Duration::from_millis(50)));
+
+        // Retargeting mid-way continues from the current value.
+        tween.set_target_at(0.0, start + Duration::from_millis(50));
+        assert_eq!(tween.value_at(start + Duration::from_millis(100)), 2.5);
+        assert_eq!(tween.value_at(start + Duration::from_millis(150)), 0.0);
+        assert!(tween.is_finished_at(start + Duration::from_millis(150)));
+    }
+}
@@ -0,0 +1,190 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The escape sequences superconsole draws with. They are written directly rather than through
+//! the terminal backend, which is only needed to query and configure the terminal.
+
+use std::fmt;
+use std::io;
+
+use crate::style::Attributes;
+use crate::style::Color;
+
+/// An escape sequence.
+pub(crate) trait Command {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result;
+}
+
+/// Writes [`Command`]s to an [`io::Write`], without flushing it.
+pub(crate) trait QueueableCommand {
+    fn queue(&mut self, command: impl Command) -> io::Result<&mut Self>;
+}
+
+impl<W: io::Write + ?Sized> QueueableCommand for W {
+    fn queue(&mut self, command: impl Command) -> io::Result<&mut Self> {
+        let mut ansi = String::new();
+        command
+            .write_ansi(&mut ansi)
+            .map_err(|_| io::Error::other("Error formatting escape sequence"))?;
+        self.write_all(ansi.as_bytes())?;
+        Ok(self)
+    }
+}
+
+/// Moves the cursor up by the given number of rows. Terminals treat 0 as 1.
+pub(crate) struct MoveUp(pub u16);
+
+impl Command for MoveUp {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b[{}A", self.0)
+    }
+}
+
+/// Moves the cursor to the start of the line the given number of rows down.
+pub(crate) struct MoveToNextLine(pub u16);
+
+impl Command for MoveToNextLine {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b[{}E", self.0)
+    }
+}
+
+/// Moves the cursor to the given column, counting from 0.
+pub(crate) struct MoveToColumn(pub u16);
+
+impl Command for MoveToColumn {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b[{}G", self.0 + 1)
+    }
+}
+
+/// Moves the cursor to the given column and row, counting from 0.
+pub(crate) struct MoveTo(pub u16, pub u16);
+
+impl Command for MoveTo {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b[{};{}H", self.1 + 1, self.0 + 1)
+    }
+}
+
+macro_rules! sequences {
+    ($($(#[$doc:meta])* $name:ident => $ansi:literal,)*) => {
+        $(
+            $(#[$doc])*
+            pub(crate) struct $name;
+
+            impl Command for $name {
+                fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+                    f.write_str($ansi)
+                }
+            }
+        )*
+    };
+}
+
+sequences! {
+    Hide => "\x1b[?25l",
+    Show => "\x1b[?25h",
+    SavePosition => "\x1b7",
+    RestorePosition => "\x1b8",
+    EnterAlternateScreen => "\x1b[?1049h",
+    LeaveAlternateScreen => "\x1b[?1049l",
+    /// Resets colors and attributes.
+    ResetColor => "\x1b[0m",
+}
+
+pub(crate) enum ClearType {
+    FromCursorDown,
+    CurrentLine,
+    UntilNewLine,
+}
+
+pub(crate) struct Clear(pub ClearType);
+
+impl Command for Clear {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(match self.0 {
+            ClearType::FromCursorDown => "\x1b[J",
+            ClearType::CurrentLine => "\x1b[2K",
+            ClearType::UntilNewLine => "\x1b[K",
+        })
+    }
+}
+
+pub(crate) struct SetForegroundColor(pub Color);
+
+impl Command for SetForegroundColor {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match self.0 {
+            Color::Reset => f.write_str("\x1b[39m"),
+            color => {
+                f.write_str("\x1b[38;")?;
+                color.write_sgr(f)?;
+                f.write_str("m")
+            }
+        }
+    }
+}
+
+pub(crate) struct SetBackgroundColor(pub Color);
+
+impl Command for SetBackgroundColor {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match self.0 {
+            Color::Reset => f.write_str("\x1b[49m"),
+            color => {
+                f.write_str("\x1b[48;")?;
+                color.write_sgr(f)?;
+                f.write_str("m")
+            }
+        }
+    }
+}
+
+pub(crate) struct SetAttributes(pub Attributes);
+
+impl Command for SetAttributes {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        for attribute in self.0.iter() {
+            write!(f, "\x1b[{}m", attribute.sgr())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Attribute;
+
+    fn ansi(command: impl Command) -> String {
+        let mut ansi = String::new();
+        command.write_ansi(&mut ansi).unwrap();
+        ansi
+    }
+
+    #[test]
+    fn test_write_ansi() {
+        assert_eq!(ansi(MoveTo(0, 2)), "\x1b[3;1H");
+        assert_eq!(ansi(MoveToColumn(0)), "\x1b[1G");
+        assert_eq!(ansi(SetForegroundColor(Color::Red)), "\x1b[38;5;9m");
+        assert_eq!(
+            ansi(SetBackgroundColor(Color::Rgb { r: 1, g: 2, b: 3 })),
+            "\x1b[48;2;1;2;3m"
+        );
+        assert_eq!(ansi(SetForegroundColor(Color::Reset)), "\x1b[39m");
+        assert_eq!(ansi(SetAttributes(Attribute::Italic.into())), "\x1b[3m");
+        assert_eq!(
+            ansi(SetAttributes(
+                Attributes::from(Attribute::Italic) | Attribute::Bold
+            )),
+            "\x1b[1m\x1b[3m"
+        );
+    }
+}
@@ -19,9 +19,8 @@ pub(crate) fn enable_ansi_support() -> anyhow::Result<()> {
     struct AnsiIsNotSupported;
 
     // This functions has side effect: it enables ANSI support in terminals on Windows.
-    // https://github.com/crossterm-rs/crossterm/blob/769b18151c6c177d43c752cc964d2b3d058bcf14/src/ansi_support.rs#L39
     // Note if this function returns false, we cannot buffer ANSI escape sequences.
-    if !crossterm::ansi_support::supports_ansi() {
+    if !crate::backend::supports_ansi() {
         return Err(AnsiIsNotSupported.into());
     }
 
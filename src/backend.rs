@@ -0,0 +1,465 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The terminal backend superconsole is built on.
+//!
+//! Superconsole draws by writing escape sequences itself, and its [`style`](crate::style) and
+//! [`input`](crate::input) types are its own, so that its API does not change with the backend's.
+//! The backend is only used to configure and query the terminal: raw mode, its size, and input.
+//!
+//! The only backend is crossterm, enabled by the default `backend-crossterm` feature, which also
+//! provides conversions between its types and superconsole's. Without it:
+//! - The terminal size is read with `ioctl` on Unix, and is an error elsewhere.
+//! - Input and raw mode are not available, so
+//!   [`SuperConsole::enable_input`](crate::SuperConsole::enable_input) fails.
+//! - ANSI escapes are assumed to be unsupported on Windows, where the console falls back to
+//!   non-interactive output.
+
+#[cfg(feature = "backend-crossterm")]
+pub use crossterm;
+
+use crate::input::Event;
+
+#[cfg(not(feature = "backend-crossterm"))]
+#[derive(Debug, thiserror::Error)]
+#[error("{0} requires the `backend-crossterm` feature")]
+struct NoBackend(&'static str);
+
+/// The size of the terminal, as columns and rows.
+pub(crate) fn terminal_size() -> anyhow::Result<(u16, u16)> {
+    #[cfg(feature = "backend-crossterm")]
+    {
+        Ok(crossterm::terminal::size()?)
+    }
+    #[cfg(all(not(feature = "backend-crossterm"), unix))]
+    {
+        let mut size = libc::winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        // SAFETY: `TIOCGWINSZ` only writes to `size`, which outlives the call.
+        if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok((size.ws_col, size.ws_row))
+    }
+    #[cfg(all(not(feature = "backend-crossterm"), not(unix)))]
+    {
+        Err(NoBackend("Reading the terminal size").into())
+    }
+}
+
+pub(crate) fn enable_raw_mode() -> anyhow::Result<()> {
+    #[cfg(feature = "backend-crossterm")]
+    {
+        Ok(crossterm::terminal::enable_raw_mode()?)
+    }
+    #[cfg(not(feature = "backend-crossterm"))]
+    {
+        Err(NoBackend("Raw mode").into())
+    }
+}
+
+/// Whether the terminal is in raw mode. Without a backend, raw mode can not have been entered.
+pub(crate) fn is_raw_mode_enabled() -> bool {
+    #[cfg(feature = "backend-crossterm")]
+    {
+        crossterm::terminal::is_raw_mode_enabled().unwrap_or(false)
+    }
+    #[cfg(not(feature = "backend-crossterm"))]
+    {
+        false
+    }
+}
+
+/// Leaves raw mode. Without a backend, raw mode can not have been entered, so this does nothing.
+pub(crate) fn disable_raw_mode() -> anyhow::Result<()> {
+    #[cfg(feature = "backend-crossterm")]
+    {
+        crossterm::terminal::disable_raw_mode()?;
+    }
+    Ok(())
+}
+
+/// Waits at most `timeout` for an input event, returning `None` if there was none.
+pub(crate) fn read_event(timeout: std::time::Duration) -> anyhow::Result<Option<Event>> {
+    #[cfg(feature = "backend-crossterm")]
+    {
+        Ok(if crossterm::event::poll(timeout)? {
+            Some(crossterm::event::read()?.into())
+        } else {
+            None
+        })
+    }
+    #[cfg(not(feature = "backend-crossterm"))]
+    {
+        let _ = timeout;
+        Err(NoBackend("Reading input").into())
+    }
+}
+
+/// Whether the terminal supports ANSI escapes. On Windows, this enables them if possible.
+#[cfg(windows)]
+pub(crate) fn supports_ansi() -> bool {
+    // https://github.com/crossterm-rs/crossterm/blob/769b18151c6c177d43c752cc964d2b3d058bcf14/src/ansi_support.rs#L39
+    #[cfg(feature = "backend-crossterm")]
+    {
+        crossterm::ansi_support::supports_ansi()
+    }
+    #[cfg(not(feature = "backend-crossterm"))]
+    {
+        false
+    }
+}
+
+#[cfg(feature = "backend-crossterm")]
+mod conversions {
+    use crossterm::event as ct_event;
+    use crossterm::style as ct_style;
+
+    use crate::input::Event;
+    use crate::input::KeyCode;
+    use crate::input::KeyEvent;
+    use crate::input::KeyModifiers;
+    use crate::input::MouseButton;
+    use crate::input::MouseEvent;
+    use crate::input::MouseEventKind;
+    use crate::style::Attribute;
+    use crate::style::Attributes;
+    use crate::style::Color;
+    use crate::style::ContentStyle;
+    use crate::style::StyledContent;
+
+    impl From<ct_style::Color> for Color {
+        fn from(color: ct_style::Color) -> Self {
+            match color {
+                ct_style::Color::Reset => Color::Reset,
+                ct_style::Color::Black => Color::Black,
+                ct_style::Color::DarkGrey => Color::DarkGrey,
+                ct_style::Color::Red => Color::Red,
+                ct_style::Color::DarkRed => Color::DarkRed,
+                ct_style::Color::Green => Color::Green,
+                ct_style::Color::DarkGreen => Color::DarkGreen,
+                ct_style::Color::Yellow => Color::Yellow,
+                ct_style::Color::DarkYellow => Color::DarkYellow,
+                ct_style::Color::Blue => Color::Blue,
+                ct_style::Color::DarkBlue => Color::DarkBlue,
+                ct_style::Color::Magenta => Color::Magenta,
+                ct_style::Color::DarkMagenta => Color::DarkMagenta,
+                ct_style::Color::Cyan => Color::Cyan,
+                ct_style::Color::DarkCyan => Color::DarkCyan,
+                ct_style::Color::White => Color::White,
+                ct_style::Color::Grey => Color::Grey,
+                ct_style::Color::Rgb { r, g, b } => Color::Rgb { r, g, b },
+                ct_style::Color::AnsiValue(value) => Color::AnsiValue(value),
+            }
+        }
+    }
+
+    impl From<Color> for ct_style::Color {
+        fn from(color: Color) -> Self {
+            match color {
+                Color::Reset => ct_style::Color::Reset,
+                Color::Black => ct_style::Color::Black,
+                Color::DarkGrey => ct_style::Color::DarkGrey,
+                Color::Red => ct_style::Color::Red,
+                Color::DarkRed => ct_style::Color::DarkRed,
+                Color::Green => ct_style::Color::Green,
+                Color::DarkGreen => ct_style::Color::DarkGreen,
+                Color::Yellow => ct_style::Color::Yellow,
+                Color::DarkYellow => ct_style::Color::DarkYellow,
+                Color::Blue => ct_style::Color::Blue,
+                Color::DarkBlue => ct_style::Color::DarkBlue,
+                Color::Magenta => ct_style::Color::Magenta,
+                Color::DarkMagenta => ct_style::Color::DarkMagenta,
+                Color::Cyan => ct_style::Color::Cyan,
+                Color::DarkCyan => ct_style::Color::DarkCyan,
+                Color::White => ct_style::Color::White,
+                Color::Grey => ct_style::Color::Grey,
+                Color::Rgb { r, g, b } => ct_style::Color::Rgb { r, g, b },
+                Color::AnsiValue(value) => ct_style::Color::AnsiValue(value),
+            }
+        }
+    }
+
+    impl From<Attribute> for ct_style::Attribute {
+        fn from(attribute: Attribute) -> Self {
+            match attribute {
+                Attribute::Reset => ct_style::Attribute::Reset,
+                Attribute::Bold => ct_style::Attribute::Bold,
+                Attribute::Dim => ct_style::Attribute::Dim,
+                Attribute::Italic => ct_style::Attribute::Italic,
+                Attribute::Underlined => ct_style::Attribute::Underlined,
+                Attribute::SlowBlink => ct_style::Attribute::SlowBlink,
+                Attribute::RapidBlink => ct_style::Attribute::RapidBlink,
+                Attribute::Reverse => ct_style::Attribute::Reverse,
+                Attribute::Hidden => ct_style::Attribute::Hidden,
+                Attribute::CrossedOut => ct_style::Attribute::CrossedOut,
+                Attribute::Fraktur => ct_style::Attribute::Fraktur,
+                Attribute::NoBold => ct_style::Attribute::NoBold,
+                Attribute::NormalIntensity => ct_style::Attribute::NormalIntensity,
+                Attribute::NoItalic => ct_style::Attribute::NoItalic,
+                Attribute::NoUnderline => ct_style::Attribute::NoUnderline,
+                Attribute::NoBlink => ct_style::Attribute::NoBlink,
+                Attribute::NoReverse => ct_style::Attribute::NoReverse,
+                Attribute::NoHidden => ct_style::Attribute::NoHidden,
+                Attribute::NotCrossedOut => ct_style::Attribute::NotCrossedOut,
+                Attribute::Framed => ct_style::Attribute::Framed,
+                Attribute::Encircled => ct_style::Attribute::Encircled,
+                Attribute::OverLined => ct_style::Attribute::OverLined,
+                Attribute::NotFramedOrEncircled => ct_style::Attribute::NotFramedOrEncircled,
+                Attribute::NotOverLined => ct_style::Attribute::NotOverLined,
+            }
+        }
+    }
+
+    /// Attributes crossterm has but superconsole does not are dropped.
+    impl From<ct_style::Attributes> for Attributes {
+        fn from(attributes: ct_style::Attributes) -> Self {
+            Attribute::iterator()
+                .filter(|attribute| attributes.has((*attribute).into()))
+                .collect()
+        }
+    }
+
+    impl From<Attributes> for ct_style::Attributes {
+        fn from(attributes: Attributes) -> Self {
+            let mut converted = ct_style::Attributes::default();
+            for attribute in attributes.iter() {
+                converted.set(attribute.into());
+            }
+            converted
+        }
+    }
+
+    impl From<ct_style::ContentStyle> for ContentStyle {
+        fn from(style: ct_style::ContentStyle) -> Self {
+            ContentStyle {
+                foreground_color: style.foreground_color.map(Color::from),
+                background_color: style.background_color.map(Color::from),
+                attributes: style.attributes.into(),
+            }
+        }
+    }
+
+    impl From<ContentStyle> for ct_style::ContentStyle {
+        fn from(style: ContentStyle) -> Self {
+            ct_style::ContentStyle {
+                foreground_color: style.foreground_color.map(ct_style::Color::from),
+                background_color: style.background_color.map(ct_style::Color::from),
+                attributes: style.attributes.into(),
+            }
+        }
+    }
+
+    impl<D: std::fmt::Display + Clone> From<ct_style::StyledContent<D>> for StyledContent<D> {
+        fn from(styled: ct_style::StyledContent<D>) -> Self {
+            let style = (*styled.style()).into();
+            StyledContent::new(style, styled.content().clone())
+        }
+    }
+
+    impl From<ct_event::KeyModifiers> for KeyModifiers {
+        fn from(modifiers: ct_event::KeyModifiers) -> Self {
+            let mut converted = KeyModifiers::NONE;
+            for (from, to) in [
+                (ct_event::KeyModifiers::SHIFT, KeyModifiers::SHIFT),
+                (ct_event::KeyModifiers::CONTROL, KeyModifiers::CONTROL),
+                (ct_event::KeyModifiers::ALT, KeyModifiers::ALT),
+            ] {
+                if modifiers.contains(from) {
+                    converted |= to;
+                }
+            }
+            converted
+        }
+    }
+
+    impl From<ct_event::KeyCode> for KeyCode {
+        fn from(code: ct_event::KeyCode) -> Self {
+            match code {
+                ct_event::KeyCode::Backspace => KeyCode::Backspace,
+                ct_event::KeyCode::Enter => KeyCode::Enter,
+                ct_event::KeyCode::Left => KeyCode::Left,
+                ct_event::KeyCode::Right => KeyCode::Right,
+                ct_event::KeyCode::Up => KeyCode::Up,
+                ct_event::KeyCode::Down => KeyCode::Down,
+                ct_event::KeyCode::Home => KeyCode::Home,
+                ct_event::KeyCode::End => KeyCode::End,
+                ct_event::KeyCode::PageUp => KeyCode::PageUp,
+                ct_event::KeyCode::PageDown => KeyCode::PageDown,
+                ct_event::KeyCode::Tab => KeyCode::Tab,
+                ct_event::KeyCode::BackTab => KeyCode::BackTab,
+                ct_event::KeyCode::Delete => KeyCode::Delete,
+                ct_event::KeyCode::Insert => KeyCode::Insert,
+                ct_event::KeyCode::F(n) => KeyCode::F(n),
+                ct_event::KeyCode::Char(c) => KeyCode::Char(c),
+                ct_event::KeyCode::Null => KeyCode::Null,
+                ct_event::KeyCode::Esc => KeyCode::Esc,
+            }
+        }
+    }
+
+    impl From<ct_event::KeyEvent> for KeyEvent {
+        fn from(key: ct_event::KeyEvent) -> Self {
+            KeyEvent::new(key.code.into(), key.modifiers.into())
+        }
+    }
+
+    impl From<ct_event::MouseButton> for MouseButton {
+        fn from(button: ct_event::MouseButton) -> Self {
+            match button {
+                ct_event::MouseButton::Left => MouseButton::Left,
+                ct_event::MouseButton::Right => MouseButton::Right,
+                ct_event::MouseButton::Middle => MouseButton::Middle,
+            }
+        }
+    }
+
+    impl From<ct_event::MouseEvent> for MouseEvent {
+        fn from(mouse: ct_event::MouseEvent) -> Self {
+            let kind = match mouse.kind {
+                ct_event::MouseEventKind::Down(button) => MouseEventKind::Down(button.into()),
+                ct_event::MouseEventKind::Up(button) => MouseEventKind::Up(button.into()),
+                ct_event::MouseEventKind::Drag(button) => MouseEventKind::Drag(button.into()),
+                ct_event::MouseEventKind::Moved => MouseEventKind::Moved,
+                ct_event::MouseEventKind::ScrollDown => MouseEventKind::ScrollDown,
+                ct_event::MouseEventKind::ScrollUp => MouseEventKind::ScrollUp,
+            };
+            MouseEvent {
+                kind,
+                column: mouse.column,
+                row: mouse.row,
+                modifiers: mouse.modifiers.into(),
+            }
+        }
+    }
+
+    impl From<ct_event::Event> for Event {
+        fn from(event: ct_event::Event) -> Self {
+            match event {
+                ct_event::Event::Key(key) => Event::Key(key.into()),
+                ct_event::Event::Mouse(mouse) => Event::Mouse(mouse.into()),
+                ct_event::Event::Resize(columns, rows) => Event::Resize(columns, rows),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_color_round_trip() {
+            for color in [
+                ct_style::Color::Reset,
+                ct_style::Color::Black,
+                ct_style::Color::DarkGrey,
+                ct_style::Color::Red,
+                ct_style::Color::DarkRed,
+                ct_style::Color::Green,
+                ct_style::Color::DarkGreen,
+                ct_style::Color::Yellow,
+                ct_style::Color::DarkYellow,
+                ct_style::Color::Blue,
+                ct_style::Color::DarkBlue,
+                ct_style::Color::Magenta,
+                ct_style::Color::DarkMagenta,
+                ct_style::Color::Cyan,
+                ct_style::Color::DarkCyan,
+                ct_style::Color::White,
+                ct_style::Color::Grey,
+                ct_style::Color::Rgb { r: 1, g: 2, b: 3 },
+                ct_style::Color::AnsiValue(42),
+            ] {
+                assert_eq!(ct_style::Color::from(Color::from(color)), color);
+            }
+            assert_eq!(
+                Color::from(ct_style::Color::Rgb { r: 1, g: 2, b: 3 }),
+                Color::Rgb { r: 1, g: 2, b: 3 }
+            );
+        }
+
+        #[test]
+        fn test_attribute_round_trip() {
+            for attribute in Attribute::iterator() {
+                let converted = ct_style::Attributes::from(Attributes::from(attribute));
+                assert!(converted.has(attribute.into()), "{:?}", attribute);
+                assert_eq!(
+                    Attributes::from(converted),
+                    Attributes::from(attribute),
+                    "{:?}",
+                    attribute
+                );
+            }
+            assert!(Attributes::from(ct_style::Attributes::default()).is_empty());
+        }
+
+        #[test]
+        fn test_style_round_trip() {
+            let style = ContentStyle {
+                foreground_color: Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+                background_color: Some(Color::DarkCyan),
+                attributes: Attributes::from(Attribute::Bold) | Attribute::Underlined,
+            };
+            let converted = ct_style::ContentStyle::from(style);
+            assert_eq!(converted.background_color, Some(ct_style::Color::DarkCyan));
+            assert!(converted.attributes.has(ct_style::Attribute::Underlined));
+            assert_eq!(ContentStyle::from(converted), style);
+
+            let styled = ct_style::StyledContent::new(converted, "text");
+            assert_eq!(
+                StyledContent::from(styled),
+                StyledContent::new(style, "text")
+            );
+        }
+
+        #[test]
+        fn test_event() {
+            let event = ct_event::Event::Key(ct_event::KeyEvent::new(
+                ct_event::KeyCode::Char('c'),
+                ct_event::KeyModifiers::CONTROL | ct_event::KeyModifiers::SHIFT,
+            ));
+            assert_eq!(
+                Event::from(event),
+                Event::Key(KeyEvent::new(
+                    KeyCode::Char('c'),
+                    KeyModifiers::CONTROL | KeyModifiers::SHIFT
+                ))
+            );
+
+            let event = ct_event::Event::Mouse(ct_event::MouseEvent {
+                kind: ct_event::MouseEventKind::Drag(ct_event::MouseButton::Middle),
+                column: 3,
+                row: 4,
+                modifiers: ct_event::KeyModifiers::ALT,
+            });
+            assert_eq!(
+                Event::from(event),
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Drag(MouseButton::Middle),
+                    column: 3,
+                    row: 4,
+                    modifiers: KeyModifiers::ALT,
+                })
+            );
+
+            assert_eq!(
+                Event::from(ct_event::Event::Resize(80, 24)),
+                Event::Resize(80, 24)
+            );
+            assert_eq!(
+                KeyModifiers::from(ct_event::KeyModifiers::NONE),
+                KeyModifiers::NONE
+            );
+        }
+    }
+}
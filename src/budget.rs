@@ -0,0 +1,135 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Per-frame time budgets for drawing.
+//!
+//! On large dashboards a single draw can take longer than the interval between renders. A
+//! [`RenderBudget`] is shared between the console and [`Budgeted`](crate::components::Budgeted)
+//! components, which measure their own draw time against it. Once the budget is exhausted,
+//! expensive components reuse their previous output and decorative ones are skipped, while
+//! critical ones keep drawing live.
+
+use std::cmp::Reverse;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+/// How much a component matters when a frame is over budget.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Priority {
+    /// Always drawn live.
+    Critical,
+    /// Reuses its previous output when drawing it would exceed the budget.
+    #[default]
+    Normal,
+    /// Not drawn at all once the budget is exhausted.
+    Decorative,
+}
+
+/// What a budgeted component did in a frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BudgetOutcome {
+    Drawn,
+    Cached,
+    Skipped,
+}
+
+/// A budgeted component's draw in a frame.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BudgetEntry {
+    pub name: String,
+    /// Time spent drawing. This is zero if the component was not drawn.
+    pub cost: Duration,
+    pub outcome: BudgetOutcome,
+}
+
+/// The budgeted draws of a frame, slowest first.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BudgetStats {
+    /// Total time spent drawing budgeted components.
+    pub spent: Duration,
+    pub entries: Vec<BudgetEntry>,
+}
+
+impl BudgetStats {
+    /// The components that were not drawn live.
+    pub fn degraded(&self) -> impl Iterator<Item = &BudgetEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.outcome != BudgetOutcome::Drawn)
+    }
+}
+
+#[derive(Debug, Default)]
+struct BudgetState {
+    current: BudgetStats,
+    last_frame: BudgetStats,
+}
+
+/// A cheaply cloneable time budget for drawing a single frame. Install it with
+/// [`SuperConsole::set_render_budget`](crate::SuperConsole::set_render_budget) so that frames are
+/// started and finished around every draw.
+#[derive(Debug, Clone)]
+pub struct RenderBudget {
+    limit: Duration,
+    state: Arc<Mutex<BudgetState>>,
+}
+
+impl RenderBudget {
+    pub fn new(limit: Duration) -> Self {
+        Self {
+            limit,
+            state: Arc::new(Mutex::new(BudgetState::default())),
+        }
+    }
+
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    /// Time left in the current frame.
+    pub fn remaining(&self) -> Duration {
+        self.limit.saturating_sub(self.lock().current.spent)
+    }
+
+    /// Stats for the last complete frame.
+    pub fn last_frame(&self) -> BudgetStats {
+        self.lock().last_frame.clone()
+    }
+
+    /// Starts a new frame with the full budget available.
+    pub fn start_frame(&self) {
+        self.lock().current = BudgetStats::default();
+    }
+
+    /// Ends the current frame, making its stats available from [`last_frame`](Self::last_frame).
+    pub fn finish_frame(&self) {
+        let mut state = self.lock();
+        let mut finished = std::mem::take(&mut state.current);
+        finished.entries.sort_by_key(|entry| Reverse(entry.cost));
+        state.last_frame = finished;
+    }
+
+    pub(crate) fn record(&self, name: &str, cost: Duration, outcome: BudgetOutcome) {
+        let mut state = self.lock();
+        state.current.spent += cost;
+        state.current.entries.push(BudgetEntry {
+            name: name.to_owned(),
+            cost,
+            outcome,
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BudgetState> {
+        // The state is only ever updated in full, so it's fine to keep using it after a panic.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
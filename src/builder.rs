@@ -7,19 +7,50 @@
  * of this source tree.
  */
 
-use std::io;
 use std::io::Write;
 
+use crate::ansi_support::enable_ansi_support;
+use crate::content::Alignment;
+use crate::emit::EmitCapacity;
+use crate::emit::EmitColumns;
+use crate::emit::EmitDedup;
+use crate::emit::Level;
 use crate::output::BlockingSuperConsoleOutput;
 use crate::output::NonBlockingSuperConsoleOutput;
+use crate::output::Stream;
 use crate::output::SuperConsoleOutput;
+use crate::output::ThrottledSuperConsoleOutput;
+use crate::style::ColorSupport;
+use crate::theme::Theme;
 use crate::Dimensions;
 use crate::SuperConsole;
 
-/// A builder to create SuperConsole, with more options.
+/// A builder to create SuperConsole, with more options. See [`SuperConsole::builder`].
 pub struct Builder {
     non_blocking: bool,
     stream: Box<dyn Write + Send + 'static + Sync>,
+    /// The standard stream written to, if it is one.
+    standard: Option<Stream>,
+    /// Replaces the output built from the options above, if set.
+    output: Option<Box<dyn SuperConsoleOutput>>,
+    throttled: bool,
+    default_size: Option<Dimensions>,
+    forced: bool,
+    max_emit_buffer: Option<usize>,
+    emit_capacity: Option<EmitCapacity>,
+    emit_dedup: Option<EmitDedup>,
+    emit_level: Option<Level>,
+    retain_filtered: Option<usize>,
+    emit_columns: Option<EmitColumns>,
+    canvas_height: Option<usize>,
+    max_canvas_height: Option<usize>,
+    max_canvas_width: Option<usize>,
+    canvas_anchor: Alignment,
+    hide_cursor: bool,
+    unicode: Option<bool>,
+    color_support: Option<ColorSupport>,
+    theme: Option<Theme>,
+    alternate_screen: bool,
 }
 
 impl Default for Builder {
@@ -32,7 +63,27 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             non_blocking: false,
-            stream: Box::new(io::stderr()),
+            stream: Stream::Stderr.writer(),
+            standard: Some(Stream::Stderr),
+            output: None,
+            throttled: false,
+            default_size: None,
+            forced: false,
+            max_emit_buffer: None,
+            emit_capacity: None,
+            emit_dedup: None,
+            emit_level: None,
+            retain_filtered: None,
+            emit_columns: None,
+            canvas_height: None,
+            max_canvas_height: None,
+            max_canvas_width: None,
+            canvas_anchor: Alignment::Left,
+            hide_cursor: false,
+            unicode: None,
+            color_support: None,
+            theme: None,
+            alternate_screen: false,
         }
     }
 
@@ -45,31 +96,225 @@ impl Builder {
     /// Write to a different I/O
     pub fn write_to(&mut self, stream: Box<dyn Write + Send + 'static + Sync>) -> &mut Self {
         self.stream = stream;
+        self.standard = None;
         self
     }
 
-    /// Build a new SuperConsole if stderr is a TTY.
+    /// Write to a standard stream of the process, stderr by default. Lines emitted to the other
+    /// one with [`SuperConsole::emit_to`] are written there.
+    pub fn write_to_stream(&mut self, stream: Stream) -> &mut Self {
+        self.stream = stream.writer();
+        self.standard = Some(stream);
+        self
+    }
+
+    /// Write to a custom output, e.g. one recording frames, instead of the stream. This takes
+    /// precedence over [`non_blocking`](Self::non_blocking) and the stream.
+    pub fn output(&mut self, output: Box<dyn SuperConsoleOutput>) -> &mut Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Skip frames when the output can't keep up, see [`ThrottledSuperConsoleOutput`].
+    pub fn throttled(&mut self, throttled: bool) -> &mut Self {
+        self.throttled = throttled;
+        self
+    }
+
+    /// The size to draw with when the terminal size can't be determined.
+    pub fn default_size(&mut self, size: Dimensions) -> &mut Self {
+        self.default_size = Some(size);
+        self
+    }
+
+    /// Build the console even if the stream it writes to is not a compatible TTY.
+    pub fn forced(&mut self, forced: bool) -> &mut Self {
+        self.forced = forced;
+        self
+    }
+
+    /// Once more graphemes than this are waiting to be emitted, they are all drawn in the next
+    /// frame rather than being spread over several. Defaults to 1,000,000.
+    pub fn max_emit_buffer(&mut self, graphemes: usize) -> &mut Self {
+        self.max_emit_buffer = Some(graphemes);
+        self
+    }
+
+    /// Bounds the number of emitted lines waiting to be drawn, see
+    /// [`SuperConsole::set_emit_capacity`].
+    pub fn emit_capacity(&mut self, capacity: EmitCapacity) -> &mut Self {
+        self.emit_capacity = Some(capacity);
+        self
+    }
+
+    /// Collapses repeated emitted lines, see [`SuperConsole::set_emit_dedup`].
+    pub fn emit_dedup(&mut self, dedup: EmitDedup) -> &mut Self {
+        self.emit_dedup = Some(dedup);
+        self
+    }
+
+    /// Leaves out lines emitted below `min`, see [`SuperConsole::set_emit_level`].
+    pub fn emit_level(&mut self, min: Level) -> &mut Self {
+        self.emit_level = Some(min);
+        self
+    }
+
+    /// Keeps lines left out by the emit level, see [`SuperConsole::set_retain_filtered`].
+    pub fn retain_filtered(&mut self, capacity: usize) -> &mut Self {
+        self.retain_filtered = Some(capacity);
+        self
+    }
+
+    /// Lays out lines emitted to columns in side-by-side panes, see
+    /// [`SuperConsole::set_emit_columns`].
+    pub fn emit_columns(&mut self, columns: EmitColumns) -> &mut Self {
+        self.emit_columns = Some(columns);
+        self
+    }
+
+    /// Keeps the cursor hidden between frames, see [`SuperConsole::set_hide_cursor`].
+    pub fn hide_cursor(&mut self, hidden: bool) -> &mut Self {
+        self.hide_cursor = hidden;
+        self
+    }
+
+    /// Reserves rows for the canvas, see [`SuperConsole::set_canvas_height`].
+    pub fn canvas_height(&mut self, height: usize) -> &mut Self {
+        self.canvas_height = Some(height);
+        self
+    }
+
+    /// Bounds the height of the canvas, see [`SuperConsole::set_max_canvas_height`].
+    pub fn max_canvas_height(&mut self, height: usize) -> &mut Self {
+        self.max_canvas_height = Some(height);
+        self
+    }
+
+    /// Bounds the width of the canvas, see [`SuperConsole::set_max_canvas_width`].
+    pub fn max_canvas_width(&mut self, width: usize) -> &mut Self {
+        self.max_canvas_width = Some(width);
+        self
+    }
+
+    /// Where a narrower canvas sits, see [`SuperConsole::set_canvas_anchor`].
+    pub fn canvas_anchor(&mut self, anchor: Alignment) -> &mut Self {
+        self.canvas_anchor = anchor;
+        self
+    }
+
+    /// Whether built-in components may use glyphs beyond ASCII, overriding the detection.
+    /// This applies to the built console only, see [`SuperConsole::set_unicode`].
+    pub fn unicode(&mut self, enabled: bool) -> &mut Self {
+        self.unicode = Some(enabled);
+        self
+    }
+
+    /// The colors drawn, overriding the detection, e.g. from a `--color` flag.
+    /// This applies to the built console only, see [`SuperConsole::set_color_support`].
+    pub fn color_support(&mut self, support: ColorSupport) -> &mut Self {
+        self.color_support = Some(support);
+        self
+    }
+
+    /// The appearance of built-in components.
+    /// This applies to the built console only, see [`SuperConsole::set_theme`].
+    pub fn theme(&mut self, theme: Theme) -> &mut Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Draw on the alternate screen, restoring the main screen and drawing the emitted lines once
+    /// the console is finalized. See [`SuperConsole::enter_alternate_screen`].
+    pub fn alternate_screen(&mut self, enabled: bool) -> &mut Self {
+        self.alternate_screen = enabled;
+        self
+    }
+
+    /// Build a new SuperConsole if the standard stream it writes to is a TTY, or if
+    /// [`forced`](Self::forced). Consoles writing elsewhere are built if stderr is a TTY.
     pub fn build(self) -> anyhow::Result<Option<SuperConsole>> {
-        if !SuperConsole::compatible() {
+        let stream = match self.output {
+            Some(_) => Stream::Stderr,
+            None => self.standard.unwrap_or(Stream::Stderr),
+        };
+        if !self.forced && !SuperConsole::compatible_stream(stream) {
             return Ok(None);
         }
         Some(self.build_inner(None)).transpose()
     }
 
-    /// Build a new SuperConsole regardless of whether stderr is a TTY.
+    /// Build a new SuperConsole regardless of whether the stream it writes to is a TTY.
     pub fn build_forced(self, fallback_size: Dimensions) -> anyhow::Result<SuperConsole> {
         self.build_inner(Some(fallback_size))
     }
 
     fn build_inner(self, fallback_size: Option<Dimensions>) -> anyhow::Result<SuperConsole> {
-        Ok(SuperConsole::new_internal(fallback_size, self.output()?))
+        // Forced consoles skip `SuperConsole::compatible`, which enables virtual terminal
+        // processing on Windows. Without it, escape sequences would be printed as is.
+        let _ = enable_ansi_support();
+        let unicode = self.unicode;
+        let color_support = self.color_support;
+        let theme = self.theme.clone();
+        let alternate_screen = self.alternate_screen;
+        let max_emit_buffer = self.max_emit_buffer;
+        let emit_capacity = self.emit_capacity;
+        let emit_dedup = self.emit_dedup;
+        let emit_level = self.emit_level;
+        let retain_filtered = self.retain_filtered;
+        let emit_columns = self.emit_columns.clone();
+        let canvas_height = self.canvas_height;
+        let max_canvas_height = self.max_canvas_height;
+        let max_canvas_width = self.max_canvas_width;
+        let canvas_anchor = self.canvas_anchor;
+        let hide_cursor = self.hide_cursor;
+        let fallback_size = fallback_size.or(self.default_size);
+        let mut console = SuperConsole::new_internal(fallback_size, self.into_output()?);
+        if let Some(max_emit_buffer) = max_emit_buffer {
+            console.max_emit_buffer = max_emit_buffer;
+        }
+        console.set_emit_capacity(emit_capacity);
+        console.set_emit_dedup(emit_dedup);
+        if let Some(emit_level) = emit_level {
+            console.set_emit_level(emit_level);
+        }
+        console.set_retain_filtered(retain_filtered);
+        console.set_emit_columns(emit_columns);
+        console.set_canvas_height(canvas_height);
+        console.set_max_canvas_height(max_canvas_height);
+        console.set_max_canvas_width(max_canvas_width);
+        console.set_canvas_anchor(canvas_anchor);
+        console.set_hide_cursor(hide_cursor);
+        if let Some(enabled) = unicode {
+            console.set_unicode(enabled);
+        }
+        if let Some(support) = color_support {
+            console.set_color_support(support);
+        }
+        if let Some(theme) = theme {
+            console.set_theme(theme);
+        }
+        if alternate_screen {
+            console.enter_alternate_screen()?;
+        }
+        Ok(console)
     }
 
-    fn output(self) -> anyhow::Result<Box<dyn SuperConsoleOutput>> {
-        if self.non_blocking {
-            Ok(Box::new(NonBlockingSuperConsoleOutput::new(self.stream)?))
-        } else {
-            Ok(Box::new(BlockingSuperConsoleOutput::new(self.stream)))
+    fn into_output(self) -> anyhow::Result<Box<dyn SuperConsoleOutput>> {
+        let output: Box<dyn SuperConsoleOutput> =
+            match (self.output, self.non_blocking, self.standard) {
+                (Some(output), _, _) => output,
+                (None, true, Some(stream)) => {
+                    Box::new(NonBlockingSuperConsoleOutput::for_stream(stream)?)
+                }
+                (None, true, None) => Box::new(NonBlockingSuperConsoleOutput::new(self.stream)?),
+                (None, false, Some(stream)) => {
+                    Box::new(BlockingSuperConsoleOutput::for_stream(stream))
+                }
+                (None, false, None) => Box::new(BlockingSuperConsoleOutput::new(self.stream)),
+            };
+        if self.throttled {
+            return Ok(Box::new(ThrottledSuperConsoleOutput::new(output)));
         }
+        Ok(output)
     }
 }
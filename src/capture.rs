@@ -0,0 +1,313 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Capturing every frame written to the terminal, to reproduce rendering glitches later.
+//!
+//! [`CaptureOutput`] wraps another output, and appends everything written to it to a capture
+//! file, one JSON object per line, along with the index, size and timestamp of the frame:
+//!
+//! ```text
+//! {"index":0,"time":0.012,"width":80,"height":24,"final":false,"data":"\u001b[2K..."}
+//! ```
+//!
+//! [`replay`] writes the captured frames to a terminal again, at their original pace or faster,
+//! and [`read_capture`] parses them for inspection. Frames are replayed as they were written, so
+//! the terminal should be at least as large as the one they were captured on.
+
+use std::any::Any;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use anyhow::Context as _;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::output::CellFrame;
+use crate::output::FrameMetadata;
+use crate::output::Stream;
+use crate::output::SuperConsoleOutput;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// An output which tees everything written to another output into a capture file.
+/// See the [module documentation](self).
+pub struct CaptureOutput {
+    inner: Box<dyn SuperConsoleOutput>,
+    capture: Box<dyn Write + Send + Sync + 'static>,
+    start: SystemTime,
+    /// The size of the last frame, for writes which aren't frames.
+    dimensions: Dimensions,
+}
+
+impl CaptureOutput {
+    /// Captures the output written to `inner` to `capture`.
+    pub fn new(
+        inner: Box<dyn SuperConsoleOutput>,
+        capture: Box<dyn Write + Send + Sync + 'static>,
+    ) -> Self {
+        let dimensions = inner.terminal_size().unwrap_or_default();
+        Self {
+            inner,
+            capture,
+            start: SystemTime::now(),
+            dimensions,
+        }
+    }
+
+    /// Captures the output written to `inner` to a new capture file at `path`.
+    pub fn create(
+        inner: Box<dyn SuperConsoleOutput>,
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Error creating capture file `{}`", path.display()))?;
+        Ok(Self::new(inner, Box::new(BufWriter::new(file))))
+    }
+
+    /// Appends `data`, written at `at`. Writes which aren't frames have no index.
+    fn capture(
+        &mut self,
+        index: Option<u64>,
+        at: SystemTime,
+        mode: DrawMode,
+        data: &[u8],
+    ) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let elapsed = at.duration_since(self.start).unwrap_or_default();
+        let frame = json!({
+            "index": index,
+            "time": elapsed.as_secs_f64(),
+            "width": self.dimensions.width,
+            "height": self.dimensions.height,
+            "final": mode == DrawMode::Final,
+            "data": String::from_utf8_lossy(data),
+        });
+        serde_json::to_writer(&mut self.capture, &frame)?;
+        self.capture.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+impl SuperConsoleOutput for CaptureOutput {
+    fn should_render(&mut self) -> bool {
+        self.inner.should_render()
+    }
+
+    fn output(&mut self, buffer: Vec<u8>) -> anyhow::Result<()> {
+        self.capture(None, SystemTime::now(), DrawMode::Normal, &buffer)?;
+        self.inner.output(buffer)
+    }
+
+    fn output_frame(&mut self, buffer: Vec<u8>, metadata: FrameMetadata) -> anyhow::Result<()> {
+        self.dimensions = metadata.dimensions;
+        self.capture(
+            Some(metadata.index),
+            metadata.timestamp,
+            metadata.mode,
+            &buffer,
+        )?;
+        self.inner.output_frame(buffer, metadata)
+    }
+
+    fn wants_cells(&self) -> bool {
+        self.inner.wants_cells()
+    }
+
+    fn output_cells(&mut self, frame: &CellFrame) -> anyhow::Result<()> {
+        self.inner.output_cells(frame)
+    }
+
+    fn wants_lines(&self) -> bool {
+        self.inner.wants_lines()
+    }
+
+    fn output_lines(
+        &mut self,
+        emitted: &Lines,
+        canvas: &Lines,
+        metadata: FrameMetadata,
+    ) -> anyhow::Result<()> {
+        self.inner.output_lines(emitted, canvas, metadata)
+    }
+
+    fn stream(&self) -> Option<Stream> {
+        self.inner.stream()
+    }
+
+    /// Lines written to other streams are captured too, as they share the terminal.
+    fn output_to(&mut self, stream: Stream, lines: &Lines) -> anyhow::Result<()> {
+        let mut data = String::new();
+        for line in lines.iter() {
+            data.push_str(&line.render());
+            data.push('\n');
+        }
+        self.capture(None, SystemTime::now(), DrawMode::Normal, data.as_bytes())?;
+        self.inner.output_to(stream, lines)
+    }
+
+    fn terminal_size(&self) -> anyhow::Result<Dimensions> {
+        self.inner.terminal_size()
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.capture.flush()?;
+        self.inner.flush()
+    }
+
+    fn finalize(mut self: Box<Self>) -> anyhow::Result<()> {
+        self.capture.flush()?;
+        self.inner.finalize()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A write captured by a [`CaptureOutput`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedFrame {
+    /// The index of the frame, or `None` for writes which aren't frames, such as lines written
+    /// to another stream.
+    pub index: Option<u64>,
+    /// The time since the capture started.
+    pub time: Duration,
+    /// The size the frame was drawn with.
+    pub dimensions: Dimensions,
+    pub mode: DrawMode,
+    /// What was written to the terminal, escape sequences included.
+    pub data: String,
+}
+
+impl CapturedFrame {
+    fn parse(line: &str) -> anyhow::Result<Self> {
+        let frame: Value = serde_json::from_str(line)?;
+        let size = |key: &str| -> anyhow::Result<usize> {
+            Ok(frame[key]
+                .as_u64()
+                .with_context(|| format!("No `{}`", key))? as usize)
+        };
+        Ok(Self {
+            index: frame["index"].as_u64(),
+            time: Duration::try_from_secs_f64(frame["time"].as_f64().context("No `time`")?)?,
+            dimensions: Dimensions::new(size("width")?, size("height")?),
+            mode: if frame["final"].as_bool().unwrap_or_default() {
+                DrawMode::Final
+            } else {
+                DrawMode::Normal
+            },
+            data: frame["data"].as_str().context("No `data`")?.to_owned(),
+        })
+    }
+}
+
+/// Parses the frames written by a [`CaptureOutput`].
+pub fn read_capture(capture: impl BufRead) -> anyhow::Result<Vec<CapturedFrame>> {
+    capture
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(i, line)| {
+            CapturedFrame::parse(&line?).with_context(|| format!("Invalid frame on line {}", i + 1))
+        })
+        .collect()
+}
+
+/// Writes the frames written by a [`CaptureOutput`] to `terminal` again, waiting between them
+/// as long as they were apart when captured, divided by `speed`: `1.0` replays them at their
+/// original pace, `4.0` four times faster, and `f64::INFINITY` without waiting.
+pub fn replay(capture: impl BufRead, terminal: &mut impl Write, speed: f64) -> anyhow::Result<()> {
+    let start = Instant::now();
+    for frame in read_capture(capture)? {
+        if speed.is_finite() && speed > 0.0 {
+            let due = start + frame.time.div_f64(speed);
+            thread::sleep(due.saturating_duration_since(Instant::now()));
+        }
+        terminal.write_all(frame.data.as_bytes())?;
+        terminal.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::testing::TestOutput;
+    use crate::SuperConsole;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_capture_and_replay() -> anyhow::Result<()> {
+        let capture = SharedBuffer::default();
+        let inner = TestOutput::new(Dimensions::new(40, 10));
+        let output = CaptureOutput::new(Box::new(inner), Box::new(capture.clone()));
+        let mut builder = SuperConsole::builder();
+        builder.forced(true).output(Box::new(output));
+        let mut console = builder.build()?.expect("forced");
+
+        let root = Echo(Lines(vec![vec!["building"].try_into()?]));
+        console.emit(Lines(vec![vec!["compiled"].try_into()?]));
+        console.render(&root)?;
+        console.finalize(&root)?;
+
+        let capture = capture.0.lock().unwrap().clone();
+        let frames = read_capture(capture.as_slice())?;
+        assert!(frames.len() >= 2);
+        assert_eq!(frames[0].index, Some(0));
+        // Frames are drawn one row short of the terminal, which keeps a blank final line.
+        assert_eq!(frames[0].dimensions, Dimensions::new(40, 9));
+        assert_eq!(frames[0].mode, DrawMode::Normal);
+        assert!(frames[0].data.contains("compiled"));
+        assert!(frames[0].data.contains("building"));
+        let last = frames.last().context("No frames")?;
+        assert_eq!(last.mode, DrawMode::Final);
+        assert!(frames.windows(2).all(|w| w[0].time <= w[1].time));
+
+        let mut terminal = Vec::new();
+        replay(capture.as_slice(), &mut terminal, f64::INFINITY)?;
+        let expected: String = frames.iter().map(|frame| frame.data.as_str()).collect();
+        assert_eq!(String::from_utf8(terminal)?, expected);
+
+        assert!(read_capture(&b"{\"index\":0}\n"[..]).is_err());
+
+        Ok(())
+    }
+}
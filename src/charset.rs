@@ -0,0 +1,230 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Sets of characters used to draw borders, trees and rules.
+//! An ASCII set is provided for terminals or fonts lacking box drawing glyphs.
+//!
+//! Built-in components fall back to ASCII when the terminal likely can't display other glyphs,
+//! see [`unicode_enabled`].
+
+use std::env;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+use crate::context;
+
+/// The glyphs used to draw lines.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LineCharset {
+    pub horizontal: &'static str,
+    pub vertical: &'static str,
+    pub top_left: &'static str,
+    pub top_right: &'static str,
+    pub bottom_left: &'static str,
+    pub bottom_right: &'static str,
+    /// Prefix of a tree node which has following siblings.
+    pub tree_branch: &'static str,
+    /// Prefix of the last child of a tree node.
+    pub tree_last: &'static str,
+    /// Continuation of a branch past a node's descendants.
+    pub tree_vertical: &'static str,
+}
+
+impl LineCharset {
+    pub const UNICODE: LineCharset = LineCharset {
+        horizontal: "─",
+        vertical: "│",
+        top_left: "┌",
+        top_right: "┐",
+        bottom_left: "└",
+        bottom_right: "┘",
+        tree_branch: "├─",
+        tree_last: "└─",
+        tree_vertical: "│ ",
+    };
+
+    pub const ROUNDED: LineCharset = LineCharset {
+        top_left: "╭",
+        top_right: "╮",
+        bottom_left: "╰",
+        bottom_right: "╯",
+        tree_last: "╰─",
+        ..Self::UNICODE
+    };
+
+    pub const HEAVY: LineCharset = LineCharset {
+        horizontal: "━",
+        vertical: "┃",
+        top_left: "┏",
+        top_right: "┓",
+        bottom_left: "┗",
+        bottom_right: "┛",
+        tree_branch: "┣━",
+        tree_last: "┗━",
+        tree_vertical: "┃ ",
+    };
+
+    pub const DOUBLE: LineCharset = LineCharset {
+        horizontal: "═",
+        vertical: "║",
+        top_left: "╔",
+        top_right: "╗",
+        bottom_left: "╚",
+        bottom_right: "╝",
+        tree_branch: "╠═",
+        tree_last: "╚═",
+        tree_vertical: "║ ",
+    };
+
+    pub const ASCII: LineCharset = LineCharset {
+        horizontal: "-",
+        vertical: "|",
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        tree_branch: "|-",
+        tree_last: "`-",
+        tree_vertical: "| ",
+    };
+
+    /// Looks up a charset by name: `unicode`, `rounded`, `heavy`, `double` or `ascii`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "unicode" => Some(Self::UNICODE),
+            "rounded" => Some(Self::ROUNDED),
+            "heavy" => Some(Self::HEAVY),
+            "double" => Some(Self::DOUBLE),
+            "ascii" => Some(Self::ASCII),
+            _ => None,
+        }
+    }
+
+    /// Picks [`UNICODE`](Self::UNICODE) unless the locale or `TERM` suggest box drawing glyphs
+    /// won't display, in which case [`ASCII`](Self::ASCII) is used. See [`unicode_enabled`].
+    pub fn detect() -> Self {
+        if unicode_enabled() {
+            Self::UNICODE
+        } else {
+            Self::ASCII
+        }
+    }
+}
+
+impl Default for LineCharset {
+    fn default() -> Self {
+        Self::UNICODE
+    }
+}
+
+const UNKNOWN: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+/// Whether glyphs beyond ASCII are used, or `UNKNOWN` if it was not detected yet.
+static UNICODE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Overrides whether built-in components may use glyphs beyond ASCII, such as box drawing
+/// characters, spinner frames and status symbols, throughout the process. See
+/// [`SuperConsole::set_unicode`](crate::SuperConsole::set_unicode) to override it for one console.
+pub fn set_unicode(enabled: bool) {
+    UNICODE.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+}
+
+/// Whether built-in components may use glyphs beyond ASCII. Unless overridden by the console
+/// drawing them, see the [`context`](crate::context) module, or with [`set_unicode`], this is
+/// guessed from the locale, `TERM` and the OS.
+pub fn unicode_enabled() -> bool {
+    if let Some(enabled) = context::current(|context| context.unicode) {
+        return enabled;
+    }
+    match UNICODE.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let enabled = unicode_likely_supported();
+            set_unicode(enabled);
+            enabled
+        }
+        state => state == ENABLED,
+    }
+}
+
+/// A warning sign, for badges drawn by built-in components.
+pub(crate) fn warning_sign() -> &'static str {
+    if unicode_enabled() {
+        "⚠"
+    } else {
+        "!"
+    }
+}
+
+/// An ellipsis, marking content left out by built-in components.
+pub(crate) fn ellipsis() -> &'static str {
+    if unicode_enabled() {
+        "…"
+    } else {
+        "..."
+    }
+}
+
+/// Guesses whether the terminal can display non-ASCII glyphs.
+fn unicode_likely_supported() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    if matches!(term.as_str(), "dumb" | "linux" | "vt100" | "vt220") {
+        return false;
+    }
+    if cfg!(windows) {
+        return true;
+    }
+    // The first of these which is set decides the character encoding.
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|var| env::var(var).ok().filter(|value| !value.is_empty()));
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_unicode;
+    use crate::theme::SpinnerStyle;
+    use crate::theme::Status;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(LineCharset::from_name("ASCII"), Some(LineCharset::ASCII));
+        assert_eq!(
+            LineCharset::from_name("rounded"),
+            Some(LineCharset::ROUNDED)
+        );
+        assert_eq!(LineCharset::from_name("double"), Some(LineCharset::DOUBLE));
+        assert_eq!(LineCharset::from_name("dotted"), None);
+    }
+
+    #[test]
+    fn test_ascii_fallback() {
+        with_unicode(false, || {
+            assert_eq!(LineCharset::detect(), LineCharset::ASCII);
+            assert_eq!(SpinnerStyle::detect(), SpinnerStyle::Ascii);
+            assert_eq!(Status::Success.symbol(), "+");
+            assert_eq!(warning_sign(), "!");
+        });
+        with_unicode(true, || {
+            assert_eq!(LineCharset::detect(), LineCharset::UNICODE);
+            assert_eq!(SpinnerStyle::detect(), SpinnerStyle::Braille);
+            assert_eq!(Status::Success.symbol(), "✓");
+            assert_eq!(warning_sign(), "⚠");
+        });
+    }
+}
@@ -9,19 +9,64 @@
 
 //! Getting started truly from scratch is excessive for every user of superconsole.
 //! A small set of starter components are provided, exposed below.
+//!
+//! Components own the state they draw: there is no separate state passed to each render, so a
+//! missing value is a compile error rather than a failed lookup while drawing. State which
+//! changes while the console runs is shared with the rest of the application through the
+//! component itself, e.g. by rendering an `Arc<Mutex<C>>` root and updating it between renders,
+//! or through atomics as [`Counter`] does.
 
 use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
 
 pub use alignment::Aligned;
 pub use blank::Blank;
 pub use bordering::Bordered;
 pub use bounding::Bounded;
+pub use budgeted::Budgeted;
 pub(crate) use canvas::Canvas;
+pub(crate) use canvas::CanvasLayout;
+#[cfg(feature = "clock")]
+pub use clock::Clock;
+#[cfg(feature = "clock")]
+pub use clock::ClockZone;
+pub use counter::Counter;
+pub use debug_stats::DebugStats;
+pub use embedded::Embedded;
+pub use error_boundary::ComponentError;
+pub use error_boundary::ErrorBoundary;
+pub use error_boundary::ErrorPolicy;
+pub use framed::Framed;
+pub use gauge::Gauge;
+pub use marquee::Marquee;
 pub use padding::Padded;
+pub use prompt::Answer;
+pub use prompt::Prompt;
+pub use prompt::PromptAnswer;
+pub use region::Region;
+pub use scrollable::Scrollable;
+pub use sparkline::Samples;
+pub use sparkline::Sparkline;
+pub use spinner::Spinner;
 pub use splitting::Split;
+pub use stalled::Stalled;
+pub use styled::Styled;
+pub use sub_canvas::SubCanvas;
+pub use table::Table;
+pub use task_list::TaskId;
+pub use task_list::TaskList;
+pub use task_list::TaskOrder;
+pub use timer::Timer;
+pub use toggle::Toggle;
+pub use tree::Tree;
+pub use tree::TreeNode;
+pub use wrapped::Wrapped;
 
 pub use crate::components::draw_horizontal::DrawHorizontal;
 pub use crate::components::draw_vertical::DrawVertical;
+use crate::debug;
 use crate::Dimensions;
 use crate::Lines;
 
@@ -29,12 +74,36 @@ pub mod alignment;
 mod blank;
 pub mod bordering;
 mod bounding;
+mod budgeted;
 mod canvas;
+#[cfg(feature = "clock")]
+mod clock;
+mod counter;
+mod debug_stats;
 mod draw_horizontal;
 mod draw_vertical;
 pub(crate) mod echo;
+mod embedded;
+pub(crate) mod error_boundary;
+pub mod framed;
+mod gauge;
+mod marquee;
 pub mod padding;
+mod prompt;
+mod region;
+mod scrollable;
+mod sparkline;
+mod spinner;
 pub mod splitting;
+mod stalled;
+mod styled;
+mod sub_canvas;
+pub mod table;
+pub mod task_list;
+mod timer;
+mod toggle;
+mod tree;
+mod wrapped;
 
 /// Used to mark whether a draw is final.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -49,24 +118,107 @@ pub trait Component {
     /// This method is to be implemented for components to provide the `draw` method.
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines>;
 
+    /// Called when the terminal was resized, before the next draw, e.g. to invalidate cached
+    /// layouts. Components with children must pass this on to them.
+    fn on_resize(&self, _dimensions: Dimensions) {}
+
+    /// Called before the component is first drawn by a console, e.g. to start a timer or open a
+    /// file. Components with children must pass this on to them.
+    fn on_attach(&self) {}
+
+    /// Called when the console is [finalized](crate::SuperConsole::finalize), before the final
+    /// draw and while the console still holds the terminal, e.g. to stop timers so that the final
+    /// draw shows where they stopped, or to flush files. Components with children must pass this
+    /// on to them.
+    fn on_finalize(&self) {}
+
+    /// Called when the console lets go of the component without finalizing, with
+    /// [`detach`](crate::SuperConsole::detach), before the terminal is restored. Components with
+    /// children must pass this on to them.
+    fn on_detach(&self) {}
+
+    /// The dimensions the component would like to be drawn with, at most `max`, so that parents
+    /// can lay out their children by their content, e.g. [`SplitKind::Content`]. By default,
+    /// components take all the space they are given.
+    ///
+    /// [`SplitKind::Content`]: crate::components::splitting::SplitKind::Content
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        max
+    }
+
     /// Interprets the current caller state to create its drawing.
     /// Dimensions refers to the maximum (width, height) this component may use.
     /// The mode refers to if this is the final time the component will be drawn.
     /// If a child component is too large to fit in the dimensions, it is truncated.
     fn draw(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
-        let mut res = self.draw_unchecked(dimensions, mode)?;
-        res.shrink_lines_to_dimensions(dimensions);
+        let node = debug::enter_component(std::any::type_name::<Self>(), dimensions);
+        let res = self
+            .draw_unchecked(dimensions, mode)
+            .or_else(|e| {
+                error_boundary::isolate_error(std::any::type_name::<Self>(), e, dimensions)
+            })
+            .map(|mut res| {
+                res.shrink_lines_to_dimensions(dimensions);
+                res
+            });
+        debug::exit_component(node, res.as_ref().map_or(0, Lines::len));
+
+        let mut res = res?;
+        if debug::layout_overlay_enabled() {
+            let name = debug::short_type_name(std::any::type_name::<Self>());
+            debug::outline(&mut res, name, dimensions);
+        }
         Ok(res)
     }
 }
 
 impl Component for Box<dyn Component> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        (**self).on_resize(dimensions)
+    }
+
+    fn on_attach(&self) {
+        (**self).on_attach()
+    }
+
+    fn on_finalize(&self) {
+        (**self).on_finalize()
+    }
+
+    fn on_detach(&self) {
+        (**self).on_detach()
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        (**self).desired_size(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         (**self).draw_unchecked(dimensions, mode)
     }
 }
 
 impl Component for Box<dyn Component + Send> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        (**self).on_resize(dimensions)
+    }
+
+    fn on_attach(&self) {
+        (**self).on_attach()
+    }
+
+    fn on_finalize(&self) {
+        (**self).on_finalize()
+    }
+
+    fn on_detach(&self) {
+        (**self).on_detach()
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        (**self).desired_size(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         (**self).draw_unchecked(dimensions, mode)
     }
@@ -74,25 +226,215 @@ impl Component for Box<dyn Component + Send> {
 
 // TODO(nga): this is not really needed.
 impl<C: Component> Component for Box<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        (**self).on_resize(dimensions)
+    }
+
+    fn on_attach(&self) {
+        (**self).on_attach()
+    }
+
+    fn on_finalize(&self) {
+        (**self).on_finalize()
+    }
+
+    fn on_detach(&self) {
+        (**self).on_detach()
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        (**self).desired_size(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         (**self).draw_unchecked(dimensions, mode)
     }
 }
 
-impl<'a> Component for &'a dyn Component {
+impl Component for &dyn Component {
+    fn on_resize(&self, dimensions: Dimensions) {
+        (**self).on_resize(dimensions)
+    }
+
+    fn on_attach(&self) {
+        (**self).on_attach()
+    }
+
+    fn on_finalize(&self) {
+        (**self).on_finalize()
+    }
+
+    fn on_detach(&self) {
+        (**self).on_detach()
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        (**self).desired_size(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        (**self).draw_unchecked(dimensions, mode)
+    }
+}
+
+impl Component for &(dyn Component + Send) {
+    fn on_resize(&self, dimensions: Dimensions) {
+        (**self).on_resize(dimensions)
+    }
+
+    fn on_attach(&self) {
+        (**self).on_attach()
+    }
+
+    fn on_finalize(&self) {
+        (**self).on_finalize()
+    }
+
+    fn on_detach(&self) {
+        (**self).on_detach()
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        (**self).desired_size(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         (**self).draw_unchecked(dimensions, mode)
     }
 }
 
-impl<'a> Component for &'a (dyn Component + Send) {
+impl<C: Component> Component for &C {
+    fn on_resize(&self, dimensions: Dimensions) {
+        (**self).on_resize(dimensions)
+    }
+
+    fn on_attach(&self) {
+        (**self).on_attach()
+    }
+
+    fn on_finalize(&self) {
+        (**self).on_finalize()
+    }
+
+    fn on_detach(&self) {
+        (**self).on_detach()
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        (**self).desired_size(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         (**self).draw_unchecked(dimensions, mode)
     }
 }
 
-impl<'a, C: Component> Component for &'a C {
+/// Shared components allow a retained-mode style of rendering: the application keeps the
+/// component tree alive between frames, mutates it directly, and passes it to
+/// [`SuperConsole::render`](crate::SuperConsole::render) whenever a new frame is wanted.
+impl<C: Component + ?Sized> Component for Arc<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        (**self).on_resize(dimensions)
+    }
+
+    fn on_attach(&self) {
+        (**self).on_attach()
+    }
+
+    fn on_finalize(&self) {
+        (**self).on_finalize()
+    }
+
+    fn on_detach(&self) {
+        (**self).on_detach()
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        (**self).desired_size(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         (**self).draw_unchecked(dimensions, mode)
     }
 }
+
+/// Locks the component for the duration of the draw.
+/// A poisoned lock is reported as a draw error rather than a panic.
+impl<C: Component + ?Sized> Component for Mutex<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        if let Ok(component) = self.lock() {
+            component.on_resize(dimensions);
+        }
+    }
+
+    fn on_attach(&self) {
+        if let Ok(component) = self.lock() {
+            component.on_attach();
+        }
+    }
+
+    fn on_finalize(&self) {
+        if let Ok(component) = self.lock() {
+            component.on_finalize();
+        }
+    }
+
+    fn on_detach(&self) {
+        if let Ok(component) = self.lock() {
+            component.on_detach();
+        }
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        match self.lock() {
+            Ok(component) => component.desired_size(max),
+            Err(_) => max,
+        }
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        self.lock()
+            .map_err(|_| anyhow::anyhow!("Component mutex was poisoned"))?
+            .draw_unchecked(dimensions, mode)
+    }
+}
+
+/// Takes a read lock on the component for the duration of the draw.
+impl<C: Component + ?Sized> Component for RwLock<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        if let Ok(component) = self.read() {
+            component.on_resize(dimensions);
+        }
+    }
+
+    fn on_attach(&self) {
+        if let Ok(component) = self.read() {
+            component.on_attach();
+        }
+    }
+
+    fn on_finalize(&self) {
+        if let Ok(component) = self.read() {
+            component.on_finalize();
+        }
+    }
+
+    fn on_detach(&self) {
+        if let Ok(component) = self.read() {
+            component.on_detach();
+        }
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        match self.read() {
+            Ok(component) => component.desired_size(max),
+            Err(_) => max,
+        }
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        self.read()
+            .map_err(|_| anyhow::anyhow!("Component lock was poisoned"))?
+            .draw_unchecked(dimensions, mode)
+    }
+}
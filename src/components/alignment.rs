@@ -13,6 +13,7 @@
 //! Additionally, horizontally left aligned text may be optionally justified.
 
 use crate::components::Blank;
+use crate::content::Alignment;
 use crate::Component;
 use crate::Dimensions;
 use crate::DrawMode;
@@ -77,6 +78,27 @@ impl Default for Aligned {
 }
 
 impl<C: Component> Component for Aligned<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    /// The child's desired size: aligning it within more space adds nothing worth asking for.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         let Dimensions { width, height } = dimensions;
         let mut output = self.child.draw(dimensions, mode)?;
@@ -103,17 +125,12 @@ impl<C: Component> Component for Aligned<C> {
             }
             HorizontalAlignmentKind::Center => {
                 for line in output.iter_mut() {
-                    let output_len = line.len();
-                    let padding_needed = width.saturating_sub(output_len);
-                    let left_pad = padding_needed / 2;
-                    line.pad_left(left_pad);
-                    // handles any rounding issues
-                    line.pad_right(padding_needed - left_pad);
+                    line.pad_to(width, Alignment::Center);
                 }
             }
             HorizontalAlignmentKind::Right => {
                 for line in output.iter_mut() {
-                    line.pad_left(width.saturating_sub(line.len()));
+                    line.pad_to(width, Alignment::Right);
                 }
             }
         }
@@ -123,8 +140,6 @@ impl<C: Component> Component for Aligned<C> {
 
 #[cfg(test)]
 mod tests {
-    use derive_more::AsRef;
-
     use crate::components::alignment::HorizontalAlignmentKind;
     use crate::components::alignment::VerticalAlignmentKind;
     use crate::components::echo::Echo;
@@ -135,9 +150,6 @@ mod tests {
     use crate::Line;
     use crate::Lines;
 
-    #[derive(AsRef, Debug)]
-    struct Msg(Lines);
-
     #[test]
     fn test_align_left_unjustified() {
         let original = Lines(vec![
@@ -19,6 +19,10 @@ use crate::Lines;
 pub struct Blank;
 
 impl Component for Blank {
+    fn desired_size(&self, _max: Dimensions) -> Dimensions {
+        Dimensions::default()
+    }
+
     /// Returns the empty vector
     fn draw_unchecked(&self, _dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
         Ok(Lines::new())
@@ -27,17 +31,12 @@ impl Component for Blank {
 
 #[cfg(test)]
 mod tests {
-    use derive_more::AsRef;
-
     use crate::components::echo::Echo;
     use crate::components::DrawMode;
     use crate::Component;
     use crate::Dimensions;
     use crate::Lines;
 
-    #[derive(AsRef, Debug)]
-    struct EchoMsg(Lines);
-
     #[test]
     fn test_echo_empty() {
         let output = Echo(Lines::new())
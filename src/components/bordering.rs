@@ -9,11 +9,15 @@
 
 use std::borrow::Cow;
 
+use crate::charset::LineCharset;
 use crate::components::alignment::HorizontalAlignmentKind;
 use crate::components::alignment::VerticalAlignmentKind;
 use crate::components::Aligned;
+use crate::theme::style;
+use crate::theme::StyleRole;
 use crate::Component;
 use crate::Dimensions;
+use crate::Direction;
 use crate::DrawMode;
 use crate::Line;
 use crate::Lines;
@@ -42,6 +46,16 @@ use crate::Span;
 pub struct Bordered<C: Component = Box<dyn Component>> {
     child: Aligned<C>,
     pub border: BorderedSpec,
+    /// Drawn where the top and bottom borders meet the side borders, if any.
+    corners: Option<Corners>,
+}
+
+#[derive(Debug)]
+struct Corners {
+    top_left: Span,
+    top_right: Span,
+    bottom_left: Span,
+    bottom_right: Span,
 }
 
 /// The `BorderedSpec` allows the callee to specify the borders (or lack thereof) of each side.
@@ -70,6 +84,20 @@ impl Default for BorderedSpec {
     }
 }
 
+impl BorderedSpec {
+    /// Borders on all sides drawn with the given charset.
+    pub fn from_charset(charset: &LineCharset) -> Self {
+        let vertical = Some(Span::new_unstyled_lossy(charset.vertical));
+        let horizontal = Some(Span::new_unstyled_lossy(charset.horizontal));
+        Self {
+            left: vertical.clone(),
+            right: vertical,
+            top: horizontal.clone(),
+            bottom: horizontal,
+        }
+    }
+}
+
 impl<C: Component> Bordered<C> {
     pub fn new(child: C, border: BorderedSpec) -> Self {
         Self {
@@ -79,6 +107,38 @@ impl<C: Component> Bordered<C> {
                 vertical: VerticalAlignmentKind::Top,
             },
             border,
+            corners: None,
+        }
+    }
+
+    /// Borders on all sides drawn with the given charset, including its corners.
+    pub fn with_charset(child: C, charset: &LineCharset) -> Self {
+        Self {
+            corners: Some(Corners {
+                top_left: Span::new_unstyled_lossy(charset.top_left),
+                top_right: Span::new_unstyled_lossy(charset.top_right),
+                bottom_left: Span::new_unstyled_lossy(charset.bottom_left),
+                bottom_right: Span::new_unstyled_lossy(charset.bottom_right),
+            }),
+            ..Self::new(child, BorderedSpec::from_charset(charset))
+        }
+    }
+
+    /// Builds the top or bottom border. Corners are only drawn if there are side borders to meet.
+    fn horizontal_border(&self, border: &Span, width: usize, top: bool) -> Vec<Line> {
+        match (&self.corners, &self.border.left, &self.border.right) {
+            (Some(corners), Some(_), Some(_)) => {
+                let (left, right) = if top {
+                    (&corners.top_left, &corners.top_right)
+                } else {
+                    (&corners.bottom_left, &corners.bottom_right)
+                };
+                let inner = width.saturating_sub(left.len() + right.len());
+                let mut fill = border.clone();
+                fill.content = Cow::Owned(border.content.repeat(inner / border.len().max(1)));
+                vec![Line::from_iter([left.clone(), fill, right.clone()])]
+            }
+            _ => construct_vertical_padding(border.clone(), width),
         }
     }
 }
@@ -98,6 +158,34 @@ fn construct_vertical_padding(padding: Span, width: usize) -> Vec<Line> {
 }
 
 impl<C: Component> Component for Bordered<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    /// The child's desired size, plus the walls.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let opt_len = |opt_word: &Option<Span>| opt_word.as_ref().map_or(0, Span::len);
+        let horizontal = opt_len(&self.border.left) + opt_len(&self.border.right);
+        let vertical = opt_len(&self.border.top) + opt_len(&self.border.bottom);
+        let child = self.child.desired_size(
+            max.saturating_sub(horizontal, Direction::Horizontal)
+                .saturating_sub(vertical, Direction::Vertical),
+        );
+        Dimensions::new(child.width + horizontal, child.height + vertical).intersect(max)
+    }
+
     fn draw_unchecked(
         &self,
 
@@ -117,21 +205,34 @@ impl<C: Component> Component for Bordered<C> {
         // The [`Aligned`] box ensures that the child is justified and bounded.
         let mut output = self.child.draw(new_dims, mode)?;
 
+        // Borders take the theme's style for anything they don't style themselves.
+        let base = style(StyleRole::Border);
+        let styled = |span: &Span| {
+            let mut span = span.clone();
+            span.apply_style_base(base);
+            span
+        };
+        let styled_lines = |mut lines: Vec<Line>| {
+            lines
+                .iter_mut()
+                .for_each(|line| line.apply_style_base(base));
+            lines
+        };
         for line in output.iter_mut() {
             if let Some(left) = &self.border.left {
-                line.push_front(left.clone());
+                line.push_front(styled(left));
             }
             if let Some(right) = &self.border.right {
-                line.push(right.clone());
+                line.push(styled(right));
             }
         }
         if let Some(top) = &self.border.top {
-            let lines = construct_vertical_padding(top.clone(), output.max_line_length());
-            output.0.splice(0..0, lines.into_iter());
+            let lines = self.horizontal_border(top, output.max_line_length(), true);
+            output.0.splice(0..0, styled_lines(lines));
         }
         if let Some(bottom) = &self.border.bottom {
-            let lines = construct_vertical_padding(bottom.clone(), output.max_line_length());
-            output.0.extend(lines.into_iter());
+            let lines = self.horizontal_border(bottom, output.max_line_length(), false);
+            output.0.extend(styled_lines(lines));
         }
 
         Ok(output)
@@ -140,14 +241,9 @@ impl<C: Component> Component for Bordered<C> {
 
 #[cfg(test)]
 mod tests {
-    use derive_more::AsRef;
-
     use super::*;
     use crate::components::echo::Echo;
 
-    #[derive(AsRef, Debug)]
-    struct Msg(Lines);
-
     #[test]
     fn test_basic() -> anyhow::Result<()> {
         let msg = Lines(vec![
@@ -212,6 +308,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_charset() -> anyhow::Result<()> {
+        let msg = Lines(vec![vec!["hi"].try_into()?, vec!["there"].try_into()?]);
+
+        let component = Bordered::with_charset(Echo(msg), &LineCharset::ROUNDED);
+
+        let output = component.draw(Dimensions::new(10, 10), DrawMode::Normal)?;
+        let expected = Lines(vec![
+            vec!["╭─────╮"].try_into()?,
+            vec!["│hi   │"].try_into()?,
+            vec!["│there│"].try_into()?,
+            vec!["╰─────╯"].try_into()?,
+        ]);
+
+        assert_eq!(output, expected);
+        Ok(())
+    }
+
     #[test]
     fn test_multi_width_unicode() -> anyhow::Result<()> {
         let multi_width = "🦶";
@@ -32,6 +32,26 @@ impl<C: Component> Bounded<C> {
 }
 
 impl<C: Component> Component for Bounded<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max.intersect(self.max_size))
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         let output = self.child.draw(dimensions.intersect(self.max_size), mode)?;
         Ok(output)
@@ -40,16 +60,11 @@ impl<C: Component> Component for Bounded<C> {
 
 #[cfg(test)]
 mod tests {
-    use derive_more::AsRef;
-
     use super::*;
     use crate::components::echo::Echo;
     use crate::Line;
     use crate::Span;
 
-    #[derive(AsRef, Debug)]
-    struct Msg(Lines);
-
     #[test]
     fn test_no_bounding() -> anyhow::Result<()> {
         let msg = Lines(vec![Line::from_iter([Span::new_unstyled("hello world")?])]);
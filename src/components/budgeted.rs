@@ -0,0 +1,210 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::budget::BudgetOutcome;
+use crate::budget::Priority;
+use crate::budget::RenderBudget;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+#[derive(Debug, Default)]
+struct Cache {
+    /// The last live output, and the dimensions it was drawn with.
+    output: Option<(Dimensions, Lines)>,
+    /// How long the last live draw took.
+    cost: Duration,
+}
+
+/// The `Budgeted` component measures how long its child takes to draw against a shared
+/// [`RenderBudget`]. When drawing the child would exceed what is left of the budget, it reuses
+/// the child's previous output, or draws nothing if the child is [`Priority::Decorative`].
+#[derive(Debug)]
+pub struct Budgeted<C: Component = Box<dyn Component>> {
+    child: C,
+    name: String,
+    priority: Priority,
+    budget: RenderBudget,
+    cache: Mutex<Cache>,
+}
+
+impl<C: Component> Budgeted<C> {
+    /// The name identifies the component in [`BudgetStats`](crate::budget::BudgetStats).
+    pub fn new(
+        child: C,
+        name: impl Into<String>,
+        priority: Priority,
+        budget: RenderBudget,
+    ) -> Self {
+        Self {
+            child,
+            name: name.into(),
+            priority,
+            budget,
+            cache: Mutex::new(Cache::default()),
+        }
+    }
+}
+
+impl<C: Component> Component for Budgeted<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut cache = self
+            .cache
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Budgeted cache mutex was poisoned"))?;
+
+        // Final frames and critical components are always live. Otherwise, predict the cost of
+        // drawing from the last live draw.
+        let live = mode == DrawMode::Final
+            || self.priority == Priority::Critical
+            || cache.cost <= self.budget.remaining();
+        if !live {
+            match (self.priority, &cache.output) {
+                (Priority::Decorative, _) => {
+                    self.budget
+                        .record(&self.name, Duration::ZERO, BudgetOutcome::Skipped);
+                    return Ok(Lines::new());
+                }
+                (_, Some((cached_dimensions, output))) if *cached_dimensions == dimensions => {
+                    self.budget
+                        .record(&self.name, Duration::ZERO, BudgetOutcome::Cached);
+                    return Ok(output.clone());
+                }
+                // Nothing usable is cached, so we have to draw anyway.
+                _ => {}
+            }
+        }
+
+        let start = Instant::now();
+        let output = self.child.draw(dimensions, mode)?;
+        let cost = start.elapsed();
+        self.budget.record(&self.name, cost, BudgetOutcome::Drawn);
+        *cache = Cache {
+            output: Some((dimensions, output.clone())),
+            cost,
+        };
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::budget::BudgetStats;
+
+    /// Takes a fixed time to draw, and counts its draws.
+    struct Slow {
+        delay: Duration,
+        draws: Cell<usize>,
+    }
+
+    impl Component for Slow {
+        fn draw_unchecked(
+            &self,
+            _dimensions: Dimensions,
+            _mode: DrawMode,
+        ) -> anyhow::Result<Lines> {
+            std::thread::sleep(self.delay);
+            self.draws.set(self.draws.get() + 1);
+            Ok(Lines(vec![vec![
+                format!("draw {}", self.draws.get()).as_str()
+            ]
+            .try_into()?]))
+        }
+    }
+
+    fn slow(millis: u64) -> Slow {
+        Slow {
+            delay: Duration::from_millis(millis),
+            draws: Cell::new(0),
+        }
+    }
+
+    fn outcomes(stats: &BudgetStats) -> Vec<(&str, BudgetOutcome)> {
+        let mut outcomes: Vec<_> = stats
+            .entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.outcome))
+            .collect();
+        outcomes.sort_by_key(|(name, _)| *name);
+        outcomes
+    }
+
+    #[test]
+    fn test_budget_degrades() -> anyhow::Result<()> {
+        let budget = RenderBudget::new(Duration::from_millis(5));
+        let critical = Budgeted::new(slow(10), "critical", Priority::Critical, budget.clone());
+        let normal = Budgeted::new(slow(10), "normal", Priority::Normal, budget.clone());
+        let decorative = Budgeted::new(slow(1), "decorative", Priority::Decorative, budget.clone());
+        let dimensions = Dimensions::new(20, 20);
+
+        let draw_all = || -> anyhow::Result<Vec<Lines>> {
+            budget.start_frame();
+            let res = vec![
+                critical.draw(dimensions, DrawMode::Normal)?,
+                normal.draw(dimensions, DrawMode::Normal)?,
+                decorative.draw(dimensions, DrawMode::Normal)?,
+            ];
+            budget.finish_frame();
+            Ok(res)
+        };
+
+        // With nothing cached, everything is drawn.
+        draw_all()?;
+        assert!(budget.last_frame().degraded().next().is_none());
+
+        // The critical component exhausts the budget, so the others degrade.
+        let output = draw_all()?;
+        assert_eq!(output[0].0[0].to_unstyled(), "draw 2");
+        assert_eq!(output[1].0[0].to_unstyled(), "draw 1");
+        assert!(output[2].is_empty());
+        assert_eq!(
+            outcomes(&budget.last_frame()),
+            vec![
+                ("critical", BudgetOutcome::Drawn),
+                ("decorative", BudgetOutcome::Skipped),
+                ("normal", BudgetOutcome::Cached),
+            ]
+        );
+        assert_eq!(budget.last_frame().entries[0].name, "critical");
+
+        // Final frames are always live.
+        let output = normal.draw(dimensions, DrawMode::Final)?;
+        assert_eq!(output.0[0].to_unstyled(), "draw 2");
+
+        Ok(())
+    }
+}
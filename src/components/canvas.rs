@@ -13,38 +13,103 @@
 
 use std::cell::Cell;
 
-use crossterm::cursor::MoveToColumn;
-use crossterm::cursor::MoveUp;
-use crossterm::terminal::Clear;
-use crossterm::terminal::ClearType;
-use crossterm::QueueableCommand;
-
+use crate::ansi::Clear;
+use crate::ansi::ClearType;
+use crate::ansi::MoveToColumn;
+use crate::ansi::MoveUp;
+use crate::ansi::QueueableCommand;
+use crate::charset::ellipsis;
 use crate::components::Dimensions;
 use crate::components::DrawMode;
+use crate::content::Alignment;
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
 use crate::Component;
+use crate::Line;
 use crate::Lines;
 
+/// How the canvas is sized and placed within the terminal.
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct CanvasLayout {
+    /// The number of rows the canvas is padded to, if fixed.
+    pub(crate) height: Option<usize>,
+    /// The number of rows the canvas is cut to, if bounded below the terminal height.
+    pub(crate) max_height: Option<usize>,
+    /// The number of columns the canvas is drawn in, if bounded below the terminal width.
+    pub(crate) max_width: Option<usize>,
+    /// Where a canvas narrower than the terminal sits.
+    pub(crate) anchor: Alignment,
+}
+
 /// The root components which manages all other components.
 #[derive(Debug, Default)]
 pub(crate) struct Canvas {
     // used to overwrite previous canvas buffer
     last_lines: Cell<u16>,
+    // the row of the canvas the cursor was left on, if not below it
+    cursor_row: Cell<Option<u16>>,
 }
 
 impl Canvas {
     /// A passthrough method that resizes the Canvas to reflect the size of the root.
     /// Allows dynamic resizing.
     /// Cuts off any lines that are too for long a single row
+    /// With a fixed `height`, shorter output is padded with blank lines above it, so that the
+    /// components stay at the bottom and the canvas doesn't grow and shrink between frames.
+    /// Output taller than `max_height` is cut, with its last row summarizing the rows left out.
+    /// With a `max_width`, the components are drawn within it and the canvas is moved to its
+    /// `anchor`, leaving the rest of the rows blank.
     pub(crate) fn draw(
         &self,
         root: &dyn Component,
-
         dimensions: Dimensions,
         mode: DrawMode,
+        layout: CanvasLayout,
     ) -> anyhow::Result<Lines> {
+        let CanvasLayout {
+            height,
+            max_height,
+            max_width,
+            anchor,
+        } = layout;
+        let max_height = max_height.map_or(dimensions.height, |max| max.min(dimensions.height));
+        let terminal_width = dimensions.width;
+        let dimensions = Dimensions {
+            width: max_width.map_or(terminal_width, |max| max.min(terminal_width)),
+            ..dimensions
+        };
+        let offset = match anchor {
+            Alignment::Left => 0,
+            Alignment::Center => (terminal_width - dimensions.width) / 2,
+            Alignment::Right => terminal_width - dimensions.width,
+        };
+        let canvas_width = dimensions.width;
+        // Roots taking all the space they are given would keep the canvas as tall as the terminal.
+        let dimensions = root.desired_size(dimensions).intersect(dimensions);
         let mut output = root.draw(dimensions, mode)?;
         // We don't trust the child to not truncate the result.
         output.shrink_lines_to_dimensions(dimensions);
+        if output.len() > max_height {
+            let kept = max_height.saturating_sub(1);
+            let overflow = format!("{} {}", ellipsis(), strings().and_more(output.len() - kept));
+            output.truncate_lines_bottom(kept);
+            if max_height > 0 {
+                let mut line = Line::from_iter([styled_span(StyleRole::Dim, &overflow)]);
+                line.truncate_line(canvas_width);
+                output.push(line);
+            }
+        }
+        if let Some(height) = height {
+            let height = height.min(max_height);
+            output.pad_lines_top(height.saturating_sub(output.len()));
+        }
+        if offset > 0 {
+            for line in output.iter_mut() {
+                line.pad_to(canvas_width, Alignment::Left);
+                line.pad_left(offset);
+            }
+        }
         self.last_lines.set(output.len().try_into()?);
         Ok(output)
     }
@@ -63,12 +128,39 @@ impl Canvas {
     /// This is used to clear the scratch area so that any possibly emitted messages can write over it.
     pub(crate) fn move_up(&self, writer: &mut Vec<u8>) -> anyhow::Result<()> {
         let len = self.last_lines.take();
-        writer.queue(MoveUp(len))?;
+        // Terminals treat moving up by zero rows as moving up by one.
+        let up = self.cursor_row.take().unwrap_or(len);
+        if up > 0 {
+            writer.queue(MoveUp(up))?;
+        }
         writer.queue(MoveToColumn(0))?;
 
         Ok(())
     }
 
+    /// The rows from the cursor up to the top of the canvas.
+    #[cfg(all(feature = "signals", unix))]
+    pub(crate) fn rows_above_cursor(&self) -> u16 {
+        self.cursor_row.get().unwrap_or(self.last_lines.get())
+    }
+
+    /// Moves the cursor from below the canvas to `column` of `row`, where it stays until the canvas
+    /// is next moved up.
+    pub(crate) fn place_cursor(
+        &self,
+        writer: &mut Vec<u8>,
+        (column, row): (usize, usize),
+    ) -> anyhow::Result<()> {
+        let row: u16 = row.try_into()?;
+        let up = self.last_lines.get().saturating_sub(row);
+        if up > 0 {
+            writer.queue(MoveUp(up))?;
+        }
+        writer.queue(MoveToColumn(column.try_into()?))?;
+        self.cursor_row.set(Some(row));
+        Ok(())
+    }
+
     /// Clears the canvas.
     pub fn clear(&self, writer: &mut Vec<u8>) -> anyhow::Result<()> {
         self.move_up(writer)?;
@@ -0,0 +1,120 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fmt::Write as _;
+
+use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::Local;
+use chrono::Utc;
+
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// The time zone a [`Clock`] shows the time in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum ClockZone {
+    /// The system's local time zone.
+    #[default]
+    Local,
+    Utc,
+    /// A fixed offset from UTC.
+    Fixed(FixedOffset),
+}
+
+/// The `Clock` component draws the current wall-clock time, e.g. for the status bar of a
+/// long-running daemon. It is updated every time it is drawn.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    format: String,
+    zone: ClockZone,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock {
+    /// A clock showing the local time as `%H:%M:%S`.
+    pub fn new() -> Self {
+        Self {
+            format: "%H:%M:%S".to_owned(),
+            zone: ClockZone::Local,
+        }
+    }
+
+    /// Sets the format, using the syntax of [`chrono::format::strftime`].
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = format.into();
+        self
+    }
+
+    pub fn zone(mut self, zone: ClockZone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    fn format_at(&self, now: DateTime<Utc>) -> anyhow::Result<String> {
+        let mut res = String::new();
+        let written = match self.zone {
+            ClockZone::Local => write!(res, "{}", now.with_timezone(&Local).format(&self.format)),
+            ClockZone::Utc => write!(res, "{}", now.format(&self.format)),
+            ClockZone::Fixed(offset) => {
+                write!(res, "{}", now.with_timezone(&offset).format(&self.format))
+            }
+        };
+        written.map_err(|_| anyhow::anyhow!("Invalid clock format `{}`", self.format))?;
+        Ok(res)
+    }
+}
+
+impl Component for Clock {
+    /// As wide as the current time in the clock's format.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let width = self
+            .format_at(Utc::now())
+            .map_or(0, |now| Span::sanitized(now).len());
+        Dimensions::new(width, 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, _dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        let now = self.format_at(Utc::now())?;
+        Ok(Lines(vec![Line::from_iter([Span::sanitized(now)])]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_clock() -> anyhow::Result<()> {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 34, 56).unwrap();
+
+        let clock = Clock::new().zone(ClockZone::Utc);
+        assert_eq!(clock.format_at(now)?, "12:34:56");
+
+        let clock = Clock::new()
+            .format("%Y-%m-%d %H:%M %z")
+            .zone(ClockZone::Fixed(FixedOffset::east_opt(-5 * 3600).unwrap()));
+        assert_eq!(clock.format_at(now)?, "2024-03-01 07:34 -0500");
+
+        assert!(Clock::new().format("%Q").format_at(now).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::estimator::Ewma;
+use crate::human;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// How long it takes by default for the displayed value to close most of the gap to the true one.
+const DEFAULT_SMOOTHING: Duration = Duration::from_millis(250);
+
+/// The `Counter` component displays a counter which may be updated thousands of times per second,
+/// e.g. from other threads. Rather than jumping to the latest value at every render, the displayed
+/// value moves smoothly towards it, and can be rounded down to a multiple of a quantum, so that it
+/// stays readable. The final draw always shows the exact value.
+#[derive(Debug)]
+pub struct Counter {
+    value: Arc<AtomicU64>,
+    label: Option<String>,
+    quantum: u64,
+    /// Smooths the displayed value.
+    displayed: Mutex<Ewma>,
+}
+
+impl Counter {
+    /// A counter showing `value`, which the caller keeps updating.
+    pub fn new(value: Arc<AtomicU64>) -> Self {
+        Self {
+            value,
+            label: None,
+            quantum: 1,
+            displayed: Mutex::new(Ewma::new(DEFAULT_SMOOTHING)),
+        }
+    }
+
+    /// Shows `label: ` before the value.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The time constant with which the displayed value approaches the true value. After this
+    /// long, about two thirds of the gap is closed. Zero disables smoothing.
+    pub fn smoothing(mut self, smoothing: Duration) -> Self {
+        self.displayed = Mutex::new(Ewma::new(smoothing));
+        self
+    }
+
+    /// Rounds the displayed value down to a multiple of `quantum`.
+    pub fn quantum(mut self, quantum: u64) -> Self {
+        self.quantum = quantum.max(1);
+        self
+    }
+
+    fn displayed_at(&self, now: Instant) -> anyhow::Result<u64> {
+        let target = self.value.load(Ordering::Relaxed) as f64;
+        let mut displayed = self
+            .displayed
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Counter mutex was poisoned"))?;
+
+        let mut value = displayed.update(target, now);
+        // Don't crawl through the last fraction forever.
+        if (target - value).abs() < 1.0 {
+            value = target;
+            displayed.set(value, now);
+        }
+
+        let value = value.round() as u64;
+        Ok(value - value % self.quantum)
+    }
+
+    fn line(&self, value: u64) -> Line {
+        let text = match &self.label {
+            Some(label) => format!("{}: {}", label, human::count(value)),
+            None => human::count(value),
+        };
+        Line::from_iter([Span::sanitized(text)])
+    }
+}
+
+impl Component for Counter {
+    /// As wide as the larger of the displayed and the true value, with the label, so that the value
+    /// isn't cut while it moves from one to the other.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let displayed = self
+            .displayed
+            .lock()
+            .ok()
+            .and_then(|displayed| displayed.value())
+            .map_or(0, |value| value.round() as u64);
+        let value = self.value.load(Ordering::Relaxed).max(displayed);
+        Dimensions::new(self.line(value).len(), 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, _dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let value = match mode {
+            DrawMode::Normal => self.displayed_at(Instant::now())?,
+            DrawMode::Final => self.value.load(Ordering::Relaxed),
+        };
+        Ok(Lines(vec![self.line(value)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothing() -> anyhow::Result<()> {
+        let value = Arc::new(AtomicU64::new(0));
+        let counter = Counter::new(value.clone()).smoothing(Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert_eq!(counter.displayed_at(start)?, 0);
+        value.store(1000, Ordering::Relaxed);
+        // After one time constant, about 63% of the way there.
+        assert_eq!(counter.displayed_at(start + Duration::from_secs(1))?, 632);
+        assert_eq!(counter.displayed_at(start + Duration::from_secs(20))?, 1000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quantum_and_final() -> anyhow::Result<()> {
+        let value = Arc::new(AtomicU64::new(12345));
+        let counter = Counter::new(value)
+            .label("files")
+            .smoothing(Duration::ZERO)
+            .quantum(100);
+
+        let output = counter.draw(Dimensions::new(20, 1), DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "files: 12,300");
+        let output = counter.draw(Dimensions::new(20, 1), DrawMode::Final)?;
+        assert_eq!(output.0[0].to_unstyled(), "files: 12,345");
+
+        Ok(())
+    }
+}
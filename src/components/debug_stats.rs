@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::debug::short_type_name;
+use crate::debug::RenderStats;
+use crate::human;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+
+/// The number of components listed by default.
+const DEFAULT_TOP: usize = 5;
+
+/// Overlay showing the time spent on recent frames, their size, and the components which took
+/// the longest to draw in the last one. Get one from
+/// [`SuperConsole::debug_stats`](crate::SuperConsole::debug_stats). As stats are recorded once a
+/// frame is done, the overlay describes the frame before the one it is drawn in.
+#[derive(Debug)]
+pub struct DebugStats {
+    stats: Arc<Mutex<RenderStats>>,
+    top: usize,
+}
+
+impl DebugStats {
+    pub(crate) fn new(stats: Arc<Mutex<RenderStats>>) -> Self {
+        Self {
+            stats,
+            top: DEFAULT_TOP,
+        }
+    }
+
+    /// Lists the `top` slowest components, 5 by default.
+    pub fn top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+}
+
+impl Component for DebugStats {
+    fn draw_unchecked(&self, _dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        if mode == DrawMode::Final {
+            return Ok(Lines::new());
+        }
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(frame) = stats.last_frame() else {
+            return Ok(Lines::new());
+        };
+
+        let mut output = vec![Line::from_iter([styled_span(
+            StyleRole::Dim,
+            &format!(
+                "frame {}: {:.1?} (avg {:.1?}), {} (avg {}), {} lines",
+                frame.index,
+                frame.duration,
+                stats.average_duration(),
+                human::bytes(frame.bytes as u64),
+                human::bytes(stats.average_bytes() as u64),
+                frame.canvas_height,
+            ),
+        )])];
+        for (node, duration) in stats.components.slowest(self.top) {
+            output.push(Line::from_iter([styled_span(
+                StyleRole::Dim,
+                &format!(
+                    "  {:.1?} {} {}x{}",
+                    duration,
+                    short_type_name(node.type_name),
+                    node.dimensions.width,
+                    node.dimensions.height,
+                ),
+            )]));
+        }
+        Ok(Lines(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::testing::test_console;
+
+    #[test]
+    fn test_debug_stats() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let echo = Echo(Lines(vec![vec!["state"].try_into()?]));
+        let stats = console.debug_stats().top(2);
+        assert!(stats
+            .draw(Dimensions::new(80, 5), DrawMode::Normal)?
+            .is_empty());
+
+        console.render(&echo)?;
+
+        let output = stats.draw(Dimensions::new(80, 5), DrawMode::Normal)?;
+        assert_eq!(output.len(), 2);
+        assert!(output.0[0].to_unstyled().starts_with("frame 0: "));
+        assert!(output.0[1].to_unstyled().ends_with("Echo 5x1"));
+        assert!(stats
+            .draw(Dimensions::new(80, 5), DrawMode::Final)?
+            .is_empty());
+        Ok(())
+    }
+}
@@ -19,6 +19,10 @@ use crate::Lines;
 pub(crate) struct Echo(pub(crate) Lines);
 
 impl Component for Echo {
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        Dimensions::new(self.0.max_line_length(), self.0.len()).intersect(max)
+    }
+
     fn draw_unchecked(&self, _dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
         Ok(self.0.clone())
     }
@@ -0,0 +1,121 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+
+/// The `Embedded` component hosts a nested console within the area allotted by its parent, e.g. for
+/// a sub-tool which renders its own UI. Like a [`SuperConsole`](crate::SuperConsole), it draws the
+/// lines emitted to it above its child, but only keeps the most recent `history` lines, which
+/// scroll away as the area fills up.
+#[derive(Debug)]
+pub struct Embedded<C: Component = Box<dyn Component>> {
+    child: C,
+    history: usize,
+    emitted: Mutex<VecDeque<Line>>,
+}
+
+impl<C: Component> Embedded<C> {
+    pub fn new(child: C, history: usize) -> Self {
+        Self {
+            child,
+            history,
+            emitted: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Adds lines to be drawn above the child.
+    pub fn emit(&self, lines: Lines) {
+        let mut emitted = self.emitted.lock().unwrap_or_else(|e| e.into_inner());
+        emitted.extend(lines.0);
+        let excess = emitted.len().saturating_sub(self.history);
+        emitted.drain(..excess);
+    }
+
+    /// Discards the emitted lines.
+    pub fn clear(&self) {
+        self.emitted
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl<C: Component> Component for Embedded<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    /// The child's desired size, plus a row for each line of history kept.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let child = self.child.desired_size(max);
+        let emitted = self.emitted.lock().unwrap_or_else(|e| e.into_inner());
+        let width = emitted.iter().map(Line::len).fold(child.width, usize::max);
+        Dimensions::new(width, child.height + emitted.len()).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let canvas = self.child.draw(dimensions, mode)?;
+        let emitted = self
+            .emitted
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Embedded emit buffer mutex was poisoned"))?;
+
+        let rows = dimensions.height.saturating_sub(canvas.len());
+        let skip = emitted.len().saturating_sub(rows);
+        let mut res = Lines(emitted.iter().skip(skip).cloned().collect());
+        res.0.extend(canvas.0);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+
+    #[test]
+    fn test_embedded() -> anyhow::Result<()> {
+        let embedded = Embedded::new(Echo(Lines(vec![vec!["status"].try_into()?])), 3);
+        for i in 0..5 {
+            embedded.emit(Lines(vec![Line::unstyled(&format!("line {}", i))?]));
+        }
+
+        let output = embedded.draw(Dimensions::new(10, 3), DrawMode::Normal)?;
+        let rows: Vec<_> = output.iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["line 3", "line 4", "status"]);
+
+        let output = embedded.draw(Dimensions::new(10, 10), DrawMode::Normal)?;
+        assert_eq!(output.len(), 4);
+
+        embedded.clear();
+        let output = embedded.draw(Dimensions::new(10, 10), DrawMode::Normal)?;
+        assert_eq!(output.len(), 1);
+
+        Ok(())
+    }
+}
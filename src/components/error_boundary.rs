@@ -0,0 +1,310 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use crate::charset::warning_sign;
+use crate::debug;
+use crate::theme::style;
+use crate::theme::theme;
+use crate::theme::StyleRole;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// What happens when a component fails to draw during a render,
+/// see [`SuperConsole::set_error_policy`](crate::SuperConsole::set_error_policy).
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum ErrorPolicy {
+    /// The error aborts the render, which returns it.
+    #[default]
+    Abort,
+    /// The failing component is drawn as an inline box naming it and describing the error, and
+    /// the rest of the frame is drawn as usual. Like wrapping every component in an
+    /// [`ErrorBoundary`].
+    Isolate,
+}
+
+/// An error caught from a component under [`ErrorPolicy::Isolate`].
+#[derive(Debug)]
+pub struct ComponentError {
+    /// The type of the component which failed, see [`std::any::type_name`]. Boxed and shared
+    /// components are named after their wrapper, e.g. `Box<dyn Component>`.
+    pub component: &'static str,
+    pub error: anyhow::Error,
+}
+
+thread_local! {
+    static ISOLATED: RefCell<Option<Vec<ComponentError>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, isolating the errors of components drawn on this thread meanwhile if `enabled`.
+/// Returns the errors caught, oldest first.
+pub(crate) fn isolate<R>(enabled: bool, f: impl FnOnce() -> R) -> (R, Vec<ComponentError>) {
+    if !enabled {
+        return (f(), Vec::new());
+    }
+    let previous = ISOLATED.with(|i| i.replace(Some(Vec::new())));
+    let res = f();
+    let errors = ISOLATED.with(|i| i.replace(previous));
+    (res, errors.unwrap_or_default())
+}
+
+/// Replaces the draw error of a component with an error box if isolating, keeping the error.
+pub(crate) fn isolate_error(
+    component: &'static str,
+    error: anyhow::Error,
+    dimensions: Dimensions,
+) -> anyhow::Result<Lines> {
+    ISOLATED.with(|i| match i.borrow_mut().as_mut() {
+        Some(errors) => {
+            let output = error_box(debug::short_type_name(component), &error, dimensions);
+            errors.push(ComponentError { component, error });
+            Ok(output)
+        }
+        None => Err(error),
+    })
+}
+
+/// A one line description of `error`, in the error style.
+fn error_message(error: &anyhow::Error) -> Span {
+    let message = format!("{} {:#}", warning_sign(), error).replace('\n', " ");
+    let mut message = Span::sanitized(message);
+    message.style = style(StyleRole::Error);
+    message
+}
+
+/// A box titled with the component's name around the error message, or just the message if the
+/// box doesn't fit.
+fn error_box(name: &str, error: &anyhow::Error, dimensions: Dimensions) -> Lines {
+    let message = error_message(error);
+    if dimensions.height < 3 || dimensions.width < 4 {
+        return Lines(vec![Line::from_iter([message])]);
+    }
+
+    let charset = theme().charset;
+    let title = format!(" {} ", name);
+    let inner = cmp::min(
+        cmp::max(message.len(), title.len() + 2),
+        dimensions.width - 2,
+    );
+    let border = |line: Line| {
+        let mut line = line;
+        line.to_exact_width(inner + 2);
+        line.apply_style_base(style(StyleRole::Error));
+        line
+    };
+
+    let mut top = Line::sanitized(&format!(
+        "{}{}{}",
+        charset.top_left, charset.horizontal, title
+    ));
+    top.truncate_line(inner + 1);
+    let fill = (inner + 1).saturating_sub(top.len());
+    top.push(Span::sanitized(charset.horizontal.repeat(fill)));
+    top.push(Span::sanitized(charset.top_right));
+
+    let mut middle = Line::from_iter([message]);
+    middle.to_exact_width(inner);
+    middle.push_front(Span::sanitized(charset.vertical));
+    middle.push(Span::sanitized(charset.vertical));
+
+    let bottom = Line::sanitized(&format!(
+        "{}{}{}",
+        charset.bottom_left,
+        charset.horizontal.repeat(inner),
+        charset.bottom_right
+    ));
+
+    Lines(vec![border(top), border(middle), border(bottom)])
+}
+
+/// The number of errors an [`ErrorBoundary`] keeps by default.
+const DEFAULT_MAX_ERRORS: usize = 64;
+
+#[derive(Debug, Default)]
+struct Errors {
+    kept: VecDeque<anyhow::Error>,
+    /// The errors dropped to keep within the limit, so far.
+    dropped: u64,
+}
+
+/// The `ErrorBoundary` component keeps a child's draw error from aborting the whole render.
+/// If the child fails to draw, a placeholder describing the error is drawn in its place, and the
+/// error is kept until the caller collects it with [`take_errors`](Self::take_errors). Only the
+/// most recent errors are kept, see [`max_errors`](Self::max_errors), so that a child failing at
+/// every frame doesn't grow without bound when they are never collected.
+#[derive(Debug)]
+pub struct ErrorBoundary<C: Component = Box<dyn Component>> {
+    child: C,
+    max_errors: usize,
+    errors: Mutex<Errors>,
+}
+
+impl<C: Component> ErrorBoundary<C> {
+    pub fn new(child: C) -> Self {
+        Self {
+            child,
+            max_errors: DEFAULT_MAX_ERRORS,
+            errors: Mutex::new(Errors::default()),
+        }
+    }
+
+    /// Keeps at most the last `max` errors caught, 64 by default. Older ones
+    /// are dropped, and counted by [`dropped_errors`](Self::dropped_errors).
+    pub fn max_errors(mut self, max: usize) -> Self {
+        self.max_errors = max;
+        self
+    }
+
+    fn errors(&self) -> MutexGuard<'_, Errors> {
+        self.errors.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the errors caught since the last call, oldest first.
+    pub fn take_errors(&self) -> Vec<anyhow::Error> {
+        mem::take(&mut self.errors().kept).into()
+    }
+
+    /// The number of errors dropped so far, as more were caught than kept.
+    pub fn dropped_errors(&self) -> u64 {
+        self.errors().dropped
+    }
+}
+
+impl<C: Component> Component for ErrorBoundary<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        match self.child.draw(dimensions, mode) {
+            Ok(output) => Ok(output),
+            Err(e) => {
+                let placeholder = error_message(&e);
+                let mut errors = self.errors();
+                errors.kept.push_back(e);
+                while errors.kept.len() > self.max_errors {
+                    errors.kept.pop_front();
+                    errors.dropped += 1;
+                }
+                Ok(Lines(vec![Line::from_iter([placeholder])]))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context as _;
+
+    use super::*;
+    use crate::testing::with_unicode;
+
+    struct Failing;
+
+    impl Component for Failing {
+        fn draw_unchecked(
+            &self,
+            _dimensions: Dimensions,
+            _mode: DrawMode,
+        ) -> anyhow::Result<Lines> {
+            Err(anyhow::anyhow!("disk\non fire")).context("Drawing status")
+        }
+    }
+
+    #[test]
+    fn test_error_boundary() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let boundary = ErrorBoundary::new(Failing);
+
+            let output = boundary.draw(Dimensions::new(100, 10), DrawMode::Normal)?;
+            assert_eq!(
+                output.fmt_for_test().to_string(),
+                "<span fg=red>⚠ Drawing status: disk on fire</span>\n"
+            );
+            // The placeholder is truncated like any other output.
+            let output = boundary.draw(Dimensions::new(5, 10), DrawMode::Normal)?;
+            assert_eq!(output.0[0].to_unstyled(), "⚠ Dra");
+
+            let errors = boundary.take_errors();
+            assert_eq!(errors.len(), 2);
+            assert_eq!(errors[0].to_string(), "Drawing status");
+            assert!(boundary.take_errors().is_empty());
+            assert_eq!(boundary.dropped_errors(), 0);
+
+            let output = with_unicode(false, || {
+                boundary.draw(Dimensions::new(100, 10), DrawMode::Normal)
+            })?;
+            assert_eq!(output.0[0].to_unstyled(), "! Drawing status: disk on fire");
+
+            // Errors beyond the limit are counted rather than kept.
+            let boundary = ErrorBoundary::new(Failing).max_errors(3);
+            for _ in 0..5 {
+                boundary.draw(Dimensions::new(100, 10), DrawMode::Normal)?;
+            }
+            assert_eq!(boundary.take_errors().len(), 3);
+            assert_eq!(boundary.dropped_errors(), 2);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_isolate() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let dimensions = Dimensions::new(40, 10);
+            assert!(Failing.draw(dimensions, DrawMode::Normal).is_err());
+
+            let (output, errors) = isolate(true, || Failing.draw(dimensions, DrawMode::Normal));
+            let rows: Vec<_> = output?.iter().map(Line::to_unstyled).collect();
+            assert_eq!(
+                rows,
+                vec![
+                    format!("┌─ Failing {}┐", "─".repeat(20)),
+                    "│⚠ Drawing status: disk on fire│".to_owned(),
+                    format!("└{}┘", "─".repeat(30)),
+                ]
+            );
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].component.ends_with("Failing"));
+
+            let (output, _) = isolate(true, || {
+                Failing.draw(Dimensions::new(40, 2), DrawMode::Normal)
+            });
+            assert_eq!(output?.len(), 1);
+
+            Ok(())
+        })
+    }
+}
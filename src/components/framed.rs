@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A box model wrapper composing margin, border, padding and background around a child.
+
+use crate::charset::LineCharset;
+use crate::style::Color;
+use crate::style::ContentStyle;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// Widths of the four sides of a margin or padding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct Edges {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+impl Edges {
+    pub fn all(width: usize) -> Self {
+        Self::symmetric(width, width)
+    }
+
+    pub fn symmetric(vertical: usize, horizontal: usize) -> Self {
+        Self {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+
+    fn horizontal(&self) -> usize {
+        self.left + self.right
+    }
+
+    fn vertical(&self) -> usize {
+        self.top + self.bottom
+    }
+}
+
+/// The `Framed` component draws its child inside, from the outside in, a margin, a border with an
+/// optional title, and padding. The background color fills the padding and the child's area.
+/// Sizes are accounted for in one place: the child is allotted what remains of the dimensions
+/// after all of them, and its lines are filled to that width.
+#[derive(Debug)]
+pub struct Framed<C: Component = Box<dyn Component>> {
+    child: C,
+    margin: Edges,
+    padding: Edges,
+    border: Option<LineCharset>,
+    title: Option<Line>,
+    background: Option<Color>,
+}
+
+impl<C: Component> Framed<C> {
+    /// A frame with no margin, border or padding.
+    pub fn new(child: C) -> Self {
+        Self {
+            child,
+            margin: Edges::default(),
+            padding: Edges::default(),
+            border: None,
+            title: None,
+            background: None,
+        }
+    }
+
+    pub fn margin(mut self, margin: Edges) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn padding(mut self, padding: Edges) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Draws a border with the given charset, e.g. [`LineCharset::ROUNDED`] or
+    /// [`LineCharset::detect`].
+    pub fn border(mut self, charset: LineCharset) -> Self {
+        self.border = Some(charset);
+        self
+    }
+
+    /// A title drawn in the top border, if there is one.
+    pub fn title(mut self, title: Line) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    fn top_border(&self, charset: &LineCharset, width: usize) -> Line {
+        let mut line = Line::default();
+        if let Some(title) = &self.title {
+            line.push(styled_span(StyleRole::Border, charset.horizontal));
+            line.push(Span::padding(1));
+            for span in title.iter() {
+                line.push(span.clone());
+            }
+            line.push(Span::padding(1));
+        }
+        let fill = width.saturating_sub(line.len());
+        line.push(styled_span(
+            StyleRole::Border,
+            &charset.horizontal.repeat(fill),
+        ));
+        line.truncate_line(width);
+        line.push_front(styled_span(StyleRole::Border, charset.top_left));
+        line.push(styled_span(StyleRole::Border, charset.top_right));
+        line
+    }
+}
+
+impl<C: Component> Component for Framed<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    /// The child's desired size, plus the margin, border and padding.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let border = if self.border.is_some() { 2 } else { 0 };
+        let horizontal = self.margin.horizontal() + border + self.padding.horizontal();
+        let vertical = self.margin.vertical() + border + self.padding.vertical();
+        let child = self.child.desired_size(Dimensions::new(
+            max.width.saturating_sub(horizontal),
+            max.height.saturating_sub(vertical),
+        ));
+        Dimensions::new(child.width + horizontal, child.height + vertical).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let border = if self.border.is_some() { 2 } else { 0 };
+        let inner = Dimensions {
+            width: dimensions
+                .width
+                .saturating_sub(self.margin.horizontal() + border + self.padding.horizontal()),
+            height: dimensions
+                .height
+                .saturating_sub(self.margin.vertical() + border + self.padding.vertical()),
+        };
+
+        let mut output = self.child.draw(inner, mode)?;
+        let width = inner.width + self.padding.horizontal();
+        output.set_lines_to_exact_width(inner.width);
+        output.pad_lines_top(self.padding.top);
+        output.pad_lines_bottom(self.padding.bottom);
+        output.pad_lines_left(self.padding.left);
+        output.set_lines_to_exact_width(width);
+        if let Some(background) = self.background {
+            output.apply_style_base(ContentStyle {
+                background_color: Some(background),
+                ..ContentStyle::default()
+            });
+        }
+
+        if let Some(charset) = &self.border {
+            for line in output.iter_mut() {
+                line.push_front(styled_span(StyleRole::Border, charset.vertical));
+                line.push(styled_span(StyleRole::Border, charset.vertical));
+            }
+            output.0.insert(0, self.top_border(charset, width));
+            output.push(Line::from_iter([
+                styled_span(StyleRole::Border, charset.bottom_left),
+                styled_span(StyleRole::Border, &charset.horizontal.repeat(width)),
+                styled_span(StyleRole::Border, charset.bottom_right),
+            ]));
+        }
+
+        output.pad_lines_top(self.margin.top);
+        output.pad_lines_bottom(self.margin.bottom);
+        output.pad_lines_left(self.margin.left);
+        output.set_lines_to_exact_width(width + border + self.margin.horizontal());
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+
+    #[test]
+    fn test_framed() -> anyhow::Result<()> {
+        let framed = Framed::new(Echo(Lines(vec![Line::unstyled("hello")?])))
+            .margin(Edges::symmetric(0, 1))
+            .border(LineCharset::ROUNDED)
+            .padding(Edges::symmetric(0, 1))
+            .title(Line::unstyled("Build")?);
+
+        let output = framed.draw(Dimensions::new(16, 10), DrawMode::Normal)?;
+        let rows: Vec<_> = output.iter().map(Line::to_unstyled).collect();
+        assert_eq!(
+            rows,
+            vec![" ╭─ Build ────╮ ", " │ hello      │ ", " ╰────────────╯ ",]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_background() -> anyhow::Result<()> {
+        let framed = Framed::new(Echo(Lines(vec![Line::unstyled("hi")?])))
+            .padding(Edges::symmetric(0, 1))
+            .background(Color::Blue);
+
+        let output = framed.draw(Dimensions::new(5, 1), DrawMode::Normal)?;
+        assert_eq!(
+            output.fmt_for_test().to_string(),
+            "<span bg=blue> hi  </span>\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::charset::unicode_enabled;
+use crate::human;
+use crate::style::Color;
+use crate::theme::styled_span;
+use crate::theme::theme;
+use crate::theme::Palette;
+use crate::theme::Status;
+use crate::theme::StyleRole;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// The `Gauge` component displays the level of a metric, such as memory use or a cache hit rate,
+/// as a labeled horizontal meter followed by its percentage, e.g. `memory [█████░░░░░] 50%`.
+/// Unlike a progress bar, the level may go up and down: the meter is colored by the thresholds
+/// the level crosses rather than by completion.
+///
+/// The level is `value / max`, where `value` is shared with the caller who keeps updating it.
+#[derive(Debug)]
+pub struct Gauge {
+    value: Arc<AtomicU64>,
+    max: u64,
+    label: Option<String>,
+    width: Option<usize>,
+    warning: f64,
+    critical: f64,
+    /// The palette given with [`palette`](Self::palette), or else that of the current theme.
+    palette: Option<Palette>,
+}
+
+impl Gauge {
+    /// A gauge showing `value` out of `max`.
+    pub fn new(value: Arc<AtomicU64>, max: u64) -> Self {
+        Self {
+            value,
+            max,
+            label: None,
+            width: None,
+            warning: 0.7,
+            critical: 0.9,
+            palette: None,
+        }
+    }
+
+    /// Shows `label` before the meter.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The number of cells in the meter, excluding brackets. By default, the meter takes up the
+    /// width left by the label and the percentage.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// The levels from which the meter is drawn in the warning and failure colors, 70% and 90%
+    /// by default. If `warning` is above `critical`, lower levels are worse, e.g. for hit rates.
+    pub fn thresholds(mut self, warning: f64, critical: f64) -> Self {
+        self.warning = warning;
+        self.critical = critical;
+        self
+    }
+
+    /// The palette whose success, warning and failure colors are used, see [`Palette`].
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// The level shown, between 0 and 1.
+    pub fn level(&self) -> f64 {
+        if self.max == 0 {
+            return 0.0;
+        }
+        (self.value.load(Ordering::Relaxed) as f64 / self.max as f64).clamp(0.0, 1.0)
+    }
+
+    fn prefix(&self) -> Span {
+        Span::sanitized(match &self.label {
+            Some(label) => format!("{} [", label),
+            None => "[".to_owned(),
+        })
+    }
+
+    fn color(&self, level: f64) -> Color {
+        let crosses = |threshold: f64| {
+            if self.warning <= self.critical {
+                level >= threshold
+            } else {
+                level <= threshold
+            }
+        };
+        let status = if crosses(self.critical) {
+            Status::Failure
+        } else if crosses(self.warning) {
+            Status::Warning
+        } else {
+            Status::Success
+        };
+        match &self.palette {
+            Some(palette) => palette.color(status),
+            None => theme().palette.color(status),
+        }
+    }
+}
+
+impl Component for Gauge {
+    /// The meter fills the width it is given, unless it has a fixed [`width`](Self::width), in
+    /// which case the gauge is as wide as the label, the meter and room for `100%`.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let width = match self.width {
+            Some(width) => self.prefix().len() + width + "] 100%".len(),
+            None => max.width,
+        };
+        Dimensions::new(width, 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        let level = self.level();
+        let prefix = self.prefix();
+        let suffix = format!("] {}", human::percent(level));
+
+        let width = self.width.unwrap_or_else(|| {
+            // Leave room for `100%`, so the meter does not shrink as the level rises.
+            dimensions
+                .width
+                .saturating_sub(prefix.len() + "] 100%".len())
+        });
+        let filled = ((level * width as f64).round() as usize).min(width);
+        let (full, empty) = if unicode_enabled() {
+            ("█", "░")
+        } else {
+            ("#", ".")
+        };
+
+        let mut line = Line::from_iter([prefix]);
+        line.push(Span::new_colored_lossy(
+            &full.repeat(filled),
+            self.color(level),
+        ));
+        line.push(styled_span(StyleRole::Dim, &empty.repeat(width - filled)));
+        line.push(Span::sanitized(suffix));
+        Ok(Lines(vec![line]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_gauge() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let value = Arc::new(AtomicU64::new(5));
+            let gauge = Gauge::new(value.clone(), 10).label("memory").width(10);
+
+            let output = gauge.draw(Dimensions::new(40, 1), DrawMode::Normal)?;
+            assert_eq!(output.0[0].to_unstyled(), "memory [█████░░░░░] 50%");
+            assert_eq!(
+                gauge.desired_size(Dimensions::new(40, 5)),
+                Dimensions::new(24, 1)
+            );
+            let output = with_unicode(false, || {
+                gauge.draw(Dimensions::new(40, 1), DrawMode::Normal)
+            })?;
+            assert_eq!(output.0[0].to_unstyled(), "memory [#####.....] 50%");
+            assert_eq!(gauge.color(gauge.level()), Color::Green);
+
+            value.store(8, Ordering::Relaxed);
+            assert_eq!(gauge.color(gauge.level()), Color::Yellow);
+            value.store(20, Ordering::Relaxed);
+            let output = gauge.draw(Dimensions::new(40, 1), DrawMode::Normal)?;
+            assert_eq!(output.0[0].to_unstyled(), "memory [██████████] 100%");
+            assert_eq!(gauge.color(gauge.level()), Color::Red);
+
+            // Fills the available width, less room for `100%`, and lower is worse.
+            let hits = Gauge::new(Arc::new(AtomicU64::new(1)), 4).thresholds(0.5, 0.25);
+            let output = hits.draw(Dimensions::new(14, 1), DrawMode::Normal)?;
+            assert_eq!(output.0[0].to_unstyled(), "[██░░░░░] 25%");
+            assert_eq!(hits.color(hits.level()), Color::Red);
+
+            Ok(())
+        })
+    }
+}
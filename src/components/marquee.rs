@@ -0,0 +1,155 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::content::Elision;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(150);
+const DEFAULT_PAUSE: Duration = Duration::from_secs(1);
+
+/// The `Marquee` component shows a line too long for its width, such as a long target name, by
+/// scrolling it back and forth so that all of it can be read, pausing at either end. Lines which
+/// fit are drawn as they are. The final draw elides the line instead, so the scrollback doesn't
+/// keep an arbitrary window of it.
+#[derive(Debug)]
+pub struct Marquee {
+    line: Line,
+    interval: Duration,
+    pause: Duration,
+    elision: Elision,
+    start: Instant,
+}
+
+impl Marquee {
+    pub fn new(line: Line) -> Self {
+        Self {
+            line,
+            interval: DEFAULT_INTERVAL,
+            pause: DEFAULT_PAUSE,
+            elision: Elision::End,
+            start: Instant::now(),
+        }
+    }
+
+    /// How long the line takes to scroll by one column, 150ms by default.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// How long the line rests at either end before scrolling back, 1s by default.
+    pub fn pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Which part of the line is elided by the final draw, the end by default.
+    pub fn elision(mut self, elision: Elision) -> Self {
+        self.elision = elision;
+        self
+    }
+
+    /// The number of columns scrolled past at `now`, out of `overflow`.
+    fn offset_at(&self, now: Instant, overflow: usize) -> usize {
+        if overflow == 0 || self.interval.is_zero() {
+            return 0;
+        }
+        let scroll = self.interval.as_nanos() * overflow as u128;
+        let period = 2 * (self.pause.as_nanos() + scroll);
+        let t = now.saturating_duration_since(self.start).as_nanos() % period;
+        // Rest at the start, scroll to the end, rest there, then scroll back.
+        let (t, forward) = if t < period / 2 {
+            (t, true)
+        } else {
+            (t - period / 2, false)
+        };
+        let scrolled = t.saturating_sub(self.pause.as_nanos()) / self.interval.as_nanos();
+        let scrolled = (scrolled as usize).min(overflow);
+        if forward {
+            scrolled
+        } else {
+            overflow - scrolled
+        }
+    }
+}
+
+impl Component for Marquee {
+    /// A single row, as wide as the whole line.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        Dimensions::new(self.line.len(), 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut line = self.line.clone();
+        let overflow = line.len().saturating_sub(dimensions.width);
+        if overflow > 0 {
+            match mode {
+                DrawMode::Normal => {
+                    line.trim_ends(self.offset_at(Instant::now(), overflow), dimensions.width)
+                }
+                DrawMode::Final => line.elide(dimensions.width, self.elision),
+            }
+        }
+        Ok(Lines(vec![line]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_offsets() {
+        let marquee = Marquee::new(Line::sanitized("//very/long:target"))
+            .interval(Duration::from_millis(100))
+            .pause(Duration::from_millis(500));
+        let start = marquee.start;
+        let at = |millis| marquee.offset_at(start + Duration::from_millis(millis), 3);
+        // Resting at the start.
+        assert_eq!(at(0), 0);
+        assert_eq!(at(499), 0);
+        // Scrolling forward, then resting at the end.
+        assert_eq!(at(600), 1);
+        assert_eq!(at(800), 3);
+        assert_eq!(at(1299), 3);
+        // Scrolling back, and starting over.
+        assert_eq!(at(1400), 2);
+        assert_eq!(at(1599), 1);
+        assert_eq!(at(1700), 0);
+    }
+
+    #[test]
+    fn test_draw() -> anyhow::Result<()> {
+        let marquee = Marquee::new(Line::sanitized("//very/long:target"));
+        let output = marquee.draw(Dimensions::new(12, 1), DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "//very/long:");
+
+        let output = with_unicode(true, || {
+            marquee.draw(Dimensions::new(12, 1), DrawMode::Final)
+        })?;
+        assert_eq!(output.0[0].to_unstyled(), "//very/long…");
+        let output = with_unicode(false, || {
+            marquee.draw(Dimensions::new(12, 1), DrawMode::Final)
+        })?;
+        assert_eq!(output.0[0].to_unstyled(), "//very/lo...");
+
+        let output = marquee.draw(Dimensions::new(40, 1), DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "//very/long:target");
+
+        Ok(())
+    }
+}
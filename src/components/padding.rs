@@ -11,6 +11,7 @@ use crate::components::Blank;
 use crate::components::Dimensions;
 use crate::components::DrawMode;
 use crate::Component;
+use crate::Direction;
 use crate::Lines;
 
 /// The `Padded` [`Component`](Component) wraps its child by padding left, right, above, and below its content.
@@ -53,6 +54,32 @@ impl<C: Component> Padded<C> {
 }
 
 impl<C: Component> Component for Padded<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let horizontal = self.left + self.right;
+        let vertical = self.top + self.bottom;
+        let child = self.child.desired_size(
+            max.saturating_sub(horizontal, Direction::Horizontal)
+                .saturating_sub(vertical, Direction::Vertical),
+        );
+        Dimensions::new(child.width + horizontal, child.height + vertical).intersect(max)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         let mut output = self.child.draw(dimensions, mode)?;
 
@@ -74,8 +101,6 @@ impl<C: Component> Component for Padded<C> {
 
 #[cfg(test)]
 mod tests {
-    use derive_more::AsRef;
-
     use crate::components::echo::Echo;
     use crate::components::Padded;
     use crate::Component;
@@ -84,9 +109,6 @@ mod tests {
     use crate::Line;
     use crate::Lines;
 
-    #[derive(Debug, AsRef)]
-    struct Msg(Lines);
-
     #[test]
     fn test_pad_left() {
         let msg = Lines(vec![
@@ -0,0 +1,350 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::input::Event;
+use crate::input::InputHandler;
+use crate::input::KeyCode;
+use crate::input::KeyEvent;
+use crate::input::KeyModifiers;
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// The answer to a [`Prompt`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Answer {
+    /// The line typed at a [`text`](Prompt::text) prompt.
+    Text(String),
+    /// The choice at a [`confirm`](Prompt::confirm) prompt.
+    Confirmed(bool),
+    /// The prompt was dismissed with Escape or Ctrl-C.
+    Cancelled,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum PromptKind {
+    Text,
+    Confirm { default: bool },
+}
+
+type AnswerCallback = Box<dyn FnOnce(&Answer) + Send>;
+
+#[derive(Default)]
+struct PromptState {
+    input: String,
+    answer: Option<Answer>,
+    wakers: Vec<Waker>,
+    on_answer: Option<AnswerCallback>,
+}
+
+struct PromptInner {
+    question: String,
+    kind: PromptKind,
+    state: Mutex<PromptState>,
+}
+
+/// Component asking a question in the canvas, e.g. `retry failed tests? [y/N]`, and capturing the
+/// answer from the keyboard without disturbing the rest of the UI. It is also an
+/// [`InputHandler`], which consumes key presses until the question is answered, so the same prompt
+/// is both drawn and registered with
+/// [`SuperConsole::add_input_handler`](crate::SuperConsole::add_input_handler). Clones share the
+/// question and answer.
+///
+/// The answer is available from [`answer`](Self::answer), is passed to the
+/// [`on_answer`](Self::on_answer) callback, and is the output of the future returned by
+/// [`answered`](Self::answered).
+#[derive(Clone)]
+pub struct Prompt(Arc<PromptInner>);
+
+impl fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prompt")
+            .field("question", &self.0.question)
+            .field("kind", &self.0.kind)
+            .field("answer", &self.answer())
+            .finish()
+    }
+}
+
+impl Prompt {
+    fn new(question: impl Into<String>, kind: PromptKind) -> Self {
+        Self(Arc::new(PromptInner {
+            question: question.into(),
+            kind,
+            state: Mutex::default(),
+        }))
+    }
+
+    /// Asks for a line of text, submitted with Enter.
+    pub fn text(question: impl Into<String>) -> Self {
+        Self::new(question, PromptKind::Text)
+    }
+
+    /// Asks a yes/no question, answered with `y` or `n`. Enter picks `default`.
+    pub fn confirm(question: impl Into<String>, default: bool) -> Self {
+        Self::new(question, PromptKind::Confirm { default })
+    }
+
+    /// Calls `callback` with the answer once it is given, from the thread handling input.
+    pub fn on_answer(self, callback: impl FnOnce(&Answer) + Send + 'static) -> Self {
+        self.state().on_answer = Some(Box::new(callback));
+        self
+    }
+
+    fn state(&self) -> MutexGuard<'_, PromptState> {
+        self.0.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The answer, once given.
+    pub fn answer(&self) -> Option<Answer> {
+        self.state().answer.clone()
+    }
+
+    pub fn is_answered(&self) -> bool {
+        self.state().answer.is_some()
+    }
+
+    /// A future resolving to the answer once it is given.
+    pub fn answered(&self) -> PromptAnswer {
+        PromptAnswer(self.clone())
+    }
+
+    fn submit(&self, mut state: MutexGuard<'_, PromptState>, answer: Answer) {
+        state.answer = Some(answer.clone());
+        let wakers = std::mem::take(&mut state.wakers);
+        let on_answer = state.on_answer.take();
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+        if let Some(on_answer) = on_answer {
+            on_answer(&answer);
+        }
+    }
+
+    fn hint(&self) -> Option<String> {
+        match self.0.kind {
+            PromptKind::Text => None,
+            PromptKind::Confirm { default } => Some(strings().confirm_hint(default)),
+        }
+    }
+}
+
+impl Component for Prompt {
+    /// As wide as the question, any hint and the input so far, with room for the cursor.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let width = self
+            .draw_unchecked(max, DrawMode::Normal)
+            .map_or(max.width, |lines| lines.max_line_length());
+        Dimensions::new(width, 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let state = self.state();
+        let mut line = Line::from_iter([styled_span(StyleRole::Highlight, &self.0.question)]);
+        line.push(Span::padding(1));
+        if let Some(answer) = &state.answer {
+            let answer = match answer {
+                Answer::Text(text) => text.clone(),
+                Answer::Confirmed(true) => strings().yes(),
+                Answer::Confirmed(false) => strings().no(),
+                Answer::Cancelled => strings().cancelled(),
+            };
+            line.push(styled_span(StyleRole::Dim, &answer));
+            return Ok(Lines(vec![line]));
+        }
+
+        if let Some(hint) = self.hint() {
+            line.push(styled_span(StyleRole::Dim, &hint));
+            line.push(Span::padding(1));
+        }
+        // Keep the end of long input visible, next to the cursor.
+        let available = dimensions.width.saturating_sub(line.len() + 1);
+        let mut shown = 0;
+        let mut width = 0;
+        for grapheme in state.input.graphemes(true).rev() {
+            width += Span::new_unstyled_lossy(grapheme).len();
+            if width > available {
+                break;
+            }
+            shown += grapheme.len();
+        }
+        line.push(Span::new_unstyled_lossy(
+            &state.input[state.input.len() - shown..],
+        ));
+        if mode == DrawMode::Normal {
+            line.push(Span::padding(1).with_cursor());
+        }
+        Ok(Lines(vec![line]))
+    }
+}
+
+impl InputHandler for Prompt {
+    fn handle_event(&self, event: &Event) -> bool {
+        let mut state = self.state();
+        if state.answer.is_some() {
+            return false;
+        }
+        let KeyEvent { code, modifiers } = match event {
+            Event::Key(key) => *key,
+            _ => return false,
+        };
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            if code == KeyCode::Char('c') {
+                self.submit(state, Answer::Cancelled);
+            }
+            return true;
+        }
+        match (self.0.kind, code) {
+            (_, KeyCode::Esc) => self.submit(state, Answer::Cancelled),
+            (PromptKind::Text, KeyCode::Enter) => {
+                let input = std::mem::take(&mut state.input);
+                self.submit(state, Answer::Text(input));
+            }
+            (PromptKind::Text, KeyCode::Backspace) => {
+                let len = state.input.graphemes(true).next_back().map_or(0, str::len);
+                let end = state.input.len() - len;
+                state.input.truncate(end);
+            }
+            (PromptKind::Text, KeyCode::Char(c)) => state.input.push(c),
+            (PromptKind::Confirm { default }, KeyCode::Enter) => {
+                self.submit(state, Answer::Confirmed(default))
+            }
+            (PromptKind::Confirm { .. }, KeyCode::Char('y' | 'Y')) => {
+                self.submit(state, Answer::Confirmed(true))
+            }
+            (PromptKind::Confirm { .. }, KeyCode::Char('n' | 'N')) => {
+                self.submit(state, Answer::Confirmed(false))
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+/// A future resolving to the answer of a [`Prompt`], see [`Prompt::answered`].
+#[derive(Debug, Clone)]
+pub struct PromptAnswer(Prompt);
+
+impl Future for PromptAnswer {
+    type Output = Answer;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Answer> {
+        let mut state = self.0.state();
+        match &state.answer {
+            Some(answer) => Poll::Ready(answer.clone()),
+            None => {
+                if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    state.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use std::task::Wake;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[derive(Default)]
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_confirm() -> anyhow::Result<()> {
+        let answered = Arc::new(Mutex::new(None));
+        let prompt = Prompt::confirm("retry failed tests?", false).on_answer({
+            let answered = answered.clone();
+            move |answer| *answered.lock().unwrap() = Some(answer.clone())
+        });
+        let dimensions = Dimensions::new(40, 1);
+
+        let output = prompt.draw(dimensions, DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "retry failed tests? [y/N]  ");
+        assert_eq!(output.cursor_position(), Some((26, 0)));
+
+        assert!(prompt.handle_event(&key(KeyCode::Char('x'))));
+        assert!(!prompt.is_answered());
+        assert!(prompt.handle_event(&key(KeyCode::Char('Y'))));
+        assert_eq!(prompt.answer(), Some(Answer::Confirmed(true)));
+        assert_eq!(*answered.lock().unwrap(), Some(Answer::Confirmed(true)));
+
+        // Once answered, input goes to other handlers.
+        assert!(!prompt.handle_event(&key(KeyCode::Char('n'))));
+        let output = prompt.draw(dimensions, DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "retry failed tests? yes");
+        assert_eq!(output.cursor_position(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_text() -> anyhow::Result<()> {
+        let prompt = Prompt::text("name:");
+        let mut answered = Box::pin(prompt.answered());
+        let flag = Arc::new(Flag::default());
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(answered.as_mut().poll(&mut cx), Poll::Pending);
+
+        for c in "abcdefgh".chars() {
+            prompt.handle_event(&key(KeyCode::Char(c)));
+        }
+        prompt.handle_event(&key(KeyCode::Backspace));
+        // The end of the input is kept next to the cursor.
+        let output = prompt.draw(Dimensions::new(10, 1), DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "name: efg ");
+
+        prompt.handle_event(&key(KeyCode::Enter));
+        assert!(flag.0.load(Ordering::Relaxed));
+        assert_eq!(
+            answered.as_mut().poll(&mut cx),
+            Poll::Ready(Answer::Text("abcdefg".to_owned()))
+        );
+
+        let prompt = Prompt::text("name:");
+        prompt.handle_event(&Event::Key(KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+        )));
+        assert_eq!(prompt.answer(), Some(Answer::Cancelled));
+
+        Ok(())
+    }
+}
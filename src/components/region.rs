@@ -0,0 +1,150 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+#[derive(Debug)]
+struct Drawn {
+    at: Instant,
+    dimensions: Dimensions,
+    output: Lines,
+}
+
+/// The `Region` component redraws its child at most once per `interval`, and reuses the previous
+/// output in between. Composing regions lets parts of the canvas refresh on their own cadence, e.g.
+/// a header every frame and an expensive table once a second.
+/// The child is always redrawn if the dimensions change, or for the final frame.
+#[derive(Debug)]
+pub struct Region<C: Component = Box<dyn Component>> {
+    child: C,
+    interval: Duration,
+    last: Mutex<Option<Drawn>>,
+}
+
+impl<C: Component> Region<C> {
+    pub fn new(child: C, interval: Duration) -> Self {
+        Self {
+            child,
+            interval,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Forces the child to be redrawn in the next frame.
+    pub fn invalidate(&self) {
+        if let Ok(mut last) = self.last.lock() {
+            *last = None;
+        }
+    }
+}
+
+impl<C: Component> Component for Region<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.invalidate();
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut last = self
+            .last
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Region cache mutex was poisoned"))?;
+        let now = Instant::now();
+
+        if let (DrawMode::Normal, Some(drawn)) = (mode, &*last) {
+            if drawn.dimensions == dimensions && now.duration_since(drawn.at) < self.interval {
+                return Ok(drawn.output.clone());
+            }
+        }
+
+        let output = self.child.draw(dimensions, mode)?;
+        *last = Some(Drawn {
+            at: now,
+            dimensions,
+            output: output.clone(),
+        });
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// Counts its draws.
+    #[derive(Default)]
+    struct Counter(Cell<usize>);
+
+    impl Component for Counter {
+        fn draw_unchecked(
+            &self,
+            _dimensions: Dimensions,
+            _mode: DrawMode,
+        ) -> anyhow::Result<Lines> {
+            self.0.set(self.0.get() + 1);
+            Ok(Lines(vec![
+                vec![self.0.get().to_string().as_str()].try_into()?
+            ]))
+        }
+    }
+
+    fn draw(
+        region: &Region<Counter>,
+        dimensions: Dimensions,
+        mode: DrawMode,
+    ) -> anyhow::Result<String> {
+        Ok(region.draw(dimensions, mode)?.0[0].to_unstyled())
+    }
+
+    #[test]
+    fn test_region_cadence() -> anyhow::Result<()> {
+        let small = Dimensions::new(10, 10);
+        let large = Dimensions::new(20, 10);
+
+        let region = Region::new(Counter::default(), Duration::from_secs(3600));
+        assert_eq!(draw(&region, small, DrawMode::Normal)?, "1");
+        assert_eq!(draw(&region, small, DrawMode::Normal)?, "1");
+        // A resize, invalidation or final draw refreshes the region.
+        assert_eq!(draw(&region, large, DrawMode::Normal)?, "2");
+        region.invalidate();
+        assert_eq!(draw(&region, large, DrawMode::Normal)?, "3");
+        assert_eq!(draw(&region, large, DrawMode::Final)?, "4");
+
+        let region = Region::new(Counter::default(), Duration::ZERO);
+        assert_eq!(draw(&region, small, DrawMode::Normal)?, "1");
+        assert_eq!(draw(&region, small, DrawMode::Normal)?, "2");
+
+        Ok(())
+    }
+}
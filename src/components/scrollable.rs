@@ -0,0 +1,212 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use crate::charset::unicode_enabled;
+use crate::charset::LineCharset;
+use crate::input::Event;
+use crate::input::InputHandler;
+use crate::input::KeyCode;
+use crate::input::KeyEvent;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// The `Scrollable` component holds a backlog of lines and shows the part of it which fits in its
+/// area, by default the most recent lines. Scrolling up stops following new lines until scrolled
+/// back to the bottom. A scrollbar is drawn in the rightmost column while the backlog does not fit.
+///
+/// It is also an [`InputHandler`], scrolling on the arrow keys, page up and down, home and end.
+#[derive(Debug)]
+pub struct Scrollable {
+    capacity: usize,
+    backlog: Mutex<VecDeque<Line>>,
+    /// How many lines above the bottom of the backlog the view is.
+    offset: AtomicUsize,
+    /// The height of the last draw, which is how far a page scrolls.
+    page: AtomicUsize,
+}
+
+impl Scrollable {
+    /// Keeps at most `capacity` lines, discarding the oldest.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            backlog: Mutex::new(VecDeque::new()),
+            offset: AtomicUsize::new(0),
+            page: AtomicUsize::new(1),
+        }
+    }
+
+    /// Appends lines to the backlog.
+    pub fn extend(&self, lines: Lines) {
+        let mut backlog = self.backlog.lock().unwrap_or_else(|e| e.into_inner());
+        let added = lines.len();
+        backlog.extend(lines.0);
+        let excess = backlog.len().saturating_sub(self.capacity);
+        backlog.drain(..excess);
+
+        // Keep the view on the same lines while scrolled up.
+        let offset = self.offset();
+        if offset > 0 {
+            self.offset
+                .store(self.clamp(offset + added, backlog.len()), Ordering::Relaxed);
+        }
+    }
+
+    /// Limits an offset so that the view stays full.
+    fn clamp(&self, offset: usize, len: usize) -> usize {
+        offset.min(len.saturating_sub(self.page.load(Ordering::Relaxed)))
+    }
+
+    pub fn clear(&self) {
+        self.backlog
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self.offset.store(0, Ordering::Relaxed);
+    }
+
+    /// How many lines above the bottom the view is, 0 when following new lines.
+    pub fn offset(&self) -> usize {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    pub fn scroll_up(&self, lines: usize) {
+        let len = self.backlog.lock().unwrap_or_else(|e| e.into_inner()).len();
+        let offset = self.clamp(self.offset().saturating_add(lines), len);
+        self.offset.store(offset, Ordering::Relaxed);
+    }
+
+    pub fn scroll_down(&self, lines: usize) {
+        self.offset
+            .store(self.offset().saturating_sub(lines), Ordering::Relaxed);
+    }
+
+    pub fn scroll_to_top(&self) {
+        self.scroll_up(usize::MAX);
+    }
+
+    /// Scrolls to the most recent lines, and follows new ones again.
+    pub fn scroll_to_bottom(&self) {
+        self.offset.store(0, Ordering::Relaxed);
+    }
+
+    fn scrollbar(total: usize, height: usize, start: usize) -> Vec<Span> {
+        let track = LineCharset::detect().vertical;
+        let thumb = if unicode_enabled() { "█" } else { "#" };
+        let size = (height * height / total).max(1);
+        let position = start * (height - size) / (total - height);
+        (0..height)
+            .map(|row| {
+                let glyph = if (position..position + size).contains(&row) {
+                    thumb
+                } else {
+                    track
+                };
+                Span::new_unstyled_lossy(glyph)
+            })
+            .collect()
+    }
+}
+
+impl Component for Scrollable {
+    fn draw_unchecked(&self, dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        let backlog = self
+            .backlog
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Scrollable backlog mutex was poisoned"))?;
+        let height = dimensions.height;
+        self.page.store(height.max(1), Ordering::Relaxed);
+        if height == 0 {
+            return Ok(Lines::new());
+        }
+
+        let offset = self.offset().min(backlog.len().saturating_sub(height));
+        let end = backlog.len() - offset;
+        let start = end.saturating_sub(height);
+        let mut res = Lines(backlog.range(start..end).cloned().collect());
+
+        if backlog.len() > height && dimensions.width > 1 {
+            res.set_lines_to_exact_width(dimensions.width - 1);
+            let bar = Self::scrollbar(backlog.len(), height, start);
+            for (line, glyph) in res.iter_mut().zip(bar) {
+                line.push(glyph);
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl InputHandler for Scrollable {
+    fn handle_event(&self, event: &Event) -> bool {
+        let page = self.page.load(Ordering::Relaxed);
+        match event {
+            Event::Key(KeyEvent { code, .. }) => match code {
+                KeyCode::Up => self.scroll_up(1),
+                KeyCode::Down => self.scroll_down(1),
+                KeyCode::PageUp => self.scroll_up(page),
+                KeyCode::PageDown => self.scroll_down(page),
+                KeyCode::Home => self.scroll_to_top(),
+                KeyCode::End => self.scroll_to_bottom(),
+                _ => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::KeyModifiers;
+    use crate::testing::draw_to_strings;
+    use crate::testing::with_unicode;
+
+    fn rows(scrollable: &Scrollable) -> anyhow::Result<Vec<String>> {
+        draw_to_strings(scrollable, Dimensions::new(8, 3), DrawMode::Normal)
+    }
+
+    #[test]
+    fn test_scrollable() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let scrollable = Scrollable::new(5);
+            for i in 0..7 {
+                scrollable.extend(Lines(vec![Line::unstyled(&format!("line {}", i))?]));
+            }
+            assert_eq!(rows(&scrollable)?, vec!["line 4 │", "line 5 │", "line 6 █"]);
+            assert_eq!(
+                with_unicode(false, || rows(&scrollable))?,
+                vec!["line 4 |", "line 5 |", "line 6 #"]
+            );
+
+            scrollable.handle_event(&Event::Key(KeyEvent::new(
+                KeyCode::PageUp,
+                KeyModifiers::NONE,
+            )));
+            assert_eq!(rows(&scrollable)?, vec!["line 2 █", "line 3 │", "line 4 │"]);
+
+            // New lines don't move the view while scrolled up.
+            scrollable.extend(Lines(vec![Line::unstyled("line 7")?]));
+            assert_eq!(rows(&scrollable)?, vec!["line 3 █", "line 4 │", "line 5 │"]);
+
+            scrollable.scroll_to_bottom();
+            assert_eq!(rows(&scrollable)?, vec!["line 5 │", "line 6 │", "line 7 █"]);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,255 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::charset::unicode_enabled;
+use crate::style::Color;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// A time series shared between the caller, who keeps pushing samples, and a [`Sparkline`].
+/// Only the latest `capacity` samples are kept. Clones share the samples.
+#[derive(Debug, Clone)]
+pub struct Samples {
+    values: Arc<Mutex<VecDeque<f64>>>,
+    capacity: usize,
+}
+
+impl Samples {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            values: Arc::default(),
+            capacity,
+        }
+    }
+
+    /// Records the latest sample, forgetting the oldest one once at capacity.
+    pub fn push(&self, value: f64) {
+        let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        if values.len() == self.capacity {
+            values.pop_front();
+        }
+        if self.capacity > 0 {
+            values.push_back(value);
+        }
+    }
+
+    /// The latest `count` samples, oldest first.
+    pub fn latest(&self, count: usize) -> Vec<f64> {
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        values
+            .iter()
+            .skip(values.len().saturating_sub(count))
+            .copied()
+            .collect()
+    }
+}
+
+/// The `Sparkline` component draws the latest [`Samples`] of a time series as a one-line chart
+/// of block characters, e.g. `actions/s 3 ▁▂▃▅▇▅▃ 42`, one sample per cell. Useful to show how
+/// throughput or memory use evolves over a build rather than only its current level.
+///
+/// The chart is scaled between the lowest and highest samples shown, which are optionally
+/// labeled on either side of it.
+#[derive(Debug)]
+pub struct Sparkline {
+    samples: Samples,
+    label: Option<String>,
+    width: Option<usize>,
+    range: bool,
+    format: fn(f64) -> String,
+    color: Option<Color>,
+}
+
+impl Sparkline {
+    pub fn new(samples: Samples) -> Self {
+        Self {
+            samples,
+            label: None,
+            width: None,
+            range: false,
+            format: format_sample,
+            color: None,
+        }
+    }
+
+    /// Shows `label` before the chart.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The number of samples shown. By default, the chart takes up the width left by the labels.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Shows the lowest sample before the chart and the highest one after it.
+    pub fn range(mut self, range: bool) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// How the lowest and highest samples are labeled, e.g. `|v| human::bytes(v as u64)`.
+    pub fn format(mut self, format: fn(f64) -> String) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// Formats whole samples without decimals, and others with one.
+fn format_sample(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// The chart of `samples`, from the lowest to the highest of `levels`.
+fn chart(samples: &[f64], levels: &[char]) -> String {
+    let (min, max) = bounds(samples);
+    let top = levels.len() - 1;
+    samples
+        .iter()
+        .map(|value| {
+            let level = if max > min {
+                ((value - min) / (max - min) * top as f64).round() as usize
+            } else {
+                // A flat series is drawn at mid-height, rather than looking empty or full.
+                top / 2
+            };
+            levels[level.min(top)]
+        })
+        .collect()
+}
+
+fn bounds(samples: &[f64]) -> (f64, f64) {
+    samples
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        })
+}
+
+impl Component for Sparkline {
+    /// The chart fills the width left by the label, unless it has a fixed [`width`](Self::width),
+    /// in which case
+    /// the sparkline is as wide as the chart of the samples so far and its labels.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let width = match self.width {
+            Some(_) => self
+                .draw_unchecked(max, DrawMode::Normal)
+                .map_or(max.width, |lines| lines.max_line_length()),
+            None => max.width,
+        };
+        Dimensions::new(width, 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut line = Line::default();
+        if let Some(label) = &self.label {
+            line.push(Span::sanitized(format!("{} ", label)));
+        }
+
+        let labels = |samples: &[f64]| {
+            if !self.range || samples.is_empty() {
+                return (String::new(), String::new());
+            }
+            let (min, max) = bounds(samples);
+            (
+                format!("{} ", (self.format)(min)),
+                format!(" {}", (self.format)(max)),
+            )
+        };
+        let mut samples = self.samples.latest(
+            self.width
+                .unwrap_or_else(|| dimensions.width.saturating_sub(line.len())),
+        );
+        let (mut low, mut high) = labels(&samples);
+        if self.width.is_none() && !low.is_empty() {
+            // Make room for the labels of the samples filling the width, then label those that
+            // still fit instead.
+            let width = dimensions
+                .width
+                .saturating_sub(line.len() + low.len() + high.len());
+            samples = self.samples.latest(width);
+            (low, high) = labels(&samples);
+        }
+
+        let levels: &[char] = if unicode_enabled() {
+            &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█']
+        } else {
+            &['_', '.', '-', '=', '+', '*', '#', '@']
+        };
+        line.push(styled_span(StyleRole::Dim, &low));
+        let chart = chart(&samples, levels);
+        line.push(match self.color {
+            Some(color) => Span::new_colored_lossy(&chart, color),
+            None => Span::new_unstyled_lossy(chart),
+        });
+        line.push(styled_span(StyleRole::Dim, &high));
+        Ok(Lines(vec![line]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_sparkline() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let samples = Samples::new(100);
+            for value in [0.0, 1.0, 2.0, 4.0, 6.0, 7.0, 5.0, 3.0] {
+                samples.push(value);
+            }
+            let sparkline = Sparkline::new(samples.clone()).label("actions/s");
+            let output = sparkline.draw(Dimensions::new(40, 1), DrawMode::Normal)?;
+            assert_eq!(output.0[0].to_unstyled(), "actions/s ▁▂▃▅▇█▆▄");
+            let output = with_unicode(false, || {
+                sparkline.draw(Dimensions::new(40, 1), DrawMode::Normal)
+            })?;
+            assert_eq!(output.0[0].to_unstyled(), "actions/s _.-+#@*=");
+
+            // Only the latest samples fit between the range labels.
+            let sparkline = Sparkline::new(samples.clone()).range(true);
+            let output = sparkline.draw(Dimensions::new(7, 1), DrawMode::Normal)?;
+            assert_eq!(output.0[0].to_unstyled(), "3 █▅▁ 7");
+
+            let flat = Samples::new(3);
+            for _ in 0..5 {
+                flat.push(1.5);
+            }
+            assert_eq!(flat.latest(10), vec![1.5; 3]);
+            let output = Sparkline::new(flat)
+                .range(true)
+                .draw(Dimensions::new(40, 1), DrawMode::Normal)?;
+            assert_eq!(output.0[0].to_unstyled(), "1.5 ▄▄▄ 1.5");
+
+            Ok(())
+        })
+    }
+}
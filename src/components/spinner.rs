@@ -0,0 +1,223 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::style::ContentStyle;
+use crate::style::StyledContent;
+use crate::theme::theme;
+use crate::theme::Palette;
+use crate::theme::SpinnerStyle;
+use crate::theme::Status;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(80);
+
+/// The `Spinner` component cycles through a set of frames to show that work is ongoing, followed by
+/// an optional label. Frames advance with the wall clock, or with an explicit tick counter.
+/// The final draw shows a success or failure symbol in place of the spinner.
+#[derive(Debug)]
+pub struct Spinner {
+    frames: &'static [&'static str],
+    interval: Duration,
+    start: Instant,
+    ticks: Option<Arc<AtomicU64>>,
+    label: Option<String>,
+    /// The palette given with [`palette`](Self::palette), or else that of the current theme.
+    palette: Option<Palette>,
+    failed: AtomicBool,
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Spinner {
+    /// A spinner in the style the terminal likely supports, see [`SpinnerStyle::detect`].
+    pub fn new() -> Self {
+        Self {
+            frames: SpinnerStyle::detect().frames(),
+            interval: DEFAULT_INTERVAL,
+            start: Instant::now(),
+            ticks: None,
+            label: None,
+            palette: None,
+            failed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn style(mut self, style: SpinnerStyle) -> Self {
+        self.frames = style.frames();
+        self
+    }
+
+    /// Cycles through custom frames, which should all have the same width.
+    pub fn frames(mut self, frames: &'static [&'static str]) -> Self {
+        assert!(!frames.is_empty(), "Spinner must have frames");
+        self.frames = frames;
+        self
+    }
+
+    /// How long each frame is shown for, when advancing with the wall clock.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Advances a frame whenever `ticks` is incremented, rather than with the wall clock.
+    pub fn ticks(mut self, ticks: Arc<AtomicU64>) -> Self {
+        self.ticks = Some(ticks);
+        self
+    }
+
+    /// Shows `label` after the spinner.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The colors of the final symbol.
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Whether the final draw shows failure rather than success.
+    pub fn set_failed(&self, failed: bool) {
+        self.failed.store(failed, Ordering::Relaxed);
+    }
+
+    fn frame_at(&self, now: Instant) -> &'static str {
+        let index = match &self.ticks {
+            Some(ticks) => ticks.load(Ordering::Relaxed),
+            None if self.interval.is_zero() => 0,
+            None => {
+                let elapsed = now.saturating_duration_since(self.start);
+                (elapsed.as_nanos() / self.interval.as_nanos()) as u64
+            }
+        };
+        self.frames[(index % self.frames.len() as u64) as usize]
+    }
+
+    fn line(&self, mode: DrawMode) -> Line {
+        let theme = theme();
+        let palette = self.palette.as_ref().unwrap_or(&theme.palette);
+        let mut line = match mode {
+            DrawMode::Normal => Line::from_iter([Span::new_styled_lossy(StyledContent::new(
+                ContentStyle {
+                    foreground_color: Some(palette.highlight),
+                    ..ContentStyle::default()
+                },
+                self.frame_at(Instant::now()).to_owned(),
+            ))]),
+            DrawMode::Final => {
+                let status = if self.failed.load(Ordering::Relaxed) {
+                    Status::Failure
+                } else {
+                    Status::Success
+                };
+                Line::from_iter([palette.status_span(status)])
+            }
+        };
+        if let Some(label) = &self.label {
+            line.push(Span::sanitized(format!(" {}", label)));
+        }
+        line
+    }
+}
+
+impl Component for Spinner {
+    /// As wide as a frame of the animation and the label.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        Dimensions::new(self.line(DrawMode::Normal).len(), 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, _dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        Ok(Lines(vec![self.line(mode)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::DrawContext;
+    use crate::testing::with_unicode;
+    use crate::theme::Theme;
+
+    #[test]
+    fn test_frames() {
+        let spinner = Spinner::new()
+            .style(SpinnerStyle::Ascii)
+            .interval(Duration::from_millis(100));
+        let start = spinner.start;
+        assert_eq!(spinner.frame_at(start), "-");
+        assert_eq!(spinner.frame_at(start + Duration::from_millis(250)), "|");
+        assert_eq!(spinner.frame_at(start + Duration::from_millis(400)), "-");
+
+        let ticks = Arc::new(AtomicU64::new(0));
+        let spinner = Spinner::new().frames(&["a", "b"]).ticks(ticks.clone());
+        assert_eq!(spinner.frame_at(Instant::now()), "a");
+        ticks.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(spinner.frame_at(Instant::now()), "b");
+    }
+
+    #[test]
+    fn test_final() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let spinner = Spinner::new().label("building");
+            let output = spinner.draw(Dimensions::new(20, 1), DrawMode::Final)?;
+            assert_eq!(output.0[0].to_unstyled(), "✓ building");
+            let output = with_unicode(false, || {
+                spinner.draw(Dimensions::new(20, 1), DrawMode::Final)
+            })?;
+            assert_eq!(output.0[0].to_unstyled(), "+ building");
+
+            spinner.set_failed(true);
+            let output = spinner.draw(Dimensions::new(20, 1), DrawMode::Final)?;
+            assert_eq!(output.0[0].to_unstyled(), "✗ building");
+            let output = with_unicode(false, || {
+                spinner.draw(Dimensions::new(20, 1), DrawMode::Final)
+            })?;
+            assert_eq!(output.0[0].to_unstyled(), "x building");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_theme_palette() -> anyhow::Result<()> {
+        // Built before the console's theme is entered, as components usually are.
+        let spinner = Spinner::new().frames(&["*"]);
+        let output = DrawContext {
+            theme: Some(Arc::new(Theme {
+                palette: Palette::light(),
+                ..Theme::default()
+            })),
+            ..DrawContext::default()
+        }
+        .scope(|| spinner.draw(Dimensions::new(20, 1), DrawMode::Normal))?;
+        assert_eq!(
+            output.0[0],
+            Line::from_iter([Span::new_colored_lossy("*", Palette::light().highlight)])
+        );
+        Ok(())
+    }
+}
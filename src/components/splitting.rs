@@ -10,6 +10,7 @@
 //! Splitting is one of the most primitive building blocks of a fully featured UI.
 //! This module contains components and enums that allow for splits along either dimension.
 
+use std::cmp;
 use std::fmt::Debug;
 
 use crate::Component;
@@ -28,6 +29,130 @@ pub enum SplitKind {
     // Each child may take as much space as it would like along the given dimension.
     // No extra padding is given between children.
     Adaptive,
+    // Each child is sized by its constraint, e.g. a fixed size header above a flexible body.
+    Constrained(Vec<Constraint>),
+    // Each child takes up its desired size along the given dimension, see
+    // [`Component::desired_size`]. When they don't all fit, the children wanting less than an
+    // equal share keep their size, and the others share the rest equally.
+    Content,
+}
+
+/// The size of a child of a [`SplitKind::Constrained`] split along the split direction.
+///
+/// Fixed children get their size first, and weighted children share the remainder in proportion
+/// to their weights, but get at least their minimum. When the space can't fit all fixed sizes and
+/// minimums, the children last in order are shrunk first, down to nothing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Constraint {
+    /// Exactly this many rows or columns.
+    Fixed(usize),
+    /// A share of the remainder proportional to `weight`, of at least `min` rows or columns.
+    Weighted { weight: f64, min: usize },
+}
+
+impl Constraint {
+    /// A share of the remainder proportional to `weight`, with no minimum.
+    pub fn weighted(weight: f64) -> Self {
+        Self::Weighted { weight, min: 0 }
+    }
+
+    /// The least size the child can be given before the split overflows.
+    fn min(&self) -> usize {
+        match *self {
+            Constraint::Fixed(size) => size,
+            Constraint::Weighted { min, .. } => min,
+        }
+    }
+
+    /// Distributes `total` rows or columns among children with the given constraints.
+    fn allocate(constraints: &[Constraint], total: usize) -> Vec<usize> {
+        let mut sizes: Vec<usize> = constraints.iter().map(Constraint::min).collect();
+
+        let needed: usize = sizes.iter().sum();
+        if needed >= total {
+            let mut remaining = total;
+            for size in &mut sizes {
+                *size = cmp::min(*size, remaining);
+                remaining -= *size;
+            }
+            return sizes;
+        }
+
+        // Weighted children whose share falls below their minimum keep it, which leaves less
+        // to share among the others, so repeat until every share is above its minimum.
+        let fixed: usize = constraints
+            .iter()
+            .filter_map(|c| match c {
+                Constraint::Fixed(size) => Some(*size),
+                Constraint::Weighted { .. } => None,
+            })
+            .sum();
+        let mut flexible: Vec<(usize, f64)> = constraints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| match c {
+                Constraint::Weighted { weight, .. } => Some((i, weight.max(0.0))),
+                Constraint::Fixed(_) => None,
+            })
+            .collect();
+        let mut available = total - fixed;
+        loop {
+            let weights: f64 = flexible.iter().map(|(_, weight)| weight).sum();
+            let clamped: Vec<usize> = flexible
+                .iter()
+                .filter(|(i, weight)| {
+                    weights <= 0.0 || (available as f64 * weight / weights) < sizes[*i] as f64
+                })
+                .map(|(i, _)| *i)
+                .collect();
+            if clamped.is_empty() || clamped.len() == flexible.len() {
+                break;
+            }
+            available -= clamped.iter().map(|i| sizes[*i]).sum::<usize>();
+            flexible.retain(|(i, _)| !clamped.contains(i));
+        }
+
+        let weights: f64 = flexible.iter().map(|(_, weight)| weight).sum();
+        if weights <= 0.0 {
+            return sizes;
+        }
+        let mut given = 0;
+        for (i, weight) in &flexible {
+            sizes[*i] = (available as f64 * weight / weights) as usize;
+            given += sizes[*i];
+        }
+        // Hand out the cells lost to rounding down, one each.
+        for (i, _) in flexible.iter().take(available.saturating_sub(given)) {
+            sizes[*i] += 1;
+        }
+        sizes
+    }
+}
+
+/// Distributes `total` rows or columns among children wanting the `desired` sizes, see
+/// [`SplitKind::Content`].
+fn fit_content(desired: &[usize], total: usize) -> Vec<usize> {
+    let mut sizes = vec![0; desired.len()];
+    let mut remaining = total;
+    let mut open: Vec<usize> = (0..desired.len()).collect();
+    while !open.is_empty() {
+        let share = remaining / open.len();
+        let (fits, rest): (Vec<usize>, Vec<usize>) =
+            open.iter().partition(|i| desired[**i] <= share);
+        if fits.is_empty() {
+            // Hand out the cells lost to rounding down, one each.
+            for (n, i) in open.iter().enumerate() {
+                sizes[*i] = share + usize::from(n < remaining % open.len());
+            }
+            break;
+        }
+        for i in fits {
+            sizes[i] = desired[i];
+            remaining -= desired[i];
+        }
+        open = rest;
+    }
+    sizes
 }
 
 /// Internally, we want to alias away the Equal case
@@ -35,6 +160,8 @@ pub enum SplitKind {
 enum InternalSplitKind {
     SizedNormalized(Vec<f64>),
     Adaptive,
+    Constrained(Vec<Constraint>),
+    Content,
 }
 
 impl SplitKind {
@@ -56,6 +183,16 @@ impl SplitKind {
                 InternalSplitKind::SizedNormalized(vec![1.0 / children_len as f64; children_len])
             }
             SplitKind::Adaptive => InternalSplitKind::Adaptive,
+            SplitKind::Constrained(constraints) => {
+                assert_eq!(
+                    constraints.len(),
+                    children_len,
+                    "There must be an equal number of constraints and children."
+                );
+
+                InternalSplitKind::Constrained(constraints.clone())
+            }
+            SplitKind::Content => InternalSplitKind::Content,
         }
     }
 }
@@ -73,26 +210,43 @@ impl InternalSplitKind {
             InternalSplitKind::SizedNormalized(sizes) => children
                 .into_iter()
                 .zip(sizes.iter())
-                .map(|(child, size)| -> anyhow::Result<_> {
+                .map(|(child, size)| {
                     // allocate alloted size
-                    let child_dimension = dimensions.multiply(*size, direction);
-                    let mut output = child.draw(child_dimension, mode)?;
-
-                    // bound non-splitting direction, pad splitting direction
-                    match direction {
-                        Direction::Horizontal => {
-                            output.truncate_lines_bottom(child_dimension.height);
-                            output.set_lines_to_exact_width(child_dimension.width);
-                        }
-                        Direction::Vertical => {
-                            output.truncate_lines(child_dimension.width);
-                            output.set_lines_to_exact_length(child_dimension.height);
-                        }
-                    }
-
-                    Ok(output)
+                    draw_exact(
+                        child,
+                        dimensions.multiply(*size, direction),
+                        direction,
+                        mode,
+                    )
                 })
                 .collect(),
+            InternalSplitKind::Constrained(constraints) => {
+                let sizes = Constraint::allocate(constraints, dimensions.dimension(direction));
+                children
+                    .into_iter()
+                    .zip(sizes)
+                    .map(|(child, size)| {
+                        let child_dimension = dimensions.with_dimension(size, direction);
+                        draw_exact(child, child_dimension, direction, mode)
+                    })
+                    .collect()
+            }
+            InternalSplitKind::Content => {
+                let children: Vec<&C> = children.into_iter().collect();
+                let desired: Vec<usize> = children
+                    .iter()
+                    .map(|child| child.desired_size(dimensions).dimension(direction))
+                    .collect();
+                let sizes = fit_content(&desired, dimensions.dimension(direction));
+                children
+                    .into_iter()
+                    .zip(sizes)
+                    .map(|(child, size)| {
+                        let child_dimension = dimensions.with_dimension(size, direction);
+                        draw_exact(child, child_dimension, direction, mode)
+                    })
+                    .collect()
+            }
             InternalSplitKind::Adaptive => {
                 let mut available = dimensions;
                 children
@@ -113,6 +267,31 @@ impl InternalSplitKind {
     }
 }
 
+/// Draws a child, bounding the non-splitting direction and padding the splitting direction so
+/// that the output is exactly `child_dimension` along it.
+fn draw_exact<C: Component>(
+    child: &C,
+    child_dimension: Dimensions,
+    direction: Direction,
+    mode: DrawMode,
+) -> anyhow::Result<Lines> {
+    let mut output = child.draw(child_dimension, mode)?;
+
+    // bound non-splitting direction, pad splitting direction
+    match direction {
+        Direction::Horizontal => {
+            output.truncate_lines_bottom(child_dimension.height);
+            output.set_lines_to_exact_width(child_dimension.width);
+        }
+        Direction::Vertical => {
+            output.truncate_lines(child_dimension.width);
+            output.set_lines_to_exact_length(child_dimension.height);
+        }
+    }
+
+    Ok(output)
+}
+
 /// [`Splits`](SplitKind) along a given [`direction`](crate::Direction) for its child [`components`](Component).
 /// Child components are truncated to the bounds passed to them.
 pub struct Split<C = Box<dyn Component>> {
@@ -136,6 +315,7 @@ impl<C: Component> Split<C> {
     /// * At least one child.
     /// * If Sized, then ratios must sum to approximately 1.
     /// * If Sized, then there must be as many ratios as components
+    /// * If Constrained, then there must be as many constraints as components
     pub fn new(children: Vec<C>, direction: Direction, split: SplitKind) -> Self {
         let split = split.to_internal_split_kind(children.len());
 
@@ -148,6 +328,51 @@ impl<C: Component> Split<C> {
 }
 
 impl<C: Component> Component for Split<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        for child in &self.children {
+            child.on_resize(dimensions);
+        }
+    }
+
+    fn on_attach(&self) {
+        self.children.iter().for_each(|child| child.on_attach());
+    }
+
+    fn on_finalize(&self) {
+        self.children.iter().for_each(|child| child.on_finalize());
+    }
+
+    fn on_detach(&self) {
+        self.children.iter().for_each(|child| child.on_detach());
+    }
+
+    /// Splits which only take the space their children want, adaptive and content ones, want the
+    /// sum of their children's sizes. Others fill the split direction.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let desired: Vec<Dimensions> = self
+            .children
+            .iter()
+            .map(|child| child.desired_size(max))
+            .collect();
+        let along = match self.split {
+            InternalSplitKind::Adaptive | InternalSplitKind::Content => cmp::min(
+                desired.iter().map(|d| d.dimension(self.direction)).sum(),
+                max.dimension(self.direction),
+            ),
+            InternalSplitKind::SizedNormalized(_) | InternalSplitKind::Constrained(_) => {
+                max.dimension(self.direction)
+            }
+        };
+        let across = self.direction.flip();
+        let across_size = desired
+            .iter()
+            .map(|d| d.dimension(across))
+            .max()
+            .unwrap_or_default();
+        max.with_dimension(along, self.direction)
+            .with_dimension(across_size, across)
+    }
+
     fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
         let outputs = self
             .split
@@ -164,7 +389,6 @@ impl<C: Component> Component for Split<C> {
 mod tests {
     use std::iter;
 
-    use derive_more::AsRef;
 
     use super::Split;
     use super::SplitKind;
@@ -174,14 +398,6 @@ mod tests {
     use crate::Line;
     use crate::Lines;
 
-    #[derive(AsRef, Debug)]
-    struct Echo1(Lines);
-    #[derive(AsRef, Debug)]
-    struct Echo2(Lines);
-
-    #[derive(AsRef, Debug)]
-    struct Echo3(Lines);
-
     mod horizontal {
         use super::*;
         use crate::components::echo::Echo;
@@ -349,9 +565,9 @@ mod tests {
             );
 
             let mut output = top;
-            output.0.extend(iter::repeat(Line::default()).take(8));
+            output.0.extend(iter::repeat_n(Line::default(), 8));
             output.0.append(&mut bottom.0);
-            output.0.extend(iter::repeat(Line::default()).take(7));
+            output.0.extend(iter::repeat_n(Line::default(), 7));
 
             let drawn = splitter
                 .draw(Dimensions::new(20, 20), DrawMode::Normal)
@@ -438,6 +654,145 @@ mod tests {
         }
     }
 
+    mod constrained {
+        use super::*;
+        use crate::components::echo::Echo;
+        use crate::components::splitting::Constraint;
+        use crate::Dimensions;
+
+        #[test]
+        fn test_allocate() {
+            let header_and_body = [Constraint::Fixed(3), Constraint::weighted(1.0)];
+            assert_eq!(Constraint::allocate(&header_and_body, 10), vec![3, 7]);
+            assert_eq!(Constraint::allocate(&header_and_body, 2), vec![2, 0]);
+
+            let weighted = [
+                Constraint::weighted(1.0),
+                Constraint::Fixed(2),
+                Constraint::weighted(2.0),
+            ];
+            assert_eq!(Constraint::allocate(&weighted, 12), vec![4, 2, 6]);
+            assert_eq!(Constraint::allocate(&weighted, 13), vec![4, 2, 7]);
+
+            let minimum = [
+                Constraint::Weighted {
+                    weight: 1.0,
+                    min: 5,
+                },
+                Constraint::weighted(9.0),
+            ];
+            assert_eq!(Constraint::allocate(&minimum, 10), vec![5, 5]);
+            assert_eq!(Constraint::allocate(&minimum, 4), vec![4, 0]);
+        }
+
+        #[test]
+        fn test_header_and_body() {
+            let header = Lines(vec![vec!["header"].try_into().unwrap()]);
+            let body = Lines(vec![
+                vec!["body 1"].try_into().unwrap(),
+                vec!["body 2"].try_into().unwrap(),
+            ]);
+            let splitter = Split::new(
+                vec![Echo(header.clone()), Echo(body.clone())],
+                Direction::Vertical,
+                SplitKind::Constrained(vec![Constraint::Fixed(3), Constraint::weighted(1.0)]),
+            );
+
+            let mut expected = header;
+            expected.0.extend(iter::repeat_n(Line::default(), 2));
+            expected.0.extend(body.0);
+            expected.0.extend(iter::repeat_n(Line::default(), 3));
+
+            let drawn = splitter
+                .draw(Dimensions::new(10, 8), DrawMode::Normal)
+                .unwrap();
+            assert_eq!(drawn, expected);
+        }
+    }
+
+    mod content {
+        use std::time::Instant;
+
+        use super::*;
+        use crate::charset::LineCharset;
+        use crate::components::echo::Echo;
+        use crate::components::splitting::fit_content;
+        use crate::components::Blank;
+        use crate::components::Framed;
+        use crate::components::Padded;
+        use crate::components::Timer;
+        use crate::Dimensions;
+
+        #[test]
+        fn test_fit_content() {
+            assert_eq!(fit_content(&[2, 10, 3], 20), vec![2, 10, 3]);
+            assert_eq!(fit_content(&[2, 10, 3], 9), vec![2, 4, 3]);
+            assert_eq!(fit_content(&[2, 10, 3], 7), vec![2, 3, 2]);
+        }
+
+        #[test]
+        fn test_content() {
+            let header = Lines(vec![vec!["header"].try_into().unwrap()]);
+            let body = Lines(vec![
+                vec!["body 1"].try_into().unwrap(),
+                vec!["body 2"].try_into().unwrap(),
+            ]);
+            let splitter = Split::new(
+                vec![
+                    Box::new(Echo(header.clone())) as Box<dyn Component>,
+                    Box::new(Blank),
+                    Box::new(Echo(body.clone())),
+                ],
+                Direction::Vertical,
+                SplitKind::Content,
+            );
+            assert_eq!(
+                splitter.desired_size(Dimensions::new(10, 8)),
+                Dimensions::new(6, 3)
+            );
+
+            let mut expected = header;
+            expected.0.extend(body.0);
+            let drawn = splitter
+                .draw(Dimensions::new(10, 8), DrawMode::Normal)
+                .unwrap();
+            assert_eq!(drawn, expected);
+        }
+
+        #[test]
+        fn test_content_wrappers() {
+            let line = |text: &str| Lines(vec![vec![text].try_into().unwrap()]);
+            let splitter = Split::new(
+                vec![
+                    Box::new(Framed::new(Echo(line("framed"))).border(LineCharset::ASCII))
+                        as Box<dyn Component>,
+                    Box::new(Padded::new(Echo(line("padded")), 2, 0, 1, 0)),
+                    Box::new(Timer::new(Instant::now())),
+                ],
+                Direction::Vertical,
+                SplitKind::Content,
+            );
+            // As wide as the frame, which is wider than the padded echo and the timer.
+            assert_eq!(
+                splitter.desired_size(Dimensions::new(20, 10)),
+                Dimensions::new(8, 6)
+            );
+
+            let drawn = splitter
+                .draw(Dimensions::new(20, 10), DrawMode::Normal)
+                .unwrap();
+            let rows: Vec<_> = drawn.iter().map(Line::to_unstyled).collect();
+            let rows: Vec<_> = rows.iter().map(|row| row.trim_end()).collect();
+            assert_eq!(rows.len(), 6);
+            assert!(rows[0].starts_with("+---"));
+            assert!(rows[1].starts_with("|framed "));
+            assert!(rows[2].starts_with("+---"));
+            assert_eq!(rows[3], "");
+            assert_eq!(rows[4], "  padded");
+            assert!(rows[5].starts_with("0:00:0"));
+        }
+    }
+
     mod panics {
         use super::*;
         use crate::components::Blank;
@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::time::Duration;
+
+use crate::charset::warning_sign;
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::watchdog::Heartbeat;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+
+/// The `Stalled` component draws its child, followed by a badge line if the given
+/// [`Heartbeat`] has not beaten within the timeout. This flags data that has gone stale because
+/// whatever produces it stopped making progress.
+#[derive(Debug)]
+pub struct Stalled<C: Component = Box<dyn Component>> {
+    pub child: C,
+    pub heartbeat: Heartbeat,
+    pub timeout: Duration,
+}
+
+impl<C: Component> Stalled<C> {
+    pub fn new(child: C, heartbeat: Heartbeat, timeout: Duration) -> Self {
+        Self {
+            child,
+            heartbeat,
+            timeout,
+        }
+    }
+}
+
+impl<C: Component> Component for Stalled<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    /// The child's desired size, plus a row for the warning while stalled.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let child = self.child.desired_size(max);
+        if self.heartbeat.elapsed() < self.timeout {
+            return child;
+        }
+        Dimensions::new(max.width, child.height + 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let elapsed = self.heartbeat.elapsed();
+        if mode == DrawMode::Final || elapsed < self.timeout {
+            return self.child.draw(dimensions, mode);
+        }
+
+        let mut output = self.child.draw(
+            Dimensions::new(dimensions.width, dimensions.height.saturating_sub(1)),
+            mode,
+        )?;
+        output.push(Line::from_iter([styled_span(
+            StyleRole::Warn,
+            &format!("{} {}", warning_sign(), strings().stalled_for(elapsed)),
+        )]));
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_stalled() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let msg = Lines(vec![vec!["hello"].try_into()?]);
+            let heartbeat = Heartbeat::new();
+
+            let live = Stalled::new(
+                Echo(msg.clone()),
+                heartbeat.clone(),
+                Duration::from_secs(3600),
+            );
+            let output = live.draw(Dimensions::new(20, 20), DrawMode::Normal)?;
+            assert_eq!(output, msg);
+
+            let stalled = Stalled::new(Echo(msg.clone()), heartbeat, Duration::ZERO);
+            let output = stalled.draw(Dimensions::new(20, 20), DrawMode::Normal)?;
+            assert_eq!(output.len(), 2);
+            assert_eq!(output.0[1].to_unstyled(), "⚠ stalled for 0s");
+            let output = with_unicode(false, || {
+                stalled.draw(Dimensions::new(20, 20), DrawMode::Normal)
+            })?;
+            assert_eq!(output.0[1].to_unstyled(), "! stalled for 0s");
+
+            let output = stalled.draw(Dimensions::new(20, 20), DrawMode::Final)?;
+            assert_eq!(output, msg);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use crate::style::ContentStyle;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// The `Styled` component applies a base style to the entire output of its child.
+/// The style only fills in what the child's spans leave unspecified, so e.g. dimming a subtree
+/// keeps the colors chosen by the components inside it.
+#[derive(Debug)]
+pub struct Styled<C: Component = Box<dyn Component>> {
+    pub child: C,
+    pub style: ContentStyle,
+}
+
+impl<C: Component> Styled<C> {
+    pub fn new(child: C, style: ContentStyle) -> Self {
+        Self { child, style }
+    }
+}
+
+impl<C: Component> Component for Styled<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut output = self.child.draw(dimensions, mode)?;
+        output.apply_style_base(self.style);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::style::Color;
+    use crate::style::Stylize;
+    use crate::Line;
+    use crate::Span;
+
+    #[test]
+    fn test_styled() -> anyhow::Result<()> {
+        let msg = Lines(vec![Line::from_iter([
+            Span::new_unstyled("plain ")?,
+            Span::new_colored("red", Color::Red)?,
+        ])]);
+        let component = Styled::new(Echo(msg), *"".to_owned().dim().style());
+
+        let output = component.draw(Dimensions::new(20, 20), DrawMode::Normal)?;
+        assert_eq!(
+            "<span dim>plain </span><span fg=red dim>red</span>\n",
+            output.fmt_for_test().to_string()
+        );
+
+        Ok(())
+    }
+}
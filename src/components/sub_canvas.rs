@@ -0,0 +1,200 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use crate::dirty::DirtyFlag;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+#[derive(Debug)]
+struct Drawn {
+    /// The generation of the flag when drawn.
+    generation: u64,
+    /// The dimensions drawn with, or `None` if cleared, which holds for any dimensions.
+    dimensions: Option<Dimensions>,
+    output: Lines,
+}
+
+/// The `SubCanvas` component wraps a subtree in a canvas of its own, which keeps the output last
+/// drawn by the subtree and reuses it until the subtree's [`DirtyFlag`] is marked, e.g. for a
+/// header showing the build configuration, which shouldn't be redrawn whenever the task list below
+/// it changes. Unlike a [`Region`](crate::components::Region), which refreshes on a timer, a
+/// sub-canvas is only redrawn when told to.
+///
+/// The flag is the sub-canvas's own by default, see [`dirty_flag`](Self::dirty_flag), or can be
+/// shared with the state the subtree draws with [`with_dirty_flag`](Self::with_dirty_flag). The
+/// subtree is also redrawn if the dimensions change, or for the final frame. Marking the flag
+/// doesn't render a frame on its own: frames are still rendered by the console, e.g. when its own
+/// flag is marked too.
+#[derive(Debug)]
+pub struct SubCanvas<C: Component = Box<dyn Component>> {
+    child: C,
+    dirty: DirtyFlag,
+    last: Mutex<Option<Drawn>>,
+}
+
+impl<C: Component> SubCanvas<C> {
+    pub fn new(child: C) -> Self {
+        Self::with_dirty_flag(child, DirtyFlag::new())
+    }
+
+    /// A sub-canvas redrawn whenever `dirty` is marked.
+    pub fn with_dirty_flag(child: C, dirty: DirtyFlag) -> Self {
+        Self {
+            child,
+            dirty,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// The flag to mark whenever the state drawn by the subtree changes.
+    pub fn dirty_flag(&self) -> DirtyFlag {
+        self.dirty.clone()
+    }
+
+    fn last(&self) -> MutexGuard<'_, Option<Drawn>> {
+        self.last.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Forces the subtree to be redrawn in the next frame.
+    pub fn invalidate(&self) {
+        *self.last() = None;
+    }
+
+    /// Blanks the sub-canvas, without drawing the subtree, until the flag is next marked.
+    pub fn clear(&self) {
+        *self.last() = Some(Drawn {
+            generation: self.dirty.generation(),
+            dimensions: None,
+            output: Lines::new(),
+        });
+    }
+
+    /// The output last drawn by the subtree, if any.
+    pub fn last_drawn(&self) -> Option<Lines> {
+        self.last().as_ref().map(|drawn| drawn.output.clone())
+    }
+}
+
+impl<C: Component> Component for SubCanvas<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.invalidate();
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut last = self.last();
+        // Read before drawing, so that marks made meanwhile redraw the next frame.
+        let generation = self.dirty.generation();
+
+        if let (DrawMode::Normal, Some(drawn)) = (mode, &*last) {
+            if drawn.generation == generation
+                && drawn.dimensions.is_none_or(|drawn| drawn == dimensions)
+            {
+                return Ok(drawn.output.clone());
+            }
+        }
+
+        let output = self.child.draw(dimensions, mode)?;
+        *last = Some(Drawn {
+            generation,
+            dimensions: Some(dimensions),
+            output: output.clone(),
+        });
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// Counts its draws.
+    #[derive(Default)]
+    struct Counter(Cell<usize>);
+
+    impl Component for Counter {
+        fn draw_unchecked(
+            &self,
+            _dimensions: Dimensions,
+            _mode: DrawMode,
+        ) -> anyhow::Result<Lines> {
+            self.0.set(self.0.get() + 1);
+            Ok(Lines(vec![
+                vec![self.0.get().to_string().as_str()].try_into()?
+            ]))
+        }
+    }
+
+    fn draw(
+        canvas: &SubCanvas<Counter>,
+        dimensions: Dimensions,
+        mode: DrawMode,
+    ) -> anyhow::Result<Vec<String>> {
+        Ok(canvas
+            .draw(dimensions, mode)?
+            .iter()
+            .map(|line| line.to_unstyled())
+            .collect())
+    }
+
+    #[test]
+    fn test_sub_canvas() -> anyhow::Result<()> {
+        let small = Dimensions::new(10, 10);
+        let large = Dimensions::new(20, 10);
+
+        let canvas = SubCanvas::new(Counter::default());
+        let dirty = canvas.dirty_flag();
+        assert_eq!(draw(&canvas, small, DrawMode::Normal)?, ["1"]);
+        assert_eq!(draw(&canvas, small, DrawMode::Normal)?, ["1"]);
+        dirty.mark();
+        assert_eq!(draw(&canvas, small, DrawMode::Normal)?, ["2"]);
+        // A resize, invalidation or final draw redraws the subtree too.
+        assert_eq!(draw(&canvas, large, DrawMode::Normal)?, ["3"]);
+        canvas.invalidate();
+        assert_eq!(draw(&canvas, large, DrawMode::Normal)?, ["4"]);
+        assert_eq!(draw(&canvas, large, DrawMode::Final)?, ["5"]);
+
+        canvas.clear();
+        assert!(draw(&canvas, small, DrawMode::Normal)?.is_empty());
+        assert_eq!(canvas.last_drawn(), Some(Lines::new()));
+        dirty.mark();
+        assert_eq!(draw(&canvas, small, DrawMode::Normal)?, ["6"]);
+
+        let shared = DirtyFlag::new();
+        let canvas = SubCanvas::with_dirty_flag(Counter::default(), shared.clone());
+        assert_eq!(draw(&canvas, small, DrawMode::Normal)?, ["1"]);
+        shared.mark();
+        assert_eq!(draw(&canvas, small, DrawMode::Normal)?, ["2"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Aligned tabular output.
+//! Column widths are computed from the content, within per-column bounds, and shrunk to fit the
+//! available width. Cells that are too wide are elided or wrapped.
+
+pub use crate::content::Alignment as ColumnAlignment;
+use crate::content::Elision;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+
+/// What happens to cells wider than their column.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Overflow {
+    /// Elided at the end.
+    #[default]
+    Truncate,
+    /// Word wrapped over several rows.
+    Wrap,
+}
+
+/// The layout of a column.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Column {
+    /// The column is never shrunk below this width, space permitting.
+    pub min_width: usize,
+    /// The column is never wider than this, even if its content is.
+    pub max_width: Option<usize>,
+    pub alignment: ColumnAlignment,
+    pub overflow: Overflow,
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Self {
+            min_width: 1,
+            max_width: None,
+            alignment: ColumnAlignment::Left,
+            overflow: Overflow::Truncate,
+        }
+    }
+}
+
+/// The `Table` component draws rows of cells in aligned columns, with an optional header row.
+/// Rows with fewer cells than there are columns are padded with empty cells.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    columns: Vec<Column>,
+    header: Option<Vec<Line>>,
+    rows: Vec<Vec<Line>>,
+    gap: usize,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            header: None,
+            rows: Vec::new(),
+            gap: 2,
+        }
+    }
+
+    /// Sets a header row, drawn above the others.
+    pub fn header(mut self, header: Vec<Line>) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// The number of spaces between columns. Defaults to 2.
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn push_row(&mut self, row: Vec<Line>) {
+        self.rows.push(row);
+    }
+
+    fn all_rows(&self) -> impl Iterator<Item = &Vec<Line>> {
+        self.header.iter().chain(&self.rows)
+    }
+
+    /// The width of each column, given `width` columns in total.
+    fn column_widths(&self, width: usize) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let content = self
+                    .all_rows()
+                    .filter_map(|row| row.get(i))
+                    .map(Line::len)
+                    .max()
+                    .unwrap_or(0);
+                let content = content.max(column.min_width);
+                column.max_width.map_or(content, |max| content.min(max))
+            })
+            .collect();
+
+        let gaps = self.gap * self.columns.len().saturating_sub(1);
+        let available = width.saturating_sub(gaps);
+        let mut total: usize = widths.iter().sum();
+        // Shrink the widest columns first, down to their minimum widths.
+        while total > available {
+            let widest = widths
+                .iter()
+                .enumerate()
+                .filter(|(i, w)| **w > self.columns[*i].min_width)
+                .max_by_key(|(_, w)| **w)
+                .map(|(i, _)| i);
+            match widest {
+                Some(i) => {
+                    widths[i] -= 1;
+                    total -= 1;
+                }
+                None => break,
+            }
+        }
+        widths
+    }
+
+    fn draw_row(&self, row: &[Line], widths: &[usize], output: &mut Lines) {
+        let cells: Vec<Lines> = self
+            .columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (column, &width))| {
+                let cell = row.get(i).cloned().unwrap_or_default();
+                let mut lines = match column.overflow {
+                    Overflow::Wrap if width > 0 => cell.wrap(width),
+                    _ => {
+                        let mut cell = cell;
+                        cell.elide(width, Elision::End);
+                        Lines(vec![cell])
+                    }
+                };
+                for line in lines.iter_mut() {
+                    line.pad_to(width, column.alignment);
+                }
+                lines
+            })
+            .collect();
+
+        let height = cells.iter().map(Lines::len).max().unwrap_or(0);
+        for r in 0..height {
+            let mut line = Line::default();
+            for (i, (cell, &width)) in cells.iter().zip(widths).enumerate() {
+                if i > 0 {
+                    line.pad_right(self.gap);
+                }
+                match cell.0.get(r) {
+                    Some(cell) => line.extend(cell.iter().cloned()),
+                    None => line.pad_right(width),
+                }
+            }
+            output.push(line);
+        }
+    }
+}
+
+impl Component for Table {
+    /// The size of the output, measured by drawing it, which is cheap.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        match self.draw_unchecked(max, DrawMode::Normal) {
+            Ok(output) => Dimensions::new(output.max_line_length(), output.len()).intersect(max),
+            Err(_) => max,
+        }
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        let widths = self.column_widths(dimensions.width);
+        let mut output = Lines::new();
+        for row in self.all_rows() {
+            if output.len() >= dimensions.height {
+                break;
+            }
+            self.draw_row(row, &widths, &mut output);
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::draw_to_strings;
+    use crate::testing::with_unicode;
+
+    fn row(cells: &[&str]) -> anyhow::Result<Vec<Line>> {
+        cells.iter().map(|cell| Line::unstyled(cell)).collect()
+    }
+
+    #[test]
+    fn test_table() -> anyhow::Result<()> {
+        let mut table = Table::new(vec![
+            Column::default(),
+            Column {
+                alignment: ColumnAlignment::Right,
+                ..Column::default()
+            },
+        ])
+        .header(row(&["name", "size"])?);
+        table.push_row(row(&["lib.rs", "12"])?);
+        table.push_row(row(&["superconsole.rs", "1024"])?);
+
+        assert_eq!(
+            draw_to_strings(&table, Dimensions::new(40, 10), DrawMode::Normal)?,
+            vec![
+                "name             size",
+                "lib.rs             12",
+                "superconsole.rs  1024",
+            ]
+        );
+
+        // The widest column shrinks to fit.
+        let draw = |unicode| {
+            with_unicode(unicode, || {
+                draw_to_strings(&table, Dimensions::new(14, 10), DrawMode::Normal)
+            })
+        };
+        assert_eq!(draw(true)?[2], "superco…  1024");
+        assert_eq!(draw(false)?[2], "super...  1024");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrap() -> anyhow::Result<()> {
+        let mut table = Table::new(vec![
+            Column {
+                max_width: Some(5),
+                overflow: Overflow::Wrap,
+                ..Column::default()
+            },
+            Column::default(),
+        ])
+        .gap(1);
+        table.push_row(row(&["aaa bbb", "x"])?);
+
+        assert_eq!(
+            draw_to_strings(&table, Dimensions::new(40, 10), DrawMode::Normal)?,
+            vec!["aaa   x", "bbb    "]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,294 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The in-flight tasks of a build, one per row with how long each has been running.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::charset::ellipsis;
+use crate::content::Alignment;
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::theme::theme;
+use crate::theme::Palette;
+use crate::theme::Status;
+use crate::theme::StyleRole;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// Identifies a task of a [`TaskList`], as returned by [`TaskList::start`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TaskId(u64);
+
+/// A task shown by a [`TaskList`].
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub name: Line,
+    pub status: Status,
+    pub started: Instant,
+}
+
+impl Task {
+    /// How long the task has been running at `now`.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.started)
+    }
+}
+
+/// The order of the rows of a [`TaskList`].
+#[derive(Debug, Copy, Clone, Default)]
+pub enum TaskOrder {
+    /// The longest running tasks first.
+    #[default]
+    Oldest,
+    /// The latest started tasks first.
+    Newest,
+    /// By name.
+    Name,
+    /// By a custom comparison, e.g. failures first.
+    Custom(fn(&Task, &Task) -> Ordering),
+}
+
+#[derive(Debug, Default)]
+struct Tasks {
+    next_id: u64,
+    tasks: BTreeMap<TaskId, Task>,
+}
+
+/// The `TaskList` component draws one row per in-flight task: a status glyph, the task's name,
+/// and how long it has been running, aligned to the right, e.g. `… //app:lib  12s`.
+///
+/// Tasks are added with [`start`](Self::start) and removed with [`finish`](Self::finish) by the
+/// caller, typically from other threads: clones share the tasks, so one can be kept to update them
+/// while another is drawn. When there are more tasks than rows, the middle of the list is
+/// replaced with a row counting the tasks left out, e.g. `… and 37 more`.
+#[derive(Debug, Clone)]
+pub struct TaskList {
+    tasks: Arc<Mutex<Tasks>>,
+    order: TaskOrder,
+    /// The palette given with [`palette`](Self::palette), or else that of the current theme.
+    palette: Option<Palette>,
+}
+
+impl Default for TaskList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskList {
+    pub fn new() -> Self {
+        Self {
+            tasks: Arc::default(),
+            order: TaskOrder::default(),
+            palette: None,
+        }
+    }
+
+    pub fn order(mut self, order: TaskOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// The colors of the status glyphs.
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    fn tasks(&self) -> MutexGuard<'_, Tasks> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Adds a pending task named `name`, running from now.
+    pub fn start(&self, name: Line) -> TaskId {
+        self.start_at(name, Instant::now())
+    }
+
+    /// Adds a pending task named `name`, running from `started`.
+    pub fn start_at(&self, name: Line, started: Instant) -> TaskId {
+        let mut tasks = self.tasks();
+        let id = TaskId(tasks.next_id);
+        tasks.next_id += 1;
+        tasks.tasks.insert(
+            id,
+            Task {
+                name,
+                status: Status::Pending,
+                started,
+            },
+        );
+        id
+    }
+
+    /// Changes the glyph of task `id`, e.g. to flag a task which is retrying.
+    pub fn set_status(&self, id: TaskId, status: Status) {
+        if let Some(task) = self.tasks().tasks.get_mut(&id) {
+            task.status = status;
+        }
+    }
+
+    /// Removes task `id`, returning it if it was still listed.
+    pub fn finish(&self, id: TaskId) -> Option<Task> {
+        self.tasks().tasks.remove(&id)
+    }
+
+    /// The number of tasks listed.
+    pub fn len(&self) -> usize {
+        self.tasks().tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn row(&self, task: &Task, width: usize, now: Instant) -> Line {
+        let elapsed = strings().duration(task.elapsed(now));
+        let theme = theme();
+        let palette = self.palette.as_ref().unwrap_or(&theme.palette);
+        let mut line = Line::from_iter([palette.status_span(task.status)]);
+        line.push(Span::padding(1));
+        let mut name = task.name.clone();
+        // Keep the elapsed time, with a space before it, even if the name is elided.
+        name.truncate_with_ellipsis(width.saturating_sub(line.len() + elapsed.len() + 1));
+        line.extend(name);
+        line.pad_to(width.saturating_sub(elapsed.len()), Alignment::Left);
+        line.push(styled_span(StyleRole::Dim, &elapsed));
+        line
+    }
+
+    fn draw_at(&self, dimensions: Dimensions, now: Instant) -> Lines {
+        let mut tasks: Vec<Task> = self.tasks().tasks.values().cloned().collect();
+        match self.order {
+            // The sort is stable, so tasks started together stay in the order they were added.
+            TaskOrder::Oldest => tasks.sort_by_key(|task| task.started),
+            TaskOrder::Newest => tasks.sort_by_key(|task| std::cmp::Reverse(task.started)),
+            TaskOrder::Name => tasks.sort_by_key(|task| task.name.to_unstyled()),
+            TaskOrder::Custom(compare) => tasks.sort_by(compare),
+        }
+
+        let rows = dimensions.height;
+        let mut output = Lines::new();
+        if tasks.len() <= rows {
+            for task in &tasks {
+                output.push(self.row(task, dimensions.width, now));
+            }
+            return output;
+        }
+        if rows == 0 {
+            return output;
+        }
+        let shown = rows - 1;
+        let tail = shown / 2;
+        let head = shown - tail;
+        for task in &tasks[..head] {
+            output.push(self.row(task, dimensions.width, now));
+        }
+        let hidden = tasks.len() - shown;
+        output.push(Line::from_iter([styled_span(
+            StyleRole::Dim,
+            &format!("{} {}", ellipsis(), strings().and_more(hidden)),
+        )]));
+        for task in &tasks[tasks.len() - tail..] {
+            output.push(self.row(task, dimensions.width, now));
+        }
+        output
+    }
+}
+
+impl Component for TaskList {
+    /// As many rows as there are tasks.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        Dimensions::new(max.width, self.len()).intersect(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        Ok(self.draw_at(dimensions, Instant::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::lines_to_strings;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_task_list() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let now = Instant::now();
+            let ago = |secs| now - Duration::from_secs(secs);
+            let list = TaskList::new();
+            let tasks = list.clone();
+            let lib = tasks.start_at(Line::unstyled("//app:lib")?, ago(12));
+            tasks.start_at(Line::unstyled("//app:bin")?, ago(75));
+            tasks.start_at(Line::unstyled("//app:test_with_a_long_name")?, ago(3));
+
+            let rows =
+                |list: &TaskList, dimensions| lines_to_strings(&list.draw_at(dimensions, now));
+            assert_eq!(
+                rows(&list, Dimensions::new(20, 10)),
+                [
+                    "… //app:bin    1m15s",
+                    "… //app:lib      12s",
+                    "… //app:test_wit… 3s",
+                ]
+            );
+            assert_eq!(
+                with_unicode(false, || rows(&list, Dimensions::new(20, 10))),
+                [
+                    "... //app:bin  1m15s",
+                    "... //app:lib    12s",
+                    "... //app:test... 3s",
+                ]
+            );
+
+            tasks.set_status(lib, Status::Failure);
+            let list = list.order(TaskOrder::Newest);
+            assert_eq!(
+                rows(&list, Dimensions::new(20, 3)),
+                [
+                    "… //app:test_wit… 3s",
+                    "✗ //app:lib      12s",
+                    "… //app:bin    1m15s",
+                ]
+            );
+
+            for i in 0..37 {
+                tasks.start_at(Line::unstyled(&format!("//lib:{}", i))?, ago(1));
+            }
+            let list = list.order(TaskOrder::Oldest);
+            assert_eq!(
+                rows(&list, Dimensions::new(20, 4)),
+                [
+                    "… //app:bin    1m15s",
+                    "✗ //app:lib      12s",
+                    "… and 37 more",
+                    "… //lib:36        1s",
+                ]
+            );
+
+            assert!(tasks.finish(lib).is_some());
+            assert!(tasks.finish(lib).is_none());
+            assert_eq!(tasks.len(), 39);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::human;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// The `Timer` component displays the time elapsed since it was started as `h:mm:ss`, e.g.
+/// `elapsed: 0:01:05`, optionally with fractions of a second. The final draw freezes the time, so
+/// that it keeps showing how long the whole run took.
+#[derive(Debug)]
+pub struct Timer {
+    start: Instant,
+    label: Option<String>,
+    decimals: usize,
+    /// The elapsed time as of the first final draw.
+    frozen: Mutex<Option<Duration>>,
+}
+
+impl Timer {
+    /// A timer counting from `start`.
+    pub fn new(start: Instant) -> Self {
+        Self {
+            start,
+            label: None,
+            decimals: 0,
+            frozen: Mutex::new(None),
+        }
+    }
+
+    /// A timer counting from now.
+    pub fn started_now() -> Self {
+        Self::new(Instant::now())
+    }
+
+    /// Shows `label: ` before the time.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Shows `decimals` digits of the fraction of a second, at most 9.
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// The time shown: the time since the start, or as of the final draw.
+    pub fn elapsed(&self) -> Duration {
+        self.frozen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .unwrap_or_else(|| self.start.elapsed())
+    }
+
+    fn freeze(&self) -> Duration {
+        *self
+            .frozen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| self.start.elapsed())
+    }
+
+    fn line(&self, elapsed: Duration) -> Line {
+        let elapsed = human::elapsed(elapsed, self.decimals);
+        let text = match &self.label {
+            Some(label) => format!("{}: {}", label, elapsed),
+            None => elapsed,
+        };
+        Line::from_iter([Span::sanitized(text)])
+    }
+}
+
+impl Component for Timer {
+    /// As wide as the time shown and the label.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        Dimensions::new(self.line(self.elapsed()).len(), 1).intersect(max)
+    }
+
+    fn draw_unchecked(&self, _dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let elapsed = match mode {
+            DrawMode::Normal => self.elapsed(),
+            DrawMode::Final => self.freeze(),
+        };
+        Ok(Lines(vec![self.line(elapsed)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer() -> anyhow::Result<()> {
+        let start = Instant::now() - Duration::from_secs(3725);
+        let timer = Timer::new(start).label("elapsed");
+
+        let output = timer.draw(Dimensions::new(20, 1), DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "elapsed: 1:02:05");
+        assert_eq!(
+            timer.desired_size(Dimensions::new(20, 5)),
+            Dimensions::new(16, 1)
+        );
+
+        let output = timer.draw(Dimensions::new(20, 1), DrawMode::Final)?;
+        let frozen = timer.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(timer.elapsed(), frozen);
+        assert_eq!(
+            timer.draw(Dimensions::new(20, 1), DrawMode::Normal)?,
+            output
+        );
+
+        let timer = Timer::new(Instant::now()).decimals(1);
+        let output = timer.draw(Dimensions::new(20, 1), DrawMode::Normal)?;
+        assert_eq!(output.0[0].to_unstyled(), "0:00:00.0");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::input::Event;
+use crate::input::InputHandler;
+use crate::input::KeyCode;
+use crate::input::KeyEvent;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// Component that shows or hides its child, e.g. a verbose panel, collapsing to no lines while
+/// hidden. Visibility is an `Arc<AtomicBool>`, so it can be shared with the rest of the
+/// application and switched between renders without restructuring the component tree.
+///
+/// With a [`key`](Self::key), it is also an [`InputHandler`] switching visibility on that key.
+#[derive(Debug)]
+pub struct Toggle<C: Component = Box<dyn Component>> {
+    child: C,
+    visible: Arc<AtomicBool>,
+    key: Option<KeyCode>,
+}
+
+impl<C: Component> Toggle<C> {
+    pub fn new(child: C, visible: bool) -> Self {
+        Self::with_flag(child, Arc::new(AtomicBool::new(visible)))
+    }
+
+    /// Shows the child while `visible` is set.
+    pub fn with_flag(child: C, visible: Arc<AtomicBool>) -> Self {
+        Self {
+            child,
+            visible,
+            key: None,
+        }
+    }
+
+    /// Switches visibility when `key` is pressed, see [`InputHandler`].
+    pub fn key(mut self, key: KeyCode) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// The flag deciding whether the child is shown.
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.visible.clone()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible.load(Ordering::Relaxed)
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.visible.store(visible, Ordering::Relaxed);
+    }
+
+    /// Switches visibility, returning whether the child is now shown.
+    pub fn toggle(&self) -> bool {
+        !self.visible.fetch_xor(true, Ordering::Relaxed)
+    }
+}
+
+impl<C: Component> Component for Toggle<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        if self.is_visible() {
+            self.child.desired_size(max)
+        } else {
+            Dimensions::default()
+        }
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        if self.is_visible() {
+            self.child.draw(dimensions, mode)
+        } else {
+            Ok(Lines::new())
+        }
+    }
+}
+
+impl<C: Component + Send + Sync> InputHandler for Toggle<C> {
+    fn handle_event(&self, event: &Event) -> bool {
+        match (event, self.key) {
+            (Event::Key(KeyEvent { code, .. }), Some(key)) if *code == key => {
+                self.toggle();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::input::KeyModifiers;
+    use crate::Line;
+
+    #[test]
+    fn test_toggle() -> anyhow::Result<()> {
+        let verbose = Lines(vec![Line::unstyled("details")?]);
+        let toggle = Toggle::new(Echo(verbose.clone()), false).key(KeyCode::Char('v'));
+        let dimensions = Dimensions::new(20, 5);
+        assert!(toggle.draw(dimensions, DrawMode::Normal)?.is_empty());
+
+        let press = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        assert!(!toggle.handle_event(&press('x')));
+        assert!(toggle.handle_event(&press('v')));
+        assert_eq!(toggle.draw(dimensions, DrawMode::Normal)?, verbose);
+
+        toggle.flag().store(false, Ordering::Relaxed);
+        assert!(toggle.draw(dimensions, DrawMode::Normal)?.is_empty());
+        assert!(toggle.toggle());
+        assert!(toggle.is_visible());
+
+        Ok(())
+    }
+}
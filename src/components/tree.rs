@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A hierarchy of nodes, such as build targets and the actions running for them, drawn with
+//! indentation guides.
+
+use crate::charset::unicode_enabled;
+use crate::charset::LineCharset;
+use crate::strings::strings;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// A node of a [`Tree`]. Its line is drawn as `status label suffix`, where the status (e.g. a
+/// status glyph) and suffix (e.g. a timer) are optional.
+#[derive(Debug, Clone, Default)]
+pub struct TreeNode {
+    pub status: Line,
+    pub label: Line,
+    pub suffix: Line,
+    pub children: Vec<TreeNode>,
+    /// Collapsed nodes hide their children, and are drawn with a count of their descendants.
+    pub collapsed: bool,
+}
+
+impl TreeNode {
+    pub fn new(label: Line) -> Self {
+        Self {
+            label,
+            ..Self::default()
+        }
+    }
+
+    pub fn status(mut self, status: Line) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn suffix(mut self, suffix: Line) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    pub fn child(mut self, child: TreeNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    pub fn toggle(&mut self) {
+        self.collapsed = !self.collapsed;
+    }
+
+    /// The node reached by following `path`, a list of child indices, from this one.
+    pub fn descendant_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        match path.split_first() {
+            Some((index, rest)) => self.children.get_mut(*index)?.descendant_mut(rest),
+            None => Some(self),
+        }
+    }
+
+    fn descendants(&self) -> usize {
+        self.children.iter().map(|c| 1 + c.descendants()).sum()
+    }
+
+    fn line(&self) -> Line {
+        let mut line = Line::default();
+        if !self.children.is_empty() {
+            let marker = match (self.collapsed, unicode_enabled()) {
+                (true, true) => "▸ ",
+                (false, true) => "▾ ",
+                (true, false) => "+ ",
+                (false, false) => "- ",
+            };
+            line.push(Span::new_unstyled_lossy(marker));
+        }
+        for part in [&self.status, &self.label, &self.suffix] {
+            if part.is_empty() {
+                continue;
+            }
+            if !line.is_empty() && !line.to_unstyled().ends_with(' ') {
+                line.push(Span::padding(1));
+            }
+            line.extend(part.iter().cloned());
+        }
+        if self.collapsed && !self.children.is_empty() {
+            line.push(Span::new_unstyled_lossy(format!(
+                " ({})",
+                strings().hidden(self.descendants())
+            )));
+        }
+        line
+    }
+}
+
+/// The `Tree` component draws a hierarchy of [`TreeNode`]s, one per line, with guides connecting
+/// each node to its parent. The roots are drawn without guides.
+#[derive(Debug, Clone)]
+pub struct Tree {
+    pub roots: Vec<TreeNode>,
+    charset: LineCharset,
+}
+
+impl Tree {
+    pub fn new(roots: Vec<TreeNode>) -> Self {
+        Self {
+            roots,
+            charset: LineCharset::detect(),
+        }
+    }
+
+    /// The glyphs to draw the guides with. Defaults to [`LineCharset::detect`].
+    pub fn charset(mut self, charset: LineCharset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    fn draw_children(&self, node: &TreeNode, guides: &Line, output: &mut Lines) {
+        if node.collapsed {
+            return;
+        }
+        let count = node.children.len();
+        for (i, child) in node.children.iter().enumerate() {
+            let last = i + 1 == count;
+            let mut line = guides.clone();
+            line.push(Span::new_unstyled_lossy(if last {
+                self.charset.tree_last
+            } else {
+                self.charset.tree_branch
+            }));
+            line.push(Span::padding(1));
+            line.extend(child.line());
+            output.push(line);
+
+            let mut child_guides = guides.clone();
+            if last {
+                child_guides.push(Span::padding(
+                    Span::new_unstyled_lossy(self.charset.tree_last).len() + 1,
+                ));
+            } else {
+                child_guides.push(Span::new_unstyled_lossy(self.charset.tree_vertical));
+                child_guides.push(Span::padding(1));
+            }
+            self.draw_children(child, &child_guides, output);
+        }
+    }
+}
+
+impl Component for Tree {
+    /// The size of the output, measured by drawing it, which is cheap.
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        match self.draw_unchecked(max, DrawMode::Normal) {
+            Ok(output) => Dimensions::new(output.max_line_length(), output.len()).intersect(max),
+            Err(_) => max,
+        }
+    }
+
+    fn draw_unchecked(&self, _dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut output = Lines::new();
+        for root in &self.roots {
+            output.push(root.line());
+            self.draw_children(root, &Line::default(), &mut output);
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::draw_to_strings;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_tree() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let node = |label: &str| -> anyhow::Result<TreeNode> {
+                Ok(TreeNode::new(Line::unstyled(label)?))
+            };
+            let mut tree = Tree::new(vec![node("//app")?
+                .child(node("compile")?.child(node("a.rs")?).child(node("b.rs")?))
+                .child(node("link")?.suffix(Line::unstyled("[3s]")?))])
+            .charset(LineCharset::UNICODE);
+
+            let rows =
+                |tree: &Tree| draw_to_strings(tree, Dimensions::new(40, 10), DrawMode::Normal);
+            assert_eq!(
+                rows(&tree)?,
+                vec![
+                    "▾ //app",
+                    "├─ ▾ compile",
+                    "│  ├─ a.rs",
+                    "│  └─ b.rs",
+                    "└─ link [3s]",
+                ]
+            );
+
+            tree.roots[0]
+                .descendant_mut(&[0])
+                .expect("node exists")
+                .toggle();
+            assert_eq!(
+                rows(&tree)?,
+                vec!["▾ //app", "├─ ▸ compile (2 hidden)", "└─ link [3s]"]
+            );
+
+            let tree = tree.charset(LineCharset::ASCII);
+            assert_eq!(
+                with_unicode(false, || rows(&tree))?,
+                vec!["- //app", "|- + compile (2 hidden)", "`- link [3s]"]
+            );
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,98 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use crate::content::WrapOptions;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// Component that soft wraps the lines of its child to the available width rather than letting
+/// them be truncated. The child is drawn with unbounded width.
+#[derive(Debug)]
+pub struct Wrapped<C: Component = Box<dyn Component>> {
+    child: C,
+    options: WrapOptions,
+}
+
+impl<C: Component> Wrapped<C> {
+    pub fn new(child: C) -> Self {
+        Self {
+            child,
+            options: WrapOptions::default(),
+        }
+    }
+
+    pub fn options(mut self, options: WrapOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<C: Component> Component for Wrapped<C> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.child.on_resize(dimensions);
+    }
+
+    fn on_attach(&self) {
+        self.child.on_attach();
+    }
+
+    fn on_finalize(&self) {
+        self.child.on_finalize();
+    }
+
+    fn on_detach(&self) {
+        self.child.on_detach();
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        self.child.desired_size(max)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let output = self.child.draw(
+            Dimensions {
+                width: usize::MAX,
+                height: dimensions.height,
+            },
+            mode,
+        )?;
+        let mut output = output.wrap_all_with(dimensions.width, self.options);
+        output.truncate_lines_bottom(dimensions.height);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::Line;
+
+    #[test]
+    fn test_wrapped() -> anyhow::Result<()> {
+        let msg = Lines(vec![Line::unstyled("hello wide world")?]);
+        let output = Wrapped::new(Echo(msg)).draw(
+            Dimensions {
+                width: 10,
+                height: 10,
+            },
+            DrawMode::Normal,
+        )?;
+        assert_eq!(
+            output,
+            Lines(vec![
+                Line::unstyled("hello wide")?,
+                Line::unstyled("world")?,
+            ])
+        );
+        Ok(())
+    }
+}
@@ -9,10 +9,37 @@
 
 //! Provides a variety of utilities for working with [`Line`s](Line).
 
+pub use bidi::TextDirection;
+pub use elision::elide;
+pub use elision::Elision;
+pub use hyperlink::hyperlinks_enabled;
+pub use hyperlink::set_hyperlinks;
+pub use hyperlink::FileLink;
+pub use hyperlink::LinkScheme;
+pub use line::Alignment;
 pub use line::Line;
 pub use lines::Lines;
+#[cfg(feature = "unicode-normalization")]
+pub use normalization::set_nfc_normalization;
+#[cfg(feature = "unicode-normalization")]
+pub use normalization::with_nfc_normalization;
 pub use span::Span;
+pub use surface::ChangedRegion;
+pub use surface::DrawSurface;
+pub use wrap::WrapMode;
+pub use wrap::WrapOptions;
+pub use wrap::BREAK_HINT;
+pub use wrap::SOFT_HYPHEN;
 
+mod bidi;
+mod elision;
+mod html;
+mod hyperlink;
 mod line;
 mod lines;
+#[cfg(feature = "markdown")]
+mod markdown;
+mod normalization;
 mod span;
+mod surface;
+mod wrap;
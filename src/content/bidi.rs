@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Support for right-to-left text.
+//!
+//! Terminals draw cells left to right, so the spans of a [`Line`](crate::Line) are always kept in
+//! *visual* order. The [`TextDirection`] of a line only decides on which side padding is added and
+//! from which side the line is truncated.
+//! With the `unicode-bidi` feature, text in logical (reading) order can be reordered for display.
+
+#[cfg(feature = "unicode-bidi")]
+use std::borrow::Cow;
+#[cfg(feature = "unicode-bidi")]
+use std::cmp;
+
+#[cfg(feature = "unicode-bidi")]
+use unicode_bidi::BidiInfo;
+#[cfg(feature = "unicode-bidi")]
+use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg(feature = "unicode-bidi")]
+use crate::Span;
+
+/// The base direction of a [`Line`](crate::Line).
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TextDirection {
+    /// The line starts on the left. Padding goes on the right and truncation removes from the right.
+    #[default]
+    LeftToRight,
+    /// The line starts on the right. Padding goes on the left and truncation removes from the left.
+    RightToLeft,
+}
+
+#[cfg(feature = "unicode-bidi")]
+impl TextDirection {
+    /// Detects the base direction of `text` from its first strong character.
+    pub fn detect(text: &str) -> Self {
+        let info = BidiInfo::new(text, None);
+        match info.paragraphs.first() {
+            Some(para) if para.level.is_rtl() => Self::RightToLeft,
+            _ => Self::LeftToRight,
+        }
+    }
+}
+
+/// Reorders spans given in logical order into visual order, returning the base direction of the text.
+/// Styles stay attached to the characters they were applied to.
+#[cfg(feature = "unicode-bidi")]
+pub(crate) fn reorder_visually(spans: Vec<Span>) -> (Vec<Span>, TextDirection) {
+    let text: String = spans.iter().map(Span::content).collect();
+    let info = BidiInfo::new(&text, None);
+    let para = match info.paragraphs.first() {
+        Some(para) => para,
+        None => return (spans, TextDirection::LeftToRight),
+    };
+    let direction = if para.level.is_rtl() {
+        TextDirection::RightToLeft
+    } else {
+        TextDirection::LeftToRight
+    };
+
+    // byte range of each span within `text`.
+    let mut offset = 0;
+    let bounds: Vec<_> = spans
+        .iter()
+        .map(|span| {
+            let range = offset..offset + span.content.len();
+            offset = range.end;
+            range
+        })
+        .collect();
+
+    let (levels, runs) = info.visual_runs(para, para.range.clone());
+    let mut reordered = Vec::new();
+    for run in runs {
+        let rtl = levels[run.start].is_rtl();
+        let mut pieces = Vec::new();
+        for (span, range) in spans.iter().zip(&bounds) {
+            let start = cmp::max(range.start, run.start);
+            let end = cmp::min(range.end, run.end);
+            if start >= end {
+                continue;
+            }
+            let content = &text[start..end];
+            let content = if rtl {
+                content.graphemes(true).rev().collect()
+            } else {
+                content.to_owned()
+            };
+            let mut piece = span.clone();
+            piece.content = Cow::Owned(content);
+            pieces.push(piece);
+        }
+        if rtl {
+            pieces.reverse();
+        }
+        reordered.extend(pieces);
+    }
+
+    (reordered, direction)
+}
+
+#[cfg(all(test, feature = "unicode-bidi"))]
+mod tests {
+    use super::*;
+    use crate::Line;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(TextDirection::detect("hello"), TextDirection::LeftToRight);
+        assert_eq!(TextDirection::detect("שלום"), TextDirection::RightToLeft);
+    }
+
+    #[test]
+    fn test_reorder() -> anyhow::Result<()> {
+        let line = Line::from_logical_order([Span::new_unstyled("שלום abc")?]);
+        assert_eq!(line.direction(), TextDirection::RightToLeft);
+        assert_eq!(line.to_unstyled(), "abc םולש");
+        Ok(())
+    }
+}
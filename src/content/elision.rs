@@ -0,0 +1,146 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Shortening text to a given width by replacing part of it with an ellipsis.
+//! Useful for digests, URLs, paths and target labels which don't fit their allotted space.
+
+use std::cmp;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::charset;
+use crate::content::span::grapheme_width;
+use crate::content::span::text_width;
+
+/// Which part of the text is dropped when it is too wide.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Elision {
+    /// Keep the end of the text, e.g. `…/src/main.rs`.
+    Start,
+    /// Keep both ends of the text, e.g. `sha256:ab…ef01`.
+    Middle,
+    /// Keep the start of the text, e.g. `https://exa…`.
+    End,
+}
+
+/// The string inserted in place of text elided to fit `width` columns: `…`, or `...` unless
+/// glyphs beyond ASCII are [enabled](charset::unicode_enabled), with fewer dots if `width` is
+/// narrower.
+pub(crate) fn ellipsis(width: usize) -> &'static str {
+    let ellipsis = charset::ellipsis();
+    if ellipsis.is_ascii() {
+        &ellipsis[..cmp::min(ellipsis.len(), width)]
+    } else {
+        ellipsis
+    }
+}
+
+/// Shortens `text` to at most `width` columns, replacing the elided part with an ellipsis: `…`,
+/// or `...` unless glyphs beyond ASCII are [enabled](charset::unicode_enabled).
+/// Text which already fits is returned unchanged.
+pub fn elide(text: &str, width: usize, elision: Elision) -> String {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let widths: Vec<usize> = graphemes.iter().map(|g| grapheme_width(g)).collect();
+    let ellipsis = ellipsis(width);
+    match plan(&widths, width, text_width(ellipsis), elision) {
+        None => text.to_owned(),
+        Some(None) => String::new(),
+        Some(Some((head, tail))) => {
+            let mut result: String = graphemes[..head].concat();
+            result.push_str(ellipsis);
+            result.push_str(&graphemes[graphemes.len() - tail..].concat());
+            result
+        }
+    }
+}
+
+/// Given the column width of each grapheme, decides how many graphemes to keep at the front and
+/// the back of the text so that they fit alongside an ellipsis `ellipsis_width` columns wide.
+/// Returns `None` if the text already fits and `Some(None)` if not even the ellipsis fits.
+pub(crate) fn plan(
+    widths: &[usize],
+    width: usize,
+    ellipsis_width: usize,
+    elision: Elision,
+) -> Option<Option<(usize, usize)>> {
+    let total: usize = widths.iter().sum();
+    if total <= width {
+        return None;
+    }
+    if width == 0 || width < ellipsis_width {
+        return Some(None);
+    }
+
+    let budget = width - ellipsis_width;
+    let (head_budget, tail_budget) = match elision {
+        Elision::Start => (0, budget),
+        Elision::Middle => (budget - budget / 2, budget / 2),
+        Elision::End => (budget, 0),
+    };
+
+    fn count_fitting<'a>(widths: impl Iterator<Item = &'a usize>, budget: usize) -> usize {
+        let mut used = 0;
+        widths
+            .take_while(|w| {
+                used += **w;
+                used <= budget
+            })
+            .count()
+    }
+
+    Some(Some((
+        count_fitting(widths.iter(), head_budget),
+        count_fitting(widths.iter().rev(), tail_budget),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_fits() {
+        assert_eq!(elide("hello", 5, Elision::Middle), "hello");
+        assert_eq!(elide("hello", 10, Elision::End), "hello");
+    }
+
+    #[test]
+    fn test_elide() {
+        with_unicode(true, || {
+            assert_eq!(elide("abcdefghij", 5, Elision::Start), "…ghij");
+            assert_eq!(elide("abcdefghij", 5, Elision::Middle), "ab…ij");
+            assert_eq!(elide("abcdefghij", 6, Elision::Middle), "abc…ij");
+            assert_eq!(elide("abcdefghij", 5, Elision::End), "abcd…");
+            assert_eq!(elide("abcdefghij", 1, Elision::End), "…");
+            assert_eq!(elide("abcdefghij", 0, Elision::End), "");
+        });
+    }
+
+    #[test]
+    fn test_elide_ascii() {
+        with_unicode(false, || {
+            assert_eq!(elide("abcdefghij", 5, Elision::Start), "...ij");
+            assert_eq!(elide("abcdefghij", 7, Elision::Middle), "ab...ij");
+            assert_eq!(elide("abcdefghij", 5, Elision::End), "ab...");
+            // Narrower widths get fewer dots.
+            assert_eq!(elide("abcdefghij", 2, Elision::End), "..");
+            assert_eq!(elide("abcdefghij", 0, Elision::End), "");
+        });
+    }
+
+    #[test]
+    fn test_wide_characters() {
+        with_unicode(true, || {
+            // Each of these characters takes two columns, so only one fits next to the ellipsis.
+            assert_eq!(elide("汉字汉字", 4, Elision::End), "汉…");
+            assert_eq!(elide("汉字汉字", 4, Elision::Start), "…字");
+        });
+    }
+}
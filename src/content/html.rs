@@ -0,0 +1,156 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Conversion of styled content to HTML with inline styles, e.g. to attach build summaries to web pages.
+
+use std::fmt::Write as _;
+
+use crate::style::to_rgb;
+use crate::style::Attribute;
+use crate::style::Color;
+use crate::style::ContentStyle;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+fn css_color(color: Color) -> Option<String> {
+    to_rgb(color).map(|(r, g, b)| format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+fn css_style(style: &ContentStyle) -> String {
+    let attributes = style.attributes;
+    let (foreground, background) = if attributes.has(Attribute::Reverse) {
+        (style.background_color, style.foreground_color)
+    } else {
+        (style.foreground_color, style.background_color)
+    };
+
+    let mut css = Vec::new();
+    if let Some(color) = foreground.and_then(css_color) {
+        css.push(format!("color:{}", color));
+    }
+    if let Some(color) = background.and_then(css_color) {
+        css.push(format!("background-color:{}", color));
+    }
+    if attributes.has(Attribute::Bold) {
+        css.push("font-weight:bold".to_owned());
+    }
+    if attributes.has(Attribute::Dim) {
+        css.push("opacity:0.5".to_owned());
+    }
+    if attributes.has(Attribute::Italic) {
+        css.push("font-style:italic".to_owned());
+    }
+    let mut decorations = Vec::new();
+    if attributes.has(Attribute::Underlined) {
+        decorations.push("underline");
+    }
+    if attributes.has(Attribute::CrossedOut) {
+        decorations.push("line-through");
+    }
+    if !decorations.is_empty() {
+        css.push(format!("text-decoration:{}", decorations.join(" ")));
+    }
+    if attributes.has(Attribute::Hidden) {
+        css.push("visibility:hidden".to_owned());
+    }
+    css.join(";")
+}
+
+fn escape(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+impl Span {
+    /// Renders the span as HTML, using an inline style for its styling and an anchor for its link.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        if let Some(link) = self.link() {
+            html.push_str("<a href=\"");
+            escape(link, &mut html);
+            html.push_str("\">");
+        }
+        let css = css_style(&self.style);
+        if css.is_empty() {
+            escape(self.content(), &mut html);
+        } else {
+            write!(html, "<span style=\"{}\">", css).unwrap();
+            escape(self.content(), &mut html);
+            html.push_str("</span>");
+        }
+        if self.link().is_some() {
+            html.push_str("</a>");
+        }
+        html
+    }
+}
+
+impl Line {
+    /// Renders the line as HTML, without a trailing newline.
+    pub fn to_html(&self) -> String {
+        self.iter().map(Span::to_html).collect()
+    }
+}
+
+impl Lines {
+    /// Renders the lines as a `<pre>` block of HTML with inline styles, preserving colors and attributes.
+    pub fn to_html(&self) -> String {
+        let mut html = "<pre class=\"superconsole\">".to_owned();
+        for line in self.iter() {
+            html.push_str(&line.to_html());
+            html.push('\n');
+        }
+        html.push_str("</pre>");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Stylize;
+
+    #[test]
+    fn test_to_html() -> anyhow::Result<()> {
+        let lines = Lines(vec![
+            Line::from_iter([
+                Span::new_unstyled("a < b ")?,
+                Span::new_styled("ok".to_owned().green().bold())?,
+            ]),
+            Line::from_iter([Span::new_unstyled("docs")?.with_link("https://example.com/?a&b")]),
+        ]);
+
+        assert_eq!(
+            lines.to_html(),
+            "<pre class=\"superconsole\">\
+             a &lt; b <span style=\"color:#00ff00;font-weight:bold\">ok</span>\n\
+             <a href=\"https://example.com/?a&amp;b\">docs</a>\n\
+             </pre>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_palette() {
+        assert_eq!(css_color(Color::AnsiValue(196)).unwrap(), "#ff0000");
+        assert_eq!(css_color(Color::AnsiValue(244)).unwrap(), "#808080");
+        assert_eq!(css_color(Color::DarkBlue).unwrap(), "#0000ee");
+        assert_eq!(css_color(Color::Reset), None);
+    }
+}
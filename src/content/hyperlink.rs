@@ -0,0 +1,256 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Helpers for building hyperlinks to local files, so diagnostics can be clicked straight into an editor.
+//!
+//! Hyperlinks are written as OSC 8 escape sequences, which terminals without support may print
+//! verbatim, so they are only written when the terminal is known to support them. Elsewhere, linked
+//! spans are drawn as plain text.
+
+use std::env;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+use crate::Span;
+
+/// How a [`FileLink`] is turned into a URL.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LinkScheme {
+    /// `file://host/path#L42`. The column is not representable and is dropped.
+    File,
+    /// `vscode://file/path:42:7`.
+    VsCode,
+    /// A template where `{path}`, `{line}` and `{column}` are substituted,
+    /// e.g. `idea://open?file={path}&line={line}`. Missing values are substituted with `1`.
+    Custom(String),
+}
+
+/// A link to a location in a local file.
+#[derive(Debug, Clone)]
+pub struct FileLink {
+    path: PathBuf,
+    host: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    scheme: LinkScheme,
+}
+
+impl FileLink {
+    /// Link to `path`. Relative paths are resolved against the current directory.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let path = match env::current_dir() {
+            Ok(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_owned(),
+        };
+        Self {
+            path,
+            host: String::new(),
+            line: None,
+            column: None,
+            scheme: LinkScheme::File,
+        }
+    }
+
+    /// The host the file lives on, which lets terminals detect links to remote machines.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// 1-based line to link to.
+    pub fn line(mut self, line: u32) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// 1-based column to link to.
+    pub fn column(mut self, column: u32) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn scheme(mut self, scheme: LinkScheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Builds the URL for this link.
+    pub fn url(&self) -> String {
+        let path = encode_path(&self.path);
+        match &self.scheme {
+            LinkScheme::File => {
+                let mut url = format!("file://{}{}", self.host, path);
+                if let Some(line) = self.line {
+                    write!(url, "#L{}", line).unwrap();
+                }
+                url
+            }
+            LinkScheme::VsCode => {
+                let mut url = format!("vscode://file{}", path);
+                if let Some(line) = self.line {
+                    write!(url, ":{}", line).unwrap();
+                    if let Some(column) = self.column {
+                        write!(url, ":{}", column).unwrap();
+                    }
+                }
+                url
+            }
+            LinkScheme::Custom(template) => template
+                .replace("{path}", &path)
+                .replace("{line}", &self.line.unwrap_or(1).to_string())
+                .replace("{column}", &self.column.unwrap_or(1).to_string()),
+        }
+    }
+
+    /// Creates a span displaying `text` which links to this location.
+    pub fn to_span<S: std::fmt::Display>(&self, text: S) -> anyhow::Result<Span> {
+        Ok(Span::new_unstyled(text)?.with_link(self.url()))
+    }
+}
+
+const UNKNOWN: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+/// Whether hyperlinks are written, or `UNKNOWN` if it was not detected yet.
+static HYPERLINKS: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Overrides whether spans with links are rendered as OSC 8 hyperlinks.
+pub fn set_hyperlinks(enabled: bool) {
+    HYPERLINKS.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+}
+
+/// Whether spans with links are rendered as OSC 8 hyperlinks. Unless overridden with
+/// [`set_hyperlinks`] or the `FORCE_HYPERLINK` environment variable (`1` or `0`), this is detected
+/// from the environment variables set by terminals known to support them.
+pub fn hyperlinks_enabled() -> bool {
+    match HYPERLINKS.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let enabled = hyperlinks_likely_supported(|var| env::var(var).ok());
+            set_hyperlinks(enabled);
+            enabled
+        }
+        state => state == ENABLED,
+    }
+}
+
+fn hyperlinks_likely_supported(var: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    if matches!(var("TERM").as_deref(), Some("dumb" | "linux")) {
+        return false;
+    }
+    if matches!(
+        var("TERM_PROGRAM").as_deref(),
+        Some("iTerm.app" | "WezTerm" | "vscode" | "Hyper" | "ghostty")
+    ) {
+        return true;
+    }
+    // VTE based terminals, e.g. GNOME Terminal, support them from 0.50.
+    if let Some(version) = var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()) {
+        return version >= 5000;
+    }
+    [
+        "WT_SESSION",
+        "KONSOLE_VERSION",
+        "KITTY_WINDOW_ID",
+        "DOMTERM",
+    ]
+    .iter()
+    .any(|name| var(name).is_some())
+}
+
+/// Turns a path into the path component of a URL, percent encoding anything unsafe.
+/// Windows paths such as `C:\src` become `/C:/src`.
+fn encode_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut encoded = String::with_capacity(path.len() + 1);
+    if !path.starts_with('/') {
+        encoded.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => write!(encoded, "%{:02X}", byte).unwrap(),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_url() {
+        let link = FileLink::new("/src/my lib.rs").line(42).column(7);
+        assert_eq!(link.url(), "file:///src/my%20lib.rs#L42");
+        assert_eq!(
+            link.clone().host("devbox").url(),
+            "file://devbox/src/my%20lib.rs#L42"
+        );
+    }
+
+    #[test]
+    fn test_vscode_url() {
+        let link = FileLink::new("/src/lib.rs").scheme(LinkScheme::VsCode);
+        assert_eq!(link.url(), "vscode://file/src/lib.rs");
+        assert_eq!(link.line(3).column(9).url(), "vscode://file/src/lib.rs:3:9");
+    }
+
+    #[test]
+    fn test_custom_url() {
+        let link = FileLink::new("/src/lib.rs")
+            .line(12)
+            .scheme(LinkScheme::Custom(
+                "idea://open?file={path}&line={line}".to_owned(),
+            ));
+        assert_eq!(link.url(), "idea://open?file=/src/lib.rs&line=12");
+    }
+
+    #[test]
+    fn test_detection() {
+        let detect = |vars: &[(&str, &str)]| {
+            hyperlinks_likely_supported(|name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| (*value).to_owned())
+            })
+        };
+        assert!(!detect(&[]));
+        assert!(detect(&[("TERM_PROGRAM", "WezTerm")]));
+        assert!(detect(&[("VTE_VERSION", "6003")]));
+        assert!(!detect(&[("VTE_VERSION", "4601")]));
+        assert!(!detect(&[("FORCE_HYPERLINK", "0"), ("WT_SESSION", "1")]));
+        assert!(detect(&[("FORCE_HYPERLINK", "1"), ("TERM", "dumb")]));
+    }
+
+    #[test]
+    fn test_span() -> anyhow::Result<()> {
+        set_hyperlinks(true);
+        let span = FileLink::new("/src/lib.rs").line(1).to_span("lib.rs:1")?;
+        assert_eq!(span.link(), Some("file:///src/lib.rs#L1"));
+
+        let mut rendered = String::new();
+        span.render(&mut rendered)?;
+        assert_eq!(
+            rendered,
+            "\x1b]8;;file:///src/lib.rs#L1\x1b\\lib.rs:1\x1b]8;;\x1b\\"
+        );
+
+        Ok(())
+    }
+}
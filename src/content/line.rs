@@ -16,23 +16,42 @@ use std::mem;
 use std::slice;
 use std::vec;
 
-use crossterm::cursor::MoveToColumn;
-use crossterm::terminal::Clear;
-use crossterm::terminal::ClearType;
-use crossterm::Command;
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::ansi::Clear;
+use crate::ansi::ClearType;
+use crate::ansi::Command;
+use crate::ansi::MoveToColumn;
+use crate::content::elision;
+use crate::content::lines::parse_ansi_line;
+use crate::content::span::grapheme_width;
+use crate::content::span::text_width;
+use crate::content::Elision;
+use crate::content::TextDirection;
+use crate::style::ContentStyle;
 use crate::vec_as_fmt_write::VecAsFmtWrite;
 use crate::Span;
 
+/// Where a [`Line`] sits within a wider width, see [`Line::pad_to`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
 /// A `Line` is an abstraction for a collection of stylized or unstylized strings.
 /// Since each `Span` denotes a portion of a single line, an ordered collection represents a single line of text.
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
 pub struct Line(
     /// Sequence is normalized.
     /// * All spans are non-empty.
-    /// * Adjacent spans have different styles.
+    /// * Adjacent spans have different styles, links or cursors.
+    /// * Spans are in visual (left to right) order.
     Vec<Span>,
+    /// Which side of the line is its start.
+    TextDirection,
 );
 
 impl Line {
@@ -40,13 +59,58 @@ impl Line {
         Ok(Line::from_iter([Span::new_unstyled(text)?]))
     }
 
+    /// Builds a line from arbitrary text, e.g. captured from a subprocess. SGR escape sequences
+    /// become the styles of the spans, as with [`from_ansi`](Self::from_ansi), while any other
+    /// escape sequences and control characters, which could move the cursor or clear the screen,
+    /// are removed.
     pub fn sanitized(text: &str) -> Line {
-        Line::from_iter([Span::sanitized(text)])
+        if text.contains(['\x1b', '\u{9b}']) {
+            Line::from_ansi(text)
+        } else {
+            Line::from_iter([Span::sanitized(text)])
+        }
+    }
+
+    /// Parses text containing ANSI escape codes into spans styled accordingly, rather than
+    /// stripping them. Line breaks are dropped, see
+    /// [`Lines::from_multiline_string_ansi`](crate::Lines::from_multiline_string_ansi) for text
+    /// spanning several lines.
+    pub fn from_ansi(text: &str) -> Line {
+        parse_ansi_line(text)
+    }
+
+    /// Builds a line from spans given in logical (reading) order, reordering them for display.
+    /// The direction of the line is taken from its first strong character.
+    #[cfg(feature = "unicode-bidi")]
+    pub fn from_logical_order(spans: impl IntoIterator<Item = Span>) -> Line {
+        let (spans, direction) =
+            crate::content::bidi::reorder_visually(spans.into_iter().collect());
+        Line::from_iter(spans).with_direction(direction)
+    }
+
+    /// The direction of the line, which decides where padding and truncation happen.
+    pub fn direction(&self) -> TextDirection {
+        self.1
+    }
+
+    pub fn set_direction(&mut self, direction: TextDirection) {
+        self.1 = direction;
+    }
+
+    pub fn with_direction(mut self, direction: TextDirection) -> Line {
+        self.1 = direction;
+        self
     }
 
-    /// Return the length of the all words in the line added together.
+    /// Return the width of the all words in the line added together, in terminal cells.
     pub fn len(&self) -> usize {
-        self.0.iter().map(Span::len).sum()
+        self.0.iter().map(Span::width).sum()
+    }
+
+    /// The column of the first span marked with [`Span::with_cursor`], if any.
+    pub fn cursor_column(&self) -> Option<usize> {
+        let index = self.0.iter().position(|span| span.cursor)?;
+        Some(self.0[..index].iter().map(Span::width).sum())
     }
 
     pub fn is_empty(&self) -> bool {
@@ -65,10 +129,20 @@ impl Line {
         self.push_front(Span::padding(amount));
     }
 
-    /// Truncates the right side of the line until it is no longer than `max_width`.
+    /// Truncates the end of the line until it is no longer than `max_width`.
+    /// This is the right side, unless the line is [right to left](TextDirection::RightToLeft).
     /// This will delete words entirely if they cannot fit.
+    /// A double-width grapheme straddling `max_width` is dropped, leaving the line a cell short.
     /// If the line is padded to 0, then it will become an empty line.
     pub fn truncate_line(&mut self, max_width: usize) {
+        if self.1 == TextDirection::RightToLeft {
+            let len = self.len();
+            if len > max_width {
+                self.trim_ends(len - max_width, max_width);
+            }
+            return;
+        }
+
         let mut cur_width = 0;
 
         for (index, span) in self.0.iter_mut().enumerate() {
@@ -76,13 +150,22 @@ impl Line {
                 self.0.truncate(index);
                 break;
             }
-            let word = span.content.graphemes(true);
-            let word_len = word.clone().count();
+            let word_len = span.width();
             // if the line is going to overflow
             if word_len + cur_width > max_width {
-                let word = word
+                let mut remaining = max_width - cur_width;
+                let word = span
+                    .content
+                    .graphemes(true)
                     // cut off the extra graphemes
-                    .take(max_width.saturating_sub(cur_width))
+                    .take_while(|g| {
+                        let width = grapheme_width(g);
+                        remaining = match remaining.checked_sub(width) {
+                            Some(remaining) => remaining,
+                            None => return false,
+                        };
+                        true
+                    })
                     .collect();
 
                 // overwrite the current word
@@ -98,52 +181,139 @@ impl Line {
         }
     }
 
-    /// Slices out some middle subline of the Line. Removes the first `start` characters and
-    /// keeps `width` characters after that.
+    /// Fills in any styling left unspecified by the spans of this line from `base`.
+    /// See [`Span::apply_style_base`].
+    pub fn apply_style_base(&mut self, base: ContentStyle) {
+        for mut span in mem::take(&mut self.0) {
+            span.apply_style_base(base);
+            self.push(span);
+        }
+    }
+
+    /// Shortens the line to at most `width` columns by replacing part of it with an ellipsis.
+    /// The remaining text keeps its styling, and the ellipsis takes the style of the text it replaces.
+    pub fn elide(&mut self, width: usize, elision: Elision) {
+        let graphemes: Vec<Span> = self.0.iter().flat_map(Span::iter).collect();
+        let widths: Vec<usize> = graphemes.iter().map(Span::len).collect();
+        let ellipsis = elision::ellipsis(width);
+        let (head, tail) = match elision::plan(&widths, width, text_width(ellipsis), elision) {
+            None => return,
+            Some(None) => {
+                self.0.clear();
+                return;
+            }
+            Some(Some(keep)) => keep,
+        };
+
+        let ellipsis = Span {
+            content: ellipsis.into(),
+            ..graphemes[head].clone()
+        };
+
+        let tail_start = graphemes.len() - tail;
+        let mut graphemes = graphemes;
+        let tail = graphemes.split_off(tail_start);
+        graphemes.truncate(head);
+
+        self.0.clear();
+        self.extend(graphemes);
+        self.push(ellipsis);
+        self.extend(tail);
+    }
+
+    /// Shortens the line to at most `width` columns, replacing its end with an ellipsis.
+    /// Shorthand for [`elide`](Self::elide) with [`Elision::End`].
+    pub fn truncate_with_ellipsis(&mut self, width: usize) {
+        self.elide(width, Elision::End);
+    }
+
+    /// Pads the line with unstyled spaces to `width` columns, placing its content as `alignment`
+    /// says. Centered lines get the extra space on the right. Lines at least `width` wide are left
+    /// as they are.
+    pub fn pad_to(&mut self, width: usize, alignment: Alignment) {
+        let padding = width.saturating_sub(self.len());
+        if padding == 0 {
+            return;
+        }
+        match alignment {
+            Alignment::Left => self.pad_right(padding),
+            Alignment::Right => self.pad_left(padding),
+            Alignment::Center => {
+                self.pad_left(padding / 2);
+                self.pad_right(padding - padding / 2);
+            }
+        }
+    }
+
+    /// Makes the line exactly `width` columns wide: longer lines are
+    /// [truncated with an ellipsis](Self::truncate_with_ellipsis), and shorter ones are
+    /// [padded](Self::pad_to) as `alignment` says.
+    pub fn justify(&mut self, alignment: Alignment, width: usize) {
+        self.truncate_with_ellipsis(width);
+        // A double-width grapheme may have been elided with a single-width ellipsis.
+        self.pad_to(width, alignment);
+    }
+
+    /// Slices out some middle subline of the Line. Removes the first `start` cells and
+    /// keeps `width` cells after that. Double-width graphemes which would be cut in half are dropped.
     pub fn trim_ends(&mut self, mut start: usize, mut width: usize) {
         let mut owned = Vec::new();
         std::mem::swap(&mut owned, &mut self.0);
 
         for mut span in owned.into_iter() {
-            // TODO(cjhopman): Other code here uses a mix of graphemes count and span.len() for computing the length of
-            // a span, but these are totally different approaches. span.len() supposedly uses a more accurate approach,
-            // so we should consider switching to that throughout. But, to keep this code self-consistent it uses only
-            // the graphemes approach.
-            let chars = span.content.graphemes(true);
-            let len = chars.clone().count();
-
-            if start > 0 && len < start {
+            let len = span.width();
+
+            if start > 0 && len <= start {
                 start -= len;
                 continue;
             }
 
-            let end = std::cmp::min(len, start + width);
-
-            if start != 0 || end != len {
-                span.content = chars.skip(start).take(end - start).collect();
+            if start != 0 || len > width {
+                let mut kept = String::new();
+                for grapheme in span.content.graphemes(true) {
+                    let grapheme_len = grapheme_width(grapheme);
+                    if start > 0 {
+                        // The cells past `start` of a grapheme cut in half are lost too.
+                        width = width.saturating_sub(grapheme_len.saturating_sub(start));
+                        start = start.saturating_sub(grapheme_len);
+                    } else if grapheme_len <= width {
+                        kept.push_str(grapheme);
+                        width -= grapheme_len;
+                    } else {
+                        width = 0;
+                        break;
+                    }
+                }
+                span.content = kept.into();
+            } else {
+                width -= len;
             }
             self.push(span);
 
-            width -= end - start;
-            start = 0;
-
             if width == 0 {
                 break;
             }
         }
     }
 
-    /// Either calls [`pad_right`](Line::pad_right) or [`truncate_line`](Line::truncate_line) until the line is the exact width specified.
-    /// This call acts on the right side of the `Line`.
+    /// Either pads or calls [`truncate_line`](Line::truncate_line) until the line is the exact width specified.
+    /// This call acts on the end of the `Line`: the right side, or the left side for right to left lines.
     pub fn to_exact_width(&mut self, exact_width: usize) {
         let len = self.len();
         match len.cmp(&exact_width) {
-            Ordering::Less => {
-                self.pad_right(exact_width - len);
-            }
+            Ordering::Less => match self.1 {
+                TextDirection::LeftToRight => self.pad_right(exact_width - len),
+                TextDirection::RightToLeft => self.pad_left(exact_width - len),
+            },
             Ordering::Equal => {}
             Ordering::Greater => {
                 self.truncate_line(exact_width);
+                // A double-width grapheme may have been dropped at the edge.
+                let len = self.len();
+                match self.1 {
+                    TextDirection::LeftToRight => self.pad_right(exact_width - len),
+                    TextDirection::RightToLeft => self.pad_left(exact_width - len),
+                }
             }
         }
     }
@@ -177,7 +347,7 @@ impl Line {
     }
 
     /// Iterate over the spans in the line.
-    pub fn iter(&self) -> slice::Iter<Span> {
+    pub fn iter(&self) -> slice::Iter<'_, Span> {
         self.0.iter()
     }
 
@@ -192,7 +362,7 @@ impl Line {
             return;
         }
         if let Some(last) = self.0.last_mut() {
-            if last.style == span.style {
+            if last.style == span.style && last.link == span.link && last.cursor == span.cursor {
                 last.content.to_mut().push_str(&span.content);
                 return;
             }
@@ -203,6 +373,7 @@ impl Line {
     /// Prepend a span to the line.
     pub fn push_front(&mut self, span: Span) {
         let this = mem::take(self);
+        self.1 = this.1;
         self.push(span);
         self.extend(this);
     }
@@ -271,9 +442,22 @@ impl Extend<Span> for Line {
 
 #[cfg(test)]
 mod tests {
-    use crossterm::style::Color;
-
     use super::*;
+    use crate::style::Color;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_sanitized() -> anyhow::Result<()> {
+        assert_eq!(
+            Line::sanitized("\x1b[31mred\x1b[0m\x1b[2J plain\x1b[10;1H\x1b]0;title\x07"),
+            Line::from_iter([
+                Span::new_colored("red", Color::AnsiValue(1))?,
+                Span::new_unstyled(" plain")?,
+            ])
+        );
+        assert_eq!(Line::sanitized("tab\there"), Line::unstyled("tabhere")?);
+        Ok(())
+    }
 
     #[test]
     fn test_words_len() {
@@ -383,6 +567,114 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_wide_graphemes() -> anyhow::Result<()> {
+        let mut line = Line::unstyled("汉字ab👍🏽")?;
+        assert_eq!(line.len(), 8);
+        assert_eq!(Span::new_unstyled("👨‍👩‍👧")?.width(), 2);
+        assert_eq!(Span::new_unstyled("e\u{301}")?.width(), 1);
+
+        let mut trimmed = line.clone();
+        trimmed.trim_ends(1, 4);
+        assert_eq!(trimmed.to_unstyled(), "字a");
+
+        line.to_exact_width(3);
+        assert_eq!(line.to_unstyled(), "汉 ");
+        assert_eq!(line.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_right_to_left() -> anyhow::Result<()> {
+        let mut line = Line::unstyled("abcdef")?.with_direction(TextDirection::RightToLeft);
+        line.push_front(Span::new_unstyled("x")?);
+        assert_eq!(line.direction(), TextDirection::RightToLeft);
+
+        line.to_exact_width(9);
+        assert_eq!(line.to_unstyled(), "  xabcdef");
+
+        line.truncate_line(4);
+        assert_eq!(line.to_unstyled(), "cdef");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_elide() {
+        let line = Line::from_iter([
+            Span::new_colored("hello", Color::Blue).unwrap(),
+            Span::new_colored("world", Color::Red).unwrap(),
+        ]);
+        let elide = |width, elision, unicode| {
+            let mut elided = line.clone();
+            with_unicode(unicode, || elided.elide(width, elision));
+            let elided = elided.fmt_for_test().to_string();
+            elided
+        };
+
+        assert_eq!(
+            "<span fg=blue>hel…</span><span fg=red>rld</span>",
+            elide(7, Elision::Middle, true)
+        );
+        assert_eq!(
+            "<span fg=blue>he...</span><span fg=red>ld</span>",
+            elide(7, Elision::Middle, false)
+        );
+        assert_eq!(
+            "<span fg=blue>…</span><span fg=red>rld</span>",
+            elide(4, Elision::Start, true)
+        );
+        assert_eq!(
+            "<span fg=blue>...</span><span fg=red>d</span>",
+            elide(4, Elision::Start, false)
+        );
+
+        let mut unchanged = line.clone();
+        unchanged.elide(10, Elision::End);
+        assert_eq!(unchanged, line);
+    }
+
+    #[test]
+    fn test_justify() -> anyhow::Result<()> {
+        let line = Line::from_iter([
+            Span::new_colored("hello", Color::Blue)?,
+            Span::new_colored("world", Color::Red)?,
+        ]);
+
+        let mut truncated = line.clone();
+        with_unicode(true, || truncated.truncate_with_ellipsis(6));
+        assert_eq!(
+            "<span fg=blue>hello</span><span fg=red>…</span>",
+            truncated.fmt_for_test().to_string()
+        );
+
+        for (alignment, expected) in [
+            (Alignment::Left, "helloworld   "),
+            (Alignment::Center, " helloworld  "),
+            (Alignment::Right, "   helloworld"),
+        ] {
+            let mut padded = line.clone();
+            padded.pad_to(13, alignment);
+            assert_eq!(padded.to_unstyled(), expected);
+        }
+
+        let mut unchanged = line.clone();
+        unchanged.pad_to(4, Alignment::Right);
+        assert_eq!(unchanged, line);
+
+        let mut justified = Line::unstyled("日本語")?;
+        with_unicode(true, || justified.justify(Alignment::Right, 4));
+        assert_eq!(justified.to_unstyled(), " 日…");
+        assert_eq!(justified.len(), 4);
+
+        let mut justified = Line::unstyled("日本語")?;
+        with_unicode(false, || justified.justify(Alignment::Right, 4));
+        assert_eq!(justified.to_unstyled(), " ...");
+
+        Ok(())
+    }
+
     #[test]
     fn test_push_collapses() {
         let mut line = Line::default();
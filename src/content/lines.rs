@@ -15,17 +15,24 @@ use std::fmt::Formatter;
 use std::iter;
 use std::mem;
 
-use crossterm::style::Attribute;
-use crossterm::style::Attributes;
-use crossterm::style::Color;
 use itertools::Itertools;
+use termwiz::cell::Blink;
 use termwiz::cell::Intensity;
+use termwiz::cell::Underline;
 use termwiz::color::ColorSpec;
 use termwiz::color::RgbColor;
 use termwiz::escape::csi::Sgr;
 use termwiz::escape::csi::CSI;
+use termwiz::escape::parser::Parser;
 use termwiz::escape::Action;
-
+use termwiz::escape::ControlCode;
+use termwiz::escape::OperatingSystemCommand;
+
+use crate::content::span::text_width;
+use crate::content::Alignment;
+use crate::style::Attribute;
+use crate::style::Attributes;
+use crate::style::Color;
 use crate::style::ContentStyle;
 use crate::style::StyledContent;
 use crate::Dimensions;
@@ -41,12 +48,17 @@ struct ColoredStringParser {
     foreground_color: Option<Color>,
     background_color: Option<Color>,
     attributes: Attributes,
+    /// The target of the OSC 8 hyperlink the text is in, if any.
+    link: Option<String>,
     line_buffer: String,
     spans: Vec<Span>,
 }
 
 impl ColoredStringParser {
     fn push_current(&mut self) {
+        if self.line_buffer.is_empty() {
+            return;
+        }
         let sc = StyledContent::new(
             ContentStyle {
                 foreground_color: self.foreground_color,
@@ -55,7 +67,21 @@ impl ColoredStringParser {
             },
             std::mem::take(&mut self.line_buffer),
         );
-        self.spans.push(Span::new_styled_lossy(sc));
+        let span = Span::new_styled_lossy(sc);
+        self.spans.push(match &self.link {
+            Some(link) => span.with_link(link.as_str()),
+            None => span,
+        });
+    }
+
+    /// Sets or clears `attribute`, starting a new span.
+    fn set_attribute(&mut self, attribute: Attribute, enabled: bool) {
+        self.push_current();
+        if enabled {
+            self.attributes.set(attribute);
+        } else {
+            self.attributes.unset(attribute);
+        }
     }
 
     fn spec_to_color(spec: ColorSpec) -> Option<Color> {
@@ -67,7 +93,7 @@ impl ColoredStringParser {
     }
 
     /// Given a line w/ some ANSI encoded color characters, turn it into a list of spans.
-    fn parse_line(&mut self, parser: &mut termwiz::escape::parser::Parser, s: &str) -> Line {
+    fn parse_line(&mut self, parser: &mut Parser, s: &str) -> Line {
         // Because we only stick "printable" characters onto the buffer, and skip any other
         // class of characters that we don't recognize, this should be a
         // roughly sanitized string. We do one more pass when we create the Span, just to
@@ -76,6 +102,11 @@ impl ColoredStringParser {
             Action::Print(c) => {
                 self.line_buffer.push(c);
             }
+            // The text written so far is overwritten, e.g. by progress output.
+            Action::Control(ControlCode::CarriageReturn) => {
+                self.line_buffer.clear();
+                self.spans.clear();
+            }
             Action::CSI(CSI::Sgr(Sgr::Reset)) => {
                 self.push_current();
                 self.foreground_color = None;
@@ -83,12 +114,33 @@ impl ColoredStringParser {
                 self.attributes = Attributes::default();
             }
             Action::CSI(CSI::Sgr(Sgr::Intensity(intensity))) => {
-                self.push_current();
-                self.attributes = match intensity {
-                    Intensity::Normal => Attributes::default(),
-                    Intensity::Bold => Attributes::from(Attribute::Bold),
-                    Intensity::Half => Attributes::from(Attribute::Dim),
-                };
+                self.set_attribute(Attribute::Bold, intensity == Intensity::Bold);
+                self.set_attribute(Attribute::Dim, intensity == Intensity::Half);
+            }
+            Action::CSI(CSI::Sgr(Sgr::Underline(underline))) => {
+                self.set_attribute(Attribute::Underlined, underline != Underline::None);
+            }
+            Action::CSI(CSI::Sgr(Sgr::Blink(blink))) => {
+                self.set_attribute(Attribute::SlowBlink, blink == Blink::Slow);
+                self.set_attribute(Attribute::RapidBlink, blink == Blink::Rapid);
+            }
+            Action::CSI(CSI::Sgr(Sgr::Italic(enabled))) => {
+                self.set_attribute(Attribute::Italic, enabled);
+            }
+            Action::CSI(CSI::Sgr(Sgr::Inverse(enabled))) => {
+                self.set_attribute(Attribute::Reverse, enabled);
+            }
+            Action::CSI(CSI::Sgr(Sgr::Invisible(enabled))) => {
+                self.set_attribute(Attribute::Hidden, enabled);
+            }
+            Action::CSI(CSI::Sgr(Sgr::StrikeThrough(enabled))) => {
+                self.set_attribute(Attribute::CrossedOut, enabled);
+            }
+            Action::OperatingSystemCommand(osc) => {
+                if let OperatingSystemCommand::SetHyperlink(link) = *osc {
+                    self.push_current();
+                    self.link = link.map(|link| link.uri().to_owned());
+                }
             }
             Action::CSI(CSI::Sgr(Sgr::Foreground(spec))) => {
                 self.push_current();
@@ -106,6 +158,17 @@ impl ColoredStringParser {
     }
 }
 
+/// Parses a single line containing ANSI escape codes, see [`Line::from_ansi`].
+pub(crate) fn parse_ansi_line(s: &str) -> Line {
+    ColoredStringParser::default().parse_line(&mut Parser::new(), s)
+}
+
+/// The text a terminal shows for `line`, where a carriage return moves back to its start.
+fn after_carriage_return(line: &str) -> &str {
+    let line = line.trim_end_matches('\r');
+    line.rsplit('\r').next().unwrap_or(line)
+}
+
 /// Set of helper methods for `Vec<Line>`, that manipulate on each line individually.
 impl Lines {
     /// Empty lines block.
@@ -114,30 +177,40 @@ impl Lines {
     }
 
     /// Creates an instance of [`Lines`] with a style applied from a single multiline string.
+    /// Lines may end with `\n` or `\r\n`. A carriage return within a line overwrites the text
+    /// before it, as progress output expects, so only the text after it is kept.
     pub fn from_multiline_string(multiline_string: &str, style: ContentStyle) -> Lines {
         multiline_string
             .lines()
             .map(|line| {
-                let styled = StyledContent::new(style, line.to_owned());
+                let styled = StyledContent::new(style, after_carriage_return(line).to_owned());
                 Line::from_iter([Span::new_styled_lossy(styled)])
             })
             .collect()
     }
 
-    /// Takes a multiline string that might contain ANSI color codes, and returns a set of lines
-    /// that include spans representing those color codes.
+    /// Takes a multiline string that might contain ANSI escape codes, e.g. captured compiler
+    /// output, and returns a set of lines that include spans styled accordingly. Colors, text
+    /// attributes such as bold or underlined, and OSC 8 hyperlinks are kept, and carry over to
+    /// the following lines until reset, as they would in a terminal.
     ///
     /// Note that any other types of control characters are omitted, and certain whitespace
-    /// characters are also disallowed / replaced.
-    pub fn from_colored_multiline_string(multiline_string: &str) -> Lines {
-        let mut parser = termwiz::escape::parser::Parser::new();
+    /// characters are also disallowed / replaced. Carriage returns are handled like in
+    /// [`from_multiline_string`](Self::from_multiline_string).
+    pub fn from_multiline_string_ansi(multiline_string: &str) -> Lines {
+        let mut parser = Parser::new();
         let mut color_parser = ColoredStringParser::default();
         multiline_string
             .lines()
-            .map(|s| color_parser.parse_line(&mut parser, s))
+            .map(|s| color_parser.parse_line(&mut parser, s.trim_end_matches('\r')))
             .collect()
     }
 
+    /// Same as [`from_multiline_string_ansi`](Self::from_multiline_string_ansi).
+    pub fn from_colored_multiline_string(multiline_string: &str) -> Lines {
+        Self::from_multiline_string_ansi(multiline_string)
+    }
+
     /// Number of lines.
     pub fn len(&self) -> usize {
         self.0.len()
@@ -166,6 +239,13 @@ impl Lines {
             .for_each(|line| line.truncate_line(max_width));
     }
 
+    /// The column and row of the first span marked with [`Span::with_cursor`], if any.
+    pub fn cursor_position(&self) -> Option<(usize, usize)> {
+        self.iter()
+            .enumerate()
+            .find_map(|(row, line)| Some((line.cursor_column()?, row)))
+    }
+
     /// Returns the max column width of any line
     pub fn max_line_length(&self) -> usize {
         // for each line in the LHS
@@ -217,6 +297,18 @@ impl Lines {
         }
     }
 
+    /// Shortens every line to at most `width` columns, see [`Line::truncate_with_ellipsis`].
+    pub fn truncate_lines_with_ellipsis(&mut self, width: usize) {
+        self.iter_mut()
+            .for_each(|line| line.truncate_with_ellipsis(width));
+    }
+
+    /// Makes every line exactly `width` columns wide, see [`Line::justify`].
+    pub fn justify_lines(&mut self, alignment: Alignment, width: usize) {
+        self.iter_mut()
+            .for_each(|line| line.justify(alignment, width));
+    }
+
     /// Given a set of lines, set them all to the exact width
     pub fn set_lines_to_exact_width(&mut self, exact_width: usize) {
         self.iter_mut()
@@ -225,13 +317,13 @@ impl Lines {
 
     /// Extends the Lines list by the given length, adding empty lines at the bottom
     pub fn pad_lines_bottom(&mut self, amount: usize) {
-        let mut extender = iter::repeat(Line::default()).take(amount);
+        let mut extender = iter::repeat_n(Line::default(), amount);
         self.0.extend(&mut extender);
     }
 
     /// Same functionality as `pad_lines_bottom` but on the top.
     pub fn pad_lines_top(&mut self, amount: usize) {
-        let extender = iter::repeat(Line::default()).take(amount);
+        let extender = iter::repeat_n(Line::default(), amount);
 
         self.0.splice(0..0, extender);
     }
@@ -280,6 +372,35 @@ impl Lines {
         Ok(())
     }
 
+    /// Returns whether `needle` occurs in the text of these lines, ignoring styling.
+    /// Matches do not span lines.
+    pub fn contains(&self, needle: &str) -> bool {
+        self.iter().any(|line| line.to_unstyled().contains(needle))
+    }
+
+    /// Returns the row and column of the first occurrence of `needle` in the text of these lines,
+    /// ignoring styling. See [`find_all`](Self::find_all).
+    pub fn find(&self, needle: &str) -> Option<(usize, usize)> {
+        self.find_all(needle).next()
+    }
+
+    /// Returns the row and column of every occurrence of `needle` in the text of these lines,
+    /// ignoring styling. Columns are in terminal cells, and matches do not span lines.
+    pub fn find_all<'a>(&'a self, needle: &'a str) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.iter().enumerate().flat_map(move |(row, line)| {
+            let text = line.to_unstyled();
+            text.match_indices(needle)
+                .map(|(i, _)| (row, text_width(&text[..i])))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Fills in any styling left unspecified by the spans of every line from `base`.
+    /// See [`Span::apply_style_base`].
+    pub fn apply_style_base(&mut self, base: ContentStyle) {
+        self.iter_mut().for_each(|line| line.apply_style_base(base));
+    }
+
     /// Returns the maximum line width and the number of lines.
     /// This corresponds to how much space a justified version of the output would take.
     pub fn dimensions(&self) -> anyhow::Result<Dimensions> {
@@ -313,7 +434,7 @@ impl Lines {
         // can't do arbitrary zip, so this'll have to do
         padded
             .reduce(|mut all, output| {
-                for (all_line, output_line) in all.iter_mut().zip(output.into_iter()) {
+                for (all_line, output_line) in all.iter_mut().zip(output) {
                     all_line.extend(output_line);
                 }
 
@@ -343,6 +464,62 @@ impl FromIterator<Line> for Lines {
     }
 }
 
+impl FromIterator<Span> for Lines {
+    /// A single line of the spans.
+    fn from_iter<I: IntoIterator<Item = Span>>(iter: I) -> Self {
+        Self(vec![Line::from_iter(iter)])
+    }
+}
+
+impl From<Line> for Lines {
+    fn from(line: Line) -> Self {
+        Self(vec![line])
+    }
+}
+
+impl From<Vec<Line>> for Lines {
+    fn from(lines: Vec<Line>) -> Self {
+        Self(lines)
+    }
+}
+
+impl From<Vec<Span>> for Lines {
+    /// A single line of the spans.
+    fn from(spans: Vec<Span>) -> Self {
+        Self::from_iter(spans)
+    }
+}
+
+/// Unstyled lines, split on `\n`. Characters which cannot be drawn are replaced or removed, see
+/// [`Span::sanitized`].
+impl From<&str> for Lines {
+    fn from(text: &str) -> Self {
+        Self::from_multiline_string(text, ContentStyle::default())
+    }
+}
+
+impl From<String> for Lines {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+/// Each string is one or more lines, see the conversion from `&str`.
+impl From<Vec<String>> for Lines {
+    fn from(texts: Vec<String>) -> Self {
+        texts
+            .iter()
+            .flat_map(|text| Self::from(text.as_str()))
+            .collect()
+    }
+}
+
+impl From<Vec<&str>> for Lines {
+    fn from(texts: Vec<&str>) -> Self {
+        texts.into_iter().flat_map(Self::from).collect()
+    }
+}
+
 impl IntoIterator for Lines {
     type Item = Line;
     type IntoIter = <Vec<Line> as IntoIterator>::IntoIter;
@@ -354,10 +531,9 @@ impl IntoIterator for Lines {
 
 #[cfg(test)]
 mod tests {
-    use crossterm::style::Attribute;
-    use crossterm::style::Color;
-
     use super::*;
+    use crate::style::Attribute;
+    use crate::style::Color;
 
     #[test]
     fn truncate_lines() -> anyhow::Result<()> {
@@ -528,6 +704,30 @@ mod tests {
         assert_eq!(test, expected);
     }
 
+    #[test]
+    fn test_carriage_returns() {
+        let text = "built\r\n10%\r50%\r\ndone\r\n";
+        let rows = |lines: Lines| lines.iter().map(Line::to_unstyled).collect::<Vec<_>>();
+        assert_eq!(
+            rows(Lines::from_multiline_string(text, ContentStyle::default())),
+            vec!["built", "50%", "done"]
+        );
+        assert_eq!(
+            rows(Lines::from_colored_multiline_string(
+                "\x1b[32m10%\r50%\x1b[0m\r\n"
+            )),
+            vec!["50%"]
+        );
+        let colored = Lines::from_colored_multiline_string("\x1b[32m10%\r50%");
+        assert_eq!(
+            colored.0[0]
+                .iter()
+                .next()
+                .and_then(|s| s.style.foreground_color),
+            Some(Color::AnsiValue(2))
+        );
+    }
+
     #[allow(clippy::from_iter_instead_of_collect)] // More readable this way.
     #[test]
     fn test_colored_from_multiline_string() {
@@ -643,6 +843,53 @@ strips out {bs}invalid control sequences",
         assert_eq!(expected, lines);
     }
 
+    #[test]
+    fn test_from_multiline_string_ansi() -> anyhow::Result<()> {
+        let lines = Lines::from_multiline_string_ansi(
+            "\x1b[1;4merror\x1b[24m: \x1b[3mfoo\x1b[0m\n\x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x1b\\ here",
+        );
+        assert_eq!(lines.len(), 2);
+        let spans: Vec<_> = lines.0[0].iter().collect();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].content(), "error");
+        assert!(spans[0].style.attributes.has(Attribute::Bold));
+        assert!(spans[0].style.attributes.has(Attribute::Underlined));
+        assert!(spans[1].style.attributes.has(Attribute::Bold));
+        assert!(!spans[1].style.attributes.has(Attribute::Underlined));
+        assert!(spans[2].style.attributes.has(Attribute::Italic));
+
+        let spans: Vec<_> = lines.0[1].iter().collect();
+        assert_eq!(spans[0].content(), "docs");
+        assert_eq!(spans[0].link(), Some("https://example.com"));
+        assert_eq!(spans[1].link(), None);
+        assert_eq!(spans[1].style, ContentStyle::default());
+
+        assert_eq!(
+            Line::from_ansi("\x1b[31mred\x1b[0m plain"),
+            Line::from_iter([
+                Span::new_colored("red", Color::AnsiValue(1))?,
+                Span::new_unstyled(" plain")?,
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_conversions() -> anyhow::Result<()> {
+        let expected = Lines(vec![
+            Line::unstyled("a")?,
+            Line::unstyled("b")?,
+            Line::unstyled("c")?,
+        ]);
+        assert_eq!(Lines::from("a\nb\r\nc"), expected);
+        assert_eq!(Lines::from(vec!["a\nb", "c"]), expected);
+        assert_eq!(
+            Lines::from(vec![Span::new_unstyled("a")?, Span::new_unstyled("b")?]),
+            Lines(vec![Line::unstyled("ab")?])
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_fmt_for_test() {
         let lines = Lines::from_iter([
@@ -654,4 +901,25 @@ strips out {bs}invalid control sequences",
             format!("{}", lines.fmt_for_test())
         );
     }
+
+    #[test]
+    fn test_find() -> anyhow::Result<()> {
+        let lines = Lines::from_iter([
+            Line::unstyled("status: ok")?,
+            Line::from_iter([
+                Span::new_unstyled("日本 ")?,
+                Span::new_colored("ok", Color::Green)?,
+                Span::new_unstyled(" ok")?,
+            ]),
+        ]);
+        assert!(lines.contains("ok"));
+        assert!(!lines.contains("okok"));
+        assert_eq!(lines.find("ok"), Some((0, 8)));
+        assert_eq!(
+            lines.find_all("ok").collect::<Vec<_>>(),
+            vec![(0, 8), (1, 5), (1, 8)]
+        );
+        assert_eq!(lines.find("missing"), None);
+        Ok(())
+    }
 }
@@ -0,0 +1,328 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Rendering of Markdown snippets into styled, wrapped [`Lines`].
+//! Only the subset of Markdown that makes sense in a terminal is supported:
+//! headings, paragraphs, emphasis, inline code, code blocks, lists, block quotes, links and rules.
+
+use std::mem;
+
+use pulldown_cmark::Event;
+use pulldown_cmark::HeadingLevel;
+use pulldown_cmark::Options;
+use pulldown_cmark::Parser;
+use pulldown_cmark::Tag;
+
+use crate::style::Attribute;
+use crate::style::Color;
+use crate::style::ContentStyle;
+use crate::style::StyledContent;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+impl Lines {
+    /// Renders a Markdown snippet into lines, word wrapped to `width` columns.
+    pub fn from_markdown(markdown: &str, width: usize) -> Lines {
+        let mut renderer = Renderer::new(width);
+        for event in Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH) {
+            renderer.event(event);
+        }
+        renderer.finish()
+    }
+}
+
+struct Renderer {
+    width: usize,
+    lines: Vec<Line>,
+    current: Line,
+    /// Whether anything besides indentation was written to the current line.
+    has_content: bool,
+    /// A space which is only written if the line continues.
+    pending_space: Option<Span>,
+    /// Prefixes of the enclosing block quotes and list items, written at the start of every line.
+    indents: Vec<Span>,
+    /// Replaces the innermost indent of the next line, e.g. with a list bullet.
+    marker: Option<Span>,
+    styles: Vec<ContentStyle>,
+    /// The next number of each enclosing list, or `None` if the list is not numbered.
+    lists: Vec<Option<u64>>,
+    link: Option<String>,
+    in_code_block: bool,
+}
+
+impl Renderer {
+    fn new(width: usize) -> Self {
+        Self {
+            width,
+            lines: Vec::new(),
+            current: Line::default(),
+            has_content: false,
+            pending_space: None,
+            indents: Vec::new(),
+            marker: None,
+            styles: Vec::new(),
+            lists: Vec::new(),
+            link: None,
+            in_code_block: false,
+        }
+    }
+
+    fn style(&self) -> ContentStyle {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, modify: impl FnOnce(&mut ContentStyle)) {
+        let mut style = self.style();
+        modify(&mut style);
+        self.styles.push(style);
+    }
+
+    fn span(&self, text: &str, style: ContentStyle) -> Span {
+        let span = Span::new_styled_lossy(StyledContent::new(style, text.to_owned()));
+        match &self.link {
+            Some(link) => span.with_link(link.clone()),
+            None => span,
+        }
+    }
+
+    fn start_line(&mut self) {
+        if !self.current.is_empty() || self.has_content {
+            return;
+        }
+        let marker = self.marker.take();
+        let innermost = self.indents.len().saturating_sub(1);
+        for (i, indent) in self.indents.iter().enumerate() {
+            match &marker {
+                Some(marker) if i == innermost => self.current.push(marker.clone()),
+                _ => self.current.push(indent.clone()),
+            }
+        }
+    }
+
+    fn end_line(&mut self) {
+        if self.has_content {
+            self.lines.push(mem::take(&mut self.current));
+        }
+        self.current = Line::default();
+        self.has_content = false;
+        self.pending_space = None;
+    }
+
+    /// Ends the current block, separating it from the next one with a blank line.
+    fn end_block(&mut self) {
+        self.end_line();
+        if matches!(self.lines.last(), Some(line) if !line.is_empty()) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn push_word(&mut self, word: &str, style: ContentStyle) {
+        let span = self.span(word, style);
+        if self.has_content {
+            let space = self.pending_space.as_ref().map_or(0, Span::len);
+            if self.current.len() + space + span.len() > self.width {
+                self.end_line();
+            }
+        }
+        self.start_line();
+        if let Some(space) = self.pending_space.take() {
+            self.current.push(space);
+        }
+        self.current.push(span);
+        self.has_content = true;
+    }
+
+    fn push_text(&mut self, text: &str, style: ContentStyle) {
+        for (i, word) in text.split(' ').enumerate() {
+            if i > 0 && self.has_content {
+                self.pending_space = Some(self.span(" ", style));
+            }
+            if !word.is_empty() {
+                self.push_word(word, style);
+            }
+        }
+    }
+
+    fn push_code_block(&mut self, text: &str) {
+        let style = ContentStyle {
+            foreground_color: Some(Color::Cyan),
+            ..self.style()
+        };
+        for line in text.lines() {
+            self.start_line();
+            self.current.push(self.span(line, style));
+            self.has_content = true;
+            self.end_line();
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_code_block => self.push_code_block(&text),
+            Event::Text(text) => self.push_text(&text, self.style()),
+            Event::Code(code) => {
+                let style = ContentStyle {
+                    foreground_color: Some(Color::Cyan),
+                    ..self.style()
+                };
+                self.push_text(&code, style);
+            }
+            Event::SoftBreak => self.push_text(" ", self.style()),
+            Event::HardBreak => self.end_line(),
+            Event::Rule => {
+                self.end_line();
+                self.start_line();
+                let width = self.width.saturating_sub(self.current.len());
+                self.current
+                    .push(self.span(&"─".repeat(width), self.style()));
+                self.has_content = true;
+                self.end_block();
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading(level, ..) => self.push_style(|style| {
+                style.attributes.set(Attribute::Bold);
+                if level == HeadingLevel::H1 {
+                    style.attributes.set(Attribute::Underlined);
+                }
+            }),
+            Tag::BlockQuote => {
+                let style = ContentStyle {
+                    attributes: Attribute::Dim.into(),
+                    ..ContentStyle::default()
+                };
+                self.indents.push(Span::new_styled_lossy(StyledContent::new(
+                    style,
+                    "│ ".to_owned(),
+                )));
+            }
+            Tag::CodeBlock(_) => {
+                self.in_code_block = true;
+                self.indents.push(Span::padding(4));
+            }
+            Tag::List(start) => self.lists.push(start),
+            Tag::Item => {
+                self.end_line();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_owned(),
+                };
+                let marker = Span::new_unstyled_lossy(marker);
+                self.indents.push(Span::padding(marker.len()));
+                self.marker = Some(marker);
+            }
+            Tag::Emphasis => self.push_style(|style| style.attributes.set(Attribute::Italic)),
+            Tag::Strong => self.push_style(|style| style.attributes.set(Attribute::Bold)),
+            Tag::Strikethrough => {
+                self.push_style(|style| style.attributes.set(Attribute::CrossedOut))
+            }
+            Tag::Link(_, url, _) => {
+                self.link = Some(url.to_string());
+                self.push_style(|style| style.attributes.set(Attribute::Underlined));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.end_block(),
+            Tag::Heading(..) => {
+                self.styles.pop();
+                self.end_block();
+            }
+            Tag::BlockQuote => {
+                self.end_line();
+                self.indents.pop();
+            }
+            Tag::CodeBlock(_) => {
+                self.in_code_block = false;
+                self.indents.pop();
+                self.end_block();
+            }
+            Tag::List(_) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.end_block();
+                }
+            }
+            Tag::Item => {
+                self.end_line();
+                self.indents.pop();
+                self.marker = None;
+            }
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough => {
+                self.styles.pop();
+            }
+            Tag::Link(..) => {
+                self.link = None;
+                self.styles.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> Lines {
+        self.end_line();
+        while matches!(self.lines.last(), Some(line) if line.is_empty()) {
+            self.lines.pop();
+        }
+        Lines(self.lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let lines = Lines::from_markdown(
+            "# Next steps\n\nRun `cargo test` *now*.\n\n- one\n- two\n\n1. first\n2. second\n",
+            40,
+        );
+        assert_eq!(
+            lines.fmt_for_test().to_string(),
+            "<span bold underlined>Next steps</span>\n\
+             \n\
+             Run <span fg=cyan>cargo test</span> <span italic>now</span>.\n\
+             \n\
+             • one\n\
+             • two\n\
+             \n\
+             1. first\n\
+             2. second\n"
+        );
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let lines = Lines::from_markdown("- aaa bbb ccc ddd", 10);
+        assert_eq!(lines.fmt_for_test().to_string(), "• aaa bbb\n  ccc ddd\n");
+    }
+
+    #[test]
+    fn test_code_block() {
+        let lines = Lines::from_markdown("```\nfn main() {}\n```\n", 40);
+        assert_eq!(
+            lines.fmt_for_test().to_string(),
+            "    <span fg=cyan>fn main() {}</span>\n"
+        );
+    }
+}
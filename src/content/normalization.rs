@@ -0,0 +1,87 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Optional Unicode normalization of text as it enters a [`Span`](crate::Span).
+//! Text assembled from several sources may mix composed and decomposed forms, which render
+//! identically but differ in length and compare unequal.
+
+#[cfg(feature = "unicode-normalization")]
+use std::cell::Cell;
+#[cfg(feature = "unicode-normalization")]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "unicode-normalization")]
+use std::sync::atomic::Ordering;
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+#[cfg(feature = "unicode-normalization")]
+static NORMALIZE_NFC: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "unicode-normalization")]
+thread_local! {
+    static SCOPED_NFC: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Enables or disables NFC normalization of all spans subsequently built from strings, including
+/// those produced by the ANSI parser. Disabled by default.
+#[cfg(feature = "unicode-normalization")]
+pub fn set_nfc_normalization(enabled: bool) {
+    NORMALIZE_NFC.store(enabled, Ordering::Relaxed);
+}
+
+/// Runs `f` with NFC normalization enabled or disabled on the current thread, regardless of the
+/// process-wide setting. The previous setting is restored when `f` returns or unwinds.
+#[cfg(feature = "unicode-normalization")]
+pub fn with_nfc_normalization<R>(enabled: bool, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<bool>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_NFC.with(|scoped| scoped.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SCOPED_NFC.with(|scoped| scoped.replace(Some(enabled))));
+    f()
+}
+
+#[cfg(feature = "unicode-normalization")]
+fn nfc_enabled() -> bool {
+    SCOPED_NFC
+        .with(Cell::get)
+        .unwrap_or_else(|| NORMALIZE_NFC.load(Ordering::Relaxed))
+}
+
+/// Applies the configured normalization to text entering a span.
+pub(crate) fn normalize(content: String) -> String {
+    #[cfg(feature = "unicode-normalization")]
+    if nfc_enabled() && !unicode_normalization::is_nfc(&content) {
+        return content.nfc().collect();
+    }
+    content
+}
+
+#[cfg(all(test, feature = "unicode-normalization"))]
+mod tests {
+    use super::*;
+    use crate::Span;
+
+    #[test]
+    fn test_nfc() -> anyhow::Result<()> {
+        let decomposed = "e\u{301}";
+        let span = with_nfc_normalization(true, || Span::new_unstyled(decomposed))?;
+        assert_eq!(span.content(), "\u{e9}");
+
+        let span = with_nfc_normalization(false, || Span::new_unstyled(decomposed))?;
+        assert_eq!(span.content(), decomposed);
+
+        Ok(())
+    }
+}
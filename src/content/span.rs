@@ -8,27 +8,47 @@
  */
 
 use std::borrow::Cow;
+use std::cmp;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::sync::Arc;
 
-use crossterm::style::Attribute;
-use crossterm::style::Color;
-use crossterm::style::ContentStyle;
-use crossterm::style::ResetColor;
-use crossterm::style::SetAttributes;
-use crossterm::style::SetBackgroundColor;
-use crossterm::style::SetForegroundColor;
-use crossterm::style::StyledContent;
-use crossterm::Command;
-use termwiz::cell;
 use unicode_segmentation::Graphemes;
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::ansi::Command;
+use crate::ansi::ResetColor;
+use crate::ansi::SetAttributes;
+use crate::ansi::SetBackgroundColor;
+use crate::ansi::SetForegroundColor;
+use crate::content::elide;
+use crate::content::hyperlinks_enabled;
+use crate::content::normalization::normalize;
+use crate::content::Elision;
+use crate::style::color_support;
+use crate::style::Attribute;
+use crate::style::Color;
+use crate::style::ContentStyle;
+use crate::style::StyledContent;
 
 #[derive(Debug, thiserror::Error)]
 enum SpanError {
     #[error("Word {0} contains non-space whitespace")]
     InvalidWhitespace(String),
+    #[error("Word {0:?} contains control characters")]
+    ControlCharacter(String),
+}
+
+impl SpanError {
+    fn invalid(content: String) -> Self {
+        if content.contains(|c: char| c.is_control() && !c.is_whitespace()) {
+            SpanError::ControlCharacter(content)
+        } else {
+            SpanError::InvalidWhitespace(content)
+        }
+    }
 }
 
 /// A `Span` is a segment of text that may or may not have [`style`](crate::style) applied to it.
@@ -38,28 +58,93 @@ enum SpanError {
 pub struct Span {
     pub(crate) content: Cow<'static, str>,
     pub style: ContentStyle,
+    /// Target of an OSC 8 hyperlink covering the span, if any.
+    pub(crate) link: Option<Arc<str>>,
+    /// Whether the terminal cursor should be placed at the span, see
+    /// [`with_cursor`](Self::with_cursor).
+    pub(crate) cursor: bool,
+}
+
+/// The number of terminal cells `grapheme` takes up. Clusters of several code points, such as
+/// emoji with modifiers or flags, are drawn as a single glyph of at most two cells.
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    cmp::min(grapheme.width(), 2)
+}
+
+/// The number of terminal cells `text` takes up, see [`grapheme_width`].
+pub(crate) fn text_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
 }
 
 /// Test whether a char is permissable to be inside a Span.
-/// Whitespace is not allowed, except for spaces.
+/// Whitespace is not allowed, except for spaces. Neither are control characters, as escape
+/// sequences could move the cursor or clear the screen, destroying the canvas.
 pub(crate) fn char_valid(c: char) -> bool {
-    c == ' ' || !c.is_whitespace()
+    c == ' ' || !(c.is_whitespace() || c.is_control())
 }
 
 /// Strip invalid characters from the string.
+/// Escape sequences are removed entirely, rather than leaving their printable parts behind.
 pub(crate) fn sanitize<S: std::fmt::Display>(stringlike: S) -> String {
-    let mut content = stringlike.to_string();
+    let mut content = strip_escape_sequences(&stringlike.to_string());
     content.retain(char_valid);
-    content
+    normalize(content)
+}
+
+/// Removes CSI (e.g. `ESC [ 2 J`), OSC (e.g. `ESC ] 0 ; title BEL`) and other escape sequences.
+/// A single span has a single style, so SGR sequences go too: [`Line::sanitized`](crate::Line::sanitized)
+/// keeps them as styles instead.
+fn strip_escape_sequences(s: &str) -> String {
+    if !s.contains(['\x1b', '\u{9b}']) {
+        return s.to_owned();
+    }
+
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let introducer = match c {
+            '\u{9b}' => '[',
+            '\x1b' => match chars.next() {
+                Some(c) => c,
+                None => break,
+            },
+            c => {
+                res.push(c);
+                continue;
+            }
+        };
+        match introducer {
+            // Parameters and intermediates, up to and including the final byte.
+            '[' => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // A string terminated by BEL or ST (`ESC \`).
+            ']' | 'P' | '_' | '^' | 'X' => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            // Other sequences are a single character after the escape.
+            _ => {}
+        }
+    }
+    res
 }
 
 impl Span {
     #[inline]
-    // This could be const fn, but `crossterm::Attributes` constructor is not const.
-    pub fn dash() -> Span {
+    pub const fn dash() -> Span {
         Span {
             content: Cow::Borrowed("-"),
-            style: ContentStyle::default(),
+            style: ContentStyle::new(),
+            link: None,
+            cursor: false,
         }
     }
 
@@ -74,6 +159,8 @@ impl Span {
         Span {
             content: Cow::Owned(content),
             style: ContentStyle::default(),
+            link: None,
+            cursor: false,
         }
     }
 
@@ -81,25 +168,62 @@ impl Span {
         &self.content
     }
 
+    /// The URL this span links to, if any.
+    pub fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
+    /// Creates an unstyled span displaying `text` which links to `url`, see [`with_link`](Self::with_link).
+    pub fn new_link<S: std::fmt::Display>(text: S, url: impl Into<String>) -> anyhow::Result<Span> {
+        Ok(Self::new_unstyled(text)?.with_link(url))
+    }
+
+    /// Turns the span into a hyperlink to `url`, which terminals supporting OSC 8 make clickable.
+    /// Elsewhere, the span is drawn as plain text, see
+    /// [`hyperlinks_enabled`](crate::content::hyperlinks_enabled).
+    /// Control characters, which could end the escape sequence early, are removed from the URL.
+    pub fn with_link(mut self, url: impl Into<String>) -> Span {
+        let mut url = url.into();
+        url.retain(|c| !c.is_control());
+        self.link = Some(Arc::from(url));
+        self
+    }
+
+    /// Places the terminal cursor at the start of the span when it is drawn on the canvas, e.g. at
+    /// the insertion point of an input field. The first such span in the frame wins. Spans are not
+    /// merged with neighbours which don't also carry the cursor.
+    pub fn with_cursor(mut self) -> Span {
+        self.cursor = true;
+        self
+    }
+
+    pub fn has_cursor(&self) -> bool {
+        self.cursor
+    }
+
     /// Create an unstyled span with the specified amount of whitespace padding.
     pub fn padding(amount: usize) -> Self {
         Self {
             content: Cow::Owned(format!("{:<width$}", "", width = amount)),
             style: ContentStyle::default(),
+            link: None,
+            cursor: false,
         }
     }
 
     /// Attempt to create a new, unstyled span equivalent to the underlying stringlike.
     /// This will fail if the input string is not [`valid`](Span::valid).
     pub fn new_unstyled<S: std::fmt::Display>(stringlike: S) -> anyhow::Result<Self> {
-        let owned = stringlike.to_string();
+        let owned = normalize(stringlike.to_string());
         if Self::valid(&owned) {
             Ok(Self {
                 content: Cow::Owned(owned),
                 style: ContentStyle::default(),
+                link: None,
+                cursor: false,
             })
         } else {
-            Err(SpanError::InvalidWhitespace(owned).into())
+            Err(SpanError::invalid(owned).into())
         }
     }
 
@@ -108,6 +232,8 @@ impl Span {
         Self {
             content: Cow::Owned(content),
             style: ContentStyle::default(),
+            link: None,
+            cursor: false,
         }
     }
 
@@ -116,11 +242,13 @@ impl Span {
     pub fn new_styled(content: StyledContent<String>) -> anyhow::Result<Self> {
         if Self::valid(content.content()) {
             Ok(Self {
-                content: Cow::Owned(content.content().clone()),
+                content: Cow::Owned(normalize(content.content().clone())),
                 style: *content.style(),
+                link: None,
+                cursor: false,
             })
         } else {
-            Err(SpanError::InvalidWhitespace(content.content().to_owned()).into())
+            Err(SpanError::invalid(content.content().to_owned()).into())
         }
     }
 
@@ -130,6 +258,8 @@ impl Span {
         Self {
             content: Cow::Owned(content),
             style: *span.style(),
+            link: None,
+            cursor: false,
         }
     }
 
@@ -153,22 +283,46 @@ impl Span {
         ))
     }
 
-    /// Returns the number of graphemes in the span.
+    /// Returns the number of terminal cells the span takes up, see [`width`](Self::width).
     pub fn len(&self) -> usize {
-        // Pulled this dep from another FB employee's project - better unicode support for terminal column widths.
-        cell::unicode_column_width(&self.content, None)
+        self.width()
+    }
+
+    /// Returns the number of terminal cells the span takes up.
+    /// Wide characters such as CJK ideographs and most emoji take two cells, and combining marks none.
+    pub fn width(&self) -> usize {
+        text_width(&self.content)
     }
 
     pub fn is_empty(&self) -> bool {
         self.content.is_empty()
     }
 
+    /// Fills in any styling this span leaves unspecified from `base`.
+    /// Colors set on the span win over those of `base`, and attributes of both are combined.
+    pub fn apply_style_base(&mut self, base: ContentStyle) {
+        self.style.foreground_color = self.style.foreground_color.or(base.foreground_color);
+        self.style.background_color = self.style.background_color.or(base.background_color);
+        self.style.attributes = base.attributes | self.style.attributes;
+    }
+
+    /// Shortens the span to at most `width` columns, keeping its style.
+    /// See [`elide`](crate::content::elide).
+    pub fn elide(&self, width: usize, elision: Elision) -> Span {
+        Span {
+            content: Cow::Owned(elide(&self.content, width, elision)),
+            style: self.style,
+            link: self.link.clone(),
+            cursor: self.cursor,
+        }
+    }
+
     /// Iterates over each [`Grapheme`](Graphemes) in the [`Span`].
     /// Applies the stylization of the `Span` to each `Grapheme`.
     /// Because a `Grapheme` is represented as another string, the sub-`Span` is represented as a `Span`.
     /// This `panics` if it encounters unicode that it doesn't know how to deal with.
     pub fn iter(&self) -> impl Iterator<Item = Span> + '_ {
-        SpanIterator(&self.style, self.content.graphemes(true))
+        SpanIterator(self, self.content.graphemes(true))
     }
 
     pub(crate) fn render(&self, f: &mut impl fmt::Write) -> fmt::Result {
@@ -176,15 +330,29 @@ impl Span {
             return Ok(());
         }
 
+        let link = self.link.as_ref().filter(|_| hyperlinks_enabled());
+        if let Some(link) = link {
+            write!(f, "\x1b]8;;{}\x1b\\", link)?;
+        }
+
         let mut reset_background = false;
         let mut reset_foreground = false;
         let mut reset = false;
 
-        if let Some(bg) = self.style.background_color {
+        let support = color_support();
+        if let Some(bg) = self
+            .style
+            .background_color
+            .and_then(|c| support.downsample(c))
+        {
             SetBackgroundColor(bg).write_ansi(f)?;
             reset_background = true;
         }
-        if let Some(fg) = self.style.foreground_color {
+        if let Some(fg) = self
+            .style
+            .foreground_color
+            .and_then(|c| support.downsample(c))
+        {
             SetForegroundColor(fg).write_ansi(f)?;
             reset_foreground = true;
         }
@@ -206,6 +374,10 @@ impl Span {
             }
         }
 
+        if link.is_some() {
+            write!(f, "\x1b]8;;\x1b\\")?;
+        }
+
         Ok(())
     }
 
@@ -262,7 +434,9 @@ impl Span {
             fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
                 let style_is_default = self.0.style.foreground_color.is_none()
                     && self.0.style.background_color.is_none()
-                    && self.0.style.attributes.is_empty();
+                    && self.0.style.attributes.is_empty()
+                    && self.0.link.is_none()
+                    && !self.0.cursor;
                 if style_is_default {
                     write!(f, "{}", self.0.content)
                 } else {
@@ -273,6 +447,12 @@ impl Span {
                     if let Some(bg) = self.0.style.background_color {
                         write!(f, " bg={}", fmt_color(bg))?;
                     }
+                    if let Some(link) = &self.0.link {
+                        write!(f, " link={}", link)?;
+                    }
+                    if self.0.cursor {
+                        write!(f, " cursor")?;
+                    }
                     if !self.0.style.attributes.is_empty() {
                         let mut a = self.0.style.attributes;
                         for known in Attribute::iterator() {
@@ -321,7 +501,7 @@ impl TryFrom<StyledContent<String>> for Span {
     }
 }
 
-pub(crate) struct SpanIterator<'a>(&'a ContentStyle, Graphemes<'a>);
+pub(crate) struct SpanIterator<'a>(&'a Span, Graphemes<'a>);
 
 impl<'a> Iterator for SpanIterator<'a> {
     type Item = Span;
@@ -329,18 +509,20 @@ impl<'a> Iterator for SpanIterator<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         let content = self.1.next();
         content.map(|content| Span {
-            style: *self.0,
+            style: self.0.style,
             content: Cow::Owned(content.to_owned()),
+            link: self.0.link.clone(),
+            cursor: self.0.cursor,
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crossterm::style::Attributes;
-    use crossterm::style::Stylize;
-
     use super::*;
+    use crate::style::Attributes;
+    use crate::style::Stylize;
+    use crate::testing::with_unicode;
 
     const BAD_WORD: &str = "i'm really gonna do it\n汉字";
     #[test]
@@ -355,6 +537,16 @@ mod tests {
         assert!(Span::new_unstyled(sanitized.content).is_ok());
     }
 
+    #[test]
+    fn escape_sequences() {
+        assert!(Span::new_unstyled("\x1b[2Jboom").is_err());
+        assert_eq!(
+            Span::sanitized("a\x1b[2J\x1b[10;1Hb\x1b]0;title\x07c\x1b]8;;x\x1b\\d\x1b7e").content,
+            "abcde"
+        );
+        assert_eq!(Span::sanitized("bell\x07").content, "bell");
+    }
+
     #[test]
     fn multi_column_character() {
         let foot = "\u{1f9b6}";
@@ -391,6 +583,26 @@ mod tests {
         assert_eq!(lhs, rhs);
     }
 
+    #[test]
+    fn test_apply_style_base() {
+        let mut span = Span::new_styled_lossy("hello".to_owned().red());
+        span.apply_style_base(*"".to_owned().blue().on_grey().dim().style());
+        assert_eq!(
+            "<span fg=red bg=grey dim>hello</span>",
+            span.fmt_for_test().to_string()
+        );
+    }
+
+    #[test]
+    fn test_elide() {
+        let span = Span::new_styled_lossy("sha256:0123456789".to_owned().red());
+        let elided = with_unicode(true, || span.elide(9, Elision::Middle));
+        assert_eq!(elided.content(), "sha2…6789");
+        assert_eq!(elided.style, span.style);
+        let elided = with_unicode(false, || span.elide(9, Elision::Middle));
+        assert_eq!(elided.content(), "sha...789");
+    }
+
     #[test]
     fn test_fmt_for_test() {
         let span = Span::new_styled(StyledContent::new(
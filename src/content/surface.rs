@@ -0,0 +1,300 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::content::span::sanitize;
+use crate::style::ContentStyle;
+use crate::Dimensions;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Cell {
+    Blank,
+    Grapheme(Span),
+    /// The second column of a double-width grapheme.
+    Continuation,
+}
+
+/// A run of cells within a row which differ between two [`DrawSurface`]s.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChangedRegion {
+    pub row: usize,
+    pub columns: Range<usize>,
+}
+
+/// A grid of styled cells, for components which place characters at arbitrary positions, such as
+/// charts and heatmaps, rather than building their output line by line.
+/// Once drawn, the surface is converted into [`Lines`] with [`to_lines`](Self::to_lines).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DrawSurface {
+    dimensions: Dimensions,
+    cells: Vec<Cell>,
+}
+
+impl DrawSurface {
+    /// A blank surface.
+    pub fn new(dimensions: Dimensions) -> Self {
+        Self {
+            dimensions,
+            cells: vec![Cell::Blank; dimensions.width * dimensions.height],
+        }
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    /// Blanks every cell.
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::Blank);
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.dimensions.width && y < self.dimensions.height)
+            .then(|| y * self.dimensions.width + x)
+    }
+
+    /// Blanks a cell, along with the other half of a double-width grapheme it is part of.
+    fn blank(&mut self, x: usize, y: usize) {
+        let Some(i) = self.index(x, y) else {
+            return;
+        };
+        match &self.cells[i] {
+            Cell::Continuation => self.cells[i - 1] = Cell::Blank,
+            Cell::Grapheme(span) if span.len() == 2 => self.cells[i + 1] = Cell::Blank,
+            _ => {}
+        }
+        self.cells[i] = Cell::Blank;
+    }
+
+    /// Places the first grapheme of `grapheme` at column `x` of row `y`. Double-width graphemes
+    /// also cover the next column. Returns false if the grapheme does not fit or is not printable.
+    pub fn set(&mut self, x: usize, y: usize, grapheme: &str, style: ContentStyle) -> bool {
+        let content = sanitize(grapheme);
+        let Some(content) = content.graphemes(true).next() else {
+            return false;
+        };
+        let span = Span {
+            content: Cow::Owned(content.to_owned()),
+            style,
+            link: None,
+            cursor: false,
+        };
+        let width = span.len();
+        if width == 0 || self.index(x + width - 1, y).is_none() {
+            return false;
+        }
+
+        for column in x..x + width {
+            self.blank(column, y);
+        }
+        let i = y * self.dimensions.width + x;
+        self.cells[i] = Cell::Grapheme(span);
+        if width == 2 {
+            self.cells[i + 1] = Cell::Continuation;
+        }
+        true
+    }
+
+    /// The grapheme at column `x` of row `y`, or `None` if the cell is blank, covered by a
+    /// double-width grapheme or out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Span> {
+        match &self.cells[self.index(x, y)?] {
+            Cell::Grapheme(span) => Some(span),
+            _ => None,
+        }
+    }
+
+    /// Writes `text` starting at column `x` of row `y`, clipped to the surface.
+    pub fn print(&mut self, x: usize, y: usize, text: &str, style: ContentStyle) {
+        let mut x = x;
+        for grapheme in sanitize(text).graphemes(true) {
+            if x >= self.dimensions.width {
+                break;
+            }
+            self.set(x, y, grapheme, style);
+            x += Span::sanitized(grapheme).len();
+        }
+    }
+
+    /// Copies the non-blank cells of `other` onto this surface, with its top left corner at
+    /// (`x`, `y`). Whatever falls outside is clipped.
+    pub fn blit(&mut self, x: usize, y: usize, other: &DrawSurface) {
+        for row in 0..other.dimensions.height {
+            for column in 0..other.dimensions.width {
+                if let Some(span) = other.get(column, row) {
+                    self.set(x + column, y + row, &span.content, span.style);
+                }
+            }
+        }
+    }
+
+    /// Draws `lines` with their top left corner at (`x`, `y`), clipped to the surface.
+    pub fn blit_lines(&mut self, x: usize, y: usize, lines: &Lines) {
+        for (row, line) in lines.iter().enumerate() {
+            let mut column = x;
+            for span in line.iter() {
+                for grapheme in span.iter() {
+                    if column >= self.dimensions.width {
+                        break;
+                    }
+                    self.set(column, y + row, &grapheme.content, grapheme.style);
+                    column += grapheme.len();
+                }
+            }
+        }
+    }
+
+    /// The runs of cells which differ from `previous`. Every row is changed if there is no previous
+    /// surface or its dimensions differ.
+    pub fn changed_regions(&self, previous: Option<&DrawSurface>) -> Vec<ChangedRegion> {
+        let width = self.dimensions.width;
+        let previous = match previous {
+            Some(previous) if previous.dimensions == self.dimensions => previous,
+            _ if width == 0 => return Vec::new(),
+            _ => {
+                return (0..self.dimensions.height)
+                    .map(|row| ChangedRegion {
+                        row,
+                        columns: 0..width,
+                    })
+                    .collect();
+            }
+        };
+
+        let mut res = Vec::new();
+        for row in 0..self.dimensions.height {
+            let mut start = None;
+            for column in 0..=width {
+                let i = row * width + column;
+                let changed = column < width && self.cells[i] != previous.cells[i];
+                match (changed, start) {
+                    (true, None) => start = Some(column),
+                    (false, Some(first)) => {
+                        res.push(ChangedRegion {
+                            row,
+                            columns: first..column,
+                        });
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        res
+    }
+
+    /// Converts the surface into lines, with blank cells as unstyled spaces.
+    pub fn to_lines(&self) -> Lines {
+        if self.dimensions.width == 0 {
+            return Lines(vec![Line::default(); self.dimensions.height]);
+        }
+        self.cells
+            .chunks(self.dimensions.width)
+            .map(|row| {
+                let mut line = Line::default();
+                for cell in row {
+                    match cell {
+                        Cell::Blank => line.push(Span::padding(1)),
+                        Cell::Grapheme(span) => line.push(span.clone()),
+                        Cell::Continuation => {}
+                    }
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+impl From<DrawSurface> for Lines {
+    fn from(surface: DrawSurface) -> Lines {
+        surface.to_lines()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Color;
+
+    fn red() -> ContentStyle {
+        ContentStyle {
+            foreground_color: Some(Color::Red),
+            ..ContentStyle::default()
+        }
+    }
+
+    #[test]
+    fn test_set() {
+        let mut surface = DrawSurface::new(Dimensions::new(4, 2));
+        assert!(surface.set(1, 0, "x", red()));
+        assert!(surface.set(2, 1, "汉", ContentStyle::default()));
+        assert!(!surface.set(3, 1, "字", ContentStyle::default()));
+        assert!(!surface.set(4, 0, "y", ContentStyle::default()));
+
+        assert_eq!(
+            surface.to_lines().fmt_for_test().to_string(),
+            " <span fg=red>x</span>  \n  汉\n"
+        );
+
+        // Overwriting half of a double-width grapheme blanks the other half.
+        surface.set(3, 1, "a", ContentStyle::default());
+        assert_eq!(surface.to_lines().0[1].to_unstyled(), "   a");
+    }
+
+    #[test]
+    fn test_changed_regions() {
+        let mut previous = DrawSurface::new(Dimensions::new(5, 2));
+        previous.print(0, 0, "hello", ContentStyle::default());
+        let mut surface = previous.clone();
+        surface.print(1, 0, "EL", ContentStyle::default());
+        surface.set(4, 0, "O", ContentStyle::default());
+        surface.set(0, 1, "x", red());
+
+        assert_eq!(
+            surface.changed_regions(Some(&previous)),
+            vec![
+                ChangedRegion {
+                    row: 0,
+                    columns: 1..3
+                },
+                ChangedRegion {
+                    row: 0,
+                    columns: 4..5
+                },
+                ChangedRegion {
+                    row: 1,
+                    columns: 0..1
+                },
+            ]
+        );
+        assert_eq!(surface.changed_regions(None).len(), 2);
+    }
+
+    #[test]
+    fn test_blit() -> anyhow::Result<()> {
+        let mut inner = DrawSurface::new(Dimensions::new(2, 1));
+        inner.print(0, 0, "ab", ContentStyle::default());
+
+        let mut surface = DrawSurface::new(Dimensions::new(3, 2));
+        surface.blit_lines(0, 0, &Lines(vec![Line::unstyled("xyzw")?]));
+        surface.blit(2, 1, &inner);
+
+        let rows: Vec<_> = surface.to_lines().iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["xyz", "  a"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,312 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Word wrapping of [`Line`]s.
+//!
+//! Lines break at spaces. Long tokens such as paths or `crate::module::TypeName` can carry
+//! explicit break hints: a [`BREAK_HINT`] marks a point where the line may break, and a
+//! [`SOFT_HYPHEN`] one where it may break with a hyphen. Both are invisible unless used. Tokens
+//! without hints can also be broken after common separators, see [`WrapOptions`]. As a last
+//! resort, tokens are broken wherever the line is full.
+//!
+//! In [`WrapMode::Grapheme`], lines instead break wherever they are full, keeping all spaces.
+
+use crate::content::span::Span;
+use crate::Line;
+use crate::Lines;
+
+/// Marks a point where a line may be broken (a zero width space).
+pub const BREAK_HINT: char = '\u{200B}';
+/// Marks a point where a line may be broken with a hyphen (a soft hyphen).
+pub const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Where lines may break.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum WrapMode {
+    /// Break at spaces and hints, only breaking tokens which do not fit on a row.
+    #[default]
+    Word,
+    /// Break at any grapheme, e.g. for output where spacing is significant.
+    Grapheme,
+}
+
+/// Options for [`Line::wrap_with`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct WrapOptions {
+    /// Allow tokens to break after `::`, `/` and `-`.
+    pub break_at_separators: bool,
+    pub mode: WrapMode,
+}
+
+/// A break opportunity in the current row.
+#[derive(Copy, Clone)]
+struct Break {
+    /// The row breaks before this grapheme.
+    index: usize,
+    /// Whether to end the row with a hyphen.
+    hyphen: bool,
+}
+
+struct Wrapper {
+    width: usize,
+    rows: Vec<Vec<Span>>,
+    row: Vec<Span>,
+    row_width: usize,
+    last_break: Option<Break>,
+}
+
+impl Wrapper {
+    fn push(&mut self, grapheme: Span) {
+        let width = grapheme.len();
+        if grapheme.content() == " " {
+            if self.row.is_empty() {
+                // Spaces at a break are dropped.
+                return;
+            }
+            self.mark_break(false);
+        } else if self.row_width + width > self.width && !self.row.is_empty() {
+            self.break_row();
+        }
+        self.row_width += width;
+        self.row.push(grapheme);
+    }
+
+    fn mark_break(&mut self, hyphen: bool) {
+        self.last_break = Some(Break {
+            index: self.row.len(),
+            hyphen,
+        });
+    }
+
+    fn break_row(&mut self) {
+        let (mut row, rest) = match self.last_break.take() {
+            Some(Break { index, hyphen }) => {
+                let rest = self.row.split_off(index);
+                let mut row = std::mem::take(&mut self.row);
+                if hyphen {
+                    if let Some(last) = row.last() {
+                        let mut dash = last.clone();
+                        dash.content = "-".into();
+                        row.push(dash);
+                    }
+                }
+                (row, rest)
+            }
+            None => (std::mem::take(&mut self.row), Vec::new()),
+        };
+        trim_trailing_spaces(&mut row);
+        self.rows.push(row);
+
+        self.row = rest
+            .into_iter()
+            .skip_while(|g| g.content() == " ")
+            .collect();
+        self.row_width = self.row.iter().map(Span::len).sum();
+    }
+
+    fn finish(mut self) -> Vec<Vec<Span>> {
+        trim_trailing_spaces(&mut self.row);
+        if !self.row.is_empty() || self.rows.is_empty() {
+            self.rows.push(self.row);
+        }
+        self.rows
+    }
+}
+
+fn trim_trailing_spaces(row: &mut Vec<Span>) {
+    while matches!(row.last(), Some(g) if g.content() == " ") {
+        row.pop();
+    }
+}
+
+impl Line {
+    /// Word wraps the line into rows of at most `width` columns, honoring break hints.
+    /// See the [module documentation](self) for details.
+    pub fn wrap(&self, width: usize) -> Lines {
+        self.wrap_with(width, WrapOptions::default())
+    }
+
+    /// Like [`wrap`](Self::wrap), with options.
+    pub fn wrap_with(&self, width: usize, options: WrapOptions) -> Lines {
+        if options.mode == WrapMode::Grapheme {
+            return self.wrap_graphemes(width);
+        }
+
+        let mut wrapper = Wrapper {
+            width,
+            rows: Vec::new(),
+            row: Vec::new(),
+            row_width: 0,
+            last_break: None,
+        };
+
+        let mut previous = None;
+        for grapheme in self.iter().flat_map(Span::iter) {
+            match grapheme.content().chars().next() {
+                Some(BREAK_HINT) => wrapper.mark_break(false),
+                // The hyphen must fit on the row too.
+                Some(SOFT_HYPHEN) if wrapper.row_width < width => wrapper.mark_break(true),
+                Some(SOFT_HYPHEN) => {}
+                Some(c) => {
+                    let separator = options.break_at_separators
+                        && (c == '/' || c == '-' || (c == ':' && previous == Some(':')));
+                    previous = Some(c);
+                    wrapper.push(grapheme);
+                    if separator {
+                        wrapper.mark_break(false);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        Lines(
+            wrapper
+                .finish()
+                .into_iter()
+                .map(|row| Line::from_iter(row).with_direction(self.direction()))
+                .collect(),
+        )
+    }
+
+    fn wrap_graphemes(&self, width: usize) -> Lines {
+        let mut rows = vec![Vec::new()];
+        let mut row_width = 0;
+        for grapheme in self.iter().flat_map(Span::iter) {
+            if matches!(
+                grapheme.content().chars().next(),
+                Some(BREAK_HINT | SOFT_HYPHEN) | None
+            ) {
+                continue;
+            }
+            let grapheme_width = grapheme.len();
+            if row_width + grapheme_width > width && row_width > 0 {
+                rows.push(Vec::new());
+                row_width = 0;
+            }
+            row_width += grapheme_width;
+            rows.last_mut().unwrap().push(grapheme);
+        }
+        Lines(
+            rows.into_iter()
+                .map(|row| Line::from_iter(row).with_direction(self.direction()))
+                .collect(),
+        )
+    }
+}
+
+impl Lines {
+    /// Wraps every line to `width` columns, see [`Line::wrap`].
+    pub fn wrap_all(&self, width: usize) -> Lines {
+        self.wrap_all_with(width, WrapOptions::default())
+    }
+
+    /// Like [`wrap_all`](Self::wrap_all), with options.
+    pub fn wrap_all_with(&self, width: usize, options: WrapOptions) -> Lines {
+        Lines(
+            self.iter()
+                .flat_map(|line| line.wrap_with(width, options).0)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Color;
+    use crate::testing::lines_to_strings;
+
+    #[test]
+    fn test_wrap_words() -> anyhow::Result<()> {
+        let line = Line::unstyled("the quick  brown fox")?;
+        assert_eq!(
+            lines_to_strings(&line.wrap(10)),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(
+            lines_to_strings(&line.wrap(100)),
+            vec!["the quick  brown fox"]
+        );
+        assert_eq!(lines_to_strings(&Line::default().wrap(10)), vec![""]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrap_long_token() -> anyhow::Result<()> {
+        let line = Line::unstyled("see crate::module::TypeName")?;
+        assert_eq!(
+            lines_to_strings(&line.wrap(10)),
+            vec!["see", "crate::mod", "ule::TypeN", "ame"]
+        );
+        assert_eq!(
+            lines_to_strings(&line.wrap_with(
+                10,
+                WrapOptions {
+                    break_at_separators: true,
+                    ..WrapOptions::default()
+                }
+            )),
+            vec!["see", "crate::", "module::", "TypeName"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrap_hints() -> anyhow::Result<()> {
+        let line = Line::unstyled("a/very/long/path\u{200B}/file.rs")?;
+        assert_eq!(
+            lines_to_strings(&line.wrap(17)),
+            vec!["a/very/long/path", "/file.rs"]
+        );
+        // Unused hints are invisible.
+        assert_eq!(
+            lines_to_strings(&line.wrap(100)),
+            vec!["a/very/long/path/file.rs"]
+        );
+
+        let line = Line::from_iter([
+            Span::new_colored("super", Color::Red)?,
+            Span::new_unstyled("\u{AD}califragilistic")?,
+        ]);
+        let wrapped = line.wrap(8);
+        assert_eq!(
+            lines_to_strings(&wrapped),
+            vec!["super-", "califrag", "ilistic"]
+        );
+        // The hyphen takes the style of the text before it.
+        assert_eq!(
+            wrapped.0[0].fmt_for_test().to_string(),
+            "<span fg=red>super-</span>"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrap_graphemes() -> anyhow::Result<()> {
+        let options = WrapOptions {
+            mode: WrapMode::Grapheme,
+            ..WrapOptions::default()
+        };
+        let lines = Lines(vec![
+            Line::unstyled("ab  cdef")?,
+            Line::default(),
+            Line::unstyled("日本語")?,
+        ]);
+        assert_eq!(
+            lines_to_strings(&lines.wrap_all_with(4, options)),
+            vec!["ab  ", "cdef", "", "日本", "語"]
+        );
+        assert_eq!(
+            lines_to_strings(&lines.wrap_all(4)),
+            vec!["ab", "cdef", "", "日本", "語"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Settings which apply to the built-in components drawn by one console, rather than to the
+//! whole process.
+//!
+//! [`theme()`](crate::theme::theme), [`unicode_enabled`](crate::charset::unicode_enabled),
+//! [`color_support`](crate::style::color_support),
+//! [`number_format`](crate::human::number_format) and [`strings`](crate::strings::strings) first
+//! consult the [`DrawContext`] entered on the current thread, and fall back to the process-wide
+//! settings. A [`SuperConsole`](crate::SuperConsole) enters its own context while it draws and
+//! queues emitted lines, so that two consoles in one process can be styled differently. Lines
+//! built elsewhere, e.g. on other threads before being sent through a handle, use the
+//! process-wide settings.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::human::NumberFormat;
+use crate::strings::Strings;
+use crate::style::ColorSupport;
+use crate::theme::Theme;
+
+thread_local! {
+    static CURRENT: RefCell<Option<DrawContext>> = const { RefCell::new(None) };
+}
+
+/// Overrides of the process-wide settings, each of which is left alone if `None`.
+#[derive(Clone, Default)]
+pub struct DrawContext {
+    pub theme: Option<Arc<Theme>>,
+    pub unicode: Option<bool>,
+    pub color_support: Option<ColorSupport>,
+    pub number_format: Option<NumberFormat>,
+    pub strings: Option<Arc<dyn Strings>>,
+}
+
+impl DrawContext {
+    /// Applies these settings on the current thread until the returned guard is dropped. Settings
+    /// left as `None` keep the value of the context entered already, if any.
+    pub fn enter(&self) -> DrawContextGuard {
+        let context = CURRENT.with(|current| match &*current.borrow() {
+            Some(outer) => self.or(outer),
+            None => self.clone(),
+        });
+        DrawContextGuard {
+            previous: CURRENT.with(|current| current.replace(Some(context))),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Runs `f` with these settings applied, see [`enter`](Self::enter).
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.enter();
+        f()
+    }
+
+    fn or(&self, outer: &DrawContext) -> DrawContext {
+        DrawContext {
+            theme: self.theme.clone().or_else(|| outer.theme.clone()),
+            unicode: self.unicode.or(outer.unicode),
+            color_support: self.color_support.or(outer.color_support),
+            number_format: self.number_format.or(outer.number_format),
+            strings: self.strings.clone().or_else(|| outer.strings.clone()),
+        }
+    }
+}
+
+/// Restores the previous settings when dropped, see [`DrawContext::enter`].
+#[must_use]
+pub struct DrawContextGuard {
+    previous: Option<DrawContext>,
+    /// The context belongs to the thread it was entered on.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for DrawContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Looks up a setting in the context entered on the current thread, if any.
+pub(crate) fn current<T>(setting: impl FnOnce(&DrawContext) -> Option<T>) -> Option<T> {
+    CURRENT.with(|current| current.borrow().as_ref().and_then(setting))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charset::unicode_enabled;
+    use crate::human;
+
+    #[test]
+    fn test_scope() {
+        let ascii = DrawContext {
+            unicode: Some(false),
+            ..DrawContext::default()
+        };
+        let german = DrawContext {
+            number_format: Some(NumberFormat::DE),
+            ..DrawContext::default()
+        };
+        ascii.scope(|| {
+            assert!(!unicode_enabled());
+            german.scope(|| {
+                // Settings not overridden by the inner context are kept.
+                assert!(!unicode_enabled());
+                assert_eq!(human::count(1234), "1.234");
+            });
+            assert_eq!(human::count(1234), "1,234");
+        });
+        assert!(current(|context| context.unicode).is_none());
+    }
+}
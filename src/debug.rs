@@ -0,0 +1,412 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Tools for debugging layouts.
+//!
+//! When the layout overlay is enabled, the area allotted to every component is outlined, and
+//! labeled with the component's type and its dimensions, e.g. `Bordered 40x12`. Enable it with
+//! [`set_layout_overlay`], or by setting `SUPERCONSOLE_DEBUG_LAYOUT=1`.
+//!
+//! The component tree drawn in the last frame is also recorded, and can be printed with
+//! [`SuperConsole::dump_tree`](crate::SuperConsole::dump_tree), along with how long each
+//! component took to draw. [`SuperConsole::render_stats`](crate::SuperConsole::render_stats) keeps
+//! those and the sizes of recent frames, to find what makes rendering slow.
+
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::env;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::charset::unicode_enabled;
+use crate::charset::LineCharset;
+use crate::observer::FrameStats;
+use crate::style::Color;
+use crate::theme::theme;
+use crate::Dimensions;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// Environment variable enabling the layout overlay when set to `1` or `true`.
+pub const LAYOUT_OVERLAY_ENV_VAR: &str = "SUPERCONSOLE_DEBUG_LAYOUT";
+
+const UNKNOWN: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+/// Whether the overlay is enabled, or `UNKNOWN` if the environment has not been checked yet.
+static LAYOUT_OVERLAY: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Enables or disables the layout overlay, overriding the environment.
+pub fn set_layout_overlay(enabled: bool) {
+    LAYOUT_OVERLAY.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+}
+
+pub(crate) fn layout_overlay_enabled() -> bool {
+    match LAYOUT_OVERLAY.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let enabled = matches!(
+                env::var(LAYOUT_OVERLAY_ENV_VAR).as_deref(),
+                Ok("1") | Ok("true")
+            );
+            set_layout_overlay(enabled);
+            enabled
+        }
+        state => state == ENABLED,
+    }
+}
+
+/// The type name without module paths or generic arguments, e.g. `Bordered`.
+pub(crate) fn short_type_name(type_name: &str) -> &str {
+    let base = type_name.split('<').next().unwrap_or(type_name);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+/// A component drawn in a frame.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TreeNode {
+    /// How many components this one is nested in.
+    pub depth: usize,
+    /// The type of the component, see [`std::any::type_name`].
+    pub type_name: &'static str,
+    /// The dimensions the component was allotted.
+    pub dimensions: Dimensions,
+    /// The number of lines the component drew.
+    pub height: usize,
+    /// The time spent drawing the component, including its children.
+    pub duration: Duration,
+}
+
+/// The components drawn in a frame, parents before their children.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ComponentTree {
+    pub nodes: Vec<TreeNode>,
+}
+
+impl ComponentTree {
+    /// The time spent drawing the node at `index`, excluding its children.
+    pub fn self_duration(&self, index: usize) -> Duration {
+        let Some(node) = self.nodes.get(index) else {
+            return Duration::ZERO;
+        };
+        let children: Duration = self.nodes[index + 1..]
+            .iter()
+            .take_while(|child| child.depth > node.depth)
+            .filter(|child| child.depth == node.depth + 1)
+            .map(|child| child.duration)
+            .sum();
+        node.duration.saturating_sub(children)
+    }
+
+    /// The `n` components which took the longest to draw, excluding their children, slowest first.
+    pub fn slowest(&self, n: usize) -> Vec<(&TreeNode, Duration)> {
+        let mut nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node, self.self_duration(index)))
+            .collect();
+        nodes.sort_by_key(|(_, duration)| Reverse(*duration));
+        nodes.truncate(n);
+        nodes
+    }
+}
+
+impl Display for ComponentTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            writeln!(
+                f,
+                "{:indent$}{} {}x{} -> {} lines",
+                "",
+                short_type_name(node.type_name),
+                node.dimensions.width,
+                node.dimensions.height,
+                node.height,
+                indent = node.depth * 2,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of frames kept by [`RenderStats`].
+const STATS_HISTORY: usize = 64;
+
+/// Profiling data about recent renders, see
+/// [`SuperConsole::render_stats`](crate::SuperConsole::render_stats).
+#[derive(Debug, Clone, Default)]
+pub struct RenderStats {
+    /// The most recent frames, oldest first.
+    pub frames: VecDeque<FrameStats>,
+    /// The components drawn in the last frame, with the time each took to draw.
+    pub components: ComponentTree,
+}
+
+impl RenderStats {
+    pub(crate) fn record(&mut self, frame: FrameStats, components: &ComponentTree) {
+        if self.frames.len() == STATS_HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+        self.components.clone_from(components);
+    }
+
+    pub fn last_frame(&self) -> Option<&FrameStats> {
+        self.frames.back()
+    }
+
+    /// The mean size of the recent frames passed to the output.
+    pub fn average_bytes(&self) -> usize {
+        match self.frames.len() {
+            0 => 0,
+            len => self.frames.iter().map(|f| f.bytes).sum::<usize>() / len,
+        }
+    }
+
+    /// The mean time spent on the recent frames.
+    pub fn average_duration(&self) -> Duration {
+        match self.frames.len() {
+            0 => Duration::ZERO,
+            len => self.frames.iter().map(|f| f.duration).sum::<Duration>() / len as u32,
+        }
+    }
+}
+
+#[derive(Default)]
+struct TreeRecorder {
+    tree: ComponentTree,
+    depth: usize,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<TreeRecorder>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, recording the components drawn on this thread meanwhile.
+pub(crate) fn record_tree<R>(f: impl FnOnce() -> R) -> (R, ComponentTree) {
+    let previous = RECORDER.with(|r| r.replace(Some(TreeRecorder::default())));
+    let res = f();
+    let recorder = RECORDER.with(|r| r.replace(previous));
+    (res, recorder.map(|r| r.tree).unwrap_or_default())
+}
+
+/// Identifies a component being drawn while recording.
+#[derive(Debug, PartialEq)]
+pub(crate) struct DrawToken {
+    index: usize,
+    start: Instant,
+}
+
+/// Records that a component is about to be drawn, if recording.
+/// Returns a token to pass to [`exit_component`].
+pub(crate) fn enter_component(
+    type_name: &'static str,
+    dimensions: Dimensions,
+) -> Option<DrawToken> {
+    RECORDER.with(|r| {
+        let mut r = r.borrow_mut();
+        let recorder = r.as_mut()?;
+        recorder.tree.nodes.push(TreeNode {
+            depth: recorder.depth,
+            type_name,
+            dimensions,
+            height: 0,
+            duration: Duration::ZERO,
+        });
+        recorder.depth += 1;
+        Some(DrawToken {
+            index: recorder.tree.nodes.len() - 1,
+            start: Instant::now(),
+        })
+    })
+}
+
+/// Records that a component has been drawn.
+pub(crate) fn exit_component(token: Option<DrawToken>, height: usize) {
+    let Some(DrawToken { index, start }) = token else {
+        return;
+    };
+    let duration = start.elapsed();
+    RECORDER.with(|r| {
+        if let Some(recorder) = r.borrow_mut().as_mut() {
+            recorder.depth = recorder.depth.saturating_sub(1);
+            if let Some(node) = recorder.tree.nodes.get_mut(index) {
+                node.height = height;
+                node.duration = duration;
+            }
+        }
+    });
+}
+
+/// Pads `output` to the allotted `dimensions` and draws an outline over their edges, labeled with
+/// `label` and the dimensions. Content under the outline is hidden.
+pub(crate) fn outline(output: &mut Lines, label: &str, dimensions: Dimensions) {
+    let Dimensions { width, height } = dimensions;
+    if width < 2 || height == 0 {
+        return;
+    }
+    output.set_lines_to_exact_dimensions(dimensions);
+
+    let charset = if unicode_enabled() {
+        theme().charset
+    } else {
+        LineCharset::ASCII
+    };
+    let border = |text: &str| Span::new_colored_lossy(text, Color::Magenta);
+    let inner = width - 2;
+
+    let mut top = Line::from_iter([border(charset.top_left)]);
+    let mut title = Line::from_iter([Span::new_colored_lossy(
+        &format!("{} {}x{}", label, dimensions.width, dimensions.height),
+        Color::Magenta,
+    )]);
+    title.truncate_line(inner);
+    let title_len = title.len();
+    top.extend(title.iter().cloned());
+    top.push(border(&charset.horizontal.repeat(inner - title_len)));
+    top.push(border(charset.top_right));
+
+    for (row, line) in output.iter_mut().enumerate() {
+        *line = if row == 0 {
+            top.clone()
+        } else if row == height - 1 {
+            Line::from_iter([border(&format!(
+                "{}{}{}",
+                charset.bottom_left,
+                charset.horizontal.repeat(inner),
+                charset.bottom_right
+            ))])
+        } else {
+            let mut middle = line.clone();
+            middle.to_exact_width(width);
+            middle.trim_ends(1, inner);
+            middle.to_exact_width(inner);
+            let mut outlined = Line::from_iter([border(charset.vertical)]);
+            outlined.extend(middle.iter().cloned());
+            outlined.push(border(charset.vertical));
+            outlined
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::lines_to_strings;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(
+            short_type_name("superconsole::components::bordering::Bordered<alloc::boxed::Box<dyn superconsole::Component>>"),
+            "Bordered"
+        );
+        assert_eq!(short_type_name("Echo"), "Echo");
+    }
+
+    #[test]
+    fn test_record_tree() -> anyhow::Result<()> {
+        use crate::components::echo::Echo;
+        use crate::components::Bordered;
+        use crate::components::DrawMode;
+        use crate::Component;
+
+        let root = Bordered::with_charset(
+            Echo(Lines(vec![vec!["hello"].try_into()?])),
+            &crate::charset::LineCharset::ASCII,
+        );
+        let (output, tree) = record_tree(|| root.draw(Dimensions::new(20, 10), DrawMode::Normal));
+        assert_eq!(output?.len(), 3);
+        assert_eq!(
+            tree.to_string(),
+            "Bordered 20x10 -> 3 lines\n  Aligned 18x8 -> 1 lines\n    Echo 18x8 -> 1 lines\n"
+        );
+
+        // Nothing is recorded outside of `record_tree`.
+        assert_eq!(enter_component("Echo", Dimensions::new(1, 1)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_slowest() {
+        let node = |depth, type_name, millis| TreeNode {
+            depth,
+            type_name,
+            dimensions: Dimensions::new(10, 10),
+            height: 1,
+            duration: Duration::from_millis(millis),
+        };
+        let tree = ComponentTree {
+            nodes: vec![
+                node(0, "Root", 10),
+                node(1, "Fast", 1),
+                node(1, "Slow", 8),
+                node(2, "Leaf", 2),
+            ],
+        };
+        assert_eq!(tree.self_duration(0), Duration::from_millis(1));
+        assert_eq!(tree.self_duration(2), Duration::from_millis(6));
+        let slowest: Vec<_> = tree
+            .slowest(2)
+            .into_iter()
+            .map(|(node, _)| node.type_name)
+            .collect();
+        assert_eq!(slowest, vec!["Slow", "Leaf"]);
+    }
+
+    #[test]
+    fn test_outline() -> anyhow::Result<()> {
+        let output = Lines(vec![
+            vec!["aaaaaaaaaa"].try_into()?,
+            vec!["bbbbb"].try_into()?,
+        ]);
+        // The allotted area is outlined, even where the output doesn't reach.
+        let outlined = |unicode| {
+            let mut output = output.clone();
+            with_unicode(unicode, || {
+                outline(&mut output, "Echo", Dimensions::new(12, 4))
+            });
+            lines_to_strings(&output)
+        };
+        assert_eq!(
+            outlined(true),
+            vec![
+                "┌Echo 12x4─┐",
+                "│bbbb      │",
+                "│          │",
+                "└──────────┘"
+            ]
+        );
+        assert_eq!(
+            outlined(false),
+            vec![
+                "+Echo 12x4-+",
+                "|bbbb      |",
+                "|          |",
+                "+----------+"
+            ]
+        );
+
+        // Labels longer than the area are cut.
+        let mut output = output;
+        with_unicode(true, || {
+            outline(&mut output, "Echo", Dimensions::new(10, 2))
+        });
+        assert_eq!(lines_to_strings(&output), vec!["┌Echo 10x┐", "└────────┘"]);
+        Ok(())
+    }
+}
@@ -27,6 +27,16 @@ pub enum Direction {
     Vertical,
 }
 
+impl Direction {
+    /// The other axis.
+    pub fn flip(self) -> Self {
+        match self {
+            Direction::Horizontal => Direction::Vertical,
+            Direction::Vertical => Direction::Horizontal,
+        }
+    }
+}
+
 impl From<(u16, u16)> for Dimensions {
     fn from((x, y): (u16, u16)) -> Self {
         Self {
@@ -54,6 +64,20 @@ impl Dimensions {
         }
     }
 
+    /// Replaces the size in a given dimension.
+    pub fn with_dimension(self, value: usize, direction: Direction) -> Self {
+        match direction {
+            Direction::Horizontal => Self {
+                width: value,
+                ..self
+            },
+            Direction::Vertical => Self {
+                height: value,
+                ..self
+            },
+        }
+    }
+
     /// Best effort conversion - it will truncate to the nearest valid dimension.
     pub fn multiply(self, multiplicand: f64, direction: Direction) -> Self {
         fn mul(lhs: usize, rhs: f64) -> usize {
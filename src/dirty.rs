@@ -0,0 +1,44 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Rendering only when something changed.
+//!
+//! Consoles rendering on a timer mostly draw frames identical to the last one. Instead, whatever
+//! updates the state drawn by the components marks a [`DirtyFlag`], obtained from
+//! [`SuperConsole::dirty_flag`](crate::SuperConsole::dirty_flag), and the timer calls
+//! [`SuperConsole::render_if_dirty`](crate::SuperConsole::render_if_dirty), which skips the render
+//! unless the flag was marked, lines were emitted or the terminal was resized since the last one.
+//!
+//! Components which change on their own, such as spinners and timers, are only redrawn with the
+//! rest, so mark the flag whenever they should move on.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// A cheaply cloneable generation counter, bumped whenever the state drawn changes.
+#[derive(Clone, Debug, Default)]
+pub struct DirtyFlag(Arc<AtomicU64>);
+
+impl DirtyFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a change to the state drawn, so that the next frame is rendered.
+    pub fn mark(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The number of times the flag was marked. It changes with every mark, so comparing it with
+    /// an earlier value tells whether anything changed since.
+    pub fn generation(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
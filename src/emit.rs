@@ -0,0 +1,676 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Policies controlling how emitted lines are drained.
+//!
+//! Each render draws the canvas and at most a limited number of the queued emitted lines, so that
+//! a burst of output does not starve the canvas. The limit is decided by an [`EmitPolicy`].
+//!
+//! The queue itself is unbounded unless given an [`EmitCapacity`], whose [`OverflowPolicy`]
+//! decides which lines are dropped when a producer outpaces rendering. With an [`EmitDedup`],
+//! repeated lines are collapsed into one with a count before they are drawn. With
+//! [`EmitColumns`], lines are emitted to side-by-side panes instead of across the terminal.
+
+use std::cmp;
+use std::collections::VecDeque;
+use std::iter;
+use std::mem;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::charset::LineCharset;
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+/// The minimum number of lines drained per frame by [`MinimumEmitPolicy::default`].
+pub(crate) const MINIMUM_EMIT: usize = 5;
+
+/// What an [`EmitPolicy`] knows about the frame being rendered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EmitContext {
+    /// Rows available for the frame, including both emitted lines and the canvas.
+    pub terminal_height: usize,
+    /// Rows used by the canvas in this frame.
+    pub canvas_height: usize,
+    /// Number of emitted lines waiting to be drawn.
+    pub backlog: usize,
+}
+
+impl EmitContext {
+    /// Rows that can be emitted without scrolling the canvas.
+    pub fn free_rows(&self) -> usize {
+        self.terminal_height.saturating_sub(self.canvas_height)
+    }
+}
+
+/// Decides how many emitted lines are drawn in a non-final frame.
+pub trait EmitPolicy: Send + Sync {
+    fn emit_limit(&self, context: EmitContext) -> usize;
+}
+
+/// Drains whatever fits above the canvas, but always at least a fixed number of lines so that
+/// emitted output isn't starved when the canvas fills the terminal.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MinimumEmitPolicy(pub usize);
+
+impl Default for MinimumEmitPolicy {
+    fn default() -> Self {
+        Self(MINIMUM_EMIT)
+    }
+}
+
+impl EmitPolicy for MinimumEmitPolicy {
+    fn emit_limit(&self, context: EmitContext) -> usize {
+        cmp::max(context.free_rows(), self.0)
+    }
+}
+
+/// Drains whatever fits above the canvas, with a guaranteed minimum which scales with the
+/// terminal height, and grows further when lines back up. This keeps small panes mostly showing
+/// the canvas, while letting tall terminals keep up with heavy output.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct AdaptiveEmitPolicy;
+
+impl EmitPolicy for AdaptiveEmitPolicy {
+    fn emit_limit(&self, context: EmitContext) -> usize {
+        let free = context.free_rows();
+        let minimum = cmp::max(context.terminal_height / 4, 1);
+        // Drain a quarter of whatever doesn't fit, up to half the terminal.
+        let pressure = cmp::min(
+            context.backlog.saturating_sub(free) / 4,
+            context.terminal_height / 2,
+        );
+        cmp::max(free, cmp::max(minimum, pressure))
+    }
+}
+
+/// Which lines to drop when more are emitted than an [`EmitCapacity`] allows.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum OverflowPolicy {
+    /// Drop the oldest waiting lines.
+    DropOldest,
+    /// Drop the lines being emitted.
+    DropNewest,
+    /// Drop the oldest waiting lines, and draw a line counting them in their place, e.g.
+    /// `1,024 lines dropped`.
+    #[default]
+    Summarize,
+}
+
+/// A bound on the number of emitted lines waiting to be drawn, see
+/// [`SuperConsole::set_emit_capacity`](crate::SuperConsole::set_emit_capacity).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EmitCapacity {
+    /// The most lines which may wait. With 0, every emitted line is dropped, whatever the policy,
+    /// since there is no room for a summary either.
+    pub lines: usize,
+    pub policy: OverflowPolicy,
+}
+
+impl EmitCapacity {
+    pub fn new(lines: usize, policy: OverflowPolicy) -> Self {
+        Self { lines, policy }
+    }
+
+    /// Drops lines from `pending` until it fits. `dropped` counts the lines summarized so far,
+    /// whose summary is the first pending line while it is non-zero.
+    pub(crate) fn enforce(&self, pending: &mut Lines, dropped: &mut u64) {
+        if self.lines == 0 {
+            pending.0.clear();
+            *dropped = 0;
+            return;
+        }
+        let excess = pending.len().saturating_sub(self.lines);
+        if excess == 0 {
+            return;
+        }
+        match self.policy {
+            OverflowPolicy::DropOldest => {
+                pending.0.drain(..excess);
+            }
+            OverflowPolicy::DropNewest => pending.0.truncate(self.lines),
+            OverflowPolicy::Summarize => {
+                let summarized = *dropped > 0;
+                let start = usize::from(summarized);
+                // A new summary takes up a line too.
+                let count = cmp::min(excess + 1 - start, pending.len() - start);
+                pending.0.drain(start..start + count);
+                *dropped += count as u64;
+                let summary =
+                    Line::from_iter([styled_span(StyleRole::Dim, &strings().dropped(*dropped))]);
+                if summarized {
+                    pending.0[0] = summary;
+                } else {
+                    pending.0.insert(0, summary);
+                }
+            }
+        }
+    }
+}
+
+/// Collapses repeated emitted lines into one, followed by a count such as `(x12)`, see
+/// [`SuperConsole::set_emit_dedup`](crate::SuperConsole::set_emit_dedup).
+///
+/// Each emitted line is compared with the last `window` distinct lines waiting to be drawn, so a
+/// window of 1 only collapses consecutive lines, while a larger one also collapses lines
+/// repeated with others interleaved, e.g. the same warning for several files. Lines already
+/// drawn are not compared.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EmitDedup {
+    pub window: usize,
+}
+
+impl EmitDedup {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: cmp::max(window, 1),
+        }
+    }
+
+    /// Only collapses consecutive identical lines.
+    pub fn consecutive() -> Self {
+        Self::new(1)
+    }
+}
+
+/// A distinct line waiting to be drawn, as tracked by a [`Deduplicator`].
+#[derive(Debug)]
+struct Repeated {
+    /// The line as emitted.
+    line: Line,
+    count: u64,
+    /// The line as queued, followed by its count once repeated.
+    queued: Line,
+}
+
+/// Applies an [`EmitDedup`] to lines as they are queued.
+#[derive(Debug)]
+pub(crate) struct Deduplicator {
+    window: usize,
+    /// The last distinct lines waiting to be drawn, in order. They are the last lines queued,
+    /// unless the queue was changed otherwise since, e.g. by drawing them.
+    recent: VecDeque<Repeated>,
+}
+
+impl Deduplicator {
+    pub(crate) fn new(dedup: EmitDedup) -> Self {
+        Self {
+            window: cmp::max(dedup.window, 1),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Appends `lines` to `pending`, collapsing those repeating a recent line into it.
+    pub(crate) fn append(&mut self, pending: &mut Lines, lines: Lines) {
+        self.forget_drawn(pending);
+        for line in lines {
+            match self.recent.iter().position(|r| r.line == line) {
+                Some(position) => {
+                    let index = pending.len() - (self.recent.len() - position);
+                    let repeated = &mut self.recent[position];
+                    repeated.count += 1;
+                    repeated.queued = repeated.line.clone();
+                    repeated.queued.push(styled_span(
+                        StyleRole::Dim,
+                        &format!(" {}", strings().repeated(repeated.count)),
+                    ));
+                    pending.0[index] = repeated.queued.clone();
+                }
+                None => {
+                    if self.recent.len() == self.window {
+                        self.recent.pop_front();
+                    }
+                    pending.push(line.clone());
+                    self.recent.push_back(Repeated {
+                        queued: line.clone(),
+                        line,
+                        count: 1,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Forgets the tracked lines unless they are still the last ones queued.
+    fn forget_drawn(&mut self, pending: &Lines) {
+        let tracked = self.recent.len();
+        let current = pending.len() >= tracked
+            && self
+                .recent
+                .iter()
+                .zip(&pending.0[pending.len() - tracked..])
+                .all(|(repeated, line)| repeated.queued == *line);
+        if !current {
+            self.recent.clear();
+        }
+    }
+}
+
+/// Lays emitted lines out in side-by-side panes, e.g. the stdout of a test run on the left and
+/// its stderr on the right, see
+/// [`SuperConsole::set_emit_columns`](crate::SuperConsole::set_emit_columns).
+///
+/// Lines are emitted to a pane with
+/// [`SuperConsole::emit_column`](crate::SuperConsole::emit_column). Each pane wraps its lines to
+/// its own width and keeps its own backlog: when a frame is drawn, the lines pending in each pane
+/// are laid out next to each other, row by row, and panes with fewer lines are left blank.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EmitColumns {
+    /// The relative width of each pane.
+    pub weights: Vec<usize>,
+    /// Drawn between panes.
+    pub separator: Span,
+}
+
+impl EmitColumns {
+    /// `count` panes of the same width.
+    pub fn new(count: usize) -> Self {
+        Self::weighted(vec![1; cmp::max(count, 1)])
+    }
+
+    /// Panes as wide as their share of `weights`, e.g. `[2, 1]` for a left pane twice as wide as
+    /// the right one.
+    pub fn weighted(weights: Vec<usize>) -> Self {
+        Self {
+            weights,
+            separator: styled_span(
+                StyleRole::Border,
+                &format!(" {} ", LineCharset::detect().vertical),
+            ),
+        }
+    }
+
+    pub fn separator(mut self, separator: Span) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// The number of panes.
+    pub fn panes(&self) -> usize {
+        cmp::max(self.weights.len(), 1)
+    }
+
+    /// The width of each pane when laid out across `width` columns.
+    fn widths(&self, width: usize) -> Vec<usize> {
+        let count = self.panes();
+        let available = width.saturating_sub(self.separator.len() * (count - 1));
+        let total: usize = self.weights.iter().sum();
+        if total == 0 {
+            return vec![available / count; count];
+        }
+        self.weights
+            .iter()
+            .map(|weight| available * weight / total)
+            .collect()
+    }
+}
+
+/// The lines emitted to each pane of [`EmitColumns`] which were not laid out yet.
+#[derive(Debug)]
+pub(crate) struct ColumnQueues {
+    layout: EmitColumns,
+    pending: Vec<Lines>,
+}
+
+impl ColumnQueues {
+    pub(crate) fn new(layout: EmitColumns) -> Self {
+        Self {
+            pending: iter::repeat_with(Lines::new).take(layout.panes()).collect(),
+            layout,
+        }
+    }
+
+    /// Queues `lines` in pane `column`. Lines for panes past the last go to the last one.
+    pub(crate) fn push(&mut self, column: usize, mut lines: Lines) {
+        let last = self.pending.len() - 1;
+        self.pending[cmp::min(column, last)].0.append(&mut lines.0);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pending.iter().all(Lines::is_empty)
+    }
+
+    /// The lines queued in each pane, one pane after the other, e.g. when the panes are removed.
+    pub(crate) fn into_lines(self) -> Lines {
+        Lines(self.pending.into_iter().flat_map(|lines| lines.0).collect())
+    }
+
+    /// Lays out the pending lines as rows `width` columns wide.
+    pub(crate) fn take_rows(&mut self, width: usize) -> Lines {
+        let widths = self.layout.widths(width);
+        let panes: Vec<Lines> = self
+            .pending
+            .iter_mut()
+            .zip(&widths)
+            .map(|(pending, width)| mem::take(pending).wrap_all(cmp::max(*width, 1)))
+            .collect();
+        let height = panes.iter().map(Lines::len).max().unwrap_or_default();
+        let last = panes.len() - 1;
+        (0..height)
+            .map(|row| {
+                let mut line = Line::default();
+                for (i, (pane, width)) in panes.iter().zip(&widths).enumerate() {
+                    let mut cell = pane.0.get(row).cloned().unwrap_or_default();
+                    if i == last {
+                        cell.truncate_line(*width);
+                    } else {
+                        cell.to_exact_width(*width);
+                    }
+                    if i > 0 {
+                        line.push(self.layout.separator.clone());
+                    }
+                    line.extend(cell);
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/// The severity of lines emitted with
+/// [`SuperConsole::emit_leveled`](crate::SuperConsole::emit_leveled), from the most verbose.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+    ];
+
+    /// The level below this one, or this one if it is the lowest.
+    pub fn more_verbose(self) -> Self {
+        Self::ALL[(self as usize).saturating_sub(1)]
+    }
+
+    /// The level above this one, or this one if it is the highest.
+    pub fn less_verbose(self) -> Self {
+        Self::ALL[cmp::min(self as usize + 1, Self::ALL.len() - 1)]
+    }
+
+    /// The role of the theme drawing lines of this level, e.g. the level names of the records
+    /// passed on from `log` and `tracing`.
+    pub fn style_role(self) -> StyleRole {
+        match self {
+            Level::Trace | Level::Debug => StyleRole::Dim,
+            Level::Info => StyleRole::Info,
+            Level::Warn => StyleRole::Warn,
+            Level::Error => StyleRole::Error,
+        }
+    }
+}
+
+/// The minimum level of the lines drawn by
+/// [`SuperConsole::emit_leveled`](crate::SuperConsole::emit_leveled), as returned by
+/// [`SuperConsole::level_filter`](crate::SuperConsole::level_filter).
+///
+/// Clones share the level, so it can be changed while the console renders, e.g. by an
+/// [`InputHandler`](crate::input::InputHandler) raising or lowering verbosity on a key press.
+/// Changes apply to the lines emitted from then on.
+#[derive(Debug, Clone)]
+pub struct LevelFilter(Arc<AtomicU8>);
+
+impl Default for LevelFilter {
+    /// Draws every line.
+    fn default() -> Self {
+        Self::new(Level::Trace)
+    }
+}
+
+impl LevelFilter {
+    pub fn new(min: Level) -> Self {
+        Self(Arc::new(AtomicU8::new(min as u8)))
+    }
+
+    pub fn get(&self) -> Level {
+        Level::ALL[self.0.load(Ordering::Relaxed) as usize]
+    }
+
+    pub fn set(&self, min: Level) {
+        self.0.store(min as u8, Ordering::Relaxed);
+    }
+
+    /// Whether lines at `level` are drawn.
+    pub fn allows(&self, level: Level) -> bool {
+        level >= self.get()
+    }
+
+    /// Draws the lines of the level below as well, returning the new minimum.
+    pub fn more_verbose(&self) -> Level {
+        self.update(Level::more_verbose)
+    }
+
+    /// Stops drawing the lines of the lowest level drawn, returning the new minimum.
+    pub fn less_verbose(&self) -> Level {
+        self.update(Level::less_verbose)
+    }
+
+    fn update(&self, f: fn(Level) -> Level) -> Level {
+        let previous = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |min| {
+                Some(f(Level::ALL[min as usize]) as u8)
+            })
+            .unwrap_or_else(|min| min);
+        f(Level::ALL[previous as usize])
+    }
+}
+
+/// The latest lines left out by a [`LevelFilter`], kept so that they can be shown later, e.g.
+/// the debug output leading to a failure.
+#[derive(Debug)]
+pub(crate) struct FilteredLines {
+    capacity: usize,
+    lines: VecDeque<(Level, Line)>,
+}
+
+impl FilteredLines {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: VecDeque::new(),
+        }
+    }
+
+    /// Keeps `lines`, forgetting the oldest ones once at capacity.
+    pub(crate) fn push(&mut self, level: Level, lines: Lines) {
+        for line in lines {
+            if self.lines.len() == self.capacity {
+                self.lines.pop_front();
+            }
+            if self.capacity > 0 {
+                self.lines.push_back((level, line));
+            }
+        }
+    }
+
+    /// Takes the lines kept, oldest first, leaving out those below `min`.
+    pub(crate) fn take(&mut self, min: Level) -> Lines {
+        Lines(
+            mem::take(&mut self.lines)
+                .into_iter()
+                .filter(|(level, _)| *level >= min)
+                .map(|(_, line)| line)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::lines_to_strings;
+
+    fn context(terminal_height: usize, canvas_height: usize, backlog: usize) -> EmitContext {
+        EmitContext {
+            terminal_height,
+            canvas_height,
+            backlog,
+        }
+    }
+
+    #[test]
+    fn test_minimum_policy() {
+        let policy = MinimumEmitPolicy::default();
+        assert_eq!(policy.emit_limit(context(10, 10, 100)), MINIMUM_EMIT);
+        assert_eq!(policy.emit_limit(context(80, 10, 100)), 70);
+    }
+
+    #[test]
+    fn test_adaptive_policy() {
+        let policy = AdaptiveEmitPolicy;
+        // Small panes keep most of the canvas visible.
+        assert_eq!(policy.emit_limit(context(10, 10, 1)), 2);
+        // Tall terminals get a proportionally larger minimum.
+        assert_eq!(policy.emit_limit(context(80, 80, 1)), 20);
+        // Free space is always used.
+        assert_eq!(policy.emit_limit(context(80, 10, 1)), 70);
+        // A backlog increases the limit, up to half the terminal.
+        assert_eq!(policy.emit_limit(context(80, 80, 120)), 30);
+        assert_eq!(policy.emit_limit(context(80, 80, 10000)), 40);
+    }
+
+    fn lines(range: std::ops::Range<usize>) -> Lines {
+        range
+            .map(|i| Line::sanitized(&format!("line {}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut dropped = 0;
+
+        let mut pending = lines(0..5);
+        EmitCapacity::new(3, OverflowPolicy::DropOldest).enforce(&mut pending, &mut dropped);
+        assert_eq!(
+            lines_to_strings(&pending),
+            vec!["line 2", "line 3", "line 4"]
+        );
+
+        let mut pending = lines(0..5);
+        EmitCapacity::new(3, OverflowPolicy::DropNewest).enforce(&mut pending, &mut dropped);
+        assert_eq!(
+            lines_to_strings(&pending),
+            vec!["line 0", "line 1", "line 2"]
+        );
+        assert_eq!(dropped, 0);
+
+        let capacity = EmitCapacity::new(3, OverflowPolicy::Summarize);
+        let mut pending = lines(0..5);
+        capacity.enforce(&mut pending, &mut dropped);
+        assert_eq!(
+            lines_to_strings(&pending),
+            vec!["3 lines dropped", "line 3", "line 4"]
+        );
+        pending.0.extend(lines(5..7).0);
+        capacity.enforce(&mut pending, &mut dropped);
+        assert_eq!(
+            lines_to_strings(&pending),
+            vec!["5 lines dropped", "line 5", "line 6"]
+        );
+
+        // Without room for a summary, everything is dropped.
+        EmitCapacity::new(0, OverflowPolicy::Summarize).enforce(&mut pending, &mut dropped);
+        assert!(pending.is_empty());
+        assert_eq!(dropped, 0);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let line = |text: &str| Line::sanitized(text);
+        let mut pending = Lines::new();
+
+        let mut dedup = Deduplicator::new(EmitDedup::consecutive());
+        dedup.append(&mut pending, Lines(vec![line("a"), line("a"), line("b")]));
+        dedup.append(&mut pending, Lines(vec![line("b"), line("a")]));
+        assert_eq!(lines_to_strings(&pending), vec!["a (x2)", "b (x2)", "a"]);
+
+        // Drawn lines are not collapsed into.
+        pending.0.clear();
+        dedup.append(&mut pending, Lines(vec![line("a")]));
+        assert_eq!(lines_to_strings(&pending), vec!["a"]);
+
+        let mut pending = Lines::new();
+        let mut dedup = Deduplicator::new(EmitDedup::new(2));
+        dedup.append(
+            &mut pending,
+            Lines(vec![line("a"), line("b"), line("a"), line("c"), line("a")]),
+        );
+        assert_eq!(lines_to_strings(&pending), vec!["a (x2)", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let filter = LevelFilter::new(Level::Info);
+        assert!(!filter.allows(Level::Debug));
+        assert!(filter.allows(Level::Warn));
+
+        let shared = filter.clone();
+        assert_eq!(shared.more_verbose(), Level::Debug);
+        assert!(filter.allows(Level::Debug));
+        assert_eq!(shared.more_verbose(), Level::Trace);
+        assert_eq!(shared.more_verbose(), Level::Trace);
+        filter.set(Level::Warn);
+        assert_eq!(shared.less_verbose(), Level::Error);
+        assert_eq!(shared.less_verbose(), Level::Error);
+        assert_eq!(filter.get(), Level::Error);
+    }
+
+    #[test]
+    fn test_filtered_lines() {
+        let line = |text: &str| Line::sanitized(text);
+        let mut filtered = FilteredLines::new(3);
+        filtered.push(Level::Trace, Lines(vec![line("a"), line("b")]));
+        filtered.push(Level::Debug, Lines(vec![line("c"), line("d")]));
+        assert_eq!(
+            lines_to_strings(&filtered.take(Level::Trace)),
+            vec!["b", "c", "d"]
+        );
+        assert!(filtered.take(Level::Trace).is_empty());
+
+        filtered.push(Level::Trace, Lines(vec![line("e")]));
+        filtered.push(Level::Debug, Lines(vec![line("f")]));
+        assert_eq!(lines_to_strings(&filtered.take(Level::Debug)), vec!["f"]);
+    }
+
+    #[test]
+    fn test_columns() -> anyhow::Result<()> {
+        let layout = EmitColumns::new(2).separator(Span::new_unstyled(" | ")?);
+        let mut columns = ColumnQueues::new(layout);
+        columns.push(0, Lines(vec![Line::sanitized("left line that wraps")]));
+        columns.push(5, Lines(vec![Line::sanitized("right")]));
+        assert!(!columns.is_empty());
+
+        assert_eq!(
+            lines_to_strings(&columns.take_rows(21)),
+            vec!["left line | right", "that      | ", "wraps     | "]
+        );
+        assert!(columns.is_empty());
+        assert!(columns.take_rows(21).is_empty());
+
+        let layout = EmitColumns::weighted(vec![2, 1]).separator(Span::new_unstyled("|")?);
+        assert_eq!(layout.widths(31), vec![20, 10]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Smoothing and rate estimation, e.g. for displaying throughput and ETAs.
+//!
+//! Everything here takes the current time as an argument rather than reading the clock, so it is
+//! straightforward to test and to drive from a simulated clock.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::time::Instant;
+
+/// An exponentially weighted moving average of samples taken at irregular intervals.
+/// The weight of a sample decays with the time since it was taken: after one time constant, it
+/// accounts for about a third of the average.
+#[derive(Debug, Clone)]
+pub struct Ewma {
+    time_constant: Duration,
+    last: Option<(f64, Instant)>,
+}
+
+impl Ewma {
+    pub fn new(time_constant: Duration) -> Self {
+        Self {
+            time_constant,
+            last: None,
+        }
+    }
+
+    /// Adds a sample taken at `now`, and returns the new average. The first sample is taken as is,
+    /// as are all samples if the time constant is zero.
+    pub fn update(&mut self, sample: f64, now: Instant) -> f64 {
+        let value = match self.last {
+            Some((previous, at)) if !self.time_constant.is_zero() => {
+                let elapsed = now.saturating_duration_since(at).as_secs_f64();
+                let weight = 1.0 - (-elapsed / self.time_constant.as_secs_f64()).exp();
+                previous + (sample - previous) * weight
+            }
+            _ => sample,
+        };
+        self.last = Some((value, now));
+        value
+    }
+
+    /// Replaces the average, e.g. to snap it to a sample.
+    pub fn set(&mut self, value: f64, now: Instant) {
+        self.last = Some((value, now));
+    }
+
+    /// The current average, if any sample was added.
+    pub fn value(&self) -> Option<f64> {
+        self.last.map(|(value, _)| value)
+    }
+}
+
+/// Estimates the rate at which a cumulative total grows, over a sliding window of time.
+#[derive(Debug, Clone)]
+pub struct RateEstimator {
+    window: Duration,
+    /// Totals and when they were recorded, oldest first.
+    samples: VecDeque<(f64, Instant)>,
+}
+
+impl RateEstimator {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Records the cumulative total at `now`.
+    pub fn record(&mut self, total: f64, now: Instant) {
+        self.samples.push_back((total, now));
+        // Keep the newest sample from before the window, so the window is always fully covered.
+        while self.samples.len() > 2
+            && now.saturating_duration_since(self.samples[1].1) >= self.window
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The rate per second over the window, if there are samples spanning some time.
+    pub fn rate(&self) -> Option<f64> {
+        let (first_total, first_at) = *self.samples.front()?;
+        let (last_total, last_at) = *self.samples.back()?;
+        let elapsed = last_at.saturating_duration_since(first_at).as_secs_f64();
+        (elapsed > 0.0).then(|| (last_total - first_total) / elapsed)
+    }
+}
+
+/// The time needed to do `remaining` work at `rate` per second, if the rate is positive.
+pub fn eta(remaining: f64, rate: f64) -> Option<Duration> {
+    if remaining <= 0.0 {
+        return Some(Duration::ZERO);
+    }
+    if rate <= 0.0 || !rate.is_finite() {
+        return None;
+    }
+    Duration::try_from_secs_f64(remaining / rate).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ewma() {
+        let start = Instant::now();
+        let mut ewma = Ewma::new(Duration::from_secs(1));
+        assert_eq!(ewma.value(), None);
+        assert_eq!(ewma.update(10.0, start), 10.0);
+        let value = ewma.update(20.0, start + Duration::from_secs(1));
+        assert!((value - 16.32).abs() < 0.01, "{}", value);
+        // Samples at the same instant carry no weight.
+        assert_eq!(ewma.update(1000.0, start + Duration::from_secs(1)), value);
+
+        let mut instant = Ewma::new(Duration::ZERO);
+        instant.update(1.0, start);
+        assert_eq!(instant.update(5.0, start), 5.0);
+    }
+
+    #[test]
+    fn test_rate() {
+        let start = Instant::now();
+        let secs = |s| start + Duration::from_secs(s);
+        let mut rate = RateEstimator::new(Duration::from_secs(10));
+        assert_eq!(rate.rate(), None);
+
+        rate.record(0.0, secs(0));
+        assert_eq!(rate.rate(), None);
+        rate.record(50.0, secs(5));
+        assert_eq!(rate.rate(), Some(10.0));
+
+        // The early, slow, part drops out of the window.
+        rate.record(100.0, secs(10));
+        rate.record(300.0, secs(20));
+        rate.record(500.0, secs(30));
+        assert_eq!(rate.rate(), Some(20.0));
+    }
+
+    #[test]
+    fn test_eta() {
+        assert_eq!(eta(100.0, 10.0), Some(Duration::from_secs(10)));
+        assert_eq!(eta(0.0, 0.0), Some(Duration::ZERO));
+        assert_eq!(eta(100.0, 0.0), None);
+    }
+}
@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Plain rendering for outputs which are not terminals, such as CI logs.
+//!
+//! Without cursor movement, the canvas can't be redrawn in place. Instead, emitted lines are
+//! written as plain text as they come, and the canvas is printed below them now and then: at most
+//! once per interval, only when it changed, and always on the final render.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::DrawMode;
+use crate::Lines;
+
+/// How often the canvas is printed by default.
+pub const DEFAULT_FALLBACK_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub(crate) struct FallbackRenderer {
+    interval: Duration,
+    last_print: Option<Instant>,
+    last_printed: Lines,
+}
+
+impl FallbackRenderer {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_print: None,
+            last_printed: Lines::new(),
+        }
+    }
+
+    pub(crate) fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Writes the emitted lines, and the canvas if it is due at `now`.
+    pub(crate) fn render(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        emitted: &Lines,
+        canvas: &Lines,
+        mode: DrawMode,
+        now: Instant,
+    ) {
+        write_plain(buffer, emitted);
+
+        let due = match self.last_print {
+            Some(last) => now.saturating_duration_since(last) >= self.interval,
+            None => true,
+        };
+        if mode == DrawMode::Final || (due && *canvas != self.last_printed) {
+            write_plain(buffer, canvas);
+            self.last_print = Some(now);
+            self.last_printed = canvas.clone();
+        }
+    }
+}
+
+fn write_plain(buffer: &mut Vec<u8>, lines: &Lines) {
+    for line in lines.iter() {
+        buffer.extend_from_slice(line.to_unstyled().trim_end().as_bytes());
+        buffer.push(b'\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Line;
+
+    fn lines(text: &str) -> anyhow::Result<Lines> {
+        Ok(Lines(vec![Line::unstyled(text)?]))
+    }
+
+    #[test]
+    fn test_fallback() -> anyhow::Result<()> {
+        let start = Instant::now();
+        let secs = Duration::from_secs;
+        let mut renderer = FallbackRenderer::new(secs(10));
+        let mut render = |emitted: &Lines, canvas: &Lines, mode, now| {
+            let mut buffer = Vec::new();
+            renderer.render(&mut buffer, emitted, canvas, mode, now);
+            String::from_utf8(buffer)
+        };
+
+        let none = Lines::new();
+        let status = lines("1/3 done")?;
+        assert_eq!(
+            render(&none, &status, DrawMode::Normal, start)?,
+            "1/3 done\n"
+        );
+        // Not due yet, but emitted lines are written right away.
+        assert_eq!(
+            render(
+                &lines("warning")?,
+                &lines("2/3 done")?,
+                DrawMode::Normal,
+                start + secs(5)
+            )?,
+            "warning\n"
+        );
+        // Due, but unchanged.
+        assert_eq!(
+            render(&none, &status, DrawMode::Normal, start + secs(20))?,
+            ""
+        );
+        assert_eq!(
+            render(
+                &none,
+                &lines("3/3 done")?,
+                DrawMode::Final,
+                start + secs(21)
+            )?,
+            "3/3 done\n"
+        );
+        Ok(())
+    }
+}
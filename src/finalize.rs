@@ -0,0 +1,80 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Options for the last render, see [`SuperConsole::finalize_with`](crate::SuperConsole::finalize_with).
+
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// What is left in the scrollback in place of the components once the console is finalized.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub enum FinalOutput {
+    /// The components' own final output, as drawn in [`DrawMode::Final`].
+    #[default]
+    Emit,
+    /// Nothing, e.g. when progress is irrelevant once done.
+    Suppress,
+    /// A summary provided by the caller instead of the components.
+    Replace(Lines),
+}
+
+/// How [`SuperConsole::finalize_with`](crate::SuperConsole::finalize_with) draws the last frame.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FinalizeOptions {
+    pub output: FinalOutput,
+    /// The mode the components are drawn in, [`DrawMode::Final`] by default.
+    pub mode: DrawMode,
+}
+
+impl Default for FinalizeOptions {
+    fn default() -> Self {
+        Self {
+            output: FinalOutput::Emit,
+            mode: DrawMode::Final,
+        }
+    }
+}
+
+impl FinalizeOptions {
+    /// Leaves nothing in place of the components.
+    pub fn suppress() -> Self {
+        Self {
+            output: FinalOutput::Suppress,
+            ..Self::default()
+        }
+    }
+
+    /// Leaves `summary` in place of the components.
+    pub fn replace(summary: impl Into<Lines>) -> Self {
+        Self {
+            output: FinalOutput::Replace(summary.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn mode(self, mode: DrawMode) -> Self {
+        Self { mode, ..self }
+    }
+}
+
+/// Draws fixed lines in place of the root, for [`FinalOutput::Suppress`] and
+/// [`FinalOutput::Replace`].
+pub(crate) struct Replacement<'a>(pub(crate) &'a Lines);
+
+impl Component for Replacement<'_> {
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        Dimensions::new(self.0.max_line_length(), self.0.len()).intersect(max)
+    }
+
+    fn draw_unchecked(&self, _dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        Ok(self.0.clone())
+    }
+}
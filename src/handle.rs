@@ -0,0 +1,131 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Emitting from other threads and tasks.
+//!
+//! [`SuperConsole::emit`](crate::SuperConsole::emit) needs `&mut SuperConsole`, so producers on
+//! other threads would have to share the console behind a mutex and contend with the render loop.
+//! A [`SuperConsoleHandle`], obtained from [`SuperConsole::handle`](crate::SuperConsole::handle),
+//! sends lines over a channel instead, which the console drains at the start of every render.
+
+use crossbeam_channel::Sender;
+
+use crate::dirty::DirtyFlag;
+use crate::emit::Level;
+use crate::output::Stream;
+use crate::Lines;
+
+/// What a handle asks the console to do on the next render.
+pub(crate) enum HandleMessage {
+    Emit(Lines),
+    EmitTo(Stream, Lines),
+    EmitColumn(usize, Lines),
+    EmitLeveled(Level, Lines),
+}
+
+/// A cheaply cloneable, `Send + Sync` handle to emit lines to a [`SuperConsole`](crate::SuperConsole)
+/// without holding it. See the [module documentation](self).
+///
+/// Sending marks the console's [`DirtyFlag`], so that
+/// [`render_if_dirty`](crate::SuperConsole::render_if_dirty) draws the lines. Lines sent after the
+/// console is dropped are discarded.
+#[derive(Clone, Debug)]
+pub struct SuperConsoleHandle {
+    sender: Sender<HandleMessage>,
+    dirty: DirtyFlag,
+}
+
+impl SuperConsoleHandle {
+    pub(crate) fn new(sender: Sender<HandleMessage>, dirty: DirtyFlag) -> Self {
+        Self { sender, dirty }
+    }
+
+    fn send(&self, message: HandleMessage) {
+        // The console is gone, so there is nowhere to draw the lines.
+        let _ = self.sender.send(message);
+        self.dirty.mark();
+    }
+
+    /// Queues lines to be drawn above the canvas, as with
+    /// [`SuperConsole::emit`](crate::SuperConsole::emit).
+    pub fn emit(&self, lines: impl Into<Lines>) {
+        self.send(HandleMessage::Emit(lines.into()));
+    }
+
+    /// Queues lines to be written to `stream`, as with
+    /// [`SuperConsole::emit_to`](crate::SuperConsole::emit_to).
+    pub fn emit_to(&self, stream: Stream, lines: impl Into<Lines>) {
+        self.send(HandleMessage::EmitTo(stream, lines.into()));
+    }
+
+    /// Queues lines in a pane of the emitted log, as with
+    /// [`SuperConsole::emit_column`](crate::SuperConsole::emit_column).
+    pub fn emit_column(&self, column: usize, lines: impl Into<Lines>) {
+        self.send(HandleMessage::EmitColumn(column, lines.into()));
+    }
+
+    /// Queues lines to be drawn above the canvas if `level` is not filtered out, as with
+    /// [`SuperConsole::emit_leveled`](crate::SuperConsole::emit_leveled).
+    pub fn emit_leveled(&self, level: Level, lines: impl Into<Lines>) {
+        self.send(HandleMessage::EmitLeveled(level, lines.into()));
+    }
+
+    /// Asks for the next [`render_if_dirty`](crate::SuperConsole::render_if_dirty) to draw a frame,
+    /// e.g. after changing state shared with the components.
+    pub fn request_render(&self) {
+        self.dirty.mark();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use anyhow::Context as _;
+
+    use crate::components::echo::Echo;
+    use crate::testing::frame_contains;
+    use crate::testing::test_console;
+    use crate::testing::SuperConsoleTestingExt;
+    use crate::Lines;
+
+    #[test]
+    fn test_handle() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.render(&root)?;
+        assert!(!console.render_if_dirty(&root)?);
+
+        let workers: Vec<_> = (0..4)
+            .map(|i| {
+                let handle = console.handle();
+                thread::spawn(move || handle.emit(format!("worker {}", i).as_str()))
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert!(console.render_if_dirty(&root)?);
+        let frame = console
+            .test_output_mut()?
+            .frames
+            .pop()
+            .context("No frame was emitted")?;
+        for i in 0..4 {
+            assert!(frame_contains(&frame, format!("worker {}", i)));
+        }
+        assert!(!console.render_if_dirty(&root)?);
+
+        console.handle().request_render();
+        assert!(console.render_if_dirty(&root)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Formatting of numbers for humans.
+//!
+//! The helpers here, and the built-in components showing numbers, use the installed
+//! [`NumberFormat`], so that tools targeting other locales can change separators in one place with
+//! [`set_number_format`].
+
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::context;
+
+static NUMBER_FORMAT: RwLock<NumberFormat> = RwLock::new(NumberFormat::EN);
+
+/// How to separate the digits of numbers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct NumberFormat {
+    /// Inserted between groups of three digits in the integer part, if any.
+    pub thousands_separator: Option<char>,
+    /// Separates the integer part from the fractional part.
+    pub decimal_separator: char,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::EN
+    }
+}
+
+impl NumberFormat {
+    /// `1,234.5`
+    pub const EN: NumberFormat = NumberFormat {
+        thousands_separator: Some(','),
+        decimal_separator: '.',
+    };
+    /// `1.234,5`
+    pub const DE: NumberFormat = NumberFormat {
+        thousands_separator: Some('.'),
+        decimal_separator: ',',
+    };
+    /// `1 234,5`
+    pub const FR: NumberFormat = NumberFormat {
+        thousands_separator: Some(' '),
+        decimal_separator: ',',
+    };
+    /// `1234.5`
+    pub const PLAIN: NumberFormat = NumberFormat {
+        thousands_separator: None,
+        decimal_separator: '.',
+    };
+
+    /// Formats an integer, e.g. `-1,234,567`.
+    pub fn format_integer(&self, n: impl Into<i128>) -> String {
+        let n = n.into();
+        let digits = n.unsigned_abs().to_string();
+        let mut res = String::with_capacity(digits.len() * 4 / 3 + 1);
+        if n < 0 {
+            res.push('-');
+        }
+        self.push_grouped(&mut res, &digits);
+        res
+    }
+
+    /// Formats a number with a fixed number of decimals, e.g. `1,234.50`.
+    pub fn format_decimal(&self, x: f64, decimals: usize) -> String {
+        if !x.is_finite() {
+            return x.to_string();
+        }
+        let formatted = format!("{:.*}", decimals, x.abs());
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut res = String::with_capacity(formatted.len() * 4 / 3 + 1);
+        // Don't show a sign if the value rounds to zero.
+        if x.is_sign_negative() && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            res.push('-');
+        }
+        self.push_grouped(&mut res, integer);
+        if let Some(fraction) = fraction {
+            res.push(self.decimal_separator);
+            res.push_str(fraction);
+        }
+        res
+    }
+
+    fn push_grouped(&self, res: &mut String, digits: &str) {
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                if let Some(separator) = self.thousands_separator {
+                    res.push(separator);
+                }
+            }
+            res.push(digit);
+        }
+    }
+}
+
+/// Installs the number format used by built-in components and the helpers of this module, unless
+/// overridden by a console, see
+/// [`SuperConsole::set_number_format`](crate::SuperConsole::set_number_format).
+pub fn set_number_format(format: NumberFormat) {
+    *NUMBER_FORMAT
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = format;
+}
+
+/// The number format currently in use: that of the console drawing, if any, see the
+/// [`context`](crate::context) module, or else the installed one.
+pub fn number_format() -> NumberFormat {
+    context::current(|context| context.number_format)
+        .unwrap_or_else(|| *NUMBER_FORMAT.read().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// A count in the installed number format, e.g. `12,345`.
+pub fn count(n: u64) -> String {
+    number_format().format_integer(n)
+}
+
+/// A fraction as a percentage with no decimals, e.g. `45%`.
+pub fn percent(fraction: f64) -> String {
+    format!("{}%", number_format().format_decimal(fraction * 100.0, 0))
+}
+
+/// A size in bytes with binary units, e.g. `1.5 MiB`.
+pub fn bytes(n: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if n < 1024 {
+        return format!("{} B", n);
+    }
+    let mut value = n as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!(
+        "{} {}",
+        number_format().format_decimal(value, 1),
+        UNITS[unit]
+    )
+}
+
+/// A duration as `h:mm:ss`, followed by `decimals` digits of the fraction of a second, e.g.
+/// `1:02:03.4`. The fraction is truncated, like a stopwatch's.
+pub fn elapsed(duration: Duration, decimals: usize) -> String {
+    let secs = duration.as_secs();
+    let mut res = format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+    let decimals = decimals.min(9);
+    if decimals > 0 {
+        let fraction = duration.subsec_nanos() / 10u32.pow(9 - decimals as u32);
+        res.push(number_format().decimal_separator);
+        res.push_str(&format!("{:0width$}", fraction, width = decimals));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_integer() {
+        assert_eq!(NumberFormat::EN.format_integer(0), "0");
+        assert_eq!(NumberFormat::EN.format_integer(999), "999");
+        assert_eq!(NumberFormat::EN.format_integer(1234567), "1,234,567");
+        assert_eq!(NumberFormat::DE.format_integer(-1234), "-1.234");
+        assert_eq!(
+            NumberFormat::PLAIN.format_integer(u64::MAX),
+            "18446744073709551615"
+        );
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        assert_eq!(NumberFormat::EN.format_decimal(1234.5, 2), "1,234.50");
+        assert_eq!(NumberFormat::FR.format_decimal(1234.5, 1), "1 234,5");
+        assert_eq!(NumberFormat::DE.format_decimal(-0.04, 1), "0,0");
+        assert_eq!(NumberFormat::EN.format_decimal(-12.0, 0), "-12");
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(count(12345), "12,345");
+        assert_eq!(percent(0.456), "46%");
+        assert_eq!(bytes(512), "512 B");
+        assert_eq!(bytes(1536 * 1024), "1.5 MiB");
+        assert_eq!(elapsed(Duration::from_millis(3723456), 0), "1:02:03");
+        assert_eq!(elapsed(Duration::from_millis(65_059), 2), "0:01:05.05");
+    }
+}
@@ -0,0 +1,226 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Keyboard and other terminal input, read while the console is running.
+//!
+//! Input is off by default, since reading it requires the terminal to be in raw mode. Once enabled
+//! with [`SuperConsole::enable_input`](crate::SuperConsole::enable_input), events are read with
+//! [`SuperConsole::poll_events`](crate::SuperConsole::poll_events). They are first offered to the
+//! registered [`InputHandler`]s, e.g. components which toggle or collapse on a key, and those
+//! which no handler consumed are returned to the caller.
+//!
+//! The event types are superconsole's own, converted from those of the terminal
+//! [`backend`](crate::backend).
+
+use std::ops::BitOr;
+use std::ops::BitOrAssign;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::backend;
+
+/// An input event.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Event {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    /// The terminal was resized to the given columns and rows.
+    Resize(u16, u16),
+}
+
+/// A key press, with the modifiers held.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum KeyCode {
+    Backspace,
+    Enter,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Tab,
+    /// Shift-Tab.
+    BackTab,
+    Delete,
+    Insert,
+    /// A function key, e.g. `F(1)` for F1.
+    F(u8),
+    Char(char),
+    Null,
+    Esc,
+}
+
+/// The modifier keys held, which can be combined with `|`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyModifiers(u8);
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers(0);
+    pub const SHIFT: KeyModifiers = KeyModifiers(1);
+    pub const CONTROL: KeyModifiers = KeyModifiers(1 << 1);
+    pub const ALT: KeyModifiers = KeyModifiers(1 << 2);
+
+    /// Whether all the modifiers of `other` are held.
+    pub fn contains(self, other: KeyModifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for KeyModifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        KeyModifiers(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for KeyModifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// A mouse event, which is only reported if the terminal was asked to capture the mouse.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub column: u16,
+    pub row: u16,
+    pub modifiers: KeyModifiers,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    Drag(MouseButton),
+    Moved,
+    ScrollDown,
+    ScrollUp,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Implemented by components, or anything else, which react to input.
+pub trait InputHandler: Send + Sync {
+    /// Returns true if the event was consumed, in which case it is not offered to later handlers.
+    fn handle_event(&self, event: &Event) -> bool;
+}
+
+impl<H: InputHandler + ?Sized> InputHandler for Arc<H> {
+    fn handle_event(&self, event: &Event) -> bool {
+        (**self).handle_event(event)
+    }
+}
+
+/// Whether `event` is Ctrl-C. Raw mode delivers it as a key event rather than as `SIGINT`, so
+/// callers reading input must check for it to stay interruptible.
+pub fn is_interrupt(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('c'),
+            modifiers,
+            ..
+        }) if modifiers.contains(KeyModifiers::CONTROL)
+    )
+}
+
+/// Reads the events which are available within `timeout`, without blocking for longer. Fails
+/// without the `backend-crossterm` feature.
+pub fn read_events(timeout: Duration) -> anyhow::Result<Vec<Event>> {
+    let mut events = Vec::new();
+    let mut timeout = timeout;
+    while let Some(event) = backend::read_event(timeout)? {
+        events.push(event);
+        // Only wait for the first event, then drain whatever else is already available.
+        timeout = Duration::ZERO;
+    }
+    Ok(events)
+}
+
+/// Offers each event to the handlers in order, and returns those which none consumed.
+pub(crate) fn dispatch(handlers: &[Box<dyn InputHandler>], events: Vec<Event>) -> Vec<Event> {
+    events
+        .into_iter()
+        .filter(|event| !handlers.iter().any(|handler| handler.handle_event(event)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Verbosity(AtomicUsize);
+
+    impl InputHandler for Verbosity {
+        fn handle_event(&self, event: &Event) -> bool {
+            match event {
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('v'),
+                    ..
+                }) => {
+                    self.0.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let verbosity = Arc::new(Verbosity::default());
+        let handlers: Vec<Box<dyn InputHandler>> = vec![Box::new(verbosity.clone())];
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+
+        let unhandled = dispatch(&handlers, vec![key('v'), key('q'), key('v')]);
+        assert_eq!(unhandled, vec![key('q')]);
+        assert_eq!(verbosity.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_is_interrupt() {
+        let ctrl_c = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(is_interrupt(&ctrl_c));
+        let c = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+        assert!(!is_interrupt(&c));
+    }
+}
@@ -0,0 +1,242 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A machine-readable output, which writes each render as a JSON event rather than ANSI frames.
+//!
+//! This lets IDEs and web frontends consume a superconsole UI without parsing escape codes. Events
+//! are written one per line ([JSON Lines](https://jsonlines.org)):
+//!
+//! ```json
+//! {"type":"frame","index":0,"timestamp_ms":1700000000000,"width":80,"height":24,"final":false,
+//!  "emitted":[[{"text":"Compiled foo"}]],"canvas":[[{"text":"Building","fg":"#00cdcd","attributes":["bold"]}]]}
+//! {"type":"stream","stream":"stderr","lines":[[{"text":"warning: unused import"}]]}
+//! ```
+//!
+//! Each line is a list of spans. Spans only carry the styling they set: colors are `#rrggbb`,
+//! attributes are snake case names such as `bold` or `crossed_out`, and `link` is the target of
+//! a hyperlink.
+
+use std::any::Any;
+use std::io::Write;
+use std::time::UNIX_EPOCH;
+
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::output::FrameMetadata;
+use crate::output::Stream;
+use crate::output::SuperConsoleOutput;
+use crate::style::to_rgb;
+use crate::style::Attribute;
+use crate::style::Color;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+
+fn color_value(color: Color) -> Option<Value> {
+    to_rgb(color).map(|(r, g, b)| Value::String(format!("#{:02x}{:02x}{:02x}", r, g, b)))
+}
+
+fn attribute_name(attribute: Attribute) -> String {
+    let mut name = String::new();
+    for c in format!("{:?}", attribute).chars() {
+        if c.is_uppercase() {
+            if !name.is_empty() {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+fn span_value(span: &Span) -> Value {
+    let mut value = Map::new();
+    value.insert("text".to_owned(), Value::String(span.content().to_owned()));
+    if let Some(fg) = span.style.foreground_color.and_then(color_value) {
+        value.insert("fg".to_owned(), fg);
+    }
+    if let Some(bg) = span.style.background_color.and_then(color_value) {
+        value.insert("bg".to_owned(), bg);
+    }
+    let attributes: Vec<Value> = Attribute::iterator()
+        .filter(|a| span.style.attributes.has(*a))
+        .map(|a| Value::String(attribute_name(a)))
+        .collect();
+    if !attributes.is_empty() {
+        value.insert("attributes".to_owned(), Value::Array(attributes));
+    }
+    if let Some(link) = &span.link {
+        value.insert("link".to_owned(), Value::String(link.to_string()));
+    }
+    Value::Object(value)
+}
+
+fn line_value(line: &Line) -> Value {
+    Value::Array(line.iter().map(span_value).collect())
+}
+
+/// Converts lines to the JSON representation used in events: a list of lines, each a list of spans.
+pub fn lines_to_json(lines: &Lines) -> Value {
+    Value::Array(lines.iter().map(line_value).collect())
+}
+
+/// An output which writes each render as a JSON event to a writer, see the
+/// [module documentation](self). The ANSI frames are discarded.
+pub struct JsonSuperConsoleOutput {
+    writer: Box<dyn Write + Send + Sync + 'static>,
+    size: Dimensions,
+}
+
+impl JsonSuperConsoleOutput {
+    /// Writes events to `writer`. As there is no terminal, frames are drawn with `size`.
+    pub fn new(writer: Box<dyn Write + Send + Sync + 'static>, size: Dimensions) -> Self {
+        Self { writer, size }
+    }
+
+    fn write_event(&mut self, event: Value) -> anyhow::Result<()> {
+        serde_json::to_writer(&mut self.writer, &event)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl SuperConsoleOutput for JsonSuperConsoleOutput {
+    fn should_render(&mut self) -> bool {
+        true
+    }
+
+    fn output(&mut self, buffer: Vec<u8>) -> anyhow::Result<()> {
+        let _ = buffer;
+        Ok(())
+    }
+
+    fn wants_lines(&self) -> bool {
+        true
+    }
+
+    fn output_lines(
+        &mut self,
+        emitted: &Lines,
+        canvas: &Lines,
+        metadata: FrameMetadata,
+    ) -> anyhow::Result<()> {
+        let timestamp = metadata
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.write_event(json!({
+            "type": "frame",
+            "index": metadata.index,
+            "timestamp_ms": u64::try_from(timestamp.as_millis())?,
+            "width": metadata.dimensions.width,
+            "height": metadata.dimensions.height,
+            "final": metadata.mode == DrawMode::Final,
+            "emitted": lines_to_json(emitted),
+            "canvas": lines_to_json(canvas),
+        }))
+    }
+
+    fn output_to(&mut self, stream: Stream, lines: &Lines) -> anyhow::Result<()> {
+        let stream = match stream {
+            Stream::Stdout => "stdout",
+            Stream::Stderr => "stderr",
+        };
+        self.write_event(json!({
+            "type": "stream",
+            "stream": stream,
+            "lines": lines_to_json(lines),
+        }))
+    }
+
+    fn terminal_size(&self) -> anyhow::Result<Dimensions> {
+        Ok(self.size)
+    }
+
+    fn finalize(mut self: Box<Self>) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::style::Stylize;
+    use crate::SuperConsole;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_output() -> anyhow::Result<()> {
+        let buffer = SharedBuffer::default();
+        let mut builder = SuperConsole::builder();
+        builder
+            .forced(true)
+            .output(Box::new(JsonSuperConsoleOutput::new(
+                Box::new(buffer.clone()),
+                Dimensions::new(20, 5),
+            )));
+        let mut console = builder.build()?.expect("forced");
+        let root = Echo(Lines(vec![Line::from_iter([Span::new_styled(
+            "status".to_owned().bold().red(),
+        )?])]));
+
+        console.emit(Lines(vec![vec!["done"].try_into()?]));
+        console.render(&root)?;
+        console.finalize(&root)?;
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone())?;
+        let events: Vec<Value> = output
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["type"], "frame");
+        assert_eq!(events[0]["emitted"], json!([[{"text": "done"}]]));
+        assert_eq!(
+            events[0]["canvas"],
+            json!([[{"text": "status", "fg": "#ff0000", "attributes": ["bold"]}]])
+        );
+        assert_eq!(events[1]["final"], true);
+        assert_eq!(events[1]["emitted"], json!([]));
+
+        Ok(())
+    }
+}
@@ -8,7 +8,7 @@
  */
 
 //! The superconsole crate provides a handler and building blocks for powerful, yet minimally intrusive TUIs.
-//! Built on-top of [`crossterm`](crossterm), it cross-compiles on Windows 7+, Linux, and MacOS.
+//! Built on-top of crossterm, see [`backend`](backend), it cross-compiles on Windows 7+, Linux, and MacOS.
 //!
 //! Rendering is handled by [`SuperConsole`](SuperConsole), which draws to [`stdout`](std::io::stdout).
 //! The caller is responsible for re-rendering whenever necessary.
@@ -17,6 +17,7 @@
 //! The rendering can be divided into two principle components:
 //! * In the *scratch* area, the previous content is overwritten at each render.
 //! * In the *emitted* area, lines scroll away above the scratch with various diagnostic output.
+//!
 //! Components live in the scratch area.
 //!
 //! A set of pre-baked composition and testing oriented components are provided in the [`components`](components) module.
@@ -32,13 +33,49 @@ pub use dimensions::Direction;
 pub use crate::builder::Builder;
 pub use crate::superconsole::SuperConsole;
 
+pub mod animation;
+pub(crate) mod ansi;
 pub(crate) mod ansi_support;
+pub mod backend;
+pub mod budget;
 pub mod builder;
+#[cfg(feature = "recording")]
+pub mod capture;
+pub mod charset;
 pub mod components;
 pub mod content;
+pub mod context;
+pub mod debug;
 mod dimensions;
+pub mod dirty;
+pub mod emit;
+pub mod estimator;
+pub mod fallback;
+pub mod finalize;
+pub mod handle;
+pub mod human;
+pub mod input;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "log")]
+pub mod logging;
+mod macros;
+pub mod observer;
 pub mod output;
+pub mod pacing;
+pub mod rate_limit;
+#[cfg(feature = "recording")]
+pub mod recording;
+#[cfg(all(feature = "signals", unix))]
+pub mod signals;
+pub mod sink;
+pub mod sticky;
+pub mod strings;
 pub mod style;
 mod superconsole;
 pub mod testing;
+pub mod theme;
+#[cfg(feature = "tracing")]
+pub mod trace;
 pub(crate) mod vec_as_fmt_write;
+pub mod watchdog;
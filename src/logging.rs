@@ -0,0 +1,224 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A bridge from the `log` crate, behind the `log` feature.
+//!
+//! Libraries logging to stderr while the canvas is drawn corrupt it. Installing a
+//! [`SuperConsoleLogger`] instead buffers their records in a [`LogHandle`], which the render
+//! loop passes on to [`SuperConsole::emit`] so they are written above the canvas. The buffer is
+//! bounded like the console's own queue, so that records logged while the loop is stuck don't
+//! grow it without end.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use log::Level;
+use log::LevelFilter;
+use log::Log;
+use log::Metadata;
+use log::Record;
+
+use crate::emit;
+use crate::emit::EmitCapacity;
+use crate::emit::OverflowPolicy;
+use crate::theme::styled_span;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+use crate::SuperConsole;
+
+/// The number of lines a [`LogHandle`] keeps by default.
+const DEFAULT_CAPACITY: usize = 10_000;
+
+#[derive(Debug)]
+struct Buffer {
+    lines: Lines,
+    /// The lines summarized by the first one, see [`EmitCapacity`].
+    dropped: u64,
+    capacity: EmitCapacity,
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Self {
+            lines: Lines::new(),
+            dropped: 0,
+            capacity: EmitCapacity::new(DEFAULT_CAPACITY, OverflowPolicy::Summarize),
+        }
+    }
+}
+
+/// The records logged since the render loop last took them.
+#[derive(Debug, Clone, Default)]
+pub struct LogHandle(Arc<Mutex<Buffer>>);
+
+impl LogHandle {
+    fn lock(&self) -> MutexGuard<'_, Buffer> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn push(&self, line: Line) {
+        let mut buffer = self.lock();
+        let Buffer {
+            lines,
+            dropped,
+            capacity,
+        } = &mut *buffer;
+        lines.push(line);
+        capacity.enforce(lines, dropped);
+    }
+
+    /// Takes the lines of the records logged since the last call.
+    pub fn take_lines(&self) -> Lines {
+        let mut buffer = self.lock();
+        buffer.dropped = 0;
+        std::mem::take(&mut buffer.lines)
+    }
+
+    /// Emits the records logged since the last call to `console`.
+    pub fn emit_to(&self, console: &mut SuperConsole) {
+        console.emit(self.take_lines());
+    }
+}
+
+/// A [`Log`] implementation writing records, with colored levels, to a [`LogHandle`].
+#[derive(Debug, Clone)]
+pub struct SuperConsoleLogger {
+    handle: LogHandle,
+    level: LevelFilter,
+}
+
+impl Default for SuperConsoleLogger {
+    fn default() -> Self {
+        Self::new(LevelFilter::Info)
+    }
+}
+
+impl SuperConsoleLogger {
+    /// A logger recording records up to `level`.
+    pub fn new(level: LevelFilter) -> Self {
+        Self {
+            handle: LogHandle::default(),
+            level,
+        }
+    }
+
+    /// Bounds the records waiting for the render loop, 10,000 lines summarizing the oldest by
+    /// default.
+    pub fn capacity(self, capacity: EmitCapacity) -> Self {
+        self.handle.lock().capacity = capacity;
+        self
+    }
+
+    pub fn handle(&self) -> LogHandle {
+        self.handle.clone()
+    }
+
+    /// Installs this logger as the global logger, returning the handle to its records.
+    /// Fails if a global logger was already installed.
+    pub fn install(self) -> anyhow::Result<LogHandle> {
+        let handle = self.handle();
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(handle)
+    }
+
+    fn format(record: &Record) -> Line {
+        let level = record.level();
+        let role = match level {
+            Level::Error => emit::Level::Error,
+            Level::Warn => emit::Level::Warn,
+            Level::Info => emit::Level::Info,
+            Level::Debug => emit::Level::Debug,
+            Level::Trace => emit::Level::Trace,
+        }
+        .style_role();
+        let mut line = Line::from_iter([styled_span(role, &format!("{:>5}", level))]);
+        line.push(Span::sanitized(format!(
+            " {}: {}",
+            record.target(),
+            record.args()
+        )));
+        line
+    }
+}
+
+impl Log for SuperConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.handle.push(Self::format(record));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logger() {
+        let logger = SuperConsoleLogger::new(LevelFilter::Warn);
+        let handle = logger.handle();
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Error)
+                .target("build")
+                .args(format_args!("failed with {}", 2))
+                .build(),
+        );
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("build")
+                .args(format_args!("ignored"))
+                .build(),
+        );
+
+        let lines = handle.take_lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines.0[0].to_unstyled(), "ERROR build: failed with 2");
+        assert!(handle.take_lines().is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let logger = SuperConsoleLogger::new(LevelFilter::Info)
+            .capacity(EmitCapacity::new(3, OverflowPolicy::Summarize));
+        let handle = logger.handle();
+        for i in 0..10 {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .target("build")
+                    .args(format_args!("step {}", i))
+                    .build(),
+            );
+        }
+
+        let rows: Vec<_> = handle.take_lines().iter().map(Line::to_unstyled).collect();
+        assert_eq!(
+            rows,
+            vec![
+                "8 lines dropped",
+                " INFO build: step 8",
+                " INFO build: step 9"
+            ]
+        );
+    }
+}
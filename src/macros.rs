@@ -0,0 +1,60 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Macros to build [`Lines`](crate::Lines) without spelling out every line and span.
+
+/// Formats its arguments like [`format!`], and splits the result into [`Lines`](crate::Lines) on
+/// `\n`. Characters which cannot be drawn, such as tabs, are replaced or removed.
+///
+/// ```ignore
+/// console.emit(superconsole::lines!("Built {}", target));
+/// ```
+#[macro_export]
+macro_rules! lines {
+    ($($arg:tt)*) => {
+        $crate::Lines::from(::std::format!($($arg)*))
+    };
+}
+
+/// Emits formatted lines to a [`SuperConsole`](crate::SuperConsole), see [`lines!`].
+///
+/// ```ignore
+/// superconsole::emit!(console, "Built {} in {:?}", target, elapsed);
+/// ```
+#[macro_export]
+macro_rules! emit {
+    ($console:expr, $($arg:tt)*) => {
+        $console.emit($crate::lines!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::test_console;
+    use crate::Line;
+    use crate::Lines;
+
+    #[test]
+    fn test_lines() -> anyhow::Result<()> {
+        let target = "//foo:bar";
+        assert_eq!(
+            crate::lines!("Built {}\nin {}s", target, 3),
+            Lines(vec![
+                Line::unstyled("Built //foo:bar")?,
+                Line::unstyled("in 3s")?
+            ])
+        );
+
+        let mut console = test_console();
+        crate::emit!(console, "Built {}", target);
+        assert_eq!(console.pending_emit_len(), 1);
+
+        Ok(())
+    }
+}
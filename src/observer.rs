@@ -0,0 +1,44 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Hooks to observe rendering, e.g. to feed an application's metrics pipeline.
+
+use std::time::Duration;
+
+use crate::DrawMode;
+
+/// Describes a frame once it was rendered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FrameStats {
+    /// Index of the frame, see [`FrameMetadata`](crate::output::FrameMetadata).
+    pub index: u64,
+    pub mode: DrawMode,
+    /// Time spent drawing the frame and passing it to the output.
+    pub duration: Duration,
+    /// Size of the frame passed to the output.
+    pub bytes: usize,
+    /// Number of emitted lines drawn in the frame.
+    pub lines_emitted: usize,
+    /// Number of lines in the canvas.
+    pub canvas_height: usize,
+}
+
+/// Registered with [`SuperConsole::add_observer`](crate::SuperConsole::add_observer) to be told
+/// about rendering. All methods do nothing by default.
+pub trait RenderObserver: Send + Sync {
+    /// Called before a frame is drawn.
+    fn on_frame_start(&mut self, _mode: DrawMode) {}
+
+    /// Called after a frame was passed to the output, whether or not that succeeded, or after
+    /// drawing it failed.
+    fn on_frame_end(&mut self, _stats: &FrameStats) {}
+
+    /// Called when a render was requested, but the output was not ready to receive a frame.
+    fn on_frame_dropped(&mut self) {}
+}
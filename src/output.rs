@@ -9,8 +9,12 @@
 
 use std::any::Any;
 use std::io;
+use std::io::IsTerminal;
 use std::io::Write;
 use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
 use anyhow::Context as _;
 use crossbeam_channel::bounded;
@@ -18,7 +22,82 @@ use crossbeam_channel::unbounded;
 use crossbeam_channel::Receiver;
 use crossbeam_channel::Sender;
 
+use crate::content::ChangedRegion;
+use crate::content::DrawSurface;
 use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// A standard stream of the process, which the canvas or emitted lines may be written to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    pub fn writer(self) -> Box<dyn Write + Send + 'static + Sync> {
+        match self {
+            Stream::Stdout => Box::new(io::stdout()),
+            Stream::Stderr => Box::new(io::stderr()),
+        }
+    }
+
+    pub fn is_tty(self) -> bool {
+        match self {
+            Stream::Stdout => io::stdout().is_terminal(),
+            Stream::Stderr => io::stderr().is_terminal(),
+        }
+    }
+
+    /// Renders lines for this stream: styled if it is a terminal, as plain text otherwise, so that
+    /// piping it captures no escape sequences.
+    pub fn render(self, lines: &Lines) -> Vec<u8> {
+        let styled = self.is_tty();
+        let mut buffer = String::new();
+        for line in lines.iter() {
+            if styled {
+                buffer.push_str(&line.render());
+            } else {
+                buffer.push_str(&line.to_unstyled());
+            }
+            buffer.push('\n');
+        }
+        buffer.into_bytes()
+    }
+
+    fn write(self, buffer: &[u8]) -> io::Result<()> {
+        let mut writer = self.writer();
+        writer.write_all(buffer)?;
+        writer.flush()
+    }
+}
+
+/// Describes a frame passed to [`SuperConsoleOutput::output_frame`]. Outputs which record frames
+/// can store this alongside the bytes so that replay tools can reconstruct timing, and consumers
+/// can tell final frames apart from intermediate ones.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FrameMetadata {
+    /// Index of this frame, counting from 0 for the first frame produced by a console.
+    pub index: u64,
+    /// Wall-clock time at which the frame was produced.
+    pub timestamp: SystemTime,
+    /// The dimensions the frame was drawn with.
+    pub dimensions: Dimensions,
+    /// The mode the frame was drawn with.
+    pub mode: DrawMode,
+}
+
+/// A drawn canvas as a grid of styled cells, passed to [`SuperConsoleOutput::output_cells`] for
+/// outputs which don't display ANSI escape sequences, such as GUI frontends or web views.
+/// Emitted lines are not included, use an [`EmitSink`](crate::sink::EmitSink) to receive them.
+#[derive(Debug, Clone)]
+pub struct CellFrame {
+    pub metadata: FrameMetadata,
+    pub cells: DrawSurface,
+    /// The cells which differ from the previous frame passed to `output_cells`.
+    pub changed: Vec<ChangedRegion>,
+}
 
 pub trait SuperConsoleOutput: Send + Sync + 'static {
     /// Called before rendering will occur. This has a chance to prevent rendering by returning
@@ -29,9 +108,68 @@ pub trait SuperConsoleOutput: Send + Sync + 'static {
     /// clearing. This should flush if possible.
     fn output(&mut self, buffer: Vec<u8>) -> anyhow::Result<()>;
 
+    /// Called to produce a drawn frame, along with metadata describing it. Outputs that record
+    /// frames should override this; by default, the metadata is discarded.
+    fn output_frame(&mut self, buffer: Vec<u8>, metadata: FrameMetadata) -> anyhow::Result<()> {
+        let _ = metadata;
+        self.output(buffer)
+    }
+
+    /// Whether [`output_cells`](Self::output_cells) should be called for each drawn frame.
+    /// Building the grid has a cost, so this is off by default.
+    fn wants_cells(&self) -> bool {
+        false
+    }
+
+    /// Called with each drawn frame as a grid of cells, before the frame is passed to
+    /// [`output_frame`](Self::output_frame), if [`wants_cells`](Self::wants_cells) is true.
+    fn output_cells(&mut self, frame: &CellFrame) -> anyhow::Result<()> {
+        let _ = frame;
+        Ok(())
+    }
+
+    /// Whether [`output_lines`](Self::output_lines) should be called for each drawn frame.
+    /// Keeping the emitted lines around has a cost, so this is off by default.
+    fn wants_lines(&self) -> bool {
+        false
+    }
+
+    /// Called with the lines emitted and the canvas of each drawn frame, before the frame is
+    /// passed to [`output_frame`](Self::output_frame), if [`wants_lines`](Self::wants_lines) is
+    /// true. Outputs which don't display ANSI escape sequences can serialize these instead.
+    fn output_lines(
+        &mut self,
+        emitted: &Lines,
+        canvas: &Lines,
+        metadata: FrameMetadata,
+    ) -> anyhow::Result<()> {
+        let _ = (emitted, canvas, metadata);
+        Ok(())
+    }
+
+    /// The standard stream the canvas is drawn on, if any. Lines emitted to this stream with
+    /// [`SuperConsole::emit_to`](crate::SuperConsole::emit_to) are drawn above the canvas as usual.
+    fn stream(&self) -> Option<Stream> {
+        None
+    }
+
+    /// Called to write lines emitted to a stream other than the canvas's. The canvas was cleared
+    /// beforehand, and is drawn again afterwards. By default, the lines are written to the stream
+    /// directly.
+    fn output_to(&mut self, stream: Stream, lines: &Lines) -> anyhow::Result<()> {
+        stream.write(&stream.render(lines))?;
+        Ok(())
+    }
+
+    /// Blocks until everything output so far has been written, e.g. before handing the terminal
+    /// over to another process. Outputs which write synchronously have nothing to do.
+    fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// How big is the terminal to write to.
     fn terminal_size(&self) -> anyhow::Result<Dimensions> {
-        Ok(crossterm::terminal::size()?.into())
+        Ok(crate::backend::terminal_size()?.into())
     }
 
     /// Called when the console has finalized. This must block if necessary. No further output will
@@ -48,11 +186,24 @@ pub trait SuperConsoleOutput: Send + Sync + 'static {
 pub struct BlockingSuperConsoleOutput {
     /// Stream to write to.
     stream: Box<dyn Write + Send + 'static + Sync>,
+    /// The standard stream written to, if it is one.
+    standard: Option<Stream>,
 }
 
 impl BlockingSuperConsoleOutput {
     pub fn new(stream: Box<dyn Write + Send + 'static + Sync>) -> Self {
-        Self { stream }
+        Self {
+            stream,
+            standard: None,
+        }
+    }
+
+    /// An output writing to a standard stream of the process.
+    pub fn for_stream(stream: Stream) -> Self {
+        Self {
+            stream: stream.writer(),
+            standard: Some(stream),
+        }
     }
 }
 
@@ -68,6 +219,10 @@ impl SuperConsoleOutput for BlockingSuperConsoleOutput {
         Ok(())
     }
 
+    fn stream(&self) -> Option<Stream> {
+        self.standard
+    }
+
     fn finalize(self: Box<Self>) -> anyhow::Result<()> {
         Ok(())
     }
@@ -81,19 +236,26 @@ impl SuperConsoleOutput for BlockingSuperConsoleOutput {
     }
 }
 
-/// A non-blocking output for the SuperConsole. This makes a few guarantees:
+/// A non-blocking output for the SuperConsole, which writes frames on a background thread so that
+/// a slow terminal (e.g. over SSH) does not stall rendering. This makes a few guarantees:
 ///
 /// - Calls to output() after should_render() returned true will not block.
 /// - When finalize() returns, the last frame passed to output() is shown.
 /// - When an error occurs, the next fallible call will return it.
-pub(crate) struct NonBlockingSuperConsoleOutput {
-    /// A channel to send frames for writing.
-    sender: Sender<Vec<u8>>,
+///
+/// When the terminal falls behind, should_render() returns false, so the console skips frames
+/// until the backlog is written. Emitted lines are kept for the next frame that is drawn.
+pub struct NonBlockingSuperConsoleOutput {
+    /// A channel to send frames for writing, along with the lines written to other streams
+    /// (tagged with the stream) so that they are written in order.
+    sender: Sender<(Option<Stream>, Vec<u8>)>,
     /// A channel back for errors encountered by the thread doing the writing.
     errors: Receiver<io::Error>,
     /// The thread doing the writing. It owns the other end of the aforementioned channels and will
     /// exit when the data sender is closed.
     handle: JoinHandle<()>,
+    /// The standard stream written to, if it is one.
+    standard: Option<Stream>,
 }
 
 impl NonBlockingSuperConsoleOutput {
@@ -101,15 +263,27 @@ impl NonBlockingSuperConsoleOutput {
         Self::new_for_writer(stream)
     }
 
+    /// An output writing to a standard stream of the process.
+    pub fn for_stream(stream: Stream) -> anyhow::Result<Self> {
+        Ok(Self {
+            standard: Some(stream),
+            ..Self::new_for_writer(stream.writer())?
+        })
+    }
+
     fn new_for_writer(mut stream: Box<dyn Write + Send + 'static + Sync>) -> anyhow::Result<Self> {
-        let (sender, receiver) = bounded::<Vec<u8>>(1);
+        let (sender, receiver) = bounded::<(Option<Stream>, Vec<u8>)>(1);
         let (error_sender, errors) = unbounded::<io::Error>();
 
         let handle = std::thread::Builder::new()
             .name("superconsole-io".to_owned())
             .spawn(move || {
-                for frame in receiver.into_iter() {
-                    match stream.write_all(&frame).and_then(|()| stream.flush()) {
+                for (target, frame) in receiver.into_iter() {
+                    let res = match target {
+                        Some(target) => target.write(&frame),
+                        None => stream.write_all(&frame).and_then(|()| stream.flush()),
+                    };
+                    match res {
                         Ok(()) => {}
                         Err(e) => {
                             // This can only fail if the sender disconnected, in which case they'll
@@ -125,8 +299,21 @@ impl NonBlockingSuperConsoleOutput {
             sender,
             errors,
             handle,
+            standard: None,
         })
     }
+
+    fn send(&mut self, target: Option<Stream>, buffer: Vec<u8>) -> anyhow::Result<()> {
+        if let Ok(err) = self.errors.try_recv() {
+            return Err(anyhow::Error::from(err).context("Superconsole I/O thread errored"));
+        }
+
+        self.sender
+            .send((target, buffer))
+            .context("Superconsole I/O thread has crashed")?;
+
+        Ok(())
+    }
 }
 
 impl SuperConsoleOutput for NonBlockingSuperConsoleOutput {
@@ -140,14 +327,28 @@ impl SuperConsoleOutput for NonBlockingSuperConsoleOutput {
     /// Attempt to send out a frame. If we called should_render, this won't block. If we didn't,
     /// then it may block.
     fn output(&mut self, buffer: Vec<u8>) -> anyhow::Result<()> {
-        if let Ok(err) = self.errors.try_recv() {
-            return Err(anyhow::Error::from(err).context("Superconsole I/O thread errored"));
-        }
+        self.send(None, buffer)
+    }
 
-        self.sender
-            .send(buffer)
-            .context("Superconsole I/O thread has crashed")?;
+    fn stream(&self) -> Option<Stream> {
+        self.standard
+    }
+
+    /// Writes the lines on the writer thread, after the frames sent before them.
+    fn output_to(&mut self, stream: Stream, lines: &Lines) -> anyhow::Result<()> {
+        self.send(Some(stream), stream.render(lines))
+    }
 
+    /// Sends an empty buffer, and waits for the writer thread to pick it up: as it writes in
+    /// order, everything sent before has been written by then.
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.send(None, Vec::new())?;
+        while !self.sender.is_empty() {
+            if self.handle.is_finished() {
+                return Err(anyhow::anyhow!("Superconsole I/O thread has crashed"));
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
         Ok(())
     }
 
@@ -157,6 +358,7 @@ impl SuperConsoleOutput for NonBlockingSuperConsoleOutput {
             sender,
             errors,
             handle,
+            standard: _,
         } = *self;
         drop(sender);
 
@@ -182,8 +384,152 @@ impl SuperConsoleOutput for NonBlockingSuperConsoleOutput {
     }
 }
 
+/// The share of the time an output is left writing frames, once it can't keep up.
+const DEFAULT_MAX_BUSY: f64 = 0.5;
+/// The longest a throttled output waits between frames, so the canvas still shows progress.
+const DEFAULT_MAX_INTERVAL: Duration = Duration::from_secs(2);
+
+/// An output which adapts the frame rate to how fast another output writes, for terminals which
+/// can't keep up with the console, e.g. over a serial line or a congested SSH connection.
+///
+/// Writes to a slow [`BlockingSuperConsoleOutput`] stall the whole application. This wrapper
+/// measures how long each write takes, and afterwards refuses to render until the terminal had
+/// time to catch up, so writing takes at most half of the time by default. Frames that would be
+/// drawn meanwhile are skipped, but emitted lines are never lost: the console keeps them for the
+/// next frame that is drawn. Final frames and clears are always written.
+pub struct ThrottledSuperConsoleOutput {
+    inner: Box<dyn SuperConsoleOutput>,
+    /// A moving average of how long writes took.
+    latency: Option<Duration>,
+    max_busy: f64,
+    max_interval: Duration,
+    /// No frame is rendered before this.
+    next_frame: Option<Instant>,
+}
+
+impl ThrottledSuperConsoleOutput {
+    pub fn new(inner: Box<dyn SuperConsoleOutput>) -> Self {
+        Self {
+            inner,
+            latency: None,
+            max_busy: DEFAULT_MAX_BUSY,
+            max_interval: DEFAULT_MAX_INTERVAL,
+            next_frame: None,
+        }
+    }
+
+    /// The share of the time, between 0 and 1, that the output may spend writing before frames
+    /// are skipped. Defaults to 0.5.
+    pub fn max_busy(mut self, max_busy: f64) -> Self {
+        self.max_busy = max_busy.clamp(f64::EPSILON, 1.0);
+        self
+    }
+
+    /// The longest to wait between frames however slow the output is. Defaults to 2s.
+    pub fn max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval;
+        self
+    }
+
+    /// How long writes take, on average, if anything was written yet.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    fn should_render_at(&self, now: Instant) -> bool {
+        self.next_frame.is_none_or(|next| now >= next)
+    }
+
+    /// Records that a write started at `start` and was done at `end`.
+    fn record(&mut self, start: Instant, end: Instant) {
+        let elapsed = end.saturating_duration_since(start);
+        let latency = match self.latency {
+            // Weighs the latest write by a quarter, so a single hiccup doesn't stall the canvas.
+            Some(latency) => (latency * 3 + elapsed) / 4,
+            None => elapsed,
+        };
+        self.latency = Some(latency);
+        let wait = latency.mul_f64(1.0 / self.max_busy - 1.0);
+        self.next_frame = Some(end + wait.min(self.max_interval));
+    }
+
+    fn timed<T>(&mut self, write: impl FnOnce(&mut dyn SuperConsoleOutput) -> T) -> T {
+        let start = Instant::now();
+        let res = write(&mut *self.inner);
+        self.record(start, Instant::now());
+        res
+    }
+}
+
+impl SuperConsoleOutput for ThrottledSuperConsoleOutput {
+    fn should_render(&mut self) -> bool {
+        self.should_render_at(Instant::now()) && self.inner.should_render()
+    }
+
+    fn output(&mut self, buffer: Vec<u8>) -> anyhow::Result<()> {
+        self.timed(|inner| inner.output(buffer))
+    }
+
+    fn output_frame(&mut self, buffer: Vec<u8>, metadata: FrameMetadata) -> anyhow::Result<()> {
+        self.timed(|inner| inner.output_frame(buffer, metadata))
+    }
+
+    fn wants_cells(&self) -> bool {
+        self.inner.wants_cells()
+    }
+
+    fn output_cells(&mut self, frame: &CellFrame) -> anyhow::Result<()> {
+        self.inner.output_cells(frame)
+    }
+
+    fn wants_lines(&self) -> bool {
+        self.inner.wants_lines()
+    }
+
+    fn output_lines(
+        &mut self,
+        emitted: &Lines,
+        canvas: &Lines,
+        metadata: FrameMetadata,
+    ) -> anyhow::Result<()> {
+        self.inner.output_lines(emitted, canvas, metadata)
+    }
+
+    fn stream(&self) -> Option<Stream> {
+        self.inner.stream()
+    }
+
+    fn output_to(&mut self, stream: Stream, lines: &Lines) -> anyhow::Result<()> {
+        self.timed(|inner| inner.output_to(stream, lines))
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.inner.flush()
+    }
+
+    fn terminal_size(&self) -> anyhow::Result<Dimensions> {
+        self.inner.terminal_size()
+    }
+
+    fn finalize(self: Box<Self>) -> anyhow::Result<()> {
+        self.inner.finalize()
+    }
+
+    /// The wrapped output, so that it can be downcast as if it were not wrapped.
+    fn as_any(&self) -> &dyn Any {
+        self.inner.as_any()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self.inner.as_any_mut()
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
     use crossbeam_channel::Receiver;
 
     use super::*;
@@ -206,7 +552,7 @@ mod test {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
             self.sender
                 .send(())
-                .map_err(|_| io::Error::new(io::ErrorKind::Other, "not writable"))?;
+                .map_err(|_| io::Error::other("not writable"))?;
 
             Ok(buf.len())
         }
@@ -221,6 +567,35 @@ mod test {
         vec![1]
     }
 
+    /// A test writer collecting everything written to it.
+    #[derive(Clone, Default)]
+    struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_non_blocking_output_writes_all_frames() -> anyhow::Result<()> {
+        let writer = SharedWriter::default();
+        let mut output = NonBlockingSuperConsoleOutput::new(Box::new(writer.clone()))?;
+
+        output.output(b"first ".to_vec())?;
+        output.output(b"second".to_vec())?;
+        // The frames are written on the background thread by the time finalize returns.
+        Box::new(output).finalize()?;
+        assert_eq!(&*writer.0.lock().unwrap(), b"first second");
+
+        Ok(())
+    }
+
     #[test]
     fn test_non_blocking_output_errors_on_next_output() -> anyhow::Result<()> {
         let (writer, drain) = TestWriter::new();
@@ -250,4 +625,34 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_throttled_output() -> anyhow::Result<()> {
+        let mut output = ThrottledSuperConsoleOutput::new(Box::new(
+            BlockingSuperConsoleOutput::new(Box::new(io::sink())),
+        ))
+        .max_interval(Duration::from_millis(250));
+        let start = Instant::now();
+        assert!(output.should_render_at(start));
+
+        // A slow write delays the next frame by as long as it took.
+        let end = start + Duration::from_millis(100);
+        output.record(start, end);
+        assert_eq!(output.latency(), Some(Duration::from_millis(100)));
+        assert!(!output.should_render_at(end + Duration::from_millis(99)));
+        assert!(output.should_render_at(end + Duration::from_millis(100)));
+
+        // The average adapts gradually, and the wait is bounded.
+        output.record(end, end + Duration::from_millis(900));
+        assert_eq!(output.latency(), Some(Duration::from_millis(300)));
+        let end = end + Duration::from_millis(900);
+        assert!(!output.should_render_at(end + Duration::from_millis(249)));
+        assert!(output.should_render_at(end + Duration::from_millis(250)));
+
+        // Fast writes restore the frame rate.
+        output.output(msg())?;
+        assert!(output.latency() < Some(Duration::from_millis(300)));
+
+        Ok(())
+    }
 }
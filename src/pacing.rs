@@ -0,0 +1,345 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Frame pacing for render loops.
+//!
+//! A loop that sleeps for a fixed interval after each render drifts behind whenever a render is
+//! slow, and one that queues ticks bunches them up afterwards. A [`FramePacer`] instead schedules
+//! ticks on a fixed cadence from its start, and skips the ticks that were missed.
+//!
+//! Event driven loops, which render whenever something happens, instead want a
+//! [`RenderScheduler`]: it caps the frame rate, so that bursts of events are coalesced into a
+//! single frame, and skips frames when nothing changed.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::Component;
+use crate::SuperConsole;
+
+/// A tick of a [`FramePacer`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Tick {
+    /// Index of this tick on the cadence, counting from 0 at the pacer's start.
+    pub index: u64,
+    /// How many ticks were missed since the previous one.
+    pub skipped: u64,
+}
+
+/// Schedules renders on a fixed cadence, skipping ticks that were missed rather than catching up.
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    interval: Duration,
+    start: Instant,
+    /// Index of the next tick to be returned, at the earliest.
+    next: u64,
+    /// Total ticks skipped so far.
+    skipped: u64,
+}
+
+impl FramePacer {
+    /// A pacer whose first tick is due immediately.
+    pub fn new(interval: Duration) -> Self {
+        Self::starting_at(interval, Instant::now())
+    }
+
+    /// A pacer whose first tick is due at `start`.
+    pub fn starting_at(interval: Duration, start: Instant) -> Self {
+        assert!(!interval.is_zero(), "Frame interval must be non-zero");
+        Self {
+            interval,
+            start,
+            next: 0,
+            skipped: 0,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// When the next tick is due.
+    pub fn next_deadline(&self) -> Instant {
+        // Computed in nanoseconds, as `Duration` only multiplies by `u32`.
+        let offset = self
+            .interval
+            .as_nanos()
+            .saturating_mul(u128::from(self.next));
+        self.start + Duration::from_nanos(u64::try_from(offset).unwrap_or(u64::MAX))
+    }
+
+    /// How long until the next tick is due, e.g. to sleep in an async loop before calling
+    /// [`poll`](Self::poll).
+    pub fn time_until_next(&self) -> Duration {
+        self.next_deadline()
+            .saturating_duration_since(Instant::now())
+    }
+
+    /// Total ticks skipped so far.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Returns the tick that is due at `now`, if any. If several are due, the latest is returned
+    /// and the others are skipped.
+    pub fn tick_at(&mut self, now: Instant) -> Option<Tick> {
+        if now < self.next_deadline() {
+            return None;
+        }
+        let elapsed = now.saturating_duration_since(self.start);
+        let index = (elapsed.as_nanos() / self.interval.as_nanos()) as u64;
+        let skipped = index - self.next;
+        self.next = index + 1;
+        self.skipped += skipped;
+        Some(Tick { index, skipped })
+    }
+
+    /// Returns the tick that is due now, if any.
+    pub fn poll(&mut self) -> Option<Tick> {
+        self.tick_at(Instant::now())
+    }
+
+    /// Blocks until the next tick is due, and returns it.
+    pub fn wait(&mut self) -> Tick {
+        loop {
+            std::thread::sleep(self.time_until_next());
+            if let Some(tick) = self.poll() {
+                return tick;
+            }
+        }
+    }
+}
+
+/// Decides when an event driven loop renders. Call [`mark_dirty`](Self::mark_dirty) when the
+/// state drawn by the components changes, and [`render`](Self::render) as often as convenient,
+/// e.g. after every event and when [`time_until_next`](Self::time_until_next) elapses, if ever.
+#[derive(Debug, Clone)]
+pub struct RenderScheduler {
+    interval: Duration,
+    keep_alive: Option<Duration>,
+    last_render: Option<Instant>,
+    dirty: bool,
+}
+
+impl RenderScheduler {
+    /// Renders at most `fps` frames per second. A frame rate of 0 is taken as 1.
+    pub fn new(fps: u32) -> Self {
+        Self::with_interval(Duration::from_secs(1) / fps.max(1))
+    }
+
+    /// Renders at most once per `interval`.
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            keep_alive: None,
+            last_render: None,
+            dirty: true,
+        }
+    }
+
+    /// Also renders once `keep_alive` passed since the last frame, even if nothing changed, e.g.
+    /// to keep spinners and timers moving.
+    pub fn keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// Notes that the state drawn by the components changed.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Whether a frame should be drawn at `now`, given whether lines are waiting to be emitted.
+    pub fn is_due_at(&self, now: Instant, pending_emit: bool) -> bool {
+        let last = match self.last_render {
+            Some(last) => last,
+            None => return true,
+        };
+        let elapsed = now.saturating_duration_since(last);
+        if elapsed < self.interval {
+            return false;
+        }
+        self.dirty || pending_emit || matches!(self.keep_alive, Some(k) if elapsed >= k)
+    }
+
+    /// How long after `now` the next frame is due, given whether lines are waiting to be
+    /// emitted: once the frame interval passed if anything changed, or else when the keep-alive
+    /// is due. Returns `None` if no frame is due until something changes.
+    pub fn time_until_next_at(&self, now: Instant, pending_emit: bool) -> Option<Duration> {
+        let last = match self.last_render {
+            Some(last) => last,
+            None => return Some(Duration::ZERO),
+        };
+        let after = if self.dirty || pending_emit {
+            self.interval
+        } else {
+            self.keep_alive?.max(self.interval)
+        };
+        Some((last + after).saturating_duration_since(now))
+    }
+
+    /// How long until the next frame of `console` is due, e.g. to sleep before calling
+    /// [`render`](Self::render) again, or `None` if no frame is due until something changes.
+    pub fn time_until_next(&self, console: &SuperConsole) -> Option<Duration> {
+        self.time_until_next_at(Instant::now(), console.has_pending_emit())
+    }
+
+    /// Renders `console` if a frame is due at `now`. Returns whether it rendered.
+    pub fn render_at(
+        &mut self,
+        console: &mut SuperConsole,
+        root: &dyn Component,
+        now: Instant,
+    ) -> anyhow::Result<bool> {
+        if !self.is_due_at(now, console.has_pending_emit()) {
+            return Ok(false);
+        }
+        self.last_render = Some(now);
+        self.dirty = false;
+        console.render(root)?;
+        Ok(true)
+    }
+
+    /// Renders `console` if a frame is due. Returns whether it rendered.
+    pub fn render(
+        &mut self,
+        console: &mut SuperConsole,
+        root: &dyn Component,
+    ) -> anyhow::Result<bool> {
+        self.render_at(console, root, Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Blank;
+    use crate::testing::test_console;
+    use crate::testing::SuperConsoleTestingExt;
+    use crate::Line;
+    use crate::Lines;
+
+    #[test]
+    fn test_pacing() {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut pacer = FramePacer::starting_at(ms(100), start);
+
+        assert_eq!(
+            pacer.tick_at(start),
+            Some(Tick {
+                index: 0,
+                skipped: 0
+            })
+        );
+        // Not due yet.
+        assert_eq!(pacer.tick_at(start + ms(50)), None);
+        assert_eq!(pacer.next_deadline(), start + ms(100));
+        assert_eq!(
+            pacer.tick_at(start + ms(120)),
+            Some(Tick {
+                index: 1,
+                skipped: 0
+            })
+        );
+
+        // A slow render overran ticks 2 and 3; we skip ahead instead of bunching them up.
+        assert_eq!(
+            pacer.tick_at(start + ms(430)),
+            Some(Tick {
+                index: 4,
+                skipped: 2
+            })
+        );
+        assert_eq!(pacer.next_deadline(), start + ms(500));
+        assert_eq!(pacer.tick_at(start + ms(450)), None);
+        assert_eq!(pacer.skipped(), 2);
+
+        // Deadlines past `u32::MAX` ticks aren't truncated.
+        let mut pacer = FramePacer::starting_at(ms(1), start);
+        let late = ms(1 << 33);
+        assert_eq!(
+            pacer.tick_at(start + late).map(|tick| tick.index),
+            Some(1 << 33)
+        );
+        assert_eq!(pacer.next_deadline(), start + late + ms(1));
+        assert_eq!(pacer.tick_at(start + late), None);
+    }
+
+    #[test]
+    fn test_scheduler() -> anyhow::Result<()> {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut console = test_console();
+        let mut scheduler = RenderScheduler::new(10).keep_alive(ms(1000));
+
+        assert!(scheduler.render_at(&mut console, &Blank, start)?);
+        // Nothing changed.
+        assert!(!scheduler.render_at(&mut console, &Blank, start + ms(150))?);
+
+        // Emits within a frame are coalesced.
+        console.emit(Lines(vec![Line::unstyled("one")?]));
+        assert!(!scheduler.render_at(&mut console, &Blank, start + ms(50))?);
+        console.emit(Lines(vec![Line::unstyled("two")?]));
+        assert!(scheduler.render_at(&mut console, &Blank, start + ms(160))?);
+
+        scheduler.mark_dirty();
+        assert!(!scheduler.render_at(&mut console, &Blank, start + ms(200))?);
+        assert!(scheduler.render_at(&mut console, &Blank, start + ms(260))?);
+
+        assert!(!scheduler.render_at(&mut console, &Blank, start + ms(1200))?);
+        assert!(scheduler.render_at(&mut console, &Blank, start + ms(1300))?);
+
+        assert_eq!(console.test_output()?.metadata.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scheduler_deadlines() -> anyhow::Result<()> {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut console = test_console();
+        let mut scheduler = RenderScheduler::new(10);
+        assert_eq!(scheduler.time_until_next_at(start, false), Some(ms(0)));
+
+        assert!(scheduler.render_at(&mut console, &Blank, start)?);
+        // Nothing is scheduled until something changes.
+        assert_eq!(scheduler.time_until_next_at(start + ms(50), false), None);
+        assert_eq!(
+            scheduler.time_until_next_at(start + ms(50), true),
+            Some(ms(50))
+        );
+        scheduler.mark_dirty();
+        assert_eq!(
+            scheduler.time_until_next_at(start + ms(150), false),
+            Some(ms(0))
+        );
+
+        let mut scheduler = RenderScheduler::new(10).keep_alive(ms(1000));
+        assert!(scheduler.render_at(&mut console, &Blank, start)?);
+        assert_eq!(
+            scheduler.time_until_next_at(start + ms(200), false),
+            Some(ms(800))
+        );
+
+        // A frame rate of 0 renders once per second.
+        let mut scheduler = RenderScheduler::new(0);
+        assert!(scheduler.render_at(&mut console, &Blank, start)?);
+        scheduler.mark_dirty();
+        assert_eq!(
+            scheduler.time_until_next_at(start + ms(200), false),
+            Some(ms(800))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Rate limiting of emitted lines per source, so that one noisy producer can't flood the
+//! scrollback. Lines over the limit are dropped, and replaced with a summary such as
+//! `suppressed 2,314 lines from [clippy]` once the source calms down.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::Line;
+use crate::Lines;
+
+/// The slowest refill a [`RateLimit`] is clamped to, one line a minute, so that a source is never
+/// silenced for good.
+const MIN_LINES_PER_SECOND: f64 = 1.0 / 60.0;
+
+/// A token bucket: each source may emit `burst` lines at once, and `lines_per_second` on average.
+///
+/// A `burst` below 1 is clamped to 1, and a `lines_per_second` below one line a minute (including
+/// zero and NaN) is clamped to that, since otherwise a source could never emit, nor have its
+/// suppressed lines summarized.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RateLimit {
+    pub lines_per_second: f64,
+    pub burst: f64,
+}
+
+impl RateLimit {
+    pub fn new(lines_per_second: f64, burst: f64) -> Self {
+        Self {
+            lines_per_second,
+            burst,
+        }
+        .clamped()
+    }
+
+    fn clamped(self) -> Self {
+        // `f64::max` returns the other operand when one is NaN.
+        Self {
+            lines_per_second: self.lines_per_second.max(MIN_LINES_PER_SECOND),
+            burst: self.burst.max(1.0),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    suppressed: u64,
+}
+
+/// Applies a [`RateLimit`] to each source separately.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: HashMap<String, Bucket>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit: limit.clamped(),
+            buckets: HashMap::new(),
+        }
+    }
+
+    pub fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// Returns the lines from `source` which are within its limit. If lines were suppressed
+    /// earlier and the source has capacity again, they are summarized first.
+    pub fn admit(&mut self, source: &str, lines: Lines, now: Instant) -> Lines {
+        let limit = self.limit;
+        let bucket = self
+            .buckets
+            .entry(source.to_owned())
+            .or_insert_with(|| Bucket {
+                tokens: limit.burst,
+                updated: now,
+                suppressed: 0,
+            });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.lines_per_second).min(limit.burst);
+        bucket.updated = now;
+
+        let mut res = Lines::new();
+        if bucket.suppressed > 0 && bucket.tokens >= 1.0 {
+            res.push(summary(source, bucket.suppressed));
+            bucket.suppressed = 0;
+        }
+        for line in lines.0 {
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                res.push(line);
+            } else {
+                bucket.suppressed += 1;
+            }
+        }
+        res
+    }
+
+    /// Summarizes the lines suppressed from every source which were not summarized yet, e.g.
+    /// before the console is finalized.
+    pub fn take_summaries(&mut self) -> Lines {
+        let mut sources: Vec<_> = self
+            .buckets
+            .iter_mut()
+            .filter(|(_, bucket)| bucket.suppressed > 0)
+            .collect();
+        sources.sort_by_key(|(source, _)| *source);
+        sources
+            .into_iter()
+            .map(|(source, bucket)| summary(source, std::mem::take(&mut bucket.suppressed)))
+            .collect()
+    }
+}
+
+fn summary(source: &str, suppressed: u64) -> Line {
+    Line::from_iter([styled_span(
+        StyleRole::Dim,
+        &strings().suppressed(suppressed, source),
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn lines(count: usize) -> Lines {
+        Lines(vec![Line::sanitized("spam"); count])
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(RateLimit {
+            lines_per_second: 10.0,
+            burst: 5.0,
+        });
+        let start = Instant::now();
+
+        assert_eq!(limiter.admit("clippy", lines(8), start).len(), 5);
+        // Other sources have their own budget.
+        assert_eq!(limiter.admit("rustc", lines(2), start).len(), 2);
+
+        // 100ms refill a single line, which follows the summary.
+        let res = limiter.admit("clippy", lines(3), start + Duration::from_millis(100));
+        let rows: Vec<_> = res.iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["suppressed 3 lines from [clippy]", "spam"]);
+
+        let rows: Vec<_> = limiter
+            .take_summaries()
+            .iter()
+            .map(Line::to_unstyled)
+            .collect();
+        assert_eq!(rows, vec!["suppressed 2 lines from [clippy]"]);
+        assert!(limiter.take_summaries().is_empty());
+    }
+
+    #[test]
+    fn test_degenerate_limit() {
+        let limit = RateLimit::new(0.0, 0.0);
+        assert_eq!(limit.lines_per_second, MIN_LINES_PER_SECOND);
+        assert_eq!(limit.burst, 1.0);
+
+        // Limits built directly are clamped too.
+        let mut limiter = RateLimiter::new(RateLimit {
+            lines_per_second: f64::NAN,
+            burst: 0.0,
+        });
+        assert_eq!(limiter.limit(), limit);
+
+        let start = Instant::now();
+        assert_eq!(limiter.admit("clippy", lines(3), start).len(), 1);
+        let res = limiter.admit("clippy", lines(1), start + Duration::from_secs(120));
+        let rows: Vec<_> = res.iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["suppressed 2 lines from [clippy]", "spam"]);
+    }
+}
@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Recording console sessions as [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/)
+//! files, which can be replayed with `asciinema play` or embedded in web pages.
+//!
+//! [`RecordingOutput`] wraps another output: everything is still written to the terminal, and
+//! also appended to the cast as an output event, timestamped with the time the frame was drawn.
+
+use std::any::Any;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context as _;
+use serde_json::json;
+
+use crate::output::CellFrame;
+use crate::output::FrameMetadata;
+use crate::output::Stream;
+use crate::output::SuperConsoleOutput;
+use crate::Dimensions;
+use crate::Lines;
+
+/// The size written to the cast header when the terminal size is unknown.
+const DEFAULT_CAST_SIZE: Dimensions = Dimensions {
+    width: 80,
+    height: 24,
+};
+
+/// An output which tees everything written to another output into an asciicast v2 file.
+/// See the [module documentation](self).
+pub struct RecordingOutput {
+    inner: Box<dyn SuperConsoleOutput>,
+    cast: Box<dyn Write + Send + Sync + 'static>,
+    start: SystemTime,
+}
+
+impl RecordingOutput {
+    /// Records the output written to `inner` to `cast`. The header is written immediately, with
+    /// the size of the terminal `inner` writes to.
+    pub fn new(
+        inner: Box<dyn SuperConsoleOutput>,
+        mut cast: Box<dyn Write + Send + Sync + 'static>,
+    ) -> anyhow::Result<Self> {
+        let start = SystemTime::now();
+        let size = inner
+            .terminal_size()
+            .ok()
+            .filter(|size| size.width > 0 && size.height > 0)
+            .unwrap_or(DEFAULT_CAST_SIZE);
+        let header = json!({
+            "version": 2,
+            "width": size.width,
+            "height": size.height,
+            "timestamp": start.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        });
+        serde_json::to_writer(&mut cast, &header)?;
+        cast.write_all(b"\n")?;
+        Ok(Self { inner, cast, start })
+    }
+
+    /// Records the output written to `inner` to a new cast file at `path`.
+    pub fn create(
+        inner: Box<dyn SuperConsoleOutput>,
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Error creating cast file `{}`", path.display()))?;
+        Self::new(inner, Box::new(BufWriter::new(file)))
+    }
+
+    /// Appends an output event for `data`, written at `at`.
+    fn record(&mut self, at: SystemTime, data: &[u8]) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let elapsed = at.duration_since(self.start).unwrap_or_default();
+        // The terminal translates newlines to carriage returns and newlines, but players don't.
+        let data = String::from_utf8_lossy(data).replace('\n', "\r\n");
+        serde_json::to_writer(&mut self.cast, &json!([elapsed.as_secs_f64(), "o", data]))?;
+        self.cast.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+impl SuperConsoleOutput for RecordingOutput {
+    fn should_render(&mut self) -> bool {
+        self.inner.should_render()
+    }
+
+    fn output(&mut self, buffer: Vec<u8>) -> anyhow::Result<()> {
+        self.record(SystemTime::now(), &buffer)?;
+        self.inner.output(buffer)
+    }
+
+    fn output_frame(&mut self, buffer: Vec<u8>, metadata: FrameMetadata) -> anyhow::Result<()> {
+        self.record(metadata.timestamp, &buffer)?;
+        self.inner.output_frame(buffer, metadata)
+    }
+
+    fn wants_cells(&self) -> bool {
+        self.inner.wants_cells()
+    }
+
+    fn output_cells(&mut self, frame: &CellFrame) -> anyhow::Result<()> {
+        self.inner.output_cells(frame)
+    }
+
+    fn wants_lines(&self) -> bool {
+        self.inner.wants_lines()
+    }
+
+    fn output_lines(
+        &mut self,
+        emitted: &Lines,
+        canvas: &Lines,
+        metadata: FrameMetadata,
+    ) -> anyhow::Result<()> {
+        self.inner.output_lines(emitted, canvas, metadata)
+    }
+
+    fn stream(&self) -> Option<Stream> {
+        self.inner.stream()
+    }
+
+    /// Lines written to other streams are recorded too, as they share the terminal.
+    fn output_to(&mut self, stream: Stream, lines: &Lines) -> anyhow::Result<()> {
+        let mut data = String::new();
+        for line in lines.iter() {
+            data.push_str(&line.render());
+            data.push('\n');
+        }
+        self.record(SystemTime::now(), data.as_bytes())?;
+        self.inner.output_to(stream, lines)
+    }
+
+    fn terminal_size(&self) -> anyhow::Result<Dimensions> {
+        self.inner.terminal_size()
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.cast.flush()?;
+        self.inner.flush()
+    }
+
+    fn finalize(mut self: Box<Self>) -> anyhow::Result<()> {
+        self.cast.flush()?;
+        self.inner.finalize()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::testing::TestOutput;
+    use crate::SuperConsole;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_recording() -> anyhow::Result<()> {
+        let cast = SharedBuffer::default();
+        let inner = TestOutput::new(Dimensions::new(40, 10));
+        let output = RecordingOutput::new(Box::new(inner), Box::new(cast.clone()))?;
+        let mut builder = SuperConsole::builder();
+        builder.forced(true).output(Box::new(output));
+        let mut console = builder.build()?.expect("forced");
+
+        let root = Echo(Lines(vec![vec!["building"].try_into()?]));
+        console.emit(Lines(vec![vec!["compiled"].try_into()?]));
+        console.render(&root)?;
+        console.finalize(&root)?;
+
+        let cast = String::from_utf8(cast.0.lock().unwrap().clone())?;
+        let mut events = cast.lines().map(serde_json::from_str::<Value>);
+        let header = events.next().context("No header")??;
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 40);
+        assert_eq!(header["height"], 10);
+
+        let events: Vec<Value> = events.collect::<Result<_, _>>()?;
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|e| e[1] == "o" && e[0].is_f64()));
+        let first = events[0][2].as_str().context("No data")?;
+        assert!(first.contains("compiled"));
+        assert!(first.contains("\r\n"));
+        assert!(first.contains("building"));
+
+        Ok(())
+    }
+}
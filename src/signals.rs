@@ -0,0 +1,192 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Restoring the terminal when the process is interrupted, with the `signals` feature.
+//!
+//! Ctrl-C or `kill` terminate the process without unwinding, so a console which hid the cursor,
+//! entered the alternate screen, set a scroll region for a header or enabled raw mode would leave
+//! the terminal that way, with a half-drawn canvas. After
+//! [`SuperConsole::restore_on_signals`](crate::SuperConsole::restore_on_signals), a background
+//! thread waits for `SIGINT` and `SIGTERM`, clears the canvas and restores the terminal, then
+//! re-raises the signal with its default behavior.
+//!
+//! The console shares the state of the terminal with the thread after every change, so the
+//! restoration undoes exactly what was done. Once the console is finalized, detached or dropped,
+//! the signals are unregistered and behave as usual, including any handlers installed since.
+
+use std::io;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU16;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+
+use signal_hook::consts::SIGINT;
+use signal_hook::consts::SIGTERM;
+use signal_hook::iterator::Handle;
+use signal_hook::iterator::Signals;
+use signal_hook::low_level::emulate_default_handler;
+
+use crate::ansi::Clear;
+use crate::ansi::ClearType;
+use crate::ansi::LeaveAlternateScreen;
+use crate::ansi::MoveToColumn;
+use crate::ansi::MoveUp;
+use crate::ansi::QueueableCommand;
+use crate::ansi::RestorePosition;
+use crate::ansi::SavePosition;
+use crate::ansi::Show;
+use crate::backend;
+use crate::output::Stream;
+use crate::superconsole::END_SYNCHRONIZED_UPDATE;
+
+/// What the console changed about the terminal, as of its last update.
+#[derive(Debug, Default)]
+pub(crate) struct TerminalState {
+    /// The rows from the cursor up to the top of the canvas.
+    canvas_rows: AtomicU16,
+    alternate_screen: AtomicBool,
+    scroll_region: AtomicBool,
+    raw_mode: AtomicBool,
+}
+
+impl TerminalState {
+    pub(crate) fn update(
+        &self,
+        canvas_rows: u16,
+        alternate_screen: bool,
+        scroll_region: bool,
+        raw_mode: bool,
+    ) {
+        self.canvas_rows.store(canvas_rows, Ordering::Relaxed);
+        self.alternate_screen
+            .store(alternate_screen, Ordering::Relaxed);
+        self.scroll_region.store(scroll_region, Ordering::Relaxed);
+        self.raw_mode.store(raw_mode, Ordering::Relaxed);
+    }
+
+    /// Records that the console gave the terminal back, so there is nothing to restore.
+    pub(crate) fn release(&self) {
+        self.update(0, false, false, false);
+    }
+
+    /// The escape sequences clearing the canvas and undoing the console's changes.
+    fn restore_sequence(&self) -> anyhow::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        // The signal may have arrived while a frame was drawn, and the terminal would hold back
+        // everything until the frame ends.
+        write!(buffer, "{}", END_SYNCHRONIZED_UPDATE)?;
+        let canvas_rows = self.canvas_rows.load(Ordering::Relaxed);
+        if self.alternate_screen.load(Ordering::Relaxed) {
+            buffer.queue(LeaveAlternateScreen)?;
+        } else if canvas_rows > 0 {
+            buffer.queue(MoveUp(canvas_rows))?;
+            buffer.queue(MoveToColumn(0))?;
+            buffer.queue(Clear(ClearType::FromCursorDown))?;
+        }
+        if self.scroll_region.load(Ordering::Relaxed) {
+            buffer.queue(SavePosition)?;
+            write!(buffer, "\x1b[r")?;
+            buffer.queue(RestorePosition)?;
+        }
+        buffer.queue(Show)?;
+        Ok(buffer)
+    }
+
+    fn restore(&self, stream: Option<Stream>) -> anyhow::Result<()> {
+        fn write_locked(mut writer: impl Write, buffer: &[u8]) -> io::Result<()> {
+            writer.write_all(buffer)?;
+            writer.flush()
+        }
+
+        let buffer = self.restore_sequence()?;
+        // Frames are written to the standard stream under its lock, so taking it keeps the
+        // sequence from landing in the middle of a frame.
+        match stream {
+            Some(Stream::Stdout) => write_locked(io::stdout().lock(), &buffer)?,
+            Some(Stream::Stderr) => write_locked(io::stderr().lock(), &buffer)?,
+            None => {}
+        }
+        if self.raw_mode.load(Ordering::Relaxed) {
+            backend::disable_raw_mode()?;
+        }
+        Ok(())
+    }
+}
+
+/// Unregisters the signals and stops the thread restoring the terminal when dropped.
+pub(crate) struct SignalsGuard(Handle);
+
+impl Drop for SignalsGuard {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+/// Starts the thread restoring the terminal described by `state`, which the console draws on
+/// `stream`, when the process is interrupted.
+pub(crate) fn install(
+    state: Arc<TerminalState>,
+    stream: Option<Stream>,
+) -> anyhow::Result<SignalsGuard> {
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+    let guard = SignalsGuard(signals.handle());
+    thread::Builder::new()
+        .name("superconsole-signals".to_owned())
+        .spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                // The process is going away, so there is nobody to report errors to.
+                let _ = state.restore(stream);
+                let _ = emulate_default_handler(signal);
+            }
+        })?;
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::frame_contains;
+
+    #[test]
+    fn test_restore_sequence() -> anyhow::Result<()> {
+        let state = TerminalState::default();
+        state.update(3, false, true, false);
+        let sequence = state.restore_sequence()?;
+        assert!(sequence.starts_with(END_SYNCHRONIZED_UPDATE.as_bytes()));
+        assert!(frame_contains(&sequence, "\x1b[3A"));
+        assert!(frame_contains(&sequence, "\x1b[r"));
+        assert!(frame_contains(&sequence, "\x1b[?25h"));
+
+        state.update(3, true, false, false);
+        let sequence = state.restore_sequence()?;
+        assert!(!frame_contains(&sequence, "\x1b[3A"));
+        assert!(frame_contains(&sequence, "\x1b[?1049l"));
+
+        state.release();
+        assert_eq!(state.restore_sequence()?, {
+            let mut show = END_SYNCHRONIZED_UPDATE.as_bytes().to_vec();
+            show.queue(Show)?;
+            show
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_guard_unregisters() -> anyhow::Result<()> {
+        let guard = install(Arc::default(), None)?;
+        let handle = guard.0.clone();
+        assert!(!handle.is_closed());
+        drop(guard);
+        assert!(handle.is_closed());
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Destinations for emitted lines besides the terminal scrollback, e.g. a log file.
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::Line;
+use crate::Lines;
+
+/// Registered with [`SuperConsole::add_sink`](crate::SuperConsole::add_sink) to receive a copy of
+/// every emitted line as it is drawn to the terminal.
+pub trait EmitSink: Send + Sync {
+    /// Called with the emitted lines drawn in a frame.
+    fn emit(&mut self, lines: &Lines) -> anyhow::Result<()>;
+
+    /// Called when the console is finalized.
+    fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes emitted lines to a stream such as a file or a socket, one per line.
+pub struct WriterSink {
+    stream: Box<dyn Write + Send + Sync + 'static>,
+    styled: bool,
+}
+
+impl WriterSink {
+    /// Writes lines without styling.
+    pub fn new(stream: Box<dyn Write + Send + Sync + 'static>) -> Self {
+        Self {
+            stream,
+            styled: false,
+        }
+    }
+
+    /// Writes lines with ANSI escape codes for their styling.
+    pub fn styled(stream: Box<dyn Write + Send + Sync + 'static>) -> Self {
+        Self {
+            stream,
+            styled: true,
+        }
+    }
+}
+
+impl EmitSink for WriterSink {
+    fn emit(&mut self, lines: &Lines) -> anyhow::Result<()> {
+        for line in lines.iter() {
+            let text = if self.styled {
+                line.render()
+            } else {
+                line.to_unstyled()
+            };
+            writeln!(self.stream, "{}", text)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(self.stream.flush()?)
+    }
+}
+
+/// Keeps the most recently emitted lines in memory, e.g. to attach them to a crash report.
+/// Clones share the same buffer, so keep one to read the lines back.
+#[derive(Clone)]
+pub struct RingBufferSink {
+    capacity: usize,
+    lines: Arc<Mutex<VecDeque<Line>>>,
+}
+
+impl RingBufferSink {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// The buffered lines, oldest first.
+    pub fn lines(&self) -> Lines {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        Lines(lines.iter().cloned().collect())
+    }
+}
+
+impl EmitSink for RingBufferSink {
+    fn emit(&mut self, lines: &Lines) -> anyhow::Result<()> {
+        let mut buffer = self
+            .lines
+            .lock()
+            .map_err(|_| anyhow::anyhow!("RingBufferSink mutex was poisoned"))?;
+        for line in lines.iter() {
+            if buffer.len() == self.capacity {
+                buffer.pop_front();
+            }
+            if self.capacity > 0 {
+                buffer.push_back(line.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer() -> anyhow::Result<()> {
+        let sink = RingBufferSink::new(2);
+        sink.clone().emit(&Lines(vec![
+            Line::unstyled("a")?,
+            Line::unstyled("b")?,
+            Line::unstyled("c")?,
+        ]))?;
+        let rows: Vec<_> = sink.lines().iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["b", "c"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Emitted lines pinned directly above the canvas.
+//!
+//! Sticky lines (e.g. critical warnings) are drawn in a band at the top of the canvas at every
+//! render instead of scrolling away. Once unpinned or expired, they are emitted normally.
+//!
+//! Transient lines (e.g. warnings which shouldn't pollute logs) share the band, but are discarded
+//! rather than emitted once they expire, after a delay or a number of renders.
+
+use std::time::Instant;
+
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// Identifies lines pinned with [`SuperConsole::pin`](crate::SuperConsole::pin).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StickyId(u64);
+
+#[derive(Debug)]
+struct Entry {
+    id: StickyId,
+    lines: Lines,
+    expires: Option<Instant>,
+    /// The number of renders left before the lines expire, if limited.
+    renders_left: Option<usize>,
+    /// Whether the lines are discarded rather than emitted when they expire.
+    transient: bool,
+}
+
+/// The currently pinned lines, in the order they were pinned.
+#[derive(Debug, Default)]
+pub(crate) struct StickyLines {
+    next_id: u64,
+    entries: Vec<Entry>,
+}
+
+impl StickyLines {
+    pub(crate) fn pin(&mut self, lines: Lines, expires: Option<Instant>) -> StickyId {
+        let id = StickyId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry {
+            id,
+            lines,
+            expires,
+            renders_left: None,
+            transient: false,
+        });
+        id
+    }
+
+    /// Pins lines which are discarded once they expire at `expires` or after `renders` renders,
+    /// whichever comes first.
+    pub(crate) fn pin_transient(
+        &mut self,
+        lines: Lines,
+        expires: Option<Instant>,
+        renders: Option<usize>,
+    ) -> StickyId {
+        let id = self.pin(lines, expires);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.renders_left = renders;
+            entry.transient = true;
+        }
+        id
+    }
+
+    /// Discards all transient lines, e.g. before the final render.
+    pub(crate) fn discard_transient(&mut self) {
+        self.entries.retain(|e| !e.transient);
+    }
+
+    /// Removes the lines pinned as `id`, if they are still pinned.
+    /// Transient lines are discarded, so no lines are returned for them.
+    pub(crate) fn unpin(&mut self, id: StickyId) -> Option<Lines> {
+        let index = self.entries.iter().position(|e| e.id == id)?;
+        let entry = self.entries.remove(index);
+        Some(if entry.transient {
+            Lines::new()
+        } else {
+            entry.lines
+        })
+    }
+
+    /// Removes and returns all lines which have expired by `now`, discarding transient ones.
+    /// This is called once per render, which counts towards the renders left of transient lines.
+    pub(crate) fn take_expired(&mut self, now: Instant) -> Lines {
+        let mut expired = Lines::new();
+        self.entries.retain_mut(|e| {
+            let timed_out = matches!(e.expires, Some(expires) if expires <= now);
+            let rendered_out = e.renders_left == Some(0);
+            if let Some(renders_left) = &mut e.renders_left {
+                *renders_left = renders_left.saturating_sub(1);
+            }
+            if !timed_out && !rendered_out {
+                return true;
+            }
+            if !e.transient {
+                expired.0.append(&mut e.lines.0);
+            }
+            false
+        });
+        expired
+    }
+
+    /// Whether the next render changes the pinned lines: some have expired by `now`, or are
+    /// counting down renders.
+    pub(crate) fn changes_at(&self, now: Instant) -> bool {
+        self.entries.iter().any(|e| {
+            matches!(e.expires, Some(expires) if expires <= now) || e.renders_left.is_some()
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn lines(&self) -> Lines {
+        Lines(
+            self.entries
+                .iter()
+                .flat_map(|e| e.lines.iter().cloned())
+                .collect(),
+        )
+    }
+}
+
+/// Draws the sticky lines above the root component, and the footer, if any, below it.
+pub(crate) struct WithSticky<'a> {
+    pub(crate) sticky: &'a StickyLines,
+    pub(crate) root: &'a dyn Component,
+    pub(crate) footer: Option<&'a dyn Component>,
+}
+
+impl<'a> Component for WithSticky<'a> {
+    fn on_resize(&self, dimensions: Dimensions) {
+        self.root.on_resize(dimensions);
+        if let Some(footer) = self.footer {
+            footer.on_resize(dimensions);
+        }
+    }
+
+    fn on_attach(&self) {
+        self.root.on_attach();
+        if let Some(footer) = self.footer {
+            footer.on_attach();
+        }
+    }
+
+    fn on_finalize(&self) {
+        self.root.on_finalize();
+        if let Some(footer) = self.footer {
+            footer.on_finalize();
+        }
+    }
+
+    fn on_detach(&self) {
+        self.root.on_detach();
+        if let Some(footer) = self.footer {
+            footer.on_detach();
+        }
+    }
+
+    fn desired_size(&self, max: Dimensions) -> Dimensions {
+        let sticky = self.sticky.lines();
+        let footer = self
+            .footer
+            .map_or(Dimensions::default(), |footer| footer.desired_size(max));
+        let remaining = Dimensions::new(
+            max.width,
+            max.height.saturating_sub(footer.height + sticky.len()),
+        );
+        let root = self.root.desired_size(remaining);
+        Dimensions::new(
+            sticky.max_line_length().max(root.width).max(footer.width),
+            sticky.len() + root.height + footer.height,
+        )
+        .intersect(max)
+    }
+
+    /// The band is part of the frame rather than a component, so it is left out of debugging aids.
+    fn draw(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        let mut res = self.draw_unchecked(dimensions, mode)?;
+        res.shrink_lines_to_dimensions(dimensions);
+        Ok(res)
+    }
+
+    fn draw_unchecked(&self, dimensions: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        // The footer takes precedence over the root, as the sticky lines do.
+        let mut footer = match self.footer {
+            Some(footer) => footer.draw(dimensions, mode)?,
+            None => Lines::new(),
+        };
+        let dimensions = Dimensions::new(
+            dimensions.width,
+            dimensions.height.saturating_sub(footer.len()),
+        );
+
+        let mut output = self.sticky.lines();
+        output.shrink_lines_to_dimensions(dimensions);
+        let dimensions = Dimensions::new(
+            dimensions.width,
+            dimensions.height.saturating_sub(output.len()),
+        );
+        let mut root = self.root.draw(
+            self.root.desired_size(dimensions).intersect(dimensions),
+            mode,
+        )?;
+        output.0.append(&mut root.0);
+        output.0.append(&mut footer.0);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::components::echo::Echo;
+
+    fn lines(text: &str) -> anyhow::Result<Lines> {
+        Ok(Lines(vec![vec![text].try_into()?]))
+    }
+
+    #[test]
+    fn test_sticky_lines() -> anyhow::Result<()> {
+        let now = Instant::now();
+        let mut sticky = StickyLines::default();
+        let warning = sticky.pin(lines("warning")?, None);
+        sticky.pin(lines("expiring")?, Some(now + Duration::from_secs(1)));
+
+        let root = Echo(lines("root")?);
+        let output = WithSticky {
+            sticky: &sticky,
+            root: &root,
+            footer: None,
+        }
+        .draw(Dimensions::new(10, 10), DrawMode::Normal)?;
+        let rows: Vec<_> = output.iter().map(|l| l.to_unstyled()).collect();
+        assert_eq!(rows, vec!["warning", "expiring", "root"]);
+
+        assert!(sticky.take_expired(now).is_empty());
+        assert_eq!(
+            sticky.take_expired(now + Duration::from_secs(1)),
+            lines("expiring")?
+        );
+        assert_eq!(sticky.unpin(warning), Some(lines("warning")?));
+        assert_eq!(sticky.unpin(warning), None);
+        assert!(sticky.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transient_lines() -> anyhow::Result<()> {
+        let now = Instant::now();
+        let mut sticky = StickyLines::default();
+        sticky.pin_transient(lines("timed")?, Some(now + Duration::from_secs(1)), None);
+        sticky.pin_transient(lines("once")?, None, Some(1));
+        let kept = sticky.pin_transient(lines("kept")?, None, None);
+
+        assert!(sticky.take_expired(now).is_empty());
+        assert_eq!(sticky.lines().len(), 3);
+        assert!(sticky.take_expired(now).is_empty());
+        assert_eq!(
+            sticky.lines(),
+            lines("timed")?.into_iter().chain(lines("kept")?).collect()
+        );
+        assert!(sticky.take_expired(now + Duration::from_secs(1)).is_empty());
+        assert_eq!(sticky.lines(), lines("kept")?);
+
+        assert_eq!(sticky.unpin(kept), Some(Lines::new()));
+        assert!(sticky.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! User-visible text of built-in components.
+//!
+//! Built-in components never hard-code English fragments; they ask the installed [`Strings`]
+//! provider instead. Applications can install their own with [`set_strings`], overriding only the
+//! methods they need to translate.
+
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::context;
+use crate::human;
+
+static STRINGS: RwLock<Option<Arc<dyn Strings>>> = RwLock::new(None);
+
+/// Provides the text of built-in components. Every method has an English default.
+pub trait Strings: Send + Sync {
+    /// Marks something as finished successfully.
+    fn done(&self) -> String {
+        "done".to_owned()
+    }
+
+    /// Marks something as finished unsuccessfully.
+    fn failed(&self) -> String {
+        "failed".to_owned()
+    }
+
+    /// Summarizes `count` items that were left out for lack of space.
+    fn and_more(&self, count: usize) -> String {
+        format!("and {} more", count)
+    }
+
+    /// A compact duration, e.g. `1h02m`, `3m05s` or `12s`.
+    fn duration(&self, duration: Duration) -> String {
+        let secs = duration.as_secs();
+        match (secs / 3600, secs / 60 % 60, secs % 60) {
+            (0, 0, s) => format!("{}s", s),
+            (0, m, s) => format!("{}m{:02}s", m, s),
+            (h, m, _) => format!("{}h{:02}m", h, m),
+        }
+    }
+
+    /// The estimated time until something finishes.
+    fn eta(&self, remaining: Duration) -> String {
+        format!("ETA {}", self.duration(remaining))
+    }
+
+    /// Summarizes `count` lines from `source` which were dropped by rate limiting.
+    fn suppressed(&self, count: u64, source: &str) -> String {
+        format!("suppressed {} lines from [{}]", human::count(count), source)
+    }
+
+    /// Counts the items hidden inside a collapsed group.
+    fn hidden(&self, count: usize) -> String {
+        format!("{} hidden", count)
+    }
+
+    /// Summarizes `count` emitted lines which were dropped because too many were waiting.
+    fn dropped(&self, count: u64) -> String {
+        format!("{} lines dropped", human::count(count))
+    }
+
+    /// Flags that something has made no progress for `elapsed`.
+    fn stalled_for(&self, elapsed: Duration) -> String {
+        format!("stalled for {}", self.duration(elapsed))
+    }
+
+    /// A confirmation, e.g. the answer to a yes/no prompt.
+    fn yes(&self) -> String {
+        "yes".to_owned()
+    }
+
+    /// A refusal, e.g. the answer to a yes/no prompt.
+    fn no(&self) -> String {
+        "no".to_owned()
+    }
+
+    /// Marks a question which was dismissed without an answer.
+    fn cancelled(&self) -> String {
+        "cancelled".to_owned()
+    }
+
+    /// The keys answering a yes/no prompt, with the answer given by Enter, `default`, capitalized.
+    fn confirm_hint(&self, default: bool) -> String {
+        if default { "[Y/n]" } else { "[y/N]" }.to_owned()
+    }
+
+    /// Counts the times an emitted line was repeated, after the line.
+    fn repeated(&self, count: u64) -> String {
+        format!("(x{})", human::count(count))
+    }
+}
+
+/// The default, English, strings.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct English;
+
+impl Strings for English {}
+
+/// Installs the strings used by built-in components from now on, unless overridden by a console,
+/// see [`SuperConsole::set_strings`](crate::SuperConsole::set_strings).
+pub fn set_strings(strings: impl Strings + 'static) {
+    *STRINGS.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(strings));
+}
+
+/// The strings currently used by built-in components: those of the console drawing them, if any,
+/// see the [`context`](crate::context) module, or else the installed ones.
+pub fn strings() -> Arc<dyn Strings> {
+    context::current(|context| context.strings.clone()).unwrap_or_else(|| {
+        STRINGS
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_else(|| Arc::new(English))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct French;
+
+    impl Strings for French {
+        fn done(&self) -> String {
+            "terminé".to_owned()
+        }
+    }
+
+    #[test]
+    fn test_english() {
+        assert_eq!(English.and_more(3), "and 3 more");
+        assert_eq!(English.eta(Duration::from_secs(12)), "ETA 12s");
+        assert_eq!(English.duration(Duration::from_secs(185)), "3m05s");
+        assert_eq!(English.duration(Duration::from_secs(3720)), "1h02m");
+        assert_eq!(English.confirm_hint(false), "[y/N]");
+        assert_eq!(English.repeated(12), "(x12)");
+    }
+
+    #[test]
+    fn test_override() {
+        let french = French;
+        assert_eq!(french.done(), "terminé");
+        // Methods that aren't overridden fall back to English.
+        assert_eq!(french.failed(), "failed");
+    }
+}
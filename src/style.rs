@@ -11,10 +11,262 @@
 //! - Create a styled string using `style`.
 //! - Set the foreground or background color of the string using the `Color` enum.
 //! - Set the attribute (bold, italic, underlined, etc) using the `Attribute` enum.
+//!
+//! These types are owned by superconsole rather than re-exported from the terminal backend, so
+//! they do not change with it. See [`backend`](crate::backend) for conversions to and from
+//! crossterm's.
+//!
+//! Any [`Color`] can be used, including [`Color::Rgb`] and the 256 color palette
+//! [`Color::AnsiValue`]: when drawn, colors are downsampled to the nearest one the terminal
+//! supports, see [`color_support`].
 
-pub use crossterm::style::style;
-pub use crossterm::style::Attribute;
-pub use crossterm::style::Color;
-pub use crossterm::style::ContentStyle;
-pub use crossterm::style::StyledContent;
-pub use crossterm::style::Stylize;
+use std::env;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+pub use attribute::Attribute;
+pub use attribute::Attributes;
+pub use color::Color;
+pub use content_style::style;
+pub use content_style::ContentStyle;
+pub use content_style::StyledContent;
+pub use content_style::Stylize;
+
+use crate::context;
+
+mod attribute;
+mod color;
+mod content_style;
+
+/// The RGB value of a color, using the xterm defaults for the 16 named colors.
+/// Returns `None` for [`Color::Reset`], whose value depends on the terminal.
+pub(crate) fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const NAMED: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb { r, g, b } => return Some((r, g, b)),
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+        Color::AnsiValue(v) => v,
+    };
+    Some(match index {
+        0..=15 => NAMED[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    })
+}
+
+/// The colors a terminal can display, from least to most.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ColorSupport {
+    /// No colors, e.g. because `NO_COLOR` is set. Attributes such as bold are still drawn.
+    None,
+    /// The 16 named colors.
+    Ansi16,
+    /// The 256 color palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Guesses the color support of the terminal from the environment:
+    /// - `NO_COLOR` disables colors, and `CLICOLOR_FORCE` enables them even if `TERM` is `dumb`.
+    /// - `COLORTERM=truecolor` or `24bit` and a few well known terminals support RGB colors.
+    /// - A `TERM` ending in `256color` supports the 256 color palette.
+    pub fn detect() -> Self {
+        Self::detect_from(|name| env::var(name).ok())
+    }
+
+    fn detect_from(var: impl Fn(&str) -> Option<String>) -> Self {
+        let set = |name| var(name).is_some_and(|value| !value.is_empty());
+        if set("NO_COLOR") {
+            return ColorSupport::None;
+        }
+        let forced = var("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+        let term = var("TERM").unwrap_or_default();
+        if !forced && (term == "dumb" || var("CLICOLOR").as_deref() == Some("0")) {
+            return ColorSupport::None;
+        }
+
+        if matches!(var("COLORTERM").as_deref(), Some("truecolor" | "24bit"))
+            || term.contains("truecolor")
+            || term.ends_with("-direct")
+            || set("WT_SESSION")
+            || matches!(
+                var("TERM_PROGRAM").as_deref(),
+                Some("iTerm.app" | "WezTerm" | "vscode")
+            )
+        {
+            ColorSupport::TrueColor
+        } else if term.ends_with("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// The nearest color to `color` this terminal supports, or `None` if it supports no colors.
+    pub fn downsample(self, color: Color) -> Option<Color> {
+        match (self, color) {
+            (ColorSupport::None, _) => None,
+            (_, Color::Reset) | (ColorSupport::TrueColor, _) => Some(color),
+            (ColorSupport::Ansi256, Color::Rgb { r, g, b }) => {
+                Some(Color::AnsiValue(nearest((r, g, b), 16..=255)))
+            }
+            (ColorSupport::Ansi256, _) => Some(color),
+            (ColorSupport::Ansi16, Color::AnsiValue(value)) if value < 16 => Some(color),
+            (ColorSupport::Ansi16, Color::Rgb { .. } | Color::AnsiValue(_)) => {
+                let rgb = to_rgb(color)?;
+                Some(Color::AnsiValue(nearest(rgb, 0..=15)))
+            }
+            (ColorSupport::Ansi16, _) => Some(color),
+        }
+    }
+}
+
+/// The palette index in `candidates` closest to `rgb`.
+fn nearest(rgb: (u8, u8, u8), candidates: impl Iterator<Item = u8>) -> u8 {
+    let distance = |index: u8| {
+        let (r, g, b) = to_rgb(Color::AnsiValue(index)).unwrap_or_default();
+        let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+        d(r, rgb.0) + d(g, rgb.1) + d(b, rgb.2)
+    };
+    candidates.min_by_key(|index| distance(*index)).unwrap_or(0)
+}
+
+const UNKNOWN: u8 = 0;
+
+/// The color support in use, offset by one, or `UNKNOWN` if it was not detected yet.
+static COLOR_SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Overrides the colors drawn throughout the process, e.g. from a `--color` flag. See
+/// [`SuperConsole::set_color_support`](crate::SuperConsole::set_color_support) to override them for
+/// one console.
+pub fn set_color_support(support: ColorSupport) {
+    COLOR_SUPPORT.store(support as u8 + 1, Ordering::Relaxed);
+}
+
+/// The colors drawn, which are [detected](ColorSupport::detect) unless overridden by the console
+/// drawing, see the [`context`](crate::context) module, or [process-wide](set_color_support).
+pub fn color_support() -> ColorSupport {
+    if let Some(support) = context::current(|context| context.color_support) {
+        return support;
+    }
+    match COLOR_SUPPORT.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let support = ColorSupport::detect();
+            set_color_support(support);
+            support
+        }
+        1 => ColorSupport::None,
+        2 => ColorSupport::Ansi16,
+        3 => ColorSupport::Ansi256,
+        _ => ColorSupport::TrueColor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> ColorSupport {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        ColorSupport::detect_from(|name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(&[]), ColorSupport::Ansi16);
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), ColorSupport::Ansi256);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]),
+            ColorSupport::None
+        );
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorSupport::None);
+        assert_eq!(
+            detect(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "1")]),
+            ColorSupport::Ansi16
+        );
+    }
+
+    #[test]
+    fn test_downsample() {
+        let orange = Color::Rgb {
+            r: 255,
+            g: 135,
+            b: 0,
+        };
+        assert_eq!(ColorSupport::TrueColor.downsample(orange), Some(orange));
+        assert_eq!(
+            ColorSupport::Ansi256.downsample(orange),
+            Some(Color::AnsiValue(208))
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(Color::Rgb {
+                r: 250,
+                g: 10,
+                b: 5
+            }),
+            Some(Color::AnsiValue(9))
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(Color::AnsiValue(196)),
+            Some(Color::AnsiValue(9))
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.downsample(Color::Red),
+            Some(Color::Red)
+        );
+        assert_eq!(ColorSupport::None.downsample(Color::Red), None);
+    }
+}
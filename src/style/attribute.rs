@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::ops::BitAnd;
+use std::ops::BitOr;
+use std::ops::BitOrAssign;
+
+macro_rules! attributes {
+    ($($(#[$doc:meta])* $name:ident = $sgr:literal,)*) => {
+        /// A text attribute, such as bold or underlined, drawn with the given SGR parameter.
+        #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub enum Attribute {
+            $($(#[$doc])* $name,)*
+        }
+
+        impl Attribute {
+            const ALL: &'static [Attribute] = &[$(Attribute::$name,)*];
+
+            /// The SGR parameter which turns the attribute on.
+            pub fn sgr(self) -> u8 {
+                match self {
+                    $(Attribute::$name => $sgr,)*
+                }
+            }
+        }
+    };
+}
+
+attributes! {
+    /// Resets all attributes and colors.
+    Reset = 0,
+    Bold = 1,
+    Dim = 2,
+    Italic = 3,
+    Underlined = 4,
+    SlowBlink = 5,
+    RapidBlink = 6,
+    /// Swaps the foreground and background colors.
+    Reverse = 7,
+    Hidden = 8,
+    CrossedOut = 9,
+    Fraktur = 20,
+    NoBold = 21,
+    NormalIntensity = 22,
+    NoItalic = 23,
+    NoUnderline = 24,
+    NoBlink = 25,
+    NoReverse = 27,
+    NoHidden = 28,
+    NotCrossedOut = 29,
+    Framed = 51,
+    Encircled = 52,
+    OverLined = 53,
+    NotFramedOrEncircled = 54,
+    NotOverLined = 55,
+}
+
+impl Attribute {
+    /// Iterates over all attributes, in the order they are drawn.
+    pub fn iterator() -> impl Iterator<Item = Attribute> {
+        Self::ALL.iter().copied()
+    }
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// A set of [`Attribute`]s.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Attributes(u32);
+
+impl Attributes {
+    /// The empty set.
+    pub const fn none() -> Self {
+        Attributes(0)
+    }
+
+    pub fn set(&mut self, attribute: Attribute) {
+        self.0 |= attribute.bit();
+    }
+
+    pub fn unset(&mut self, attribute: Attribute) {
+        self.0 &= !attribute.bit();
+    }
+
+    pub fn toggle(&mut self, attribute: Attribute) {
+        self.0 ^= attribute.bit();
+    }
+
+    pub fn has(self, attribute: Attribute) -> bool {
+        self.0 & attribute.bit() != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates over the attributes in the set, in the order they are drawn.
+    pub fn iter(self) -> impl Iterator<Item = Attribute> {
+        Attribute::iterator().filter(move |attribute| self.has(*attribute))
+    }
+}
+
+impl From<Attribute> for Attributes {
+    fn from(attribute: Attribute) -> Self {
+        Attributes(attribute.bit())
+    }
+}
+
+impl From<&[Attribute]> for Attributes {
+    fn from(attributes: &[Attribute]) -> Self {
+        attributes.iter().copied().collect()
+    }
+}
+
+impl FromIterator<Attribute> for Attributes {
+    fn from_iter<I: IntoIterator<Item = Attribute>>(iter: I) -> Self {
+        let mut attributes = Attributes::default();
+        for attribute in iter {
+            attributes.set(attribute);
+        }
+        attributes
+    }
+}
+
+impl BitOr for Attributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Attributes(self.0 | rhs.0)
+    }
+}
+
+impl BitOr<Attribute> for Attributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Attribute) -> Self {
+        Attributes(self.0 | rhs.bit())
+    }
+}
+
+impl BitOrAssign<Attribute> for Attributes {
+    fn bitor_assign(&mut self, rhs: Attribute) {
+        self.set(rhs);
+    }
+}
+
+impl BitAnd for Attributes {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Attributes(self.0 & rhs.0)
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fmt;
+
+/// A foreground or background color.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Color {
+    /// The terminal's default color.
+    Reset,
+    Black,
+    DarkGrey,
+    Red,
+    DarkRed,
+    Green,
+    DarkGreen,
+    Yellow,
+    DarkYellow,
+    Blue,
+    DarkBlue,
+    Magenta,
+    DarkMagenta,
+    Cyan,
+    DarkCyan,
+    White,
+    Grey,
+    /// A 24-bit color.
+    Rgb {
+        r: u8,
+        g: u8,
+        b: u8,
+    },
+    /// A color of the 256 color palette, whose first 16 entries are the named colors.
+    AnsiValue(u8),
+}
+
+impl Color {
+    /// Writes the SGR parameters selecting this color, without the `38;` or `48;` prefix.
+    /// [`Reset`](Color::Reset) has none, since it is selected by `39` or `49` on its own.
+    pub(crate) fn write_sgr(self, f: &mut impl fmt::Write) -> fmt::Result {
+        let index = match self {
+            Color::Reset => return Ok(()),
+            Color::Rgb { r, g, b } => return write!(f, "2;{};{};{}", r, g, b),
+            Color::AnsiValue(value) => value,
+            Color::Black => 0,
+            Color::DarkRed => 1,
+            Color::DarkGreen => 2,
+            Color::DarkYellow => 3,
+            Color::DarkBlue => 4,
+            Color::DarkMagenta => 5,
+            Color::DarkCyan => 6,
+            Color::Grey => 7,
+            Color::DarkGrey => 8,
+            Color::Red => 9,
+            Color::Green => 10,
+            Color::Yellow => 11,
+            Color::Blue => 12,
+            Color::Magenta => 13,
+            Color::Cyan => 14,
+            Color::White => 15,
+        };
+        write!(f, "5;{}", index)
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Color::Rgb { r, g, b }
+    }
+}
+
+/// Parses the snake case name of a named color, e.g. `dark_grey`.
+impl TryFrom<&str> for Color {
+    type Error = ();
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        Ok(match name.to_lowercase().as_str() {
+            "black" => Color::Black,
+            "dark_grey" => Color::DarkGrey,
+            "red" => Color::Red,
+            "dark_red" => Color::DarkRed,
+            "green" => Color::Green,
+            "dark_green" => Color::DarkGreen,
+            "yellow" => Color::Yellow,
+            "dark_yellow" => Color::DarkYellow,
+            "blue" => Color::Blue,
+            "dark_blue" => Color::DarkBlue,
+            "magenta" => Color::Magenta,
+            "dark_magenta" => Color::DarkMagenta,
+            "cyan" => Color::Cyan,
+            "dark_cyan" => Color::DarkCyan,
+            "white" => Color::White,
+            "grey" => Color::Grey,
+            _ => return Err(()),
+        })
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fmt::Display;
+
+use crate::style::Attribute;
+use crate::style::Attributes;
+use crate::style::Color;
+
+/// The colors and attributes of some text. Colors left as `None` are the terminal's, or those of
+/// the enclosing style, see [`Span::apply_style_base`](crate::Span::apply_style_base).
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ContentStyle {
+    pub foreground_color: Option<Color>,
+    pub background_color: Option<Color>,
+    pub attributes: Attributes,
+}
+
+impl ContentStyle {
+    pub const fn new() -> Self {
+        ContentStyle {
+            foreground_color: None,
+            background_color: None,
+            attributes: Attributes::none(),
+        }
+    }
+
+    /// Applies the style to `content`.
+    pub fn apply<D: Display>(self, content: D) -> StyledContent<D> {
+        StyledContent::new(self, content)
+    }
+}
+
+impl AsRef<ContentStyle> for ContentStyle {
+    fn as_ref(&self) -> &ContentStyle {
+        self
+    }
+}
+
+impl AsMut<ContentStyle> for ContentStyle {
+    fn as_mut(&mut self) -> &mut ContentStyle {
+        self
+    }
+}
+
+/// Content with a [`ContentStyle`], e.g. a `String` to turn into a [`Span`](crate::Span).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StyledContent<D: Display> {
+    style: ContentStyle,
+    content: D,
+}
+
+impl<D: Display> StyledContent<D> {
+    pub fn new(style: ContentStyle, content: D) -> Self {
+        Self { style, content }
+    }
+
+    pub fn content(&self) -> &D {
+        &self.content
+    }
+
+    pub fn style(&self) -> &ContentStyle {
+        &self.style
+    }
+
+    pub fn style_mut(&mut self) -> &mut ContentStyle {
+        &mut self.style
+    }
+}
+
+impl<D: Display> AsRef<ContentStyle> for StyledContent<D> {
+    fn as_ref(&self) -> &ContentStyle {
+        &self.style
+    }
+}
+
+impl<D: Display> AsMut<ContentStyle> for StyledContent<D> {
+    fn as_mut(&mut self) -> &mut ContentStyle {
+        &mut self.style
+    }
+}
+
+/// Styles `content`, which can then be given colors and attributes with [`Stylize`].
+pub fn style<D: Display>(content: D) -> StyledContent<D> {
+    ContentStyle::new().apply(content)
+}
+
+macro_rules! stylize_colors {
+    ($($color:ident => $fg:ident, $bg:ident;)*) => {
+        $(
+            fn $fg(self) -> Self::Styled {
+                self.with(Color::$color)
+            }
+
+            fn $bg(self) -> Self::Styled {
+                self.on(Color::$color)
+            }
+        )*
+    };
+}
+
+macro_rules! stylize_attributes {
+    ($($attribute:ident => $method:ident;)*) => {
+        $(
+            fn $method(self) -> Self::Styled {
+                self.attribute(Attribute::$attribute)
+            }
+        )*
+    };
+}
+
+/// Sets the colors and attributes of styles, styled content, and strings.
+///
+/// ```
+/// use superconsole::style::Stylize;
+///
+/// let warning = "warning".bold().yellow();
+/// assert_eq!(warning.content(), &"warning");
+/// ```
+pub trait Stylize: Sized {
+    type Styled: AsRef<ContentStyle> + AsMut<ContentStyle>;
+
+    fn stylize(self) -> Self::Styled;
+
+    fn with(self, color: Color) -> Self::Styled {
+        let mut styled = self.stylize();
+        styled.as_mut().foreground_color = Some(color);
+        styled
+    }
+
+    fn on(self, color: Color) -> Self::Styled {
+        let mut styled = self.stylize();
+        styled.as_mut().background_color = Some(color);
+        styled
+    }
+
+    fn attribute(self, attribute: Attribute) -> Self::Styled {
+        let mut styled = self.stylize();
+        styled.as_mut().attributes.set(attribute);
+        styled
+    }
+
+    /// Clears all colors and attributes.
+    fn reset(self) -> Self::Styled {
+        let mut styled = self.stylize();
+        *styled.as_mut() = ContentStyle::default();
+        styled
+    }
+
+    stylize_colors! {
+        Black => black, on_black;
+        DarkGrey => dark_grey, on_dark_grey;
+        Red => red, on_red;
+        DarkRed => dark_red, on_dark_red;
+        Green => green, on_green;
+        DarkGreen => dark_green, on_dark_green;
+        Yellow => yellow, on_yellow;
+        DarkYellow => dark_yellow, on_dark_yellow;
+        Blue => blue, on_blue;
+        DarkBlue => dark_blue, on_dark_blue;
+        Magenta => magenta, on_magenta;
+        DarkMagenta => dark_magenta, on_dark_magenta;
+        Cyan => cyan, on_cyan;
+        DarkCyan => dark_cyan, on_dark_cyan;
+        White => white, on_white;
+        Grey => grey, on_grey;
+    }
+
+    stylize_attributes! {
+        Bold => bold;
+        Dim => dim;
+        Italic => italic;
+        Underlined => underlined;
+        SlowBlink => slow_blink;
+        RapidBlink => rapid_blink;
+        Reverse => reverse;
+        Reverse => negative;
+        Hidden => hidden;
+        CrossedOut => crossed_out;
+    }
+}
+
+impl Stylize for ContentStyle {
+    type Styled = Self;
+
+    fn stylize(self) -> Self {
+        self
+    }
+}
+
+impl<D: Display> Stylize for StyledContent<D> {
+    type Styled = Self;
+
+    fn stylize(self) -> Self {
+        self
+    }
+}
+
+macro_rules! stylize_content {
+    ($($content:ty),*) => {
+        $(
+            impl Stylize for $content {
+                type Styled = StyledContent<Self>;
+
+                fn stylize(self) -> Self::Styled {
+                    style(self)
+                }
+            }
+        )*
+    };
+}
+
+stylize_content!(char, String, &str);
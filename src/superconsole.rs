@@ -9,26 +9,104 @@
 
 use std::cmp;
 use std::env;
-use std::io;
+use std::io::Write as _;
+use std::mem;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
-use crossterm::terminal::Clear;
-use crossterm::terminal::ClearType;
-use crossterm::tty::IsTty;
-use crossterm::QueueableCommand;
+use crossbeam_channel::unbounded;
+use crossbeam_channel::Receiver;
+use crossbeam_channel::Sender;
 
+use crate::ansi::Clear;
+use crate::ansi::ClearType;
+use crate::ansi::EnterAlternateScreen;
+use crate::ansi::Hide;
+use crate::ansi::LeaveAlternateScreen;
+use crate::ansi::MoveTo;
+use crate::ansi::MoveToNextLine;
+use crate::ansi::MoveUp;
+use crate::ansi::QueueableCommand;
+use crate::ansi::RestorePosition;
+use crate::ansi::SavePosition;
+use crate::ansi::Show;
 use crate::ansi_support::enable_ansi_support;
+use crate::backend;
+use crate::budget::RenderBudget;
+use crate::builder::Builder;
+use crate::components::error_boundary;
 use crate::components::Canvas;
+use crate::components::CanvasLayout;
 use crate::components::Component;
+use crate::components::ComponentError;
+use crate::components::DebugStats;
 use crate::components::DrawMode;
+use crate::components::ErrorPolicy;
+use crate::content::Alignment;
+use crate::content::DrawSurface;
 use crate::content::Line;
+use crate::context::DrawContext;
+use crate::debug;
+use crate::debug::ComponentTree;
+use crate::debug::RenderStats;
+use crate::dirty::DirtyFlag;
+use crate::emit::AdaptiveEmitPolicy;
+use crate::emit::ColumnQueues;
+use crate::emit::Deduplicator;
+use crate::emit::EmitCapacity;
+use crate::emit::EmitColumns;
+use crate::emit::EmitContext;
+use crate::emit::EmitDedup;
+use crate::emit::EmitPolicy;
+use crate::emit::FilteredLines;
+use crate::emit::Level;
+use crate::emit::LevelFilter;
+use crate::fallback::FallbackRenderer;
+use crate::fallback::DEFAULT_FALLBACK_INTERVAL;
+use crate::finalize::FinalOutput;
+use crate::finalize::FinalizeOptions;
+use crate::finalize::Replacement;
+use crate::handle::HandleMessage;
+use crate::handle::SuperConsoleHandle;
+use crate::human::NumberFormat;
+use crate::input;
+use crate::input::Event;
+use crate::input::InputHandler;
+use crate::observer::FrameStats;
+use crate::observer::RenderObserver;
 use crate::output::BlockingSuperConsoleOutput;
+use crate::output::CellFrame;
+use crate::output::FrameMetadata;
+use crate::output::Stream;
 use crate::output::SuperConsoleOutput;
+use crate::rate_limit::RateLimit;
+use crate::rate_limit::RateLimiter;
+use crate::sink::EmitSink;
+use crate::sticky::StickyId;
+use crate::sticky::StickyLines;
+use crate::sticky::WithSticky;
+use crate::strings::Strings;
+use crate::style::ColorSupport;
+use crate::theme::detect_background;
+use crate::theme::Theme;
+use crate::watchdog::Heartbeat;
 use crate::Dimensions;
 use crate::Direction;
 use crate::Lines;
 
-const MINIMUM_EMIT: usize = 5;
 const MAX_GRAPHEME_BUFFER: usize = 1000000;
+/// The size components are drawn with when rendering plainly, as the output has none.
+const FALLBACK_SIZE: Dimensions = Dimensions {
+    width: 120,
+    height: 50,
+};
+/// Starts a frame which the terminal displays at once, see
+/// <https://gist.github.com/christianparpart/d8a62cc1ab659194337d73e399004036>.
+const BEGIN_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026h";
+pub(crate) const END_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026l";
 
 /// Handles rendering the console using the user-defined [Component](Component)s and emitted messages.
 /// A Canvas area at the bottom of the terminal is re-rendered in place at each tick for the components,
@@ -41,6 +119,108 @@ pub struct SuperConsole {
     // from the terminal. This generally is only used for testing
     // situations.
     fallback_size: Option<Dimensions>,
+    /// Number of frames drawn so far.
+    frame_index: u64,
+    /// Beaten whenever a frame is drawn.
+    heartbeat: Heartbeat,
+    /// Reset at the start of every frame, if set.
+    budget: Option<RenderBudget>,
+    /// Decides how many emitted lines are drawn per frame.
+    emit_policy: Box<dyn EmitPolicy>,
+    /// Whether the last emitted line that was drawn is directly above the canvas and fits on a
+    /// single row, so it can be overwritten.
+    last_emitted_replaceable: bool,
+    /// Number of rows of previously drawn emitted lines to overwrite in the next frame.
+    rows_to_replace: u16,
+    /// Emitted lines pinned directly above the canvas.
+    sticky: StickyLines,
+    /// The canvas drawn in the last frame.
+    last_frame: Lines,
+    /// The size of the terminal, less the final blank line, in the last frame.
+    last_size: Option<Dimensions>,
+    /// The size of the canvas, below any header, in the last frame. The canvas is fully redrawn
+    /// when it changes, as the terminal may have reflowed it.
+    last_canvas_size: Option<Dimensions>,
+    /// The canvas last passed to the output as cells, if it wants them.
+    last_cells: Option<DrawSurface>,
+    /// Told about every frame.
+    observers: Vec<Box<dyn RenderObserver>>,
+    /// The components drawn in the last frame.
+    last_tree: ComponentTree,
+    /// Marked when anything drawn changes, see [`render_if_dirty`](SuperConsole::render_if_dirty).
+    dirty: DirtyFlag,
+    /// Lines emitted to side-by-side panes, if laid out in columns.
+    columns: Option<ColumnQueues>,
+    /// How the canvas is sized and placed within the terminal.
+    layout: CanvasLayout,
+    /// Whether the cursor stays hidden between frames, see
+    /// [`set_hide_cursor`](SuperConsole::set_hide_cursor).
+    hide_cursor: bool,
+    /// Which components were told they are drawn, see [`Component::on_attach`].
+    attached: Attached,
+    /// Shows the cursor again if the console is dropped without being finalized.
+    cursor_guard: Option<ShowCursorOnDrop>,
+    /// Shared with the thread restoring the terminal on signals, see
+    /// [`restore_on_signals`](SuperConsole::restore_on_signals).
+    #[cfg(all(feature = "signals", unix))]
+    terminal_state: Arc<crate::signals::TerminalState>,
+    /// Keeps the signals registered while the console holds the terminal.
+    #[cfg(all(feature = "signals", unix))]
+    signals: Option<crate::signals::SignalsGuard>,
+    /// The generation of `dirty` as of the last render, if any.
+    rendered_generation: Option<u64>,
+    /// Profiling data about recent frames, shared with [`DebugStats`] overlays.
+    stats: Arc<Mutex<RenderStats>>,
+    /// Receive a copy of emitted lines as they are drawn.
+    sinks: Vec<Box<dyn EmitSink>>,
+    /// Called with the errors of sinks, which don't fail the render.
+    on_sink_error: Option<Box<dyn FnMut(anyhow::Error) + Send>>,
+    /// Lines sent by [`SuperConsoleHandle`]s, drained at the start of every render.
+    handle_sender: Sender<HandleMessage>,
+    handle_receiver: Receiver<HandleMessage>,
+    /// Applied to lines passed to `emit_from`.
+    rate_limiter: Option<RateLimiter>,
+    /// Whether the terminal was put in raw mode to read input.
+    input_enabled: bool,
+    /// Set while the terminal is released with [`pause`](SuperConsole::pause).
+    paused: bool,
+    /// Offered input events before they are returned from `poll_events`.
+    input_handlers: Vec<Box<dyn InputHandler>>,
+    /// While drawing on the alternate screen, the emitted lines to draw once the main screen is
+    /// restored.
+    alternate_screen_log: Option<AlternateScreenLog>,
+    /// Restores the main screen if the console is dropped on the alternate screen.
+    alternate_screen_guard: Option<LeaveAlternateScreenOnDrop>,
+    /// Bounds the emitted lines waiting to be drawn, if set.
+    emit_capacity: Option<EmitCapacity>,
+    /// Collapses repeated emitted lines, if set.
+    dedup: Option<Deduplicator>,
+    /// The minimum level of the lines passed to `emit_leveled` which are drawn.
+    level_filter: LevelFilter,
+    /// Keeps the lines left out by `level_filter`, if set.
+    filtered: Option<FilteredLines>,
+    /// The number of lines summarized by the first waiting line, if the capacity summarizes.
+    emit_dropped: u64,
+    /// Once more graphemes than this are waiting to be emitted, they are all drawn at once.
+    pub(crate) max_emit_buffer: usize,
+    /// Pinned to the top rows of the terminal, if set.
+    header: Option<Box<dyn Component + Send>>,
+    /// The number of rows above the scroll region, which the header was last drawn on.
+    header_height: usize,
+    /// Drawn below the root, if set.
+    footer: Option<Box<dyn Component + Send>>,
+    error_policy: ErrorPolicy,
+    /// Called with each error caught under [`ErrorPolicy::Isolate`].
+    on_component_error: Option<Box<dyn FnMut(ComponentError) + Send>>,
+    /// The emitted lines drawn in the current frame, kept if the output wants them.
+    frame_emitted: Lines,
+    /// Emitted lines to be written to other streams than the canvas's.
+    routed: Vec<(Stream, Lines)>,
+    /// Set when rendering plainly, because the output is not a terminal.
+    fallback: Option<FallbackRenderer>,
+    /// Entered while drawing and queueing emitted lines, see the [`context`](crate::context)
+    /// module.
+    context: DrawContext,
     pub(crate) output: Box<dyn SuperConsoleOutput>,
 }
 
@@ -50,7 +230,7 @@ impl SuperConsole {
         Self::compatible().then(|| {
             Self::new_internal(
                 None,
-                Box::new(BlockingSuperConsoleOutput::new(Box::new(io::stderr()))),
+                Box::new(BlockingSuperConsoleOutput::for_stream(Stream::Stderr)),
             )
         })
     }
@@ -60,18 +240,114 @@ impl SuperConsole {
     pub fn forced_new(fallback_size: Dimensions) -> Self {
         Self::new_internal(
             Some(fallback_size),
-            Box::new(BlockingSuperConsoleOutput::new(Box::new(io::stderr()))),
+            Box::new(BlockingSuperConsoleOutput::for_stream(Stream::Stderr)),
         )
     }
 
+    /// A builder for a console with more options, such as a custom output or default size.
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// Build a new SuperConsole, which renders plainly if stderr is not a compatible terminal (e.g.
+    /// in CI), so that the same code path serves both. Rendering plainly, emitted lines are written
+    /// as plain text, and the canvas is printed below them without cursor movement, only when it
+    /// changed and at most every 10 seconds (see
+    /// [`set_fallback_interval`](Self::set_fallback_interval)), as well as when finalizing.
+    pub fn new_or_fallback() -> Self {
+        match Self::new() {
+            Some(console) => console,
+            None => {
+                let mut console = Self::new_internal(
+                    Some(FALLBACK_SIZE),
+                    Box::new(BlockingSuperConsoleOutput::for_stream(Stream::Stderr)),
+                );
+                console.fallback = Some(FallbackRenderer::new(DEFAULT_FALLBACK_INTERVAL));
+                console
+            }
+        }
+    }
+
+    /// Whether this console renders plainly, see [`new_or_fallback`](Self::new_or_fallback).
+    pub fn is_fallback(&self) -> bool {
+        self.fallback.is_some()
+    }
+
+    /// Sets how often the canvas is printed when rendering plainly.
+    pub fn set_fallback_interval(&mut self, interval: Duration) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.set_interval(interval);
+        }
+    }
+
+    /// Build a new SuperConsole writing to a custom output, e.g. a
+    /// [`NonBlockingSuperConsoleOutput`](crate::output::NonBlockingSuperConsoleOutput), regardless
+    /// of whether the tty is compatible.
+    pub fn with_output(output: Box<dyn SuperConsoleOutput>) -> Self {
+        Self::new_internal(None, output)
+    }
+
     pub(crate) fn new_internal(
         fallback_size: Option<Dimensions>,
         output: Box<dyn SuperConsoleOutput>,
     ) -> Self {
+        // Queried now rather than on the first draw, when input may already be enabled.
+        detect_background();
+        let (handle_sender, handle_receiver) = unbounded();
         Self {
             root: Canvas::new(),
             to_emit: Lines::new(),
             fallback_size,
+            frame_index: 0,
+            heartbeat: Heartbeat::new(),
+            budget: None,
+            emit_policy: Box::new(AdaptiveEmitPolicy),
+            last_emitted_replaceable: false,
+            rows_to_replace: 0,
+            sticky: StickyLines::default(),
+            last_frame: Lines::new(),
+            last_size: None,
+            last_canvas_size: None,
+            last_cells: None,
+            observers: Vec::new(),
+            last_tree: ComponentTree::default(),
+            stats: Arc::default(),
+            dirty: DirtyFlag::new(),
+            columns: None,
+            layout: CanvasLayout::default(),
+            hide_cursor: false,
+            attached: Attached::default(),
+            cursor_guard: None,
+            #[cfg(all(feature = "signals", unix))]
+            terminal_state: Arc::default(),
+            #[cfg(all(feature = "signals", unix))]
+            signals: None,
+            rendered_generation: None,
+            sinks: Vec::new(),
+            on_sink_error: None,
+            handle_sender,
+            handle_receiver,
+            rate_limiter: None,
+            input_enabled: false,
+            paused: false,
+            input_handlers: Vec::new(),
+            alternate_screen_log: None,
+            alternate_screen_guard: None,
+            emit_capacity: None,
+            dedup: None,
+            level_filter: LevelFilter::default(),
+            filtered: None,
+            emit_dropped: 0,
+            max_emit_buffer: MAX_GRAPHEME_BUFFER,
+            error_policy: ErrorPolicy::default(),
+            on_component_error: None,
+            frame_emitted: Lines::new(),
+            header: None,
+            header_height: 0,
+            footer: None,
+            routed: Vec::new(),
+            fallback: None,
+            context: DrawContext::default(),
             output,
         }
     }
@@ -79,7 +355,12 @@ impl SuperConsole {
     pub fn compatible() -> bool {
         // Superconsole only renders on the stderr, so we can display the superconsole
         // even if someone does `command > out.txt`.
-        io::stderr().is_tty() && !Self::is_term_dumb() && enable_ansi_support().is_ok()
+        Self::compatible_stream(Stream::Stderr)
+    }
+
+    /// Whether a console drawing on `stream` can render, see [`compatible`](Self::compatible).
+    pub(crate) fn compatible_stream(stream: Stream) -> bool {
+        stream.is_tty() && !Self::is_term_dumb() && enable_ansi_support().is_ok()
     }
 
     fn is_term_dumb() -> bool {
@@ -90,13 +371,22 @@ impl SuperConsole {
 
     /// Render at a given tick.  Draws all components and drains the emitted events buffer.
     /// This will produce any pending emitting events above the Canvas and will re-render the drawing area.
+    ///
+    /// The root may be a long-lived, shared component (e.g. `Arc<Mutex<C>>`) which the caller
+    /// mutates directly between renders.
     pub fn render(&mut self, root: &dyn Component) -> anyhow::Result<()> {
+        if self.paused {
+            return Ok(());
+        }
         // `render_general` refuses to drain more than a single frame, so repeat until done.
         // or until the rendered frame is too large to print anything.
         let mut anything_emitted = true;
         let mut has_rendered = false;
         while !has_rendered || (anything_emitted && !self.to_emit.is_empty()) {
             if !self.output.should_render() {
+                if !has_rendered {
+                    self.observers.iter_mut().for_each(|o| o.on_frame_dropped());
+                }
                 break;
             }
 
@@ -109,6 +399,48 @@ impl SuperConsole {
         Ok(())
     }
 
+    /// Renders like [`render`](Self::render), but only if anything changed since the last render:
+    /// the [`dirty_flag`](Self::dirty_flag) was marked, lines were emitted or pinned, or the
+    /// terminal was resized. Returns whether a frame was rendered.
+    pub fn render_if_dirty(&mut self, root: &dyn Component) -> anyhow::Result<bool> {
+        if self.paused {
+            return Ok(false);
+        }
+        let size = self.size()?.saturating_sub(1, Direction::Vertical);
+        let dirty = self.rendered_generation != Some(self.dirty.generation())
+            || self.has_pending_emit()
+            || self.sticky.changes_at(Instant::now())
+            || self.last_size != Some(size);
+        if dirty {
+            self.render(root)?;
+        }
+        Ok(dirty)
+    }
+
+    /// A handle to emit lines from other threads and tasks, see the [`handle`](crate::handle)
+    /// module.
+    pub fn handle(&self) -> SuperConsoleHandle {
+        SuperConsoleHandle::new(self.handle_sender.clone(), self.dirty.clone())
+    }
+
+    /// Queues the lines sent by handles since the last render.
+    fn drain_handles(&mut self) {
+        while let Ok(message) = self.handle_receiver.try_recv() {
+            match message {
+                HandleMessage::Emit(lines) => self.emit(lines),
+                HandleMessage::EmitTo(stream, lines) => self.emit_to(stream, lines),
+                HandleMessage::EmitColumn(column, lines) => self.emit_column(column, lines),
+                HandleMessage::EmitLeveled(level, lines) => self.emit_leveled(level, lines),
+            }
+        }
+    }
+
+    /// The flag to mark whenever the state drawn by the components changes, see
+    /// [`render_if_dirty`](Self::render_if_dirty) and the [`dirty`](crate::dirty) module.
+    pub fn dirty_flag(&self) -> DirtyFlag {
+        self.dirty.clone()
+    }
+
     /// Perform a final render with [`DrawMode::Final`].
     /// Each component will have a chance to finalize themselves before the terminal is disposed of.
     pub fn finalize(self, root: &dyn Component) -> anyhow::Result<()> {
@@ -117,13 +449,91 @@ impl SuperConsole {
 
     /// Perform a final render, using a specified [`DrawMode`].
     /// Each component will have a chance to finalize themselves before the terminal is disposed of.
-    pub fn finalize_with_mode(
+    pub fn finalize_with_mode(self, root: &dyn Component, mode: DrawMode) -> anyhow::Result<()> {
+        self.finalize_with(root, FinalizeOptions::default().mode(mode))?;
+        Ok(())
+    }
+
+    /// Perform a final render as described by `options`, which choose whether the components'
+    /// final output is left in the scrollback, left out, or replaced by a summary. Pinned lines
+    /// and the footer are drawn either way.
+    /// Returns the final frame, as [`last_frame`](Self::last_frame) would, e.g. to log it.
+    pub fn finalize_with(
         mut self,
         root: &dyn Component,
+        options: FinalizeOptions,
+    ) -> anyhow::Result<Lines> {
+        let FinalizeOptions { output, mode } = options;
+        let _context = self.context.enter();
+        self.attach(root);
+        self.for_each_component(root, |c| c.on_finalize());
+        self.resume()?;
+        if let Some(limiter) = &mut self.rate_limiter {
+            let summaries = limiter.take_summaries();
+            self.emit(summaries);
+        }
+        self.leave_alternate_screen()?;
+        self.sticky.discard_transient();
+        match &output {
+            FinalOutput::Emit => self.render_with_mode(root, mode)?,
+            FinalOutput::Suppress => self.render_with_mode(&Replacement(&Lines::new()), mode)?,
+            FinalOutput::Replace(summary) => self.render_with_mode(&Replacement(summary), mode)?,
+        }
+        // The final frame showed the cursor.
+        if let Some(guard) = self.cursor_guard.take() {
+            guard.disarm();
+        }
+        if self.header_height > 0 {
+            let mut buffer = Vec::new();
+            buffer.queue(SavePosition)?;
+            write!(buffer, "\x1b[r")?;
+            buffer.queue(RestorePosition)?;
+            self.output.output(buffer)?;
+        }
+        self.flush_sinks();
+        if self.input_enabled {
+            backend::disable_raw_mode()?;
+        }
+        #[cfg(all(feature = "signals", unix))]
+        {
+            self.terminal_state.release();
+            self.signals = None;
+        }
+        let frame = mem::take(&mut self.last_frame);
+        self.output.finalize()?;
+        Ok(frame)
+    }
 
-        mode: DrawMode,
-    ) -> anyhow::Result<()> {
-        self.render_with_mode(root, mode)?;
+    /// Lets go of the terminal without a final draw, e.g. when the application is cancelled:
+    /// the components are told with [`Component::on_detach`], then the canvas is cleared and the
+    /// terminal restored. Lines still waiting to be emitted are discarded.
+    ///
+    /// The console doesn't own the components, so it can't tell them when it is merely dropped;
+    /// cleanup which must happen before the terminal is restored belongs here or in
+    /// [`Component::on_finalize`] rather than in `Drop`.
+    pub fn detach(mut self, root: &dyn Component) -> anyhow::Result<()> {
+        let attached = self.attached;
+        if attached.root {
+            root.on_detach();
+        }
+        for (component, attached) in [
+            (&self.header, attached.header),
+            (&self.footer, attached.footer),
+        ] {
+            if let Some(component) = component.as_deref().filter(|_| attached) {
+                component.on_detach();
+            }
+        }
+        self.pause()?;
+        if let Some(guard) = self.cursor_guard.take() {
+            guard.disarm();
+        }
+        self.flush_sinks();
+        #[cfg(all(feature = "signals", unix))]
+        {
+            self.terminal_state.release();
+            self.signals = None;
+        }
         self.output.finalize()
     }
 
@@ -140,218 +550,2201 @@ impl SuperConsole {
 
     /// Queues the passed lines to be drawn on the next render.
     /// The lines *will not* appear until the next render is called.
-    pub fn emit(&mut self, mut lines: Lines) {
-        self.to_emit.0.append(&mut lines.0);
+    ///
+    /// Anything convertible to [`Lines`] is accepted, e.g. a string split into lines on `\n`.
+    /// See also [`emit!`](crate::emit!).
+    pub fn emit(&mut self, lines: impl Into<Lines>) {
+        let _context = self.context.enter();
+        let mut lines = lines.into();
+        match &mut self.dedup {
+            Some(dedup) => dedup.append(&mut self.to_emit, lines),
+            None => self.to_emit.0.append(&mut lines.0),
+        }
+        if let Some(capacity) = &self.emit_capacity {
+            capacity.enforce(&mut self.to_emit, &mut self.emit_dropped);
+        }
     }
 
-    fn size(&self) -> anyhow::Result<Dimensions> {
-        // We want to get the size, but if that fails or is empty use the fallback_size if available.
-        match (self.output.terminal_size(), self.fallback_size) {
-            (Ok(size), Some(fallback)) if size.width == 0 || size.height == 0 => Ok(fallback),
-            (Ok(size), _) => Ok(size),
-            (Err(_), Some(fallback)) => Ok(fallback),
-            (Err(e), None) => Err(e),
+    /// Queues the passed lines to be drawn on the next render if `level` is at least the
+    /// [`emit_level`](Self::emit_level). Otherwise, they are dropped, or kept to be taken later
+    /// with [`take_filtered`](Self::take_filtered) if
+    /// [`set_retain_filtered`](Self::set_retain_filtered) was called.
+    pub fn emit_leveled(&mut self, level: Level, lines: impl Into<Lines>) {
+        if self.level_filter.allows(level) {
+            self.emit(lines);
+        } else if let Some(filtered) = &mut self.filtered {
+            filtered.push(level, lines.into());
         }
     }
 
-    /// Clears the canvas portion of the superconsole.
-    pub fn clear(&mut self) -> anyhow::Result<()> {
-        let mut buffer = vec![];
-        self.root.clear(&mut buffer)?;
-        self.output.output(buffer)
+    /// The minimum level of the lines passed to [`emit_leveled`](Self::emit_leveled) which are
+    /// drawn, [`Level::Trace`] by default.
+    pub fn emit_level(&self) -> Level {
+        self.level_filter.get()
     }
 
-    /// Helper method to share render + finalize behavior by specifying mode.
-    fn render_with_mode(&mut self, root: &dyn Component, mode: DrawMode) -> anyhow::Result<()> {
-        // TODO(cjhopman): We may need to try to keep each write call to be under the pipe buffer
-        // size so it can be completed in a single syscall otherwise we might see a partially
-        // rendered frame.
-
-        // We remove the last line as we always have a blank final line in our output.
-        let size = self.size()?.saturating_sub(1, Direction::Vertical);
-        let mut buffer = Vec::new();
+    pub fn set_emit_level(&mut self, min: Level) {
+        self.level_filter.set(min);
+    }
 
-        self.render_general(&mut buffer, root, mode, size)?;
-        self.output.output(buffer)
+    /// The minimum level of the lines drawn, shared so that it can be changed while rendering,
+    /// e.g. from an [`InputHandler`].
+    pub fn level_filter(&self) -> LevelFilter {
+        self.level_filter.clone()
     }
 
-    /// Helper method that makes rendering highly configurable.
-    fn render_general(
-        &mut self,
-        buffer: &mut Vec<u8>,
-        root: &dyn Component,
+    /// Keeps up to `capacity` of the latest lines left out by the [`emit_level`](Self::emit_level)
+    /// rather than dropping them, e.g. to show the debug output leading to a failure. Disabled by
+    /// default.
+    pub fn set_retain_filtered(&mut self, capacity: Option<usize>) {
+        self.filtered = capacity.map(FilteredLines::new);
+    }
 
-        mode: DrawMode,
-        size: Dimensions,
-    ) -> anyhow::Result<()> {
-        /// Heuristic to determine if a buffer is too large to buffer.
-        /// Can be tuned, but is currently set to 1000000 graphemes.
-        #[allow(clippy::ptr_arg)]
-        fn is_big(buf: &Lines) -> bool {
-            let len: usize = buf.iter().map(Line::len).sum();
-            len > MAX_GRAPHEME_BUFFER
+    /// Takes the lines kept by [`set_retain_filtered`](Self::set_retain_filtered), oldest first,
+    /// leaving out those below `min`. Pass them to [`emit`](Self::emit) to draw them.
+    pub fn take_filtered(&mut self, min: Level) -> Lines {
+        match &mut self.filtered {
+            Some(filtered) => filtered.take(min),
+            None => Lines::new(),
         }
+    }
 
-        // Go the beginning of the canvas.
-        self.root.move_up(buffer)?;
+    /// The number of emitted lines waiting to be drawn, e.g. for producers to apply backpressure.
+    pub fn pending_emit_len(&self) -> usize {
+        self.to_emit.len()
+    }
 
-        // Pre-draw the frame *and then* start rendering emitted messages.
-        let mut frame = self.root.draw(root, size, mode)?;
-        // Render at most a single frame if this not the last render.
-        // Does not buffer if there is a ridiculous amount of data.
-        let limit = match mode {
-            DrawMode::Normal if !is_big(&self.to_emit) => {
-                let limit = size.height.saturating_sub(frame.len());
-                // arbitrary value picked so we don't starve `emit` on small terminal sizes.
-                Some(cmp::max(limit, MINIMUM_EMIT))
-            }
-            _ => None,
-        };
-        self.to_emit.render(buffer, limit)?;
-        frame.render(buffer, None)?;
+    /// Bounds the number of emitted lines waiting to be drawn, which is unbounded by default.
+    /// Lines over the capacity are dropped according to its
+    /// [`OverflowPolicy`](crate::emit::OverflowPolicy).
+    pub fn set_emit_capacity(&mut self, capacity: Option<EmitCapacity>) {
+        self.emit_capacity = capacity;
+        self.emit(Lines::new());
+    }
 
-        // clear any residue from the previous render.
-        buffer.queue(Clear(ClearType::FromCursorDown))?;
+    /// Collapses repeated emitted lines into one with a count, e.g. `warning: unused import (x12)`,
+    /// before they are drawn. Disabled by default.
+    pub fn set_emit_dedup(&mut self, dedup: Option<EmitDedup>) {
+        self.dedup = dedup.map(Deduplicator::new);
+    }
+
+    /// Restyles the built-in components drawn by this console from the next render on, rather
+    /// than with the process-wide [`theme::set_theme`](crate::theme::set_theme).
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.context.theme = Some(Arc::new(theme));
+        self.dirty.mark();
+    }
+
+    /// Whether the built-in components drawn by this console may use glyphs beyond ASCII, rather
+    /// than as set by the process-wide [`charset::set_unicode`](crate::charset::set_unicode).
+    pub fn set_unicode(&mut self, enabled: bool) {
+        self.context.unicode = Some(enabled);
+        self.dirty.mark();
+    }
+
+    /// The colors this console draws, rather than as set by the process-wide
+    /// [`style::set_color_support`](crate::style::set_color_support).
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.context.color_support = Some(support);
+        self.dirty.mark();
+    }
+
+    /// How the built-in components drawn by this console format numbers, rather than with the
+    /// process-wide [`human::set_number_format`](crate::human::set_number_format).
+    pub fn set_number_format(&mut self, format: NumberFormat) {
+        self.context.number_format = Some(format);
+        self.dirty.mark();
+    }
+
+    /// The text of the built-in components drawn by this console, rather than that installed
+    /// process-wide with [`strings::set_strings`](crate::strings::set_strings).
+    pub fn set_strings(&mut self, strings: impl Strings + 'static) {
+        self.context.strings = Some(Arc::new(strings));
+        self.dirty.mark();
+    }
 
+    /// Clears the canvas and restores the terminal if the process is interrupted by `SIGINT` or
+    /// `SIGTERM`, before terminating it as usual. See the [`signals`](crate::signals) module.
+    /// The signals are unregistered once the console is finalized, detached or dropped.
+    #[cfg(all(feature = "signals", unix))]
+    pub fn restore_on_signals(&mut self) -> anyhow::Result<()> {
+        self.publish_terminal_state();
+        // Replacing an earlier registration stops its thread.
+        self.signals = Some(crate::signals::install(
+            self.terminal_state.clone(),
+            self.output.stream(),
+        )?);
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use anyhow::Context as _;
-    use derive_more::AsRef;
+    /// Shares what the console changed about the terminal with the thread restoring it on signals.
+    fn publish_terminal_state(&self) {
+        #[cfg(all(feature = "signals", unix))]
+        self.terminal_state.update(
+            if self.fallback.is_some() || self.paused {
+                0
+            } else {
+                self.root.rows_above_cursor()
+            },
+            self.alternate_screen_log.is_some() && !self.paused,
+            self.header_height > 0,
+            self.input_enabled && !self.paused,
+        );
+    }
 
-    use super::*;
-    use crate::components::echo::Echo;
-    use crate::testing::frame_contains;
-    use crate::testing::test_console;
-    use crate::testing::SuperConsoleTestingExt;
-    use crate::Lines;
+    /// Keeps the cursor hidden between frames, rather than only while they are drawn, so that it
+    /// doesn't flicker across the canvas. It is shown again when the console is paused, finalized
+    /// or dropped, and while a component places it with [`Span::with_cursor`](crate::Span::with_cursor).
+    pub fn set_hide_cursor(&mut self, hidden: bool) {
+        self.hide_cursor = hidden;
+        if let Some(guard) = self.cursor_guard.take() {
+            guard.disarm();
+        }
+        if hidden {
+            self.cursor_guard = self.output.stream().map(ShowCursorOnDrop);
+        }
+        self.dirty.mark();
+    }
 
-    #[derive(AsRef, Debug)]
-    struct Msg(Lines);
+    /// Reserves `height` rows for the canvas, bounded by the terminal height. Components are
+    /// anchored to the bottom and the rows above them are left blank, so a canvas whose content
+    /// changes in height doesn't make the emitted output above it jump. By default the canvas is
+    /// as tall as its content.
+    pub fn set_canvas_height(&mut self, height: Option<usize>) {
+        self.layout.height = height;
+        self.dirty.mark();
+    }
 
-    #[test]
-    fn test_small_buffer() -> anyhow::Result<()> {
-        let mut console = test_console();
-        let msg_count = MINIMUM_EMIT + 5;
-        console.emit(Lines(vec![vec!["line 1"].try_into()?; msg_count]));
-        let msg = Lines(vec![vec!["line"].try_into()?; msg_count]);
-        let root = Echo(msg);
-        let mut buffer = Vec::new();
+    /// Bounds the canvas to `height` rows even on tall terminals, keeping the rest of the screen
+    /// for the emitted output. Taller canvases are cut, and their last row is replaced with one
+    /// counting the rows left out, e.g. `… and 12 more`. By default the canvas may be as tall as
+    /// the terminal.
+    pub fn set_max_canvas_height(&mut self, height: Option<usize>) {
+        self.layout.max_height = height;
+        self.dirty.mark();
+    }
 
-        // even though the canvas is larger than the tty
-        console.render_general(
-            &mut buffer,
-            &root,
-            DrawMode::Normal,
-            Dimensions::new(100, 2),
-        )?;
+    /// Bounds the canvas to `width` columns even on wide terminals, so that components filling
+    /// their width, e.g. with a background color, don't stretch across the whole screen. The
+    /// canvas is placed as set with [`set_canvas_anchor`](Self::set_canvas_anchor), and the rest
+    /// of its rows are left blank. By default the canvas is as wide as the terminal.
+    pub fn set_max_canvas_width(&mut self, width: Option<usize>) {
+        self.layout.max_width = width;
+        self.dirty.mark();
+    }
 
-        // we should still drain a minimum of 5 messages.
-        assert_eq!(console.to_emit.len(), msg_count - MINIMUM_EMIT);
+    /// Where a canvas narrower than the terminal sits, on the left by default. See
+    /// [`set_max_canvas_width`](Self::set_max_canvas_width).
+    pub fn set_canvas_anchor(&mut self, anchor: Alignment) {
+        self.layout.anchor = anchor;
+        self.dirty.mark();
+    }
 
-        Ok(())
+    /// Lays out lines emitted with [`emit_column`](Self::emit_column) in side-by-side panes, or
+    /// stops doing so with `None`, in which case any lines still pending in the panes are emitted
+    /// one pane after the other.
+    pub fn set_emit_columns(&mut self, columns: Option<EmitColumns>) {
+        if let Some(previous) = self.columns.take() {
+            self.emit(previous.into_lines());
+        }
+        self.columns = columns.map(ColumnQueues::new);
     }
 
-    #[test]
-    fn test_huge_buffer() -> anyhow::Result<()> {
-        let mut console = test_console();
-        console.emit(Lines(vec![
-            vec!["line 1"].try_into()?;
-            MAX_GRAPHEME_BUFFER * 2
-        ]));
-        let root = Echo(Lines(vec![vec!["line"].try_into()?; 1]));
-        let mut buffer = Vec::new();
+    /// Queues the passed lines in pane `column` of the emitted log, see [`EmitColumns`]. Without
+    /// [`set_emit_columns`](Self::set_emit_columns), this is the same as [`emit`](Self::emit).
+    pub fn emit_column(&mut self, column: usize, lines: impl Into<Lines>) {
+        match &mut self.columns {
+            Some(columns) => columns.push(column, lines.into()),
+            None => self.emit(lines),
+        }
+    }
 
-        // Even though we have more messages than fit on the screen in the `to_emit` buffer
-        console.render_general(
-            &mut buffer,
-            &root,
-            DrawMode::Normal,
-            Dimensions::new(100, 20),
-        )?;
+    /// Lays out the lines pending in the panes of the emitted log, if any, as emitted lines.
+    fn emit_columns(&mut self, width: usize) {
+        if let Some(columns) = &mut self.columns {
+            if !columns.is_empty() {
+                let rows = columns.take_rows(width);
+                self.emit(rows);
+            }
+        }
+    }
 
-        // We have so many that we should just drain them all.
-        assert!(console.to_emit.is_empty());
+    /// Queues the passed lines to be written to `stream` on the next render. Lines for the stream
+    /// the canvas is drawn on are drawn above it as with [`emit`](Self::emit). Lines for another
+    /// stream are written there before the next frame, as plain text unless it is a terminal, so
+    /// that e.g. piping stdout captures only program output while diagnostics go to stderr.
+    pub fn emit_to(&mut self, stream: Stream, lines: Lines) {
+        if self.output.stream() == Some(stream) {
+            self.emit(lines);
+        } else {
+            self.routed.push((stream, lines));
+        }
+    }
 
-        Ok(())
+    /// Whether lines were emitted which were not drawn yet.
+    pub fn has_pending_emit(&self) -> bool {
+        !self.to_emit.is_empty()
+            || !self.routed.is_empty()
+            || !self.handle_receiver.is_empty()
+            || self.columns.as_ref().is_some_and(|c| !c.is_empty())
     }
 
-    /// Check that no frames are produced when should_render returns false.
-    #[test]
-    fn test_block_render() -> anyhow::Result<()> {
-        let mut console = test_console();
+    /// Like [`emit`](Self::emit), but the lines are subject to the rate limit of `source`, if
+    /// [`set_emit_rate_limit`](Self::set_emit_rate_limit) was used. Lines over the limit are
+    /// dropped and summarized, e.g. `suppressed 2,314 lines from [clippy]`.
+    pub fn emit_from(&mut self, source: &str, lines: Lines) {
+        let _context = self.context.enter();
+        let lines = match &mut self.rate_limiter {
+            Some(limiter) => limiter.admit(source, lines, Instant::now()),
+            None => lines,
+        };
+        self.emit(lines);
+    }
 
-        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+    /// Sets the rate limit applied to each source passed to [`emit_from`](Self::emit_from).
+    pub fn set_emit_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.rate_limiter = limit.map(RateLimiter::new);
+    }
 
-        console.render(&root)?;
-        assert_eq!(console.test_output()?.frames.len(), 1);
+    /// Queues the passed lines to be drawn on the next render in place of the most recently emitted
+    /// line. This is useful for simple progress updates such as "downloading… 45%".
+    /// If that line has not been drawn yet, it is replaced in the queue. If it was drawn, but can no
+    /// longer be overwritten (e.g. because it wrapped), this behaves like [`emit`](Self::emit).
+    pub fn emit_replace_last(&mut self, lines: Lines) {
+        if self.to_emit.0.pop().is_none() && self.last_emitted_replaceable {
+            self.last_emitted_replaceable = false;
+            self.rows_to_replace = 1;
+        }
+        self.emit(lines);
+    }
 
-        console.test_output_mut()?.should_render = false;
-        console.render(&root)?;
-        assert_eq!(console.test_output()?.frames.len(), 1);
+    /// Pins the passed lines in a band directly above the canvas, where they are drawn at every
+    /// render rather than scrolling away. Use [`unpin`](Self::unpin) to emit them normally.
+    pub fn pin(&mut self, lines: Lines) -> StickyId {
+        self.dirty.mark();
+        self.sticky.pin(lines, None)
+    }
 
-        console.emit(Lines(vec![vec!["line 1"].try_into()?]));
-        console.render(&root)?;
-        assert_eq!(console.test_output()?.frames.len(), 1);
+    /// Like [`pin`](Self::pin), but the lines are emitted normally after `duration`.
+    pub fn pin_for(&mut self, lines: Lines, duration: Duration) -> StickyId {
+        self.dirty.mark();
+        self.sticky.pin(lines, Some(Instant::now() + duration))
+    }
+
+    /// Shows the passed lines in the band above the canvas for `ttl`, after which they disappear
+    /// instead of being emitted to the scrollback. Useful for warnings which shouldn't pollute logs.
+    pub fn emit_transient(&mut self, lines: Lines, ttl: Duration) -> StickyId {
+        self.dirty.mark();
+        self.sticky
+            .pin_transient(lines, Some(Instant::now() + ttl), None)
+    }
+
+    /// Like [`emit_transient`](Self::emit_transient), but the lines disappear after being drawn
+    /// by the next `renders` renders.
+    pub fn emit_transient_renders(&mut self, lines: Lines, renders: usize) -> StickyId {
+        self.dirty.mark();
+        self.sticky.pin_transient(lines, None, Some(renders))
+    }
+
+    /// Unpins lines, which are queued to be emitted normally on the next render.
+    /// Transient lines are discarded instead.
+    /// Returns false if the lines were already unpinned or expired.
+    pub fn unpin(&mut self, id: StickyId) -> bool {
+        self.dirty.mark();
+        match self.sticky.unpin(id) {
+            Some(lines) => {
+                self.emit(lines);
+                true
+            }
+            None => false,
+        }
+    }
 
+    /// Queues a Markdown snippet to be drawn on the next render, word wrapped to the terminal width.
+    /// Useful for rich messages such as "next steps" at the end of a run.
+    #[cfg(feature = "markdown")]
+    pub fn emit_markdown(&mut self, markdown: &str) -> anyhow::Result<()> {
+        let width = self.size()?.width;
+        self.emit(Lines::from_markdown(markdown, width));
         Ok(())
     }
 
-    /// Check that lines are deferred when should_render returns false, and emitted once the output
-    /// is unblocked.
-    #[test]
-    fn test_block_lines() -> anyhow::Result<()> {
-        let mut console = test_console();
+    /// A heartbeat that beats whenever the console draws a frame. Pass it to a
+    /// [`Watchdog`](crate::watchdog::Watchdog) to detect when rendering stalls.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
 
-        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+    /// Sets a time budget for drawing each frame, which is shared with the
+    /// [`Budgeted`](crate::components::Budgeted) components in the tree. The stats for the last
+    /// frame drawn are available from the budget.
+    pub fn set_render_budget(&mut self, budget: Option<RenderBudget>) {
+        self.budget = budget;
+    }
 
-        console.test_output_mut()?.should_render = false;
-        console.emit(Lines(vec![vec!["line 1"].try_into()?]));
-        console.render(&root)?;
-        assert_eq!(console.test_output()?.frames.len(), 0);
+    /// Sets the policy deciding how many emitted lines are drawn per frame. Defaults to
+    /// [`AdaptiveEmitPolicy`].
+    pub fn set_emit_policy(&mut self, policy: Box<dyn EmitPolicy>) {
+        self.emit_policy = policy;
+    }
 
-        console.test_output_mut()?.should_render = true;
-        console.emit(Lines(vec![vec!["line 2"].try_into()?]));
-        console.render(&root)?;
+    /// Registers an observer to be told about rendering.
+    pub fn add_observer(&mut self, observer: Box<dyn RenderObserver>) {
+        self.observers.push(observer);
+    }
 
-        let frame = console
-            .test_output_mut()?
-            .frames
-            .pop()
-            .context("No frame was emitted")?;
+    /// Registers a sink to receive a copy of emitted lines as they are drawn, in addition to the
+    /// terminal. Lines replaced with [`emit_replace_last`](Self::emit_replace_last) after they
+    /// were drawn are sent again.
+    pub fn add_sink(&mut self, sink: Box<dyn EmitSink>) {
+        self.sinks.push(sink);
+    }
 
-        assert!(frame_contains(&frame, "state"));
-        assert!(frame_contains(&frame, "line 1"));
-        assert!(frame_contains(&frame, "line 2"));
+    /// Calls `callback` with the errors of sinks. A failing sink doesn't fail the render, nor
+    /// keep the terminal and the other sinks from getting the lines. Without a callback, the
+    /// errors are ignored.
+    pub fn on_sink_error(&mut self, callback: impl FnMut(anyhow::Error) + Send + 'static) {
+        self.on_sink_error = Some(Box::new(callback));
+    }
 
-        Ok(())
+    /// Passes `lines` to every sink, reporting their errors to the `on_sink_error` callback.
+    fn emit_to_sinks(&mut self, lines: &Lines) {
+        for (i, sink) in self.sinks.iter_mut().enumerate() {
+            if let (Err(e), Some(on_sink_error)) = (sink.emit(lines), &mut self.on_sink_error) {
+                on_sink_error(e.context(format!("Emit sink {} failed", i)));
+            }
+        }
     }
 
-    /// Check that render_with_mode does not respect should_render.
-    #[test]
-    fn test_block_finalize() -> anyhow::Result<()> {
-        let mut console = test_console();
+    /// Flushes every sink, reporting their errors to the `on_sink_error` callback.
+    fn flush_sinks(&mut self) {
+        for (i, sink) in self.sinks.iter_mut().enumerate() {
+            if let (Err(e), Some(on_sink_error)) = (sink.flush(), &mut self.on_sink_error) {
+                on_sink_error(e.context(format!("Emit sink {} failed to flush", i)));
+            }
+        }
+    }
 
-        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+    /// Decides what happens when a component fails to draw. By default, the render is aborted.
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.error_policy = policy;
+    }
 
-        console.test_output_mut()?.should_render = false;
-        console.emit(Lines(vec![vec!["line 1"].try_into()?]));
-        console.emit(Lines(vec![vec!["line 2"].try_into()?]));
-        console.render_with_mode(&root, DrawMode::Final)?;
+    /// Calls `callback` with each component error caught under [`ErrorPolicy::Isolate`], after
+    /// the frame it happened in was drawn.
+    pub fn on_component_error(&mut self, callback: impl FnMut(ComponentError) + Send + 'static) {
+        self.on_component_error = Some(Box::new(callback));
+    }
 
-        let frame = console
-            .test_output_mut()?
-            .frames
-            .pop()
-            .context("No frame was emitted")?;
+    /// Pins a component to the top rows of the terminal, e.g. a status bar, which emitted lines
+    /// scroll beneath. This restricts the terminal's scroll region to the rows below the header,
+    /// which is restored when the header is removed or the console is finalized. The header is
+    /// drawn over whatever was on those rows before.
+    pub fn set_header(&mut self, header: Option<Box<dyn Component + Send>>) {
+        let previous = mem::replace(&mut self.header, header);
+        self.replaced(previous, Slot::Header);
+        self.dirty.mark();
+    }
 
-        assert!(frame_contains(&frame, "state"));
+    /// Draws a component below the root at every render, e.g. a status bar. It is allotted space
+    /// before the root and the pinned lines, so it is never truncated by them.
+    pub fn set_footer(&mut self, footer: Option<Box<dyn Component + Send>>) {
+        let previous = mem::replace(&mut self.footer, footer);
+        self.replaced(previous, Slot::Footer);
+        self.dirty.mark();
+    }
+
+    /// Swaps a retained root for `current`, telling `previous` that it is no longer drawn and
+    /// `current` that it is, if the root was attached. Roots built afresh at every render are the
+    /// same root as far as the console is concerned, so this is only needed when a root keeping
+    /// state across renders is replaced by another.
+    pub fn replace_root(&mut self, previous: &dyn Component, current: &dyn Component) {
+        if self.attached.root {
+            previous.on_detach();
+            current.on_attach();
+        }
+        self.dirty.mark();
+    }
+
+    /// Tells the component replaced in `slot` that it is no longer drawn, if it was attached,
+    /// and the one now in `slot` that it is, if the root is attached already.
+    fn replaced(&mut self, previous: Option<Box<dyn Component + Send>>, slot: Slot) {
+        let live = self.attached.root;
+        let (current, attached) = match slot {
+            Slot::Header => (&self.header, &mut self.attached.header),
+            Slot::Footer => (&self.footer, &mut self.attached.footer),
+        };
+        if let Some(previous) = previous.filter(|_| *attached) {
+            previous.on_detach();
+        }
+        *attached = false;
+        if let Some(current) = current.as_deref().filter(|_| live) {
+            current.on_attach();
+            *attached = true;
+        }
+    }
+
+    /// Calls `hook` on the root and on the header and footer, if any.
+    fn for_each_component(&self, root: &dyn Component, hook: impl Fn(&dyn Component)) {
+        hook(root);
+        for component in [&self.header, &self.footer].into_iter().flatten() {
+            hook(component);
+        }
+    }
+
+    /// Calls [`Component::on_attach`] before the first draw, on the components not attached yet.
+    fn attach(&mut self, root: &dyn Component) {
+        if !self.attached.root {
+            root.on_attach();
+            self.attached.root = true;
+        }
+        let slots = [
+            (&self.header, &mut self.attached.header),
+            (&self.footer, &mut self.attached.footer),
+        ];
+        for (component, attached) in slots {
+            if let Some(component) = component.as_deref().filter(|_| !*attached) {
+                component.on_attach();
+                *attached = true;
+            }
+        }
+    }
+
+    /// Draws the header, if any, for a terminal of `size`.
+    fn draw_header(&self, size: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+        match &self.header {
+            // Leave at least a row for the canvas and emitted lines.
+            Some(header) => header.draw(size.saturating_sub(1, Direction::Vertical), mode),
+            None => Ok(Lines::new()),
+        }
+    }
+
+    /// Draws the header on the top rows of a terminal with `rows` rows, and sets the scroll region
+    /// below it if its height changed. The cursor is left where it was.
+    fn render_header(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        header: &Lines,
+        rows: usize,
+    ) -> anyhow::Result<()> {
+        if header.is_empty() && self.header_height == 0 {
+            return Ok(());
+        }
+        // Setting the scroll region moves the cursor home.
+        buffer.queue(SavePosition)?;
+        if header.len() != self.header_height {
+            if header.is_empty() {
+                write!(buffer, "\x1b[r")?;
+            } else {
+                write!(buffer, "\x1b[{};{}r", header.len() + 1, rows)?;
+            }
+            // Rows no longer covered by the header are left with its old content.
+            for row in header.len()..self.header_height {
+                buffer.queue(MoveTo(0, row.try_into()?))?;
+                buffer.queue(Clear(ClearType::CurrentLine))?;
+            }
+            self.header_height = header.len();
+        }
+        for (row, line) in header.iter().enumerate() {
+            buffer.queue(MoveTo(0, row.try_into()?))?;
+            line.render_with_clear_and_nl(buffer)?;
+        }
+        buffer.queue(RestorePosition)?;
+        Ok(())
+    }
+
+    /// Switches to the terminal's alternate screen, where the canvas is drawn from the top, without
+    /// scrolling the main screen. Emitted lines are kept until the main screen is restored, with
+    /// [`leave_alternate_screen`](Self::leave_alternate_screen) or when the console is finalized.
+    pub fn enter_alternate_screen(&mut self) -> anyhow::Result<()> {
+        if self.alternate_screen_log.is_some() {
+            return Ok(());
+        }
+        self.clear()?;
+        let mut buffer = Vec::new();
+        buffer.queue(EnterAlternateScreen)?;
+        buffer.queue(MoveTo(0, 0))?;
+        self.output.output(buffer)?;
+        self.alternate_screen_log = Some(AlternateScreenLog::default());
+        self.alternate_screen_guard = self.output.stream().map(LeaveAlternateScreenOnDrop);
+        self.publish_terminal_state();
+        Ok(())
+    }
+
+    /// Restores the main screen, and queues the lines emitted on the alternate screen to be drawn
+    /// on the next render. Those are bounded by the [emit capacity](Self::set_emit_capacity) too.
+    pub fn leave_alternate_screen(&mut self) -> anyhow::Result<()> {
+        let AlternateScreenLog {
+            lines: mut log,
+            dropped,
+        } = match self.alternate_screen_log.take() {
+            Some(log) => log,
+            None => return Ok(()),
+        };
+        if let Some(guard) = self.alternate_screen_guard.take() {
+            guard.disarm();
+        }
+        let mut buffer = Vec::new();
+        buffer.queue(LeaveAlternateScreen)?;
+        self.output.output(buffer)?;
+        // The cursor is back where it was on the main screen, below the previous output.
+        self.root = Canvas::new();
+        self.last_frame = Lines::new();
+        self.dirty.mark();
+        log.0.append(&mut self.to_emit.0);
+        self.to_emit = log;
+        self.emit_dropped = dropped;
+        let _context = self.context.enter();
+        if let Some(capacity) = &self.emit_capacity {
+            capacity.enforce(&mut self.to_emit, &mut self.emit_dropped);
+        }
+        self.publish_terminal_state();
+        Ok(())
+    }
+
+    /// Puts the terminal in raw mode, so that input can be read with
+    /// [`poll_events`](Self::poll_events) as it is typed. The terminal is restored when the console
+    /// is finalized.
+    ///
+    /// Raw mode stops the terminal from turning Ctrl-C into `SIGINT`: it is read as a key event
+    /// instead, which [`poll_events`](Self::poll_events) returns unless a handler consumes it.
+    /// Callers must check for it with [`input::is_interrupt`] and stop themselves, or the process
+    /// can no longer be interrupted from the keyboard.
+    ///
+    /// Input requires the `backend-crossterm` feature, see [`backend`](crate::backend).
+    pub fn enable_input(&mut self) -> anyhow::Result<()> {
+        if !self.input_enabled {
+            backend::enable_raw_mode()?;
+            self.input_enabled = true;
+            self.publish_terminal_state();
+        }
+        Ok(())
+    }
+
+    /// Registers a handler to be offered input events, in registration order.
+    pub fn add_input_handler(&mut self, handler: Box<dyn InputHandler>) {
+        self.input_handlers.push(handler);
+    }
+
+    /// Reads the input events available within `timeout`, offers them to the registered handlers,
+    /// and returns those which were not consumed. Returns nothing unless
+    /// [`enable_input`](Self::enable_input) was called.
+    pub fn poll_events(&mut self, timeout: Duration) -> anyhow::Result<Vec<Event>> {
+        if !self.input_enabled {
+            return Ok(Vec::new());
+        }
+        let events = input::read_events(timeout)?;
+        Ok(input::dispatch(&self.input_handlers, events))
+    }
+
+    /// Releases the terminal, e.g. to run an interactive subprocess such as an editor: the canvas
+    /// is cleared, the main screen and the cursor are restored, and raw mode is disabled. Renders
+    /// draw nothing until [`resume`](Self::resume) is called, and emitted lines are kept for then.
+    pub fn pause(&mut self) -> anyhow::Result<()> {
+        if self.paused {
+            return Ok(());
+        }
+        let mut buffer = Vec::new();
+        if self.alternate_screen_log.is_some() {
+            buffer.queue(LeaveAlternateScreen)?;
+            if let Some(guard) = self.alternate_screen_guard.take() {
+                guard.disarm();
+            }
+        } else {
+            self.root.clear(&mut buffer)?;
+        }
+        if self.header_height > 0 {
+            buffer.queue(SavePosition)?;
+            write!(buffer, "\x1b[r")?;
+            buffer.queue(RestorePosition)?;
+            // Drawn again with the next frame.
+            self.header_height = 0;
+        }
+        buffer.queue(Show)?;
+        self.output.output(buffer)?;
+        self.output.flush()?;
+        if self.input_enabled {
+            backend::disable_raw_mode()?;
+        }
+        self.last_frame = Lines::new();
+        self.last_cells = None;
+        self.paused = true;
+        self.publish_terminal_state();
+        Ok(())
+    }
+
+    /// Takes the terminal back after [`pause`](Self::pause). The canvas is drawn afresh below
+    /// whatever was written in the meantime on the next render.
+    pub fn resume(&mut self) -> anyhow::Result<()> {
+        if !self.paused {
+            return Ok(());
+        }
+        if self.input_enabled {
+            backend::enable_raw_mode()?;
+        }
+        if self.alternate_screen_log.is_some() {
+            let mut buffer = Vec::new();
+            buffer.queue(EnterAlternateScreen)?;
+            buffer.queue(MoveTo(0, 0))?;
+            self.output.output(buffer)?;
+            self.alternate_screen_guard = self.output.stream().map(LeaveAlternateScreenOnDrop);
+        }
+        self.root = Canvas::new();
+        self.paused = false;
+        self.dirty.mark();
+        self.publish_terminal_state();
+        Ok(())
+    }
+
+    /// Runs `f` with the terminal released, see [`pause`](Self::pause). The terminal is taken
+    /// back afterwards, even if `f` fails.
+    pub fn paused<R>(&mut self, f: impl FnOnce() -> R) -> anyhow::Result<R> {
+        self.pause()?;
+        let res = f();
+        self.resume()?;
+        Ok(res)
+    }
+
+    /// Whether the terminal is released, see [`pause`](Self::pause).
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    fn size(&self) -> anyhow::Result<Dimensions> {
+        // We want to get the size, but if that fails or is empty use the fallback_size if available.
+        match (self.output.terminal_size(), self.fallback_size) {
+            (Ok(size), Some(fallback)) if size.width == 0 || size.height == 0 => Ok(fallback),
+            (Ok(size), _) => Ok(size),
+            (Err(_), Some(fallback)) => Ok(fallback),
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    /// The canvas drawn in the last frame, including any pinned lines but not emitted ones.
+    /// This is empty if nothing was drawn yet, or the canvas was cleared since.
+    /// Use [`Lines::find`] and friends to search it.
+    pub fn last_frame(&self) -> &Lines {
+        &self.last_frame
+    }
+
+    /// The components drawn in the last frame, with the dimensions they were allotted and the
+    /// number of lines they drew.
+    pub fn last_tree(&self) -> &ComponentTree {
+        &self.last_tree
+    }
+
+    /// Describes the components drawn in the last frame, one per line and indented by nesting,
+    /// e.g. `Echo 78x20 -> 3 lines`.
+    pub fn dump_tree(&self) -> String {
+        self.last_tree.to_string()
+    }
+
+    /// The time spent on recent frames and their size, along with the time each component took to
+    /// draw in the last frame. Use [`ComponentTree::slowest`] to find what makes rendering slow.
+    pub fn render_stats(&self) -> RenderStats {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// An overlay showing the [`render_stats`](Self::render_stats), to draw as part of the root.
+    pub fn debug_stats(&self) -> DebugStats {
+        DebugStats::new(self.stats.clone())
+    }
+
+    /// An owned copy of the canvas as last drawn, e.g. to include the state of the UI in a crash
+    /// report.
+    pub fn screenshot(&self) -> Lines {
+        self.last_frame.clone()
+    }
+
+    /// Like [`screenshot`](Self::screenshot), but preceded by the emitted lines which have not
+    /// been drawn yet, as they would appear on the next render.
+    pub fn screenshot_with_pending(&self) -> Lines {
+        let mut screenshot = self.to_emit.clone();
+        screenshot.0.extend(self.last_frame.iter().cloned());
+        screenshot
+    }
+
+    /// Clears the canvas portion of the superconsole.
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.dirty.mark();
+        self.last_frame = Lines::new();
+        self.last_cells = None;
+        let mut buffer = vec![];
+        self.root.clear(&mut buffer)?;
+        self.publish_terminal_state();
+        self.output.output(buffer)
+    }
+
+    /// Helper method to share render + finalize behavior by specifying mode.
+    fn render_with_mode(&mut self, root: &dyn Component, mode: DrawMode) -> anyhow::Result<()> {
+        // TODO(cjhopman): We may need to try to keep each write call to be under the pipe buffer
+        // size so it can be completed in a single syscall otherwise we might see a partially
+        // rendered frame.
+
+        let _context = self.context.enter();
+        // We remove the last line as we always have a blank final line in our output.
+        let size = self.size()?.saturating_sub(1, Direction::Vertical);
+        self.attach(root);
+        self.drain_handles();
+        self.emit_columns(size.width);
+        self.write_routed()?;
+        // Changes made while drawing are picked up by the next render.
+        let generation = self.dirty.generation();
+        let mut buffer = Vec::new();
+
+        let start = Instant::now();
+        self.observers
+            .iter_mut()
+            .for_each(|o| o.on_frame_start(mode));
+        if let Some(budget) = &self.budget {
+            budget.start_frame();
+        }
+        let (lines_emitted, errors) =
+            error_boundary::isolate(self.error_policy == ErrorPolicy::Isolate, || {
+                if self.fallback.is_some() {
+                    self.render_fallback(&mut buffer, root, mode, size)
+                } else {
+                    self.render_general(&mut buffer, root, mode, size)
+                }
+            });
+        if let Some(on_component_error) = &mut self.on_component_error {
+            errors.into_iter().for_each(on_component_error);
+        }
+        // The frame is ended even if drawing it failed, so that budgets and observers stay
+        // balanced with the starts.
+        if let Some(budget) = &self.budget {
+            budget.finish_frame();
+        }
+        let bytes = buffer.len();
+        let index = self.frame_index;
+        let (lines_emitted, res) = match lines_emitted {
+            Ok(lines_emitted) => {
+                self.rendered_generation = Some(generation);
+                self.last_size = Some(size);
+                (lines_emitted, self.output_frame(buffer, mode, size))
+            }
+            Err(e) => (0, Err(e)),
+        };
+        self.publish_terminal_state();
+
+        let stats = FrameStats {
+            index,
+            mode,
+            duration: start.elapsed(),
+            bytes,
+            lines_emitted,
+            canvas_height: self.last_frame.len(),
+        };
+        self.observers
+            .iter_mut()
+            .for_each(|o| o.on_frame_end(&stats));
+        self.stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(stats, &self.last_tree);
+        res
+    }
+
+    /// Passes the frame just drawn to the output.
+    fn output_frame(
+        &mut self,
+        buffer: Vec<u8>,
+        mode: DrawMode,
+        size: Dimensions,
+    ) -> anyhow::Result<()> {
+        let metadata = FrameMetadata {
+            index: self.frame_index,
+            timestamp: SystemTime::now(),
+            dimensions: size,
+            mode,
+        };
+        self.frame_index += 1;
+        self.heartbeat.beat();
+        if self.output.wants_cells() {
+            self.output_cells(metadata)?;
+        }
+        if self.output.wants_lines() {
+            let emitted = mem::take(&mut self.frame_emitted);
+            self.output
+                .output_lines(&emitted, &self.last_frame, metadata)?;
+        }
+        self.output.output_frame(buffer, metadata)
+    }
+
+    /// Writes the lines emitted to other streams. If those share the terminal, the lines would
+    /// scroll the canvas up with them, so it is cleared first and drawn afresh.
+    fn write_routed(&mut self) -> anyhow::Result<()> {
+        if self.routed.is_empty() {
+            return Ok(());
+        }
+        if self.fallback.is_none() {
+            self.clear()?;
+        }
+        for (stream, lines) in mem::take(&mut self.routed) {
+            self.output.output_to(stream, &lines)?;
+        }
+        Ok(())
+    }
+
+    /// Clears the previous canvas after the terminal was resized, and tells all the components.
+    fn handle_resize(
+        &self,
+        buffer: &mut Vec<u8>,
+        root: &dyn Component,
+        last: Dimensions,
+        size: Dimensions,
+    ) -> anyhow::Result<()> {
+        if size.width < last.width && size.width > 0 {
+            // Terminals which reflow their content wrapped the previous canvas over more rows.
+            let extra: usize = self
+                .last_frame
+                .iter()
+                .map(|line| line.len().saturating_sub(1) / size.width)
+                .sum();
+            if extra > 0 {
+                buffer.queue(MoveUp(extra.try_into()?))?;
+            }
+        }
+        buffer.queue(Clear(ClearType::FromCursorDown))?;
+        self.for_each_component(root, |c| c.on_resize(size));
+        Ok(())
+    }
+
+    /// Passes the canvas of the frame just drawn to the output as cells.
+    fn output_cells(&mut self, metadata: FrameMetadata) -> anyhow::Result<()> {
+        let mut cells = DrawSurface::new(Dimensions::new(
+            metadata.dimensions.width,
+            self.last_frame.len(),
+        ));
+        cells.blit_lines(0, 0, &self.last_frame);
+        let changed = cells.changed_regions(self.last_cells.as_ref());
+        let frame = CellFrame {
+            metadata,
+            cells,
+            changed,
+        };
+        let res = self.output.output_cells(&frame);
+        self.last_cells = Some(frame.cells);
+        res
+    }
+
+    /// Renders without cursor movement, see [`new_or_fallback`](Self::new_or_fallback).
+    /// Returns the number of emitted lines drawn.
+    fn render_fallback(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        root: &dyn Component,
+        mode: DrawMode,
+        size: Dimensions,
+    ) -> anyhow::Result<usize> {
+        let expired = self.sticky.take_expired(Instant::now());
+        self.emit(expired);
+        let root = WithSticky {
+            sticky: &self.sticky,
+            root,
+            footer: self.footer.as_deref().map(|f| f as &dyn Component),
+        };
+        let mut frame = self.draw_header(size, mode)?;
+        let (canvas, tree) = debug::record_tree(|| {
+            let size = size.saturating_sub(frame.len(), Direction::Vertical);
+            self.root.draw(&root, size, mode, self.layout)
+        });
+        self.last_tree = tree;
+        frame.0.append(&mut canvas?.0);
+
+        let emitted = mem::take(&mut self.to_emit);
+        self.emit_dropped = 0;
+        if !emitted.is_empty() {
+            self.emit_to_sinks(&emitted);
+        }
+        if let Some(fallback) = &mut self.fallback {
+            fallback.render(buffer, &emitted, &frame, mode, Instant::now());
+        }
+        if self.output.wants_lines() {
+            self.frame_emitted = emitted.clone();
+        }
+        self.last_frame = frame;
+        Ok(emitted.len())
+    }
+
+    /// Helper method that makes rendering highly configurable.
+    /// Returns the number of emitted lines drawn.
+    fn render_general(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        root: &dyn Component,
+
+        mode: DrawMode,
+        size: Dimensions,
+    ) -> anyhow::Result<usize> {
+        /// Heuristic to determine if a buffer is too large to buffer.
+        /// Can be tuned with `Builder::max_emit_buffer`, and defaults to 1000000 graphemes.
+        #[allow(clippy::ptr_arg)]
+        fn is_big(buf: &Lines, max: usize) -> bool {
+            let len: usize = buf.iter().map(Line::len).sum();
+            len > max
+        }
+
+        let rows = size.height + 1;
+        let terminal = size;
+        let header = self.draw_header(size, mode)?;
+        let size = size.saturating_sub(header.len(), Direction::Vertical);
+
+        // Terminals which support synchronized output show the frame at once, rather than as it is
+        // written, and the others ignore it. Hiding the cursor also keeps it from flickering
+        // across the canvas, which slow consoles such as cmd.exe otherwise show as torn frames.
+        write!(buffer, "{}", BEGIN_SYNCHRONIZED_UPDATE)?;
+        buffer.queue(Hide)?;
+
+        // Go the beginning of the canvas.
+        self.root.move_up(buffer)?;
+
+        let expired = self.sticky.take_expired(Instant::now());
+        self.emit(expired);
+        if let Some(log) = &mut self.alternate_screen_log {
+            log.lines.0.append(&mut self.to_emit.0);
+            self.emit_dropped = 0;
+            if let Some(capacity) = &self.emit_capacity {
+                capacity.enforce(&mut log.lines, &mut log.dropped);
+            }
+        }
+        if let Some(last) = self.last_size.filter(|last| *last != terminal) {
+            self.handle_resize(buffer, root, last, terminal)?;
+        } else if self.last_canvas_size.is_some_and(|last| last != size) {
+            // The header changed height: the canvas moved, but the terminal didn't reflow it.
+            buffer.queue(Clear(ClearType::FromCursorDown))?;
+        }
+
+        // Pre-draw the frame *and then* start rendering emitted messages.
+        let root = WithSticky {
+            sticky: &self.sticky,
+            root,
+            footer: self.footer.as_deref().map(|f| f as &dyn Component),
+        };
+        let (frame, tree) = debug::record_tree(|| self.root.draw(&root, size, mode, self.layout));
+        self.last_tree = tree;
+        let mut frame = frame?;
+        // Render at most a single frame if this not the last render.
+        // Does not buffer if there is a ridiculous amount of data.
+        let limit = match mode {
+            DrawMode::Normal if !is_big(&self.to_emit, self.max_emit_buffer) => {
+                Some(self.emit_policy.emit_limit(EmitContext {
+                    terminal_height: size.height,
+                    canvas_height: frame.len(),
+                    backlog: self.to_emit.len(),
+                }))
+            }
+            _ => None,
+        };
+        let replacing = self.rows_to_replace > 0 && limit != Some(0);
+        if replacing {
+            buffer.queue(MoveUp(self.rows_to_replace))?;
+            self.rows_to_replace = 0;
+        }
+        let drawn = cmp::min(limit.unwrap_or(usize::MAX), self.to_emit.len());
+        if drawn > 0 {
+            // Any summary of dropped lines is first, so it was drawn.
+            self.emit_dropped = 0;
+        }
+        if let Some(last) = self.to_emit.0[..drawn].last() {
+            self.last_emitted_replaceable = last.len() < size.width;
+        }
+        if drawn > 0 && (!self.sinks.is_empty() || self.output.wants_lines()) {
+            let lines = Lines(self.to_emit.0[..drawn].to_vec());
+            self.emit_to_sinks(&lines);
+            if self.output.wants_lines() {
+                self.frame_emitted = lines;
+            }
+        }
+        self.to_emit.render(buffer, limit)?;
+
+        // The previous canvas is still on screen unless emitted lines were drawn over it, so only
+        // the lines which changed need to be redrawn.
+        let previous = mem::replace(&mut self.last_frame, frame.clone());
+        let redraw = mode == DrawMode::Final
+            || drawn > 0
+            || replacing
+            || self.last_canvas_size != Some(size);
+        self.last_canvas_size = Some(size);
+        if redraw {
+            frame.render(buffer, None)?;
+        } else {
+            render_changed(buffer, &frame, &previous)?;
+        }
+
+        // clear any residue from the previous render.
+        buffer.queue(Clear(ClearType::FromCursorDown))?;
+        self.render_header(buffer, &header, rows)?;
+
+        let cursor = frame.cursor_position().filter(|_| mode == DrawMode::Normal);
+        if let Some(position) = cursor {
+            self.root.place_cursor(buffer, position)?;
+        }
+        if cursor.is_some() || !self.hide_cursor || mode == DrawMode::Final {
+            buffer.queue(Show)?;
+        }
+        write!(buffer, "{}", END_SYNCHRONIZED_UPDATE)?;
+        Ok(drawn)
+    }
+}
+
+/// Which components were told they are drawn. The header and footer are tracked apart from the
+/// root, as they are replaced on their own.
+#[derive(Debug, Default, Clone, Copy)]
+struct Attached {
+    root: bool,
+    header: bool,
+    footer: bool,
+}
+
+/// The components a console holds.
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Header,
+    Footer,
+}
+
+/// The lines emitted while on the alternate screen, see [`SuperConsole::enter_alternate_screen`].
+#[derive(Debug, Default)]
+struct AlternateScreenLog {
+    lines: Lines,
+    /// The lines dropped to keep within the emit capacity, summarized by the first line.
+    dropped: u64,
+}
+
+/// Restores the main screen when dropped, e.g. when unwinding from a panic, unless disarmed.
+struct LeaveAlternateScreenOnDrop(Stream);
+
+impl LeaveAlternateScreenOnDrop {
+    fn disarm(self) {
+        mem::forget(self);
+    }
+}
+
+impl Drop for LeaveAlternateScreenOnDrop {
+    fn drop(&mut self) {
+        let mut writer = self.0.writer();
+        let _ = writer.queue(LeaveAlternateScreen);
+        let _ = writer.flush();
+    }
+}
+
+/// Shows the cursor hidden by [`SuperConsole::set_hide_cursor`] when dropped, e.g. when unwinding
+/// from a panic, unless disarmed.
+struct ShowCursorOnDrop(Stream);
+
+impl ShowCursorOnDrop {
+    fn disarm(self) {
+        mem::forget(self);
+    }
+}
+
+impl Drop for ShowCursorOnDrop {
+    fn drop(&mut self) {
+        let mut writer = self.0.writer();
+        let _ = writer.queue(Show);
+        let _ = writer.flush();
+    }
+}
+
+/// Draws the lines of `frame` which differ from `previous`, which is on screen from the cursor
+/// down, and moves past the others.
+fn render_changed(buffer: &mut Vec<u8>, frame: &Lines, previous: &Lines) -> anyhow::Result<()> {
+    let mut unchanged: u16 = 0;
+    for (i, line) in frame.iter().enumerate() {
+        if previous.0.get(i) == Some(line) && unchanged < u16::MAX {
+            unchanged += 1;
+            continue;
+        }
+        if unchanged > 0 {
+            buffer.queue(MoveToNextLine(mem::take(&mut unchanged)))?;
+        }
+        line.render_with_clear_and_nl(buffer)?;
+    }
+    if unchanged > 0 {
+        buffer.queue(MoveToNextLine(unchanged))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use anyhow::Context as _;
+
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::components::splitting::SplitKind;
+    use crate::components::Blank;
+    use crate::components::Split;
+    use crate::components::Styled;
+    use crate::content::ChangedRegion;
+    use crate::emit::MinimumEmitPolicy;
+    use crate::emit::OverflowPolicy;
+    use crate::emit::MINIMUM_EMIT;
+    use crate::sink::RingBufferSink;
+    use crate::style::ContentStyle;
+    use crate::testing::frame_contains;
+    use crate::testing::test_console;
+    use crate::testing::with_unicode;
+    use crate::testing::SuperConsoleTestingExt;
+    use crate::testing::TestOutput;
+    use crate::Lines;
+    use crate::Span;
+
+    #[test]
+    fn test_small_buffer() -> anyhow::Result<()> {
+        let mut console = test_console();
+        console.set_emit_policy(Box::new(MinimumEmitPolicy::default()));
+        let msg_count = MINIMUM_EMIT + 5;
+        console.emit(Lines(vec![vec!["line 1"].try_into()?; msg_count]));
+        let msg = Lines(vec![vec!["line"].try_into()?; msg_count]);
+        let root = Echo(msg);
+        let mut buffer = Vec::new();
+
+        // even though the canvas is larger than the tty
+        console.render_general(
+            &mut buffer,
+            &root,
+            DrawMode::Normal,
+            Dimensions::new(100, 2),
+        )?;
+
+        // we should still drain a minimum of 5 messages.
+        assert_eq!(console.to_emit.len(), msg_count - MINIMUM_EMIT);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_huge_buffer() -> anyhow::Result<()> {
+        let mut console = test_console();
+        console.emit(Lines(vec![
+            vec!["line 1"].try_into()?;
+            MAX_GRAPHEME_BUFFER * 2
+        ]));
+        let root = Echo(Lines(vec![vec!["line"].try_into()?; 1]));
+        let mut buffer = Vec::new();
+
+        // Even though we have more messages than fit on the screen in the `to_emit` buffer
+        console.render_general(
+            &mut buffer,
+            &root,
+            DrawMode::Normal,
+            Dimensions::new(100, 20),
+        )?;
+
+        // We have so many that we should just drain them all.
+        assert!(console.to_emit.is_empty());
+
+        Ok(())
+    }
+
+    /// Check that no frames are produced when should_render returns false.
+    #[test]
+    fn test_block_render() -> anyhow::Result<()> {
+        let mut console = test_console();
+
+        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+
+        console.render(&root)?;
+        assert_eq!(console.test_output()?.frames.len(), 1);
+
+        console.test_output_mut()?.should_render = false;
+        console.render(&root)?;
+        assert_eq!(console.test_output()?.frames.len(), 1);
+
+        console.emit(Lines(vec![vec!["line 1"].try_into()?]));
+        console.render(&root)?;
+        assert_eq!(console.test_output()?.frames.len(), 1);
+
+        Ok(())
+    }
+
+    /// Check that lines are deferred when should_render returns false, and emitted once the output
+    /// is unblocked.
+    #[test]
+    fn test_block_lines() -> anyhow::Result<()> {
+        let mut console = test_console();
+
+        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+
+        console.test_output_mut()?.should_render = false;
+        console.emit(Lines(vec![vec!["line 1"].try_into()?]));
+        console.render(&root)?;
+        assert_eq!(console.test_output()?.frames.len(), 0);
+
+        console.test_output_mut()?.should_render = true;
+        console.emit(Lines(vec![vec!["line 2"].try_into()?]));
+        console.render(&root)?;
+
+        let frame = console
+            .test_output_mut()?
+            .frames
+            .pop()
+            .context("No frame was emitted")?;
+
+        assert!(frame_contains(&frame, "state"));
+        assert!(frame_contains(&frame, "line 1"));
+        assert!(frame_contains(&frame, "line 2"));
+
+        Ok(())
+    }
+
+    /// Check that render_with_mode does not respect should_render.
+    #[test]
+    fn test_block_finalize() -> anyhow::Result<()> {
+        let mut console = test_console();
+
+        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+
+        console.test_output_mut()?.should_render = false;
+        console.emit(Lines(vec![vec!["line 1"].try_into()?]));
+        console.emit(Lines(vec![vec!["line 2"].try_into()?]));
+        console.render_with_mode(&root, DrawMode::Final)?;
+
+        let frame = console
+            .test_output_mut()?
+            .frames
+            .pop()
+            .context("No frame was emitted")?;
+
+        assert!(frame_contains(&frame, "state"));
         assert!(frame_contains(&frame, "line 1"));
         assert!(frame_contains(&frame, "line 2"));
 
         Ok(())
     }
+
+    /// Check that drawn frames carry metadata describing them.
+    #[test]
+    fn test_frame_metadata() -> anyhow::Result<()> {
+        let mut console = test_console();
+
+        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+
+        console.render(&root)?;
+        console.clear()?;
+        console.render_with_mode(&root, DrawMode::Final)?;
+
+        let output = console.test_output()?;
+        assert_eq!(output.frames.len(), 3);
+        assert_eq!(output.metadata.len(), 2);
+        assert_eq!(output.metadata[0].index, 0);
+        assert_eq!(output.metadata[0].mode, DrawMode::Normal);
+        assert_eq!(output.metadata[0].dimensions, Dimensions::new(80, 79));
+        assert_eq!(output.metadata[1].index, 1);
+        assert_eq!(output.metadata[1].mode, DrawMode::Final);
+        assert!(output.metadata[0].timestamp <= output.metadata[1].timestamp);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_changed() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let frame = |status: &str| -> anyhow::Result<Echo> {
+            Ok(Echo(Lines(vec![
+                vec!["header"].try_into()?,
+                vec![status].try_into()?,
+            ])))
+        };
+
+        console.render(&frame("status: 1")?)?;
+        console.render(&frame("status: 2")?)?;
+        let frames = &console.test_output()?.frames;
+        assert!(frames[0].starts_with(BEGIN_SYNCHRONIZED_UPDATE.as_bytes()));
+        assert!(frames[0].ends_with(END_SYNCHRONIZED_UPDATE.as_bytes()));
+        assert!(frame_contains(&frames[0], "header"));
+        assert!(!frame_contains(&frames[1], "header"));
+        assert!(frame_contains(&frames[1], "status: 2"));
+
+        // Emitted lines are drawn over the canvas, so it is fully redrawn.
+        console.emit(Lines(vec![vec!["line"].try_into()?]));
+        console.render(&frame("status: 2")?)?;
+        let frames = &console.test_output()?.frames;
+        assert!(frame_contains(&frames[2], "header"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_alternate_screen() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+
+        console.enter_alternate_screen()?;
+        console.emit(Lines(vec![vec!["log line"].try_into()?]));
+        console.render(&root)?;
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(!frame_contains(frame, "log line"));
+
+        console.leave_alternate_screen()?;
+        console.render(&root)?;
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "log line"));
+        assert!(frame_contains(frame, "state"));
+
+        // The lines kept meanwhile are bounded too.
+        console.set_emit_capacity(Some(EmitCapacity::new(3, OverflowPolicy::Summarize)));
+        console.enter_alternate_screen()?;
+        for i in 0..10 {
+            console.emit(Lines(vec![vec![format!("line {}", i)].try_into()?]));
+            console.render(&root)?;
+        }
+        console.leave_alternate_screen()?;
+        let rows: Vec<_> = console.to_emit.iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["8 lines dropped", "line 8", "line 9"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pause() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.render(&root)?;
+
+        assert_eq!(console.paused(|| 42)?, 42);
+        assert!(!console.is_paused());
+
+        let frames = console.test_output()?.frames.len();
+        console.pause()?;
+        // Only the canvas was cleared.
+        assert_eq!(console.test_output()?.frames.len(), frames + 1);
+        console.emit(Lines(vec![vec!["while paused"].try_into()?]));
+        console.render(&root)?;
+        assert_eq!(console.test_output()?.frames.len(), frames + 1);
+        assert!(console.last_frame().is_empty());
+
+        console.resume()?;
+        console.render(&root)?;
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "while paused"));
+        assert!(frame_contains(frame, "state"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_emit_columns() -> anyhow::Result<()> {
+        let mut console = test_console();
+        console.set_emit_columns(Some(
+            EmitColumns::new(2).separator(Span::new_unstyled(" | ")?),
+        ));
+        console.emit_column(0, "passed");
+        console.emit_column(1, Lines(vec![vec!["warning"].try_into()?; 2]));
+        assert!(console.has_pending_emit());
+
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.render(&root)?;
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(
+            frame,
+            format!("passed{} | warning", " ".repeat(32))
+        ));
+        assert!(frame_contains(
+            frame,
+            format!("{} | warning", " ".repeat(38))
+        ));
+
+        console.emit_column(0, "pending");
+        console.set_emit_columns(None);
+        assert_eq!(console.pending_emit_len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cursor() -> anyhow::Result<()> {
+        const SHOW: &str = "\x1b[?25h";
+        let mut console = test_console();
+        console.set_hide_cursor(true);
+        let status = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.render(&status)?;
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(!frame_contains(frame, SHOW));
+
+        let prompt = Echo(Lines(vec![
+            Line::from_iter([
+                Span::new_unstyled("> ")?,
+                Span::new_unstyled(" ")?.with_cursor(),
+            ]),
+            vec!["state"].try_into()?,
+        ]));
+        console.render(&prompt)?;
+        assert_eq!(console.last_frame().cursor_position(), Some((2, 0)));
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, SHOW));
+
+        // The canvas is redrawn from its top, where the cursor was left.
+        console.render(&status)?;
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(!frame_contains(frame, SHOW));
+        assert!(!frame_contains(frame, "\x1b[2A"));
+        assert_eq!(console.last_frame().len(), 1);
+
+        console.finalize(&status)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_if_dirty() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        let dirty = console.dirty_flag();
+
+        assert!(console.render_if_dirty(&root)?);
+        let frames = console.test_output()?.frames.len();
+        assert!(!console.render_if_dirty(&root)?);
+        assert_eq!(console.test_output()?.frames.len(), frames);
+
+        dirty.mark();
+        assert!(console.render_if_dirty(&root)?);
+        assert!(!console.render_if_dirty(&root)?);
+
+        console.emit(Lines(vec![vec!["line"].try_into()?]));
+        assert!(console.render_if_dirty(&root)?);
+        assert!(!console.render_if_dirty(&root)?);
+
+        console.pin(Lines(vec![vec!["pinned"].try_into()?]));
+        assert!(console.render_if_dirty(&root)?);
+        assert!(!console.render_if_dirty(&root)?);
+
+        // A header doesn't make every frame look resized.
+        console.set_header(Some(Box::new(Echo(Lines(
+            vec![vec!["header"].try_into()?],
+        )))));
+        assert!(console.render_if_dirty(&root)?);
+        assert!(!console.render_if_dirty(&root)?);
+
+        console.test_output_mut()?.terminal_size = Dimensions::new(40, 40);
+        assert!(console.render_if_dirty(&root)?);
+        assert!(!console.render_if_dirty(&root)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_render_stays_dirty() -> anyhow::Result<()> {
+        struct Failing;
+
+        impl Component for Failing {
+            fn draw_unchecked(
+                &self,
+                _dimensions: Dimensions,
+                _mode: DrawMode,
+            ) -> anyhow::Result<Lines> {
+                Err(anyhow::anyhow!("broken"))
+            }
+        }
+
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        assert!(console.render_if_dirty(&root)?);
+        console.dirty_flag().mark();
+        assert!(console.render_if_dirty(&Failing).is_err());
+        assert!(console.render_if_dirty(&root)?);
+        assert!(!console.render_if_dirty(&root)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finalize_with() -> anyhow::Result<()> {
+        let root = Echo(Lines(vec![Line::unstyled("progress")?]));
+
+        let mut console = test_console();
+        console.render(&root)?;
+        let frame = console.finalize_with(&root, FinalizeOptions::default())?;
+        assert!(frame.contains("progress"));
+
+        let frame = test_console().finalize_with(&root, FinalizeOptions::suppress())?;
+        assert!(frame.is_empty());
+
+        let mut console = test_console();
+        console.pin(Lines(vec![Line::unstyled("pinned")?]));
+        let summary = Lines(vec![Line::unstyled("built 12 targets")?]);
+        let frame = console.finalize_with(&root, FinalizeOptions::replace(summary))?;
+        assert!(!frame.contains("progress"));
+        assert!(frame.contains("pinned"));
+        assert!(frame.contains("built 12 targets"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lifecycle() -> anyhow::Result<()> {
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<&'static str>>);
+
+        impl Recorder {
+            fn record(&self, event: &'static str) {
+                self.0.lock().unwrap().push(event);
+            }
+
+            fn take(&self) -> Vec<&'static str> {
+                mem::take(&mut *self.0.lock().unwrap())
+            }
+        }
+
+        impl Component for Recorder {
+            fn on_attach(&self) {
+                self.record("attach");
+            }
+
+            fn on_finalize(&self) {
+                self.record("finalize");
+            }
+
+            fn on_detach(&self) {
+                self.record("detach");
+            }
+
+            fn draw_unchecked(&self, _: Dimensions, mode: DrawMode) -> anyhow::Result<Lines> {
+                self.record(match mode {
+                    DrawMode::Normal => "draw",
+                    DrawMode::Final => "final draw",
+                });
+                Ok(Lines::new())
+            }
+        }
+
+        let root = Arc::new(Recorder::default());
+        let mut console = test_console();
+        console.render(&root)?;
+        console.render(&root)?;
+        assert_eq!(root.take(), ["attach", "draw", "draw"]);
+        console.finalize(&root)?;
+        assert_eq!(root.take(), ["finalize", "final draw"]);
+
+        // Containers pass the hooks on, and detaching doesn't draw.
+        let root = Arc::new(Recorder::default());
+        let mut console = test_console();
+        console.set_footer(Some(Box::new(Styled::new(
+            root.clone(),
+            ContentStyle::default(),
+        ))));
+        console.render(&Blank)?;
+        console.detach(&Blank)?;
+        assert_eq!(root.take(), ["attach", "draw", "detach"]);
+
+        // Swapped components are detached, and their replacements attached.
+        let root = Arc::new(Recorder::default());
+        let header = Arc::new(Recorder::default());
+        let mut console = test_console();
+        console.set_header(Some(Box::new(header.clone())));
+        console.set_header(None);
+        assert!(header.take().is_empty());
+        console.set_header(Some(Box::new(header.clone())));
+        console.render(&root)?;
+        assert_eq!(header.take(), ["attach", "draw"]);
+
+        let next = Arc::new(Recorder::default());
+        console.set_header(Some(Box::new(next.clone())));
+        assert_eq!(header.take(), ["detach"]);
+        assert_eq!(next.take(), ["attach"]);
+
+        let other = Arc::new(Recorder::default());
+        console.replace_root(&root, &other);
+        console.render(&other)?;
+        assert_eq!(root.take(), ["attach", "draw", "detach"]);
+        assert_eq!(other.take(), ["attach", "draw"]);
+        console.detach(&other)?;
+        assert_eq!(other.take(), ["detach"]);
+        assert_eq!(next.take(), ["draw", "detach"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canvas_height() -> anyhow::Result<()> {
+        let mut console = test_console();
+        console.set_canvas_height(Some(3));
+        let status = Line::unstyled("status")?;
+        let root = Echo(Lines(vec![status.clone()]));
+        console.render(&root)?;
+        assert_eq!(
+            console.last_frame(),
+            &Lines(vec![Line::default(), Line::default(), status.clone()])
+        );
+
+        // Canvases taller than the reserved height are left as they are.
+        let root = Echo(Lines(vec![status; 4]));
+        console.render(&root)?;
+        assert_eq!(console.last_frame().len(), 4);
+
+        console.set_canvas_height(Some(1000));
+        console.render(&root)?;
+        assert_eq!(console.last_frame().len(), 79);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canvas_anchor() -> anyhow::Result<()> {
+        let mut console = test_console();
+        console.set_max_canvas_width(Some(20));
+        let root = Echo(Lines(vec![Line::unstyled(&"x".repeat(30))?]));
+        console.render(&root)?;
+        assert_eq!(console.last_frame().0[0].to_unstyled(), "x".repeat(20));
+
+        console.set_canvas_anchor(Alignment::Right);
+        let root = Echo(Lines(vec![Line::unstyled("status")?]));
+        console.render(&root)?;
+        let line = &console.last_frame().0[0];
+        assert_eq!(line.len(), 80);
+        assert_eq!(line.to_unstyled().trim(), "status");
+        assert_eq!(line.to_unstyled().find("status"), Some(60));
+
+        console.set_canvas_anchor(Alignment::Center);
+        console.render(&root)?;
+        assert_eq!(
+            console.last_frame().0[0].to_unstyled().find("status"),
+            Some(30)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_canvas_height() -> anyhow::Result<()> {
+        with_unicode(true, || {
+            let mut console = test_console();
+            console.set_max_canvas_height(Some(3));
+            let root = Echo(
+                (0..15)
+                    .map(|i| Line::unstyled(&format!("task {}", i)))
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .map(Lines)?,
+            );
+            console.render(&root)?;
+            let frame = console.last_frame();
+            assert_eq!(frame.len(), 3);
+            assert_eq!(frame.0[1].to_unstyled(), "task 1");
+            assert_eq!(frame.0[2].to_unstyled(), "… and 13 more");
+            console.set_unicode(false);
+            console.render(&root)?;
+            assert_eq!(console.last_frame().0[2].to_unstyled(), "... and 13 more");
+
+            // Canvases which fit are drawn in full.
+            let root = Echo(Lines(vec![Line::unstyled("status")?; 3]));
+            console.render(&root)?;
+            assert!(!console.last_frame().contains("more"));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_resize() -> anyhow::Result<()> {
+        #[derive(Default)]
+        struct ResizeRecorder(Mutex<Vec<Dimensions>>);
+
+        impl Component for ResizeRecorder {
+            fn draw_unchecked(&self, _: Dimensions, _: DrawMode) -> anyhow::Result<Lines> {
+                Ok(Lines(vec![vec!["state"].try_into()?]))
+            }
+
+            fn on_resize(&self, dimensions: Dimensions) {
+                self.0.lock().unwrap().push(dimensions);
+            }
+        }
+
+        let mut console = test_console();
+        let root = ResizeRecorder::default();
+        console.render(&root)?;
+        console.render(&root)?;
+        assert!(root.0.lock().unwrap().is_empty());
+
+        console.test_output_mut()?.terminal_size = Dimensions::new(40, 20);
+        console.render(&root)?;
+        assert_eq!(*root.0.lock().unwrap(), vec![Dimensions::new(40, 19)]);
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "state"));
+
+        // The header and footer are told too, but a header changing height isn't a resize.
+        let header = Arc::new(ResizeRecorder::default());
+        let footer = Arc::new(ResizeRecorder::default());
+        console.set_header(Some(Box::new(header.clone())));
+        console.set_footer(Some(Box::new(footer.clone())));
+        console.render(&root)?;
+        assert_eq!(root.0.lock().unwrap().len(), 1);
+        console.test_output_mut()?.terminal_size = Dimensions::new(30, 20);
+        console.render(&root)?;
+        for recorder in [&root, &*header, &*footer] {
+            assert_eq!(
+                recorder.0.lock().unwrap().last(),
+                Some(&Dimensions::new(30, 19))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder() -> anyhow::Result<()> {
+        let mut builder = SuperConsole::builder();
+        builder
+            .output(Box::new(TestOutput::new(Dimensions::new(0, 0))))
+            .default_size(Dimensions::new(30, 10))
+            .forced(true);
+        let mut console = builder.build()?.context("Console was not built")?;
+
+        console.render(&Echo(Lines(vec![vec!["state"].try_into()?])))?;
+        let output = console.test_output()?;
+        assert_eq!(output.metadata[0].dimensions, Dimensions::new(30, 9));
+        assert!(frame_contains(&output.frames[0], "state"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_context() -> anyhow::Result<()> {
+        struct Count;
+
+        impl Component for Count {
+            fn draw_unchecked(
+                &self,
+                _dimensions: Dimensions,
+                _mode: DrawMode,
+            ) -> anyhow::Result<Lines> {
+                Ok(Lines(vec![vec![crate::human::count(1234)].try_into()?]))
+            }
+        }
+
+        let mut german = test_console();
+        german.set_number_format(NumberFormat::DE);
+        let mut english = test_console();
+        english.set_number_format(NumberFormat::EN);
+
+        german.render(&Count)?;
+        english.render(&Count)?;
+        assert!(frame_contains(&german.test_output()?.frames[0], "1.234"));
+        assert!(frame_contains(&english.test_output()?.frames[0], "1,234"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fallback() -> anyhow::Result<()> {
+        let mut console = test_console();
+        console.fallback = Some(FallbackRenderer::new(Duration::from_secs(60)));
+        assert!(console.is_fallback());
+
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.emit(Lines(vec![vec!["hello"].try_into()?]));
+        console.render(&root)?;
+        console.render(&root)?;
+        let frames = &console.test_output()?.frames;
+        assert_eq!(frames[0], b"hello\nstate\n");
+        assert_eq!(frames[1], b"");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_emit_to() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.render(&root)?;
+
+        console.emit_to(Stream::Stdout, Lines(vec![vec!["result"].try_into()?]));
+        console.emit_to(Stream::Stderr, Lines(vec![vec!["warning"].try_into()?]));
+        assert!(console.has_pending_emit());
+        console.render(&root)?;
+
+        let output = console.test_output()?;
+        assert_eq!(
+            output.streams,
+            vec![(Stream::Stdout, Lines(vec![vec!["result"].try_into()?]))]
+        );
+        let frame = output.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "warning"));
+        assert!(!frame_contains(frame, "result"));
+        assert!(frame_contains(frame, "state"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_emit_leveled() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.set_emit_level(Level::Info);
+        console.set_retain_filtered(Some(10));
+
+        console.emit_leveled(Level::Debug, Lines(vec![vec!["resolving"].try_into()?]));
+        console.emit_leveled(Level::Warn, Lines(vec![vec!["deprecated"].try_into()?]));
+        // Lowered from elsewhere, e.g. by an input handler.
+        console.level_filter().more_verbose();
+        assert_eq!(console.emit_level(), Level::Debug);
+        console.emit_leveled(Level::Debug, Lines(vec![vec!["fetching"].try_into()?]));
+        console.emit_leveled(Level::Trace, Lines(vec![vec!["cache hit"].try_into()?]));
+        console.render(&root)?;
+
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "deprecated"));
+        assert!(frame_contains(frame, "fetching"));
+        assert!(!frame_contains(frame, "resolving"));
+        assert!(!frame_contains(frame, "cache hit"));
+
+        assert_eq!(
+            console.take_filtered(Level::Debug),
+            Lines(vec![vec!["resolving"].try_into()?])
+        );
+        assert!(console.take_filtered(Level::Trace).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cells() -> anyhow::Result<()> {
+        let mut console = test_console();
+        console.test_output_mut()?.cells = Some(Vec::new());
+
+        console.render(&Echo(Lines(vec![vec!["state: ok"].try_into()?])))?;
+        console.render(&Echo(Lines(vec![vec!["state: no"].try_into()?])))?;
+
+        let cells = console.test_output()?.cells.as_ref().context("No cells")?;
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].cells.dimensions(), Dimensions::new(80, 1));
+        assert_eq!(cells[0].changed.len(), 1);
+        assert_eq!(cells[1].metadata.index, 1);
+        assert_eq!(
+            cells[1].changed,
+            vec![ChangedRegion {
+                row: 0,
+                columns: 7..9
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_emit_replace_last() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+        let line =
+            |text: &str| -> anyhow::Result<Lines> { Ok(Lines(vec![vec![text].try_into()?])) };
+
+        // Nothing was emitted yet, so this is a plain emit.
+        console.emit_replace_last(line("1%")?);
+        console.render(&root)?;
+        assert!(console.last_emitted_replaceable);
+
+        // A drawn line is overwritten by moving up past it.
+        console.emit_replace_last(line("2%")?);
+        assert_eq!(console.rows_to_replace, 1);
+        console.render(&root)?;
+        assert_eq!(console.rows_to_replace, 0);
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "2%"));
+
+        // A pending line is replaced in the queue.
+        console.emit(line("done")?);
+        console.emit_replace_last(line("really done")?);
+        assert_eq!(console.to_emit, line("really done")?);
+        assert_eq!(console.rows_to_replace, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sticky() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?; 1]));
+
+        let id = console.pin(Lines(vec![vec!["warning"].try_into()?]));
+        console.render(&root)?;
+        console.render(&root)?;
+        assert!(console.last_frame().contains("warning"));
+
+        assert!(console.unpin(id));
+        assert!(!console.unpin(id));
+        assert_eq!(console.to_emit.len(), 1);
+
+        console.pin_for(Lines(vec![vec!["brief"].try_into()?]), Duration::ZERO);
+        console.render(&root)?;
+        assert!(console.sticky.is_empty());
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "brief"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_and_footer() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.set_header(Some(Box::new(Echo(Lines(
+            vec![vec!["header"].try_into()?],
+        )))));
+        console.set_footer(Some(Box::new(Echo(Lines(
+            vec![vec!["footer"].try_into()?],
+        )))));
+
+        console.render(&root)?;
+        assert_eq!(console.last_frame().find("footer"), Some((1, 0)));
+        assert!(!console.last_frame().contains("header"));
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "header"));
+        assert!(frame_contains(frame, "\x1b[2;80r"));
+
+        console.finalize(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_emit_transient() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+
+        console.emit_transient_renders(Lines(vec![vec!["retrying"].try_into()?]), 1);
+        console.render(&root)?;
+        assert!(console.last_frame().contains("retrying"));
+        console.render(&root)?;
+        assert!(!console.last_frame().contains("retrying"));
+        assert!(console.to_emit.is_empty());
+
+        console.emit_transient(Lines(vec![vec!["slow"].try_into()?]), Duration::ZERO);
+        console.render(&root)?;
+        assert!(!console.last_frame().contains("slow"));
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(!frame_contains(frame, "slow"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_frame() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![
+            vec!["header"].try_into()?,
+            vec!["status: ok"].try_into()?,
+        ]));
+        assert!(console.last_frame().is_empty());
+
+        console.emit(Lines(vec![vec!["emitted"].try_into()?]));
+        console.render(&root)?;
+        assert_eq!(console.last_frame().find("ok"), Some((1, 8)));
+        assert!(!console.last_frame().contains("emitted"));
+
+        console.clear()?;
+        assert!(console.last_frame().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_screenshot() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+
+        console.render(&root)?;
+        console.emit(Lines(vec![vec!["pending"].try_into()?]));
+
+        assert_eq!(console.screenshot(), root.0);
+        let screenshot = console.screenshot_with_pending();
+        let rows: Vec<_> = screenshot.iter().map(|l| l.to_unstyled()).collect();
+        assert_eq!(rows, vec!["pending", "state"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_observer() -> anyhow::Result<()> {
+        #[derive(Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl RenderObserver for Recorder {
+            fn on_frame_start(&mut self, mode: DrawMode) {
+                self.0.lock().unwrap().push(format!("start {:?}", mode));
+            }
+
+            fn on_frame_end(&mut self, stats: &FrameStats) {
+                assert!(stats.bytes > 0);
+                self.0.lock().unwrap().push(format!(
+                    "end {} emitted={} canvas={}",
+                    stats.index, stats.lines_emitted, stats.canvas_height
+                ));
+            }
+
+            fn on_frame_dropped(&mut self) {
+                self.0.lock().unwrap().push("dropped".to_owned());
+            }
+        }
+
+        struct Failing;
+
+        impl Component for Failing {
+            fn draw_unchecked(
+                &self,
+                _dimensions: Dimensions,
+                _mode: DrawMode,
+            ) -> anyhow::Result<Lines> {
+                Err(anyhow::anyhow!("broken"))
+            }
+        }
+
+        let mut console = test_console();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        console.add_observer(Box::new(Recorder(events.clone())));
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+
+        console.emit(Lines(vec![vec!["line"].try_into()?; 2]));
+        console.render(&root)?;
+        console.test_output_mut()?.should_render = false;
+        console.render(&root)?;
+        // Frames which fail to draw are ended too.
+        console.test_output_mut()?.should_render = true;
+        assert!(console.render(&Failing).is_err());
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "start Normal",
+                "end 0 emitted=2 canvas=1",
+                "dropped",
+                "start Normal",
+                "end 1 emitted=0 canvas=1",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dump_tree() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        console.pin(Lines(vec![vec!["pinned"].try_into()?]));
+
+        console.render(&root)?;
+        assert_eq!(console.dump_tree(), "Echo 5x1 -> 1 lines\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_policy() -> anyhow::Result<()> {
+        struct Failing;
+
+        impl Component for Failing {
+            fn draw_unchecked(
+                &self,
+                _dimensions: Dimensions,
+                _mode: DrawMode,
+            ) -> anyhow::Result<Lines> {
+                Err(anyhow::anyhow!("broken"))
+            }
+        }
+
+        let mut console = test_console();
+        let root = Split::new(
+            vec![
+                Box::new(Echo(Lines(vec![vec!["fine"].try_into()?]))) as Box<dyn Component>,
+                Box::new(Failing),
+            ],
+            Direction::Vertical,
+            SplitKind::Adaptive,
+        );
+        assert!(console.render(&root).is_err());
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        console.set_error_policy(ErrorPolicy::Isolate);
+        console.on_component_error({
+            let errors = errors.clone();
+            move |e| errors.lock().unwrap().push(e.error.to_string())
+        });
+        console.render(&root)?;
+        assert!(console.last_frame().contains("fine"));
+        assert!(console.last_frame().contains("broken"));
+        assert_eq!(*errors.lock().unwrap(), vec!["broken".to_owned()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sinks() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        let sink = RingBufferSink::new(10);
+        console.add_sink(Box::new(sink.clone()));
+
+        console.emit(Lines(vec![
+            vec!["one"].try_into()?,
+            vec!["two"].try_into()?,
+        ]));
+        assert!(sink.lines().is_empty());
+        console.render(&root)?;
+        console.emit(Lines(vec![vec!["three"].try_into()?]));
+        console.finalize(&root)?;
+
+        let rows: Vec<_> = sink.lines().iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["one", "two", "three"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_failing_sink() -> anyhow::Result<()> {
+        struct Failing;
+
+        impl EmitSink for Failing {
+            fn emit(&mut self, _lines: &Lines) -> anyhow::Result<()> {
+                Err(anyhow::anyhow!("disk full"))
+            }
+        }
+
+        let mut console = test_console();
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let sink = RingBufferSink::new(10);
+        console.add_sink(Box::new(Failing));
+        console.add_sink(Box::new(sink.clone()));
+        console.on_sink_error({
+            let errors = errors.clone();
+            move |e| errors.lock().unwrap().push(format!("{:#}", e))
+        });
+
+        console.emit(Lines(vec![vec!["one"].try_into()?]));
+        console.render(&root)?;
+        console.emit(Lines(vec![vec!["two"].try_into()?]));
+        console.render(&root)?;
+
+        // The frames were drawn, and the other sinks got the lines once.
+        let frame = console.test_output()?.frames.last().context("No frame")?;
+        assert!(frame_contains(frame, "two"));
+        let rows: Vec<_> = sink.lines().iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows, vec!["one", "two"]);
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec!["Emit sink 0 failed: disk full"; 2]
+        );
+
+        Ok(())
+    }
+
+    /// Check that a shared root can be mutated in place between renders.
+    #[test]
+    fn test_retained_root() -> anyhow::Result<()> {
+        let mut console = test_console();
+
+        let root = Arc::new(Mutex::new(Echo(Lines(vec![vec!["before"].try_into()?]))));
+
+        console.render(&root)?;
+        root.lock().unwrap().0 = Lines(vec![vec!["after"].try_into()?]);
+        console.render(&root)?;
+
+        let frames = &console.test_output()?.frames;
+        assert_eq!(frames.len(), 2);
+        assert!(frame_contains(&frames[0], "before"));
+        assert!(frame_contains(&frames[1], "after"));
+
+        Ok(())
+    }
 }
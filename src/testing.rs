@@ -8,15 +8,34 @@
  */
 
 //! Testing utilities for Superconsole.
+//!
+//! Components can be snapshot tested by drawing them with [`draw_to_strings`] and comparing the
+//! rows with [`assert_golden!`](crate::assert_golden) against a file checked in next to the test.
+//! Whole consoles can be tested by rendering to a [`test_console`], which records its frames.
 use std::any::Any;
+use std::env;
+use std::fs;
+use std::path::Path;
 
 use anyhow::Context as _;
 
+use crate::context::DrawContext;
+use crate::output::CellFrame;
+use crate::output::FrameMetadata;
+use crate::output::Stream;
 use crate::output::SuperConsoleOutput;
 use crate::superconsole::SuperConsole;
+use crate::Component;
 use crate::Dimensions;
+use crate::DrawMode;
+use crate::Lines;
+
+/// Set to `1` to have [`assert_golden`] write the actual output to golden files instead of
+/// comparing against them.
+pub const UPDATE_GOLDEN_ENV_VAR: &str = "SUPERCONSOLE_UPDATE_GOLDEN";
 
 /// An output for testing that doesn't do real I/O.
+#[non_exhaustive]
 pub struct TestOutput {
     /// Callers can modify this to indicate whether the output is blocked.
     pub should_render: bool,
@@ -24,6 +43,27 @@ pub struct TestOutput {
     pub terminal_size: Dimensions,
     /// The frames that were written to this output.
     pub frames: Vec<Vec<u8>>,
+    /// Metadata for the drawn frames written to this output. Frames written without metadata
+    /// (e.g. when clearing) have no entry here.
+    pub metadata: Vec<FrameMetadata>,
+    /// The frames passed as cells, if set. Set to `Some` to have the console produce them.
+    pub cells: Option<Vec<CellFrame>>,
+    /// The lines emitted to other streams than the canvas's, which is stderr.
+    pub streams: Vec<(Stream, Lines)>,
+}
+
+impl TestOutput {
+    /// An output reporting a terminal of `size`, which records everything written to it.
+    pub fn new(size: Dimensions) -> Self {
+        Self {
+            should_render: true,
+            terminal_size: size,
+            frames: Vec::new(),
+            metadata: Vec::new(),
+            cells: None,
+            streams: Vec::new(),
+        }
+    }
 }
 
 impl SuperConsoleOutput for TestOutput {
@@ -36,6 +76,31 @@ impl SuperConsoleOutput for TestOutput {
         Ok(())
     }
 
+    fn output_frame(&mut self, buffer: Vec<u8>, metadata: FrameMetadata) -> anyhow::Result<()> {
+        self.metadata.push(metadata);
+        self.output(buffer)
+    }
+
+    fn wants_cells(&self) -> bool {
+        self.cells.is_some()
+    }
+
+    fn output_cells(&mut self, frame: &CellFrame) -> anyhow::Result<()> {
+        if let Some(cells) = &mut self.cells {
+            cells.push(frame.clone());
+        }
+        Ok(())
+    }
+
+    fn stream(&self) -> Option<Stream> {
+        Some(Stream::Stderr)
+    }
+
+    fn output_to(&mut self, stream: Stream, lines: &Lines) -> anyhow::Result<()> {
+        self.streams.push((stream, lines.clone()));
+        Ok(())
+    }
+
     fn terminal_size(&self) -> anyhow::Result<Dimensions> {
         Ok(self.terminal_size)
     }
@@ -79,16 +144,96 @@ pub fn test_console() -> SuperConsole {
         width: 80,
         height: 80,
     };
-    SuperConsole::new_internal(
-        Some(size),
-        Box::new(TestOutput {
-            should_render: true,
-            terminal_size: size,
-            frames: Vec::new(),
-        }),
-    )
+    SuperConsole::new_internal(Some(size), Box::new(TestOutput::new(size)))
+}
+
+/// Runs `f` with glyphs beyond ASCII enabled or disabled on the current thread, whatever was
+/// detected or set process-wide, so that tests don't depend on the locale or on each other.
+pub fn with_unicode<R>(enabled: bool, f: impl FnOnce() -> R) -> R {
+    DrawContext {
+        unicode: Some(enabled),
+        ..DrawContext::default()
+    }
+    .scope(f)
+}
+
+/// Returns the rows of `lines` as plain text.
+pub fn lines_to_strings(lines: &Lines) -> Vec<String> {
+    lines.iter().map(|line| line.to_unstyled()).collect()
 }
 
+/// Draws `component` and returns its rows as plain text.
+pub fn draw_to_strings(
+    component: &dyn Component,
+    dimensions: Dimensions,
+    mode: DrawMode,
+) -> anyhow::Result<Vec<String>> {
+    Ok(lines_to_strings(&component.draw(dimensions, mode)?))
+}
+
+/// Draws `component` and returns its rows with ANSI escape sequences for their styles.
+pub fn draw_to_ansi_strings(
+    component: &dyn Component,
+    dimensions: Dimensions,
+    mode: DrawMode,
+) -> anyhow::Result<Vec<String>> {
+    let output = component.draw(dimensions, mode)?;
+    Ok(output.iter().map(|line| line.render()).collect())
+}
+
+/// Compares `actual` rows against the golden file at `path`, one row per line, panicking with both
+/// if they differ. With [`UPDATE_GOLDEN_ENV_VAR`] set to `1`, the file is written instead.
+/// See [`assert_golden!`](crate::assert_golden), which resolves paths against the crate root.
+pub fn assert_golden(path: impl AsRef<Path>, actual: &[String]) {
+    let path = path.as_ref();
+    let mut actual = actual.join("\n");
+    actual.push('\n');
+
+    if env::var(UPDATE_GOLDEN_ENV_VAR).as_deref() == Ok("1") {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create golden file directory");
+        }
+        fs::write(path, &actual).expect("Failed to write golden file");
+        return;
+    }
+
+    let expected = fs::read_to_string(path).unwrap_or_else(|e| {
+        panic!(
+            "Failed to read golden file `{}` ({}), run with {}=1 to create it",
+            path.display(),
+            e,
+            UPDATE_GOLDEN_ENV_VAR
+        )
+    });
+    assert!(
+        expected == actual,
+        "Output differs from golden file `{}`, run with {}=1 to update it\n\
+         --- expected\n{}--- actual\n{}",
+        path.display(),
+        UPDATE_GOLDEN_ENV_VAR,
+        expected,
+        actual
+    );
+}
+
+/// Compares rows, e.g. from [`draw_to_strings`], against a golden file whose path is relative to
+/// the root of the crate running the test. See [`assert_golden`](crate::testing::assert_golden).
+///
+/// ```ignore
+/// let rows = draw_to_strings(&component, Dimensions::new(40, 10), DrawMode::Normal)?;
+/// superconsole::assert_golden!("tests/golden/status.txt", rows);
+/// ```
+#[macro_export]
+macro_rules! assert_golden {
+    ($path:expr, $actual:expr) => {
+        $crate::testing::assert_golden(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+            &$actual,
+        )
+    };
+}
+
+/// Whether `needle` occurs in the bytes of a frame, e.g. from [`TestOutput::frames`].
 pub fn frame_contains(frame: &[u8], needle: impl AsRef<[u8]>) -> bool {
     let needle = needle.as_ref();
     for w in frame.windows(needle.len()) {
@@ -98,3 +243,38 @@ pub fn frame_contains(frame: &[u8], needle: impl AsRef<[u8]>) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::Line;
+    use crate::Span;
+
+    #[test]
+    fn test_draw_to_strings() -> anyhow::Result<()> {
+        let component = Echo(Lines(vec![Line::from_iter([
+            Span::new_unstyled("ok ")?,
+            Span::new_colored("42", crate::style::Color::Green)?,
+        ])]));
+        let dimensions = Dimensions::new(10, 10);
+        assert_eq!(
+            draw_to_strings(&component, dimensions, DrawMode::Normal)?,
+            vec!["ok 42"]
+        );
+        let ansi = draw_to_ansi_strings(&component, dimensions, DrawMode::Normal)?;
+        assert!(ansi[0].starts_with("ok \x1b["));
+        Ok(())
+    }
+
+    #[test]
+    fn test_golden() -> anyhow::Result<()> {
+        let path = env::temp_dir().join(format!("superconsole-golden-{}.txt", std::process::id()));
+        fs::write(&path, "ok 42\n")?;
+        assert_golden(&path, &["ok 42".to_owned()]);
+        let res = std::panic::catch_unwind(|| assert_golden(&path, &["ok 43".to_owned()]));
+        fs::remove_file(&path)?;
+        assert!(res.is_err());
+        Ok(())
+    }
+}
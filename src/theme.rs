@@ -0,0 +1,476 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Themes control the appearance of built-in components.
+//!
+//! The [`Palette`] holds the colors used to convey the state of things, with alternatives for color
+//! vision deficiencies. Built-in components always pair these colors with a [`Status`] symbol, so
+//! color is never the only signal.
+//!
+//! Other text drawn by built-in components, such as warnings, summaries and borders, is styled by
+//! [`StyleRole`], resolved through the [`Styles`] of the installed theme. Applications restyle
+//! them all at once with [`set_theme`] or
+//! [`SuperConsole::set_theme`](crate::SuperConsole::set_theme).
+//!
+//! With the `theme-config` feature, themes can also be loaded from TOML or JSON files, see
+//! [`ThemeFile`], and from the file named by `SUPERCONSOLE_THEME`.
+
+use std::env;
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+use crate::charset::unicode_enabled;
+use crate::charset::LineCharset;
+use crate::context;
+use crate::style::Color;
+use crate::style::ContentStyle;
+use crate::style::StyledContent;
+use crate::Span;
+
+/// Environment variable selecting the palette by name, see [`Palette::from_name`].
+pub const PALETTE_ENV_VAR: &str = "SUPERCONSOLE_PALETTE";
+/// Environment variable selecting the line charset by name, see [`LineCharset::from_name`].
+pub const CHARSET_ENV_VAR: &str = "SUPERCONSOLE_CHARSET";
+/// Environment variable naming a theme file, see [`Theme::from_env`].
+#[cfg(feature = "theme-config")]
+pub const THEME_ENV_VAR: &str = "SUPERCONSOLE_THEME";
+
+static THEME: RwLock<Option<Arc<Theme>>> = RwLock::new(None);
+
+pub use background::detect_background;
+pub use background::known_background;
+pub use background::query_background;
+pub use background::Background;
+pub use background::BACKGROUND_ENV_VAR;
+#[cfg(feature = "theme-config")]
+pub use config::ThemeFile;
+
+mod background;
+#[cfg(feature = "theme-config")]
+mod config;
+
+/// The appearance of built-in components.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Theme {
+    pub palette: Palette,
+    pub charset: LineCharset,
+    pub spinner: SpinnerStyle,
+    pub styles: Styles,
+}
+
+impl Theme {
+    /// A theme chosen from the environment: the palette comes from `SUPERCONSOLE_PALETTE` or the
+    /// terminal background, see [`Palette::from_env`], and the charset from `SUPERCONSOLE_CHARSET`
+    /// or, if unset, from what the terminal likely supports.
+    ///
+    /// With the `theme-config` feature, a valid theme file named by `SUPERCONSOLE_THEME` takes
+    /// precedence over all of these.
+    pub fn from_env() -> Self {
+        #[cfg(feature = "theme-config")]
+        if let Some(theme) = env::var_os(THEME_ENV_VAR).and_then(|path| Self::load(path).ok()) {
+            return theme;
+        }
+        let charset = env::var(CHARSET_ENV_VAR)
+            .ok()
+            .and_then(|name| LineCharset::from_name(&name))
+            .unwrap_or_else(LineCharset::detect);
+        let palette = Palette::from_env();
+        Self {
+            styles: Styles::from_palette(&palette),
+            palette,
+            charset,
+            spinner: SpinnerStyle::detect(),
+        }
+    }
+
+    pub fn style(&self, role: StyleRole) -> ContentStyle {
+        self.styles.get(role)
+    }
+}
+
+impl Default for Theme {
+    /// The default charset and spinner, with the palette suiting the terminal background, see
+    /// [`Palette::detect`].
+    fn default() -> Self {
+        let palette = Palette::detect();
+        Self {
+            styles: Styles::from_palette(&palette),
+            palette,
+            charset: LineCharset::default(),
+            spinner: SpinnerStyle::default(),
+        }
+    }
+}
+
+/// Installs the theme used by built-in components from now on, unless overridden by a console, see
+/// [`SuperConsole::set_theme`](crate::SuperConsole::set_theme).
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(theme));
+}
+
+/// The theme currently used by built-in components: that of the console drawing them, if any,
+/// see the [`context`](crate::context) module, or else the installed one.
+pub fn theme() -> Arc<Theme> {
+    context::current(|context| context.theme.clone()).unwrap_or_else(|| {
+        THEME
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_default()
+    })
+}
+
+/// The style of `role` in the installed theme.
+pub fn style(role: StyleRole) -> ContentStyle {
+    theme().style(role)
+}
+
+/// `text` in the style of `role` in the installed theme.
+pub fn styled_span(role: StyleRole, text: &str) -> Span {
+    Span::new_styled_lossy(StyledContent::new(style(role), text.to_owned()))
+}
+
+/// What a piece of text drawn by a built-in component is for, deciding its style.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StyleRole {
+    Info,
+    Warn,
+    Error,
+    /// Draws attention, e.g. to what is in progress.
+    Highlight,
+    /// Borders and frames around components.
+    Border,
+    /// Secondary text, such as counts and summaries of dropped lines.
+    Dim,
+}
+
+impl StyleRole {
+    /// Looks up a role by name: `info`, `warn`, `error`, `highlight`, `border` or `dim`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            "highlight" => Some(Self::Highlight),
+            "border" => Some(Self::Border),
+            "dim" => Some(Self::Dim),
+            _ => None,
+        }
+    }
+}
+
+/// The style of each [`StyleRole`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Styles {
+    pub info: ContentStyle,
+    pub warn: ContentStyle,
+    pub error: ContentStyle,
+    pub highlight: ContentStyle,
+    pub border: ContentStyle,
+    pub dim: ContentStyle,
+}
+
+impl Default for Styles {
+    fn default() -> Self {
+        Self::from_palette(&Palette::default())
+    }
+}
+
+impl Styles {
+    /// Colors each role with the matching color of `palette`. Borders are left unstyled.
+    pub fn from_palette(palette: &Palette) -> Self {
+        let fg = |color| ContentStyle {
+            foreground_color: Some(color),
+            ..ContentStyle::default()
+        };
+        Self {
+            info: fg(palette.info),
+            warn: fg(palette.warning),
+            error: fg(palette.failure),
+            highlight: fg(palette.highlight),
+            border: ContentStyle::default(),
+            dim: fg(palette.pending),
+        }
+    }
+
+    pub fn get(&self, role: StyleRole) -> ContentStyle {
+        match role {
+            StyleRole::Info => self.info,
+            StyleRole::Warn => self.warn,
+            StyleRole::Error => self.error,
+            StyleRole::Highlight => self.highlight,
+            StyleRole::Border => self.border,
+            StyleRole::Dim => self.dim,
+        }
+    }
+
+    pub fn get_mut(&mut self, role: StyleRole) -> &mut ContentStyle {
+        match role {
+            StyleRole::Info => &mut self.info,
+            StyleRole::Warn => &mut self.warn,
+            StyleRole::Error => &mut self.error,
+            StyleRole::Highlight => &mut self.highlight,
+            StyleRole::Border => &mut self.border,
+            StyleRole::Dim => &mut self.dim,
+        }
+    }
+}
+
+/// The frames cycled through by spinners.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SpinnerStyle {
+    #[default]
+    Braille,
+    Dots,
+    Ascii,
+}
+
+impl SpinnerStyle {
+    pub fn frames(self) -> &'static [&'static str] {
+        match self {
+            SpinnerStyle::Braille => &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            SpinnerStyle::Dots => &["·", "•", "●", "•"],
+            SpinnerStyle::Ascii => &["-", "\\", "|", "/"],
+        }
+    }
+
+    /// Looks up a spinner style by name: `braille`, `dots` or `ascii`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "braille" => Some(Self::Braille),
+            "dots" => Some(Self::Dots),
+            "ascii" => Some(Self::Ascii),
+            _ => None,
+        }
+    }
+
+    /// Picks [`Braille`](Self::Braille) unless the terminal likely can't display it, in which case
+    /// [`Ascii`](Self::Ascii) is used. See [`unicode_enabled`].
+    pub fn detect() -> Self {
+        if unicode_enabled() {
+            Self::Braille
+        } else {
+            Self::Ascii
+        }
+    }
+}
+
+/// The state of a task or message.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Status {
+    Success,
+    Failure,
+    Warning,
+    Info,
+    Pending,
+}
+
+impl Status {
+    /// A symbol conveying the status without relying on color.
+    /// ASCII symbols are used if [`unicode_enabled`] is false.
+    pub fn symbol(self) -> &'static str {
+        match (self, unicode_enabled()) {
+            (Status::Success, true) => "✓",
+            (Status::Success, false) => "+",
+            (Status::Failure, true) => "✗",
+            (Status::Failure, false) => "x",
+            (Status::Warning, _) => "!",
+            (Status::Info, _) => "i",
+            (Status::Pending, true) => "…",
+            (Status::Pending, false) => "...",
+        }
+    }
+}
+
+/// The colors associated with each [`Status`], plus one to draw attention.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Palette {
+    pub success: Color,
+    pub failure: Color,
+    pub warning: Color,
+    pub info: Color,
+    pub pending: Color,
+    pub highlight: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Palette {
+    /// The usual green, red and yellow.
+    pub fn standard() -> Self {
+        Self {
+            success: Color::Green,
+            failure: Color::Red,
+            warning: Color::Yellow,
+            info: Color::Blue,
+            pending: Color::DarkGrey,
+            highlight: Color::Cyan,
+        }
+    }
+
+    /// The standard colors, darkened to stay legible on light backgrounds.
+    pub fn light() -> Self {
+        Self {
+            success: Color::DarkGreen,
+            failure: Color::DarkRed,
+            warning: Color::DarkYellow,
+            info: Color::DarkBlue,
+            pending: Color::DarkGrey,
+            highlight: Color::DarkMagenta,
+        }
+    }
+
+    /// Avoids red/green distinctions, for deuteranopia (reduced green sensitivity).
+    /// Uses blue for success and vermillion for failure.
+    pub fn deuteranopia() -> Self {
+        Self {
+            success: rgb(0, 114, 178),
+            failure: rgb(213, 94, 0),
+            warning: rgb(240, 228, 66),
+            info: rgb(86, 180, 233),
+            pending: Color::DarkGrey,
+            highlight: rgb(204, 121, 167),
+        }
+    }
+
+    /// Avoids red/green distinctions, for protanopia (reduced red sensitivity).
+    /// Reds appear dark to protanopes, so failure uses a brighter orange.
+    pub fn protanopia() -> Self {
+        Self {
+            failure: rgb(230, 159, 0),
+            ..Self::deuteranopia()
+        }
+    }
+
+    /// Avoids blue/yellow distinctions, for tritanopia.
+    /// Uses teal for success, red for failure and magenta for warnings.
+    pub fn tritanopia() -> Self {
+        Self {
+            success: rgb(0, 158, 115),
+            failure: rgb(220, 50, 47),
+            warning: rgb(204, 121, 167),
+            info: rgb(0, 158, 115),
+            pending: Color::DarkGrey,
+            highlight: rgb(213, 94, 0),
+        }
+    }
+
+    /// Looks up a palette by name: `standard`, `light`, `deuteranopia`, `protanopia` or
+    /// `tritanopia`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "standard" | "default" => Some(Self::standard()),
+            "light" => Some(Self::light()),
+            "deuteranopia" => Some(Self::deuteranopia()),
+            "protanopia" => Some(Self::protanopia()),
+            "tritanopia" => Some(Self::tritanopia()),
+            _ => None,
+        }
+    }
+
+    /// The palette named by the `SUPERCONSOLE_PALETTE` environment variable, or else the
+    /// [detected](Self::detect) one.
+    pub fn from_env() -> Self {
+        env::var(PALETTE_ENV_VAR)
+            .ok()
+            .and_then(|name| Self::from_name(&name))
+            .unwrap_or_else(Self::detect)
+    }
+
+    /// The [`light`](Self::light) palette on light backgrounds, and the standard one elsewhere.
+    /// This doesn't query the terminal, see [`known_background`].
+    pub fn detect() -> Self {
+        match known_background() {
+            Some(Background::Light) => Self::light(),
+            _ => Self::standard(),
+        }
+    }
+
+    pub fn color(&self, status: Status) -> Color {
+        match status {
+            Status::Success => self.success,
+            Status::Failure => self.failure,
+            Status::Warning => self.warning,
+            Status::Info => self.info,
+            Status::Pending => self.pending,
+        }
+    }
+
+    /// The symbol of `status` in its color.
+    pub fn status_span(&self, status: Status) -> Span {
+        Span::new_styled_lossy(StyledContent::new(
+            ContentStyle {
+                foreground_color: Some(self.color(status)),
+                ..ContentStyle::default()
+            },
+            status.symbol().to_owned(),
+        ))
+    }
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_unicode;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(
+            Palette::from_name("Deuteranopia"),
+            Some(Palette::deuteranopia())
+        );
+        assert_eq!(Palette::from_name("default"), Some(Palette::standard()));
+        assert_eq!(Palette::from_name("sepia"), None);
+    }
+
+    #[test]
+    fn test_styles() {
+        let styles = Styles::from_palette(&Palette::tritanopia());
+        assert_eq!(
+            styles.get(StyleRole::Warn).foreground_color,
+            Some(rgb(204, 121, 167))
+        );
+        assert_eq!(styles.get(StyleRole::Border), ContentStyle::default());
+        assert_eq!(StyleRole::from_name("Warning"), Some(StyleRole::Warn));
+        assert_eq!(StyleRole::from_name("title"), None);
+
+        // The installed theme falls back to the default.
+        assert_eq!(
+            style(StyleRole::Dim).foreground_color,
+            Some(Color::DarkGrey)
+        );
+    }
+
+    #[test]
+    fn test_status_span() {
+        with_unicode(true, || {
+            let span = Palette::deuteranopia().status_span(Status::Failure);
+            assert_eq!(
+                span.fmt_for_test().to_string(),
+                "<span fg=rgb(213, 94, 0)>✗</span>"
+            );
+        });
+        with_unicode(false, || {
+            let span = Palette::deuteranopia().status_span(Status::Pending);
+            assert_eq!(
+                span.fmt_for_test().to_string(),
+                "<span fg=dark_grey>...</span>"
+            );
+        })
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Detection of whether the terminal has a light or dark background.
+
+use std::env;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// Environment variable overriding the detected background: `light` or `dark`.
+pub const BACKGROUND_ENV_VAR: &str = "SUPERCONSOLE_BACKGROUND";
+
+/// How long to wait for the terminal to answer a background color query.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Whether the terminal background is light or dark.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Background {
+    Light,
+    Dark,
+}
+
+impl Background {
+    /// Classifies a background color by its perceived brightness.
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        let luma = 0.2126 * f64::from(r) + 0.7152 * f64::from(g) + 0.0722 * f64::from(b);
+        if luma > 127.5 {
+            Background::Light
+        } else {
+            Background::Dark
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "light" => Some(Background::Light),
+            "dark" => Some(Background::Dark),
+            _ => None,
+        }
+    }
+}
+
+const UNKNOWN: u8 = 0;
+const UNDETECTED: u8 = 1;
+const LIGHT: u8 = 2;
+const DARK: u8 = 3;
+
+/// The detected background, or `UNKNOWN` if detection has not run yet.
+static DETECTED: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// The terminal background, or `None` if it could not be detected. This is decided by, in order:
+/// `SUPERCONSOLE_BACKGROUND`, `COLORFGBG` as set by some terminals, or querying the terminal with
+/// OSC 11. The result is cached, so the terminal is queried at most once per process.
+///
+/// Consoles run this when they are built, before they take over the terminal, so that the query
+/// doesn't compete with their input.
+pub fn detect_background() -> Option<Background> {
+    match DETECTED.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let background = background_from_env().or_else(|| query_background(QUERY_TIMEOUT));
+            let state = match background {
+                Some(Background::Light) => LIGHT,
+                Some(Background::Dark) => DARK,
+                None => UNDETECTED,
+            };
+            DETECTED.store(state, Ordering::Relaxed);
+            background
+        }
+        LIGHT => Some(Background::Light),
+        DARK => Some(Background::Dark),
+        _ => None,
+    }
+}
+
+/// The terminal background as far as it is known without querying the terminal: that
+/// [detected](detect_background) before, if any, or else that given by the environment.
+pub fn known_background() -> Option<Background> {
+    match DETECTED.load(Ordering::Relaxed) {
+        UNKNOWN => background_from_env(),
+        LIGHT => Some(Background::Light),
+        DARK => Some(Background::Dark),
+        _ => None,
+    }
+}
+
+fn background_from_env() -> Option<Background> {
+    env::var(BACKGROUND_ENV_VAR)
+        .ok()
+        .and_then(|name| Background::from_name(&name))
+        .or_else(|| env::var("COLORFGBG").ok().and_then(|v| from_colorfgbg(&v)))
+}
+
+/// `COLORFGBG` holds the foreground and background as ANSI color indices, e.g. `15;0`.
+fn from_colorfgbg(value: &str) -> Option<Background> {
+    match value.rsplit(';').next()?.trim().parse::<u8>().ok()? {
+        7 | 9..=15 => Some(Background::Light),
+        0..=6 | 8 => Some(Background::Dark),
+        _ => None,
+    }
+}
+
+/// Parses a reply to OSC 11, e.g. `ESC ] 11 ; rgb:ffff/ffff/ffff BEL`.
+fn parse_osc11_reply(reply: &str) -> Option<(u8, u8, u8)> {
+    let color = reply.split("rgb:").nth(1)?;
+    let color = color.trim_end_matches(['\x07', '\x1b', '\\']);
+    let mut channels = color.split('/').map(|channel| {
+        // Each channel has 1 to 4 hex digits, scaled to their own range.
+        let digits = u32::try_from(channel.len())
+            .ok()
+            .filter(|n| (1..=4).contains(n))?;
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        Some((value * 255 / (16u32.pow(digits) - 1)) as u8)
+    });
+    let res = (channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(res)
+}
+
+/// Asks the terminal for its background color with OSC 11, waiting at most `timeout` for the
+/// reply. The terminal stays in raw mode until the reply is read or the timeout elapses, so that
+/// the reply is neither echoed nor left waiting for a newline. A terminal which replies only after
+/// the timeout leaves the reply to be read as input. A terminal already in raw mode is left alone,
+/// as its input belongs to whoever entered it.
+#[cfg(unix)]
+pub fn query_background(timeout: Duration) -> Option<Background> {
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::io::IsTerminal;
+    use std::io::Read;
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    use crate::backend;
+
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() || backend::is_raw_mode_enabled() {
+        return None;
+    }
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    backend::enable_raw_mode().ok()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    if tty
+        .write_all(b"\x1b]11;?\x07")
+        .and_then(|_| tty.flush())
+        .is_ok()
+    {
+        let mut byte = [0];
+        while reply.len() < 64 {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let mut fd = libc::pollfd {
+                fd: tty.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let millis = libc::c_int::try_from(remaining.as_millis()).unwrap_or(libc::c_int::MAX);
+            // SAFETY: `fd` is a single valid `pollfd` which outlives the call.
+            if unsafe { libc::poll(&mut fd, 1, millis) } <= 0 || tty.read_exact(&mut byte).is_err()
+            {
+                break;
+            }
+            reply.push(byte[0]);
+            if byte[0] == b'\x07' || reply.ends_with(b"\x1b\\") {
+                break;
+            }
+        }
+    }
+    let _ = backend::disable_raw_mode();
+
+    let (r, g, b) = parse_osc11_reply(&String::from_utf8_lossy(&reply))?;
+    Some(Background::from_rgb(r, g, b))
+}
+
+/// Asks the terminal for its background color with OSC 11. Not supported on this platform.
+#[cfg(not(unix))]
+pub fn query_background(_timeout: Duration) -> Option<Background> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_osc11_reply() {
+        assert_eq!(
+            parse_osc11_reply("\x1b]11;rgb:ffff/ffff/ffff\x07"),
+            Some((255, 255, 255))
+        );
+        assert_eq!(
+            parse_osc11_reply("\x1b]11;rgb:1e/1e/2e\x1b\\"),
+            Some((30, 30, 46))
+        );
+        assert_eq!(parse_osc11_reply("\x1b]11;rgb:ff/ff\x07"), None);
+        assert_eq!(parse_osc11_reply("garbage"), None);
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(Background::from_rgb(255, 255, 255), Background::Light);
+        assert_eq!(Background::from_rgb(30, 30, 46), Background::Dark);
+        assert_eq!(from_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(from_colorfgbg("15;default;0"), Some(Background::Dark));
+    }
+}
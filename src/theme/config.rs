@@ -0,0 +1,359 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Loading themes from TOML or JSON files.
+//!
+//! A theme file starts from a named palette, charset and spinner style, and may override
+//! individual colors by status and styles by [`StyleRole`]:
+//!
+//! ```toml
+//! palette = "deuteranopia"
+//! charset = "rounded"
+//! spinner = "dots"
+//!
+//! [colors]
+//! failure = "#d55e00"
+//! pending = "dark_grey"
+//! highlight = "208"
+//!
+//! [styles]
+//! error = "bold red"
+//! border = "dark_grey"
+//! highlight = "black on cyan"
+//! ```
+//!
+//! Colors are either `#rrggbb`, an ANSI 256-color index, or a name such as `red` or `dark_blue`.
+//! Styles are made of attributes (`bold`, `dim`, `italic`, `underlined`, `reverse` or
+//! `crossed_out`), a foreground color, and a background color following `on`. Styles which are not
+//! overridden follow the colors.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use anyhow::Context as _;
+use serde::Deserialize;
+
+use crate::charset::LineCharset;
+use crate::style::Attribute;
+use crate::style::Color;
+use crate::style::ContentStyle;
+use crate::theme::Palette;
+use crate::theme::SpinnerStyle;
+use crate::theme::StyleRole;
+use crate::theme::Styles;
+use crate::theme::Theme;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeConfig {
+    palette: Option<String>,
+    charset: Option<String>,
+    spinner: Option<String>,
+    colors: ColorsConfig,
+    styles: StylesConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ColorsConfig {
+    success: Option<String>,
+    failure: Option<String>,
+    warning: Option<String>,
+    info: Option<String>,
+    pending: Option<String>,
+    highlight: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StylesConfig {
+    info: Option<String>,
+    warn: Option<String>,
+    error: Option<String>,
+    highlight: Option<String>,
+    border: Option<String>,
+    dim: Option<String>,
+}
+
+impl ThemeConfig {
+    fn into_theme(self) -> anyhow::Result<Theme> {
+        let mut palette = match &self.palette {
+            Some(name) => {
+                Palette::from_name(name).with_context(|| format!("Unknown palette `{}`", name))?
+            }
+            None => Palette::detect(),
+        };
+        let charset = match &self.charset {
+            Some(name) => LineCharset::from_name(name)
+                .with_context(|| format!("Unknown charset `{}`", name))?,
+            None => LineCharset::default(),
+        };
+        let spinner = match &self.spinner {
+            Some(name) => SpinnerStyle::from_name(name)
+                .with_context(|| format!("Unknown spinner style `{}`", name))?,
+            None => SpinnerStyle::default(),
+        };
+
+        let colors = self.colors;
+        for (color, value) in [
+            (&mut palette.success, colors.success),
+            (&mut palette.failure, colors.failure),
+            (&mut palette.warning, colors.warning),
+            (&mut palette.info, colors.info),
+            (&mut palette.pending, colors.pending),
+            (&mut palette.highlight, colors.highlight),
+        ] {
+            if let Some(value) = value {
+                *color = parse_color(&value)?;
+            }
+        }
+
+        let mut styles = Styles::from_palette(&palette);
+        let overrides = self.styles;
+        for (role, value) in [
+            (StyleRole::Info, overrides.info),
+            (StyleRole::Warn, overrides.warn),
+            (StyleRole::Error, overrides.error),
+            (StyleRole::Highlight, overrides.highlight),
+            (StyleRole::Border, overrides.border),
+            (StyleRole::Dim, overrides.dim),
+        ] {
+            if let Some(value) = value {
+                *styles.get_mut(role) = parse_style(&value)?;
+            }
+        }
+
+        Ok(Theme {
+            palette,
+            charset,
+            spinner,
+            styles,
+        })
+    }
+}
+
+fn parse_style(value: &str) -> anyhow::Result<ContentStyle> {
+    let mut style = ContentStyle::default();
+    let mut words = value.split_whitespace();
+    while let Some(word) = words.next() {
+        let attribute = match word {
+            "bold" => Attribute::Bold,
+            "dim" => Attribute::Dim,
+            "italic" => Attribute::Italic,
+            "underlined" => Attribute::Underlined,
+            "reverse" => Attribute::Reverse,
+            "crossed_out" => Attribute::CrossedOut,
+            "on" => {
+                let color = words
+                    .next()
+                    .with_context(|| format!("Missing background color in style `{}`", value))?;
+                style.background_color = Some(parse_color(color)?);
+                continue;
+            }
+            color => {
+                style.foreground_color = Some(parse_color(color)?);
+                continue;
+            }
+        };
+        style.attributes.set(attribute);
+    }
+    Ok(style)
+}
+
+fn parse_color(value: &str) -> anyhow::Result<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let rgb = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 6)
+            .with_context(|| format!("Invalid hex color `{}`", value))?;
+        return Ok(Color::Rgb {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+        });
+    }
+    if let Ok(index) = value.parse::<u8>() {
+        return Ok(Color::AnsiValue(index));
+    }
+    Color::try_from(value).map_err(|()| anyhow::anyhow!("Unknown color `{}`", value))
+}
+
+impl Theme {
+    /// Parses a theme from TOML. See the [module documentation](self) for the format.
+    pub fn from_toml(config: &str) -> anyhow::Result<Self> {
+        let config: ThemeConfig = toml::from_str(config).context("Error parsing TOML theme")?;
+        config.into_theme()
+    }
+
+    /// Parses a theme from JSON, with the same structure as [`from_toml`](Self::from_toml).
+    pub fn from_json(config: &str) -> anyhow::Result<Self> {
+        let config: ThemeConfig =
+            serde_json::from_str(config).context("Error parsing JSON theme")?;
+        config.into_theme()
+    }
+
+    /// Loads a theme from a file, which is parsed as JSON if it has a `.json` extension, and as
+    /// TOML otherwise.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let config = fs::read_to_string(path)
+            .with_context(|| format!("Error reading theme `{}`", path.display()))?;
+        let theme = if path.extension().is_some_and(|e| e == "json") {
+            Self::from_json(&config)
+        } else {
+            Self::from_toml(&config)
+        };
+        theme.with_context(|| format!("Error loading theme `{}`", path.display()))
+    }
+}
+
+/// A theme loaded from a file, which can be reloaded when the file changes so that users can tweak
+/// the appearance of a tool while it runs.
+#[derive(Debug)]
+pub struct ThemeFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    theme: Theme,
+}
+
+impl ThemeFile {
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let modified = modified(&path);
+        let theme = Theme::load(&path)?;
+        Ok(Self {
+            path,
+            modified,
+            theme,
+        })
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Reloads the theme if the file was modified since it was last loaded, e.g. once per tick.
+    /// Returns whether the theme changed. If the modified file is invalid, the current theme is
+    /// kept and the error is returned; it is not reported again until the file changes.
+    pub fn reload_if_changed(&mut self) -> anyhow::Result<bool> {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return Ok(false);
+        }
+        self.modified = modified;
+
+        let theme = Theme::load(&self.path)?;
+        let changed = theme != self.theme;
+        self.theme = theme;
+        Ok(changed)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() -> anyhow::Result<()> {
+        let theme = Theme::from_toml(
+            r##"
+            palette = "deuteranopia"
+            charset = "ascii"
+            spinner = "dots"
+
+            [colors]
+            failure = "#ff0000"
+            pending = "dark_grey"
+            highlight = "208"
+            "##,
+        )?;
+        assert_eq!(
+            theme.palette,
+            Palette {
+                failure: Color::Rgb { r: 255, g: 0, b: 0 },
+                highlight: Color::AnsiValue(208),
+                ..Palette::deuteranopia()
+            }
+        );
+        assert_eq!(theme.charset, LineCharset::ASCII);
+        assert_eq!(theme.spinner, SpinnerStyle::Dots);
+        // Styles which aren't overridden follow the colors.
+        assert_eq!(
+            theme.styles.error.foreground_color,
+            Some(Color::Rgb { r: 255, g: 0, b: 0 })
+        );
+
+        assert_eq!(Theme::from_toml("")?, Theme::default());
+        assert!(Theme::from_toml(r#"palette = "sepia""#).is_err());
+        assert!(Theme::from_toml(r#"colour = "red""#).is_err());
+        assert!(Theme::from_toml("[colors]\nsuccess = \"#12345\"").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_styles() -> anyhow::Result<()> {
+        let theme = Theme::from_toml(
+            r#"
+            [styles]
+            error = "bold red"
+            highlight = "black on cyan"
+            "#,
+        )?;
+        assert_eq!(theme.style(StyleRole::Error).foreground_color, Some(Color::Red));
+        assert!(theme.style(StyleRole::Error).attributes.has(Attribute::Bold));
+        assert_eq!(
+            theme.style(StyleRole::Highlight),
+            ContentStyle {
+                foreground_color: Some(Color::Black),
+                background_color: Some(Color::Cyan),
+                ..ContentStyle::default()
+            }
+        );
+        assert_eq!(theme.style(StyleRole::Dim).foreground_color, Some(Color::DarkGrey));
+
+        assert!(Theme::from_toml("[styles]\nerror = \"red on\"").is_err());
+        assert!(Theme::from_toml("[styles]\ntitle = \"red\"").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_json() -> anyhow::Result<()> {
+        let theme = Theme::from_json(r#"{"colors": {"success": "blue"}}"#)?;
+        assert_eq!(theme.palette.success, Color::Blue);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reload() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("superconsole-theme-{}.toml", std::process::id()));
+        fs::write(&path, r#"charset = "ascii""#)?;
+        let mut file = ThemeFile::open(&path)?;
+        assert_eq!(file.theme().charset, LineCharset::ASCII);
+        assert!(!file.reload_if_changed()?);
+
+        // Force a different modification time, as some filesystems have coarse timestamps.
+        fs::write(&path, r#"charset = "heavy""#)?;
+        file.modified = None;
+        assert!(file.reload_if_changed()?);
+        assert_eq!(file.theme().charset, LineCharset::HEAVY);
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Integration with `tracing`, behind the `tracing` feature.
+//!
+//! A [`SuperConsoleLayer`] collects events, formatted with level colors, along with the spans
+//! which are open. Since the console is owned by the render loop, events are buffered in a shared
+//! [`TracingHandle`], which the loop passes on with [`TracingHandle::emit_to`] before each
+//! render. The buffer is bounded like the console's own queue. The handle is also a component
+//! which draws the open spans.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Instant;
+
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::span;
+use tracing::Event;
+use tracing::Level;
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use crate::emit;
+use crate::emit::EmitCapacity;
+use crate::emit::OverflowPolicy;
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::Component;
+use crate::Dimensions;
+use crate::DrawMode;
+use crate::Line;
+use crate::Lines;
+use crate::Span;
+use crate::SuperConsole;
+
+/// The number of lines a [`TracingHandle`] keeps by default.
+const DEFAULT_CAPACITY: usize = 10_000;
+
+#[derive(Debug)]
+struct Inner {
+    lines: Lines,
+    /// The lines summarized by the first one, see [`EmitCapacity`].
+    dropped: u64,
+    capacity: EmitCapacity,
+    spans: Vec<(span::Id, &'static str, Instant)>,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Self {
+            lines: Lines::new(),
+            dropped: 0,
+            capacity: EmitCapacity::new(DEFAULT_CAPACITY, OverflowPolicy::Summarize),
+            spans: Vec::new(),
+        }
+    }
+}
+
+/// Shared between a [`SuperConsoleLayer`] and the render loop.
+#[derive(Debug, Clone, Default)]
+pub struct TracingHandle(Arc<Mutex<Inner>>);
+
+impl TracingHandle {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn push(&self, line: Line) {
+        let mut inner = self.lock();
+        let Inner {
+            lines,
+            dropped,
+            capacity,
+            ..
+        } = &mut *inner;
+        lines.push(line);
+        capacity.enforce(lines, dropped);
+    }
+
+    /// Takes the lines of the events recorded since the last call.
+    pub fn take_lines(&self) -> Lines {
+        let mut inner = self.lock();
+        inner.dropped = 0;
+        std::mem::take(&mut inner.lines)
+    }
+
+    /// Emits the events recorded since the last call to `console`.
+    pub fn emit_to(&self, console: &mut SuperConsole) {
+        console.emit(self.take_lines());
+    }
+
+    /// The names of the open spans, oldest first.
+    pub fn active_spans(&self) -> Vec<&'static str> {
+        self.lock().spans.iter().map(|(_, name, _)| *name).collect()
+    }
+}
+
+/// Draws the open spans, one per line, with how long they have been open.
+impl Component for TracingHandle {
+    fn draw_unchecked(&self, _dimensions: Dimensions, _mode: DrawMode) -> anyhow::Result<Lines> {
+        let inner = self.lock();
+        Ok(inner
+            .spans
+            .iter()
+            .map(|(_, name, start)| {
+                Line::from_iter([Span::sanitized(format!(
+                    "{} [{}]",
+                    name,
+                    strings().duration(start.elapsed())
+                ))])
+            })
+            .collect())
+    }
+}
+
+/// A `tracing` layer recording events and open spans into a [`TracingHandle`].
+#[derive(Debug, Clone, Default)]
+pub struct SuperConsoleLayer {
+    handle: TracingHandle,
+}
+
+impl SuperConsoleLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bounds the events waiting for the render loop, 10,000 lines summarizing the oldest by
+    /// default.
+    pub fn capacity(self, capacity: EmitCapacity) -> Self {
+        self.handle.lock().capacity = capacity;
+        self
+    }
+
+    pub fn handle(&self) -> TracingHandle {
+        self.handle.clone()
+    }
+}
+
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl Visit for EventVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+fn emit_level(level: Level) -> emit::Level {
+    match level {
+        Level::ERROR => emit::Level::Error,
+        Level::WARN => emit::Level::Warn,
+        Level::INFO => emit::Level::Info,
+        Level::DEBUG => emit::Level::Debug,
+        Level::TRACE => emit::Level::Trace,
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SuperConsoleLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let level = *metadata.level();
+
+        let mut text = format!("{}: {}", metadata.target(), visitor.message);
+        for field in visitor.fields {
+            text.push(' ');
+            text.push_str(&field);
+        }
+        let mut line = Line::from_iter([styled_span(
+            emit_level(level).style_role(),
+            &format!("{:>5}", level),
+        )]);
+        line.push(Span::sanitized(format!(" {}", text)));
+        self.handle.push(line);
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, _ctx: Context<'_, S>) {
+        self.handle
+            .lock()
+            .spans
+            .push((id.clone(), attrs.metadata().name(), Instant::now()));
+    }
+
+    fn on_close(&self, id: span::Id, _ctx: Context<'_, S>) {
+        self.handle.lock().spans.retain(|(span, _, _)| *span != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn test_layer() {
+        let layer = SuperConsoleLayer::new();
+        let handle = layer.handle();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("compile");
+            let _guard = span.enter();
+            tracing::warn!(crate_name = "foo", "slow build");
+            assert_eq!(handle.active_spans(), vec!["compile"]);
+        });
+
+        assert!(handle.active_spans().is_empty());
+        let lines = handle.take_lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(
+            lines.0[0].to_unstyled(),
+            " WARN superconsole::trace::tests: slow build crate_name=\"foo\""
+        );
+        assert!(handle.take_lines().is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let layer =
+            SuperConsoleLayer::new().capacity(EmitCapacity::new(3, OverflowPolicy::Summarize));
+        let handle = layer.handle();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            for i in 0..10 {
+                tracing::info!("step {}", i);
+            }
+        });
+
+        let rows: Vec<_> = handle.take_lines().iter().map(Line::to_unstyled).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], "8 lines dropped");
+        assert!(rows[2].ends_with("step 9"));
+    }
+}
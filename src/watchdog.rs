@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Detection of stalled renders.
+//!
+//! If whatever drives the console stops calling [`render`](crate::SuperConsole::render), the UI
+//! silently freezes while showing stale data. Every console beats a [`Heartbeat`] when it renders,
+//! which a [`Watchdog`] can monitor from another thread. Heartbeats can also be created for other
+//! work (e.g. a thread producing the state that is drawn), and shown as a badge with
+//! [`Stalled`](crate::components::Stalled).
+
+use std::cmp;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Context as _;
+use crossbeam_channel::bounded;
+use crossbeam_channel::RecvTimeoutError;
+use crossbeam_channel::Sender;
+
+use crate::charset::warning_sign;
+use crate::emit::Level;
+use crate::handle::SuperConsoleHandle;
+use crate::strings::strings;
+use crate::theme::styled_span;
+use crate::theme::StyleRole;
+use crate::Line;
+use crate::Lines;
+
+/// How often the watchdog checks its heartbeat, relative to the timeout.
+const POLLS_PER_TIMEOUT: u32 = 4;
+/// Lower bound on the interval between checks, so tiny timeouts don't spin.
+const MINIMUM_POLL: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+struct HeartbeatInner {
+    start: Instant,
+    /// Nanoseconds since `start` at which the last beat happened.
+    last_beat: AtomicU64,
+}
+
+/// A cheaply cloneable record of when some work last made progress.
+#[derive(Clone, Debug)]
+pub struct Heartbeat(Arc<HeartbeatInner>);
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Heartbeat {
+    /// A new heartbeat, which counts as having just beaten.
+    pub fn new() -> Self {
+        Self(Arc::new(HeartbeatInner {
+            start: Instant::now(),
+            last_beat: AtomicU64::new(0),
+        }))
+    }
+
+    /// Records that progress was made.
+    pub fn beat(&self) {
+        let nanos = self.0.start.elapsed().as_nanos() as u64;
+        self.0.last_beat.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// How long it has been since the last beat.
+    pub fn elapsed(&self) -> Duration {
+        let last_beat = Duration::from_nanos(self.0.last_beat.load(Ordering::Relaxed));
+        self.0.start.elapsed().saturating_sub(last_beat)
+    }
+
+    /// Whether there has been no beat for at least `timeout`.
+    pub fn is_stalled(&self, timeout: Duration) -> bool {
+        self.elapsed() >= timeout
+    }
+}
+
+/// Monitors a [`Heartbeat`] on a background thread, and reacts when it stalls.
+/// The reaction happens once per stall: the watchdog re-arms when the heartbeat beats again.
+/// Dropping the watchdog stops the thread.
+pub struct Watchdog {
+    /// Dropped to notify the thread that it should exit.
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Invokes `on_stall` with the time since the last beat whenever `heartbeat` stalls for
+    /// `timeout`.
+    pub fn spawn(
+        heartbeat: Heartbeat,
+        timeout: Duration,
+        mut on_stall: impl FnMut(Duration) + Send + 'static,
+    ) -> anyhow::Result<Self> {
+        let (stop, stopped) = bounded::<()>(0);
+        let poll = cmp::max(timeout / POLLS_PER_TIMEOUT, MINIMUM_POLL);
+
+        let handle = std::thread::Builder::new()
+            .name("superconsole-watchdog".to_owned())
+            .spawn(move || {
+                let mut fired = false;
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(poll) {
+                    let elapsed = heartbeat.elapsed();
+                    if elapsed < timeout {
+                        fired = false;
+                    } else if !fired {
+                        fired = true;
+                        on_stall(elapsed);
+                    }
+                }
+            })
+            .context("Error spawning Superconsole watchdog thread")?;
+
+        Ok(Self {
+            stop: Some(stop),
+            handle: Some(handle),
+        })
+    }
+
+    /// Emits a warning line to `console` whenever `heartbeat` stalls for `timeout`, at the
+    /// [`Warn`](Level::Warn) level. Writing to stderr instead would corrupt the canvas; the line
+    /// is drawn by the next render, e.g. once whatever drives the console catches up.
+    pub fn warn_on_stall(
+        heartbeat: Heartbeat,
+        timeout: Duration,
+        console: SuperConsoleHandle,
+    ) -> anyhow::Result<Self> {
+        Self::spawn(heartbeat, timeout, move |elapsed| {
+            let warning = format!("{} {}", warning_sign(), strings().stalled_for(elapsed));
+            console.emit_leveled(
+                Level::Warn,
+                Lines(vec![Line::from_iter([styled_span(
+                    StyleRole::Warn,
+                    &warning,
+                )])]),
+            );
+        })
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            // A panicking callback has already reported itself.
+            let _ignored = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context as _;
+    use crossbeam_channel::unbounded;
+
+    use super::*;
+    use crate::components::echo::Echo;
+    use crate::testing::frame_contains;
+    use crate::testing::test_console;
+    use crate::testing::SuperConsoleTestingExt;
+
+    #[test]
+    fn test_heartbeat() {
+        let heartbeat = Heartbeat::new();
+        assert!(heartbeat.is_stalled(Duration::ZERO));
+        assert!(!heartbeat.is_stalled(Duration::from_secs(3600)));
+
+        std::thread::sleep(Duration::from_millis(5));
+        let before = heartbeat.elapsed();
+        heartbeat.clone().beat();
+        assert!(heartbeat.elapsed() < before);
+    }
+
+    #[test]
+    fn test_watchdog_fires_once_per_stall() -> anyhow::Result<()> {
+        let heartbeat = Heartbeat::new();
+        let (sender, receiver) = unbounded();
+        let watchdog = Watchdog::spawn(heartbeat.clone(), Duration::from_millis(20), move |d| {
+            let _ignored = sender.send(d);
+        })?;
+
+        let elapsed = receiver.recv_timeout(Duration::from_secs(10))?;
+        assert!(elapsed >= Duration::from_millis(20));
+        // Still stalled, so this must not fire again until the heartbeat beats.
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+        heartbeat.beat();
+        receiver.recv_timeout(Duration::from_secs(10))?;
+
+        drop(watchdog);
+        Ok(())
+    }
+
+    #[test]
+    fn test_warn_on_stall() -> anyhow::Result<()> {
+        let mut console = test_console();
+        let watchdog = Watchdog::warn_on_stall(
+            Heartbeat::new(),
+            Duration::from_millis(20),
+            console.handle(),
+        )?;
+
+        let root = Echo(Lines(vec![vec!["state"].try_into()?]));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut warned = false;
+        while !warned && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            console.render(&root)?;
+            let frame = console.test_output()?.frames.last().context("No frame")?;
+            warned = frame_contains(frame, "stalled for");
+        }
+        drop(watchdog);
+        assert!(warned);
+
+        Ok(())
+    }
+}